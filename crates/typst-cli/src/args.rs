@@ -56,6 +56,10 @@ pub enum Command {
     /// Processes an input file to extract provided metadata
     Query(QueryCommand),
 
+    /// Runs test files and compares their output against reference images
+    #[command(visible_alias = "t")]
+    Test(TestCommand),
+
     /// Lists all discovered fonts in system and custom font paths
     Fonts(FontsCommand),
 
@@ -170,6 +174,46 @@ pub struct QueryCommand {
     pub pretty: bool,
 }
 
+/// Runs test files and compares their output against reference images
+///
+/// The input may be a single test file or a directory that is recursively
+/// searched for test files, whose names end in `.test.typ`. The reference
+/// image of `foo.test.typ` is `foo.png`.
+#[derive(Debug, Clone, Parser)]
+pub struct TestCommand {
+    /// Shared arguments
+    #[clap(flatten)]
+    pub common: SharedArgs,
+
+    /// Only runs tests whose path contains one of the given strings
+    #[clap(long = "filter", value_name = "PATTERN")]
+    pub filter: Vec<String>,
+
+    /// Directory with the reference images, defaults to `ref` next to the
+    /// tests
+    #[clap(long = "ref-dir", value_name = "DIR")]
+    pub ref_dir: Option<PathBuf>,
+
+    /// Directory to write renderings and diffs of failed tests to, defaults to
+    /// `store` next to the tests
+    #[clap(long = "store-dir", value_name = "DIR")]
+    pub store_dir: Option<PathBuf>,
+
+    /// Writes the current renderings as the new reference images instead of
+    /// comparing against them
+    #[arg(long = "update")]
+    pub update: bool,
+
+    /// The maximum difference per color channel at which two pixels are still
+    /// considered equal
+    #[arg(long = "tolerance", default_value_t = 1)]
+    pub tolerance: u8,
+
+    /// The PPI (pixels per inch) to render tests with
+    #[arg(long = "ppi", default_value_t = 144.0)]
+    pub ppi: f32,
+}
+
 // Output file format for query command
 #[derive(Debug, Copy, Clone, Eq, PartialEq, ValueEnum)]
 pub enum SerializationFormat {
@@ -177,7 +221,7 @@ pub enum SerializationFormat {
     Yaml,
 }
 
-/// Common arguments of compile, watch, and query.
+/// Common arguments of compile, watch, query, and test.
 #[derive(Debug, Clone, Args)]
 pub struct SharedArgs {
     /// Path to input Typst file. Use `-` to read input from stdin
@@ -13,7 +13,7 @@ use typst::foundations::{Datetime, Smart};
 use typst::layout::{Frame, Page, PageRanges};
 use typst::model::Document;
 use typst::syntax::{FileId, Source, Span};
-use typst::WorldExt;
+use typst::{Feature, Features, WorldExt};
 
 use crate::args::{
     CompileCommand, DiagnosticFormat, Input, Output, OutputFormat, PageRangeArgument,
@@ -61,6 +61,16 @@ impl CompileCommand {
         })
     }
 
+    /// The opt-in compiler features needed by the output format.
+    pub fn features(&self) -> Features {
+        match self.output_format() {
+            Ok(OutputFormat::Pdf | OutputFormat::Svg) => {
+                [Feature::Tagging].into_iter().collect()
+            }
+            _ => Features::default(),
+        }
+    }
+
     /// The ranges of the pages to be exported as specified by the user.
     ///
     /// This returns `None` if all pages should be exported.
@@ -75,8 +85,8 @@ impl CompileCommand {
 
 /// Execute a compilation command.
 pub fn compile(mut timer: Timer, mut command: CompileCommand) -> StrResult<()> {
-    let mut world =
-        SystemWorld::new(&command.common).map_err(|err| eco_format!("{err}"))?;
+    let mut world = SystemWorld::new(&command.common, command.features())
+        .map_err(|err| eco_format!("{err}"))?;
     timer.record(&mut world, |world| compile_once(world, &mut command, false))??;
     Ok(())
 }
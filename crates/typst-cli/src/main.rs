@@ -6,6 +6,7 @@ mod init;
 mod package;
 mod query;
 mod terminal;
+mod test;
 mod timings;
 #[cfg(feature = "self-update")]
 mod update;
@@ -54,6 +55,7 @@ fn dispatch() -> HintedStrResult<()> {
         Command::Watch(command) => crate::watch::watch(timer, command.clone())?,
         Command::Init(command) => crate::init::init(command)?,
         Command::Query(command) => crate::query::query(command)?,
+        Command::Test(command) => crate::test::test(command)?,
         Command::Fonts(command) => crate::fonts::fonts(command)?,
         Command::Update(command) => crate::update::update(command)?,
     }
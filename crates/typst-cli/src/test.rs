@@ -0,0 +1,424 @@
+use std::fs;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+use codespan_reporting::term::termcolor::{Color, ColorSpec, WriteColor};
+use ecow::eco_format;
+use tiny_skia as sk;
+use typst::diag::{bail, SourceDiagnostic, StrResult, Warned};
+use typst::layout::{Abs, Frame, FrameItem};
+use typst::model::Document;
+use typst::Features;
+
+use crate::args::{Input, TestCommand};
+use crate::compile::print_diagnostics;
+use crate::world::SystemWorld;
+use crate::{set_failed, terminal};
+
+/// The suffix of the files that are run as tests when discovering tests in a
+/// directory. Other Typst files, like templates imported by the tests, are
+/// skipped.
+const TEST_SUFFIX: &str = ".test.typ";
+
+/// Execute a test command.
+pub fn test(command: &TestCommand) -> StrResult<()> {
+    let Input::Path(input) = &command.common.input else {
+        bail!("cannot run tests from stdin");
+    };
+
+    let mut world = SystemWorld::new(&command.common, Features::default())?;
+
+    // Reference images and renderings are stored relative to the directory
+    // that contains the tests.
+    let base = if input.is_dir() {
+        input.clone()
+    } else {
+        input.parent().map(Path::to_path_buf).unwrap_or_default()
+    };
+    let ref_dir = command.ref_dir.clone().unwrap_or_else(|| base.join("ref"));
+    let store_dir = command.store_dir.clone().unwrap_or_else(|| base.join("store"));
+
+    let mut tests = vec![];
+    if input.is_dir() {
+        discover(input, &[&ref_dir, &store_dir], &mut tests)
+            .map_err(|err| eco_format!("failed to discover tests ({err})"))?;
+    } else {
+        tests.push(input.clone());
+    }
+
+    tests.retain(|path| {
+        let name = name(&base, path);
+        command.filter.is_empty()
+            || command.filter.iter().any(|pattern| name.contains(pattern.as_str()))
+    });
+
+    if tests.is_empty() {
+        bail!("no tests found in {}", input.display());
+    }
+
+    let mut passed = 0;
+    for path in &tests {
+        let name = name(&base, path);
+        world.set_main(path)?;
+        world.reset();
+
+        let Warned { output, warnings } = typst::compile(&world);
+        let outcome = match output {
+            Ok(document) => check(command, &document, &name, &ref_dir, &store_dir)?,
+            Err(errors) => Outcome::Errored(errors.into_iter().collect()),
+        };
+
+        if matches!(outcome, Outcome::Passed | Outcome::Updated(_)) {
+            passed += 1;
+        }
+
+        outcome
+            .print(&name)
+            .map_err(|err| eco_format!("failed to print test result ({err})"))?;
+
+        let errors = match &outcome {
+            Outcome::Errored(errors) => errors.as_slice(),
+            _ => &[],
+        };
+
+        print_diagnostics(&world, errors, &warnings, command.common.diagnostic_format)
+            .map_err(|err| eco_format!("failed to print diagnostics ({err})"))?;
+    }
+
+    let failed = tests.len() - passed;
+    if failed > 0 {
+        set_failed();
+    }
+
+    writeln!(terminal::out(), "{passed} passed, {failed} failed")
+        .map_err(|err| eco_format!("failed to print test summary ({err})"))?;
+
+    Ok(())
+}
+
+/// Recursively collects all test files in a directory, in a stable order.
+///
+/// Hidden directories and the given excluded ones are skipped.
+fn discover(dir: &Path, excluded: &[&Path], tests: &mut Vec<PathBuf>) -> io::Result<()> {
+    let mut entries = fs::read_dir(dir)?
+        .map(|entry| entry.map(|entry| entry.path()))
+        .collect::<io::Result<Vec<_>>>()?;
+    entries.sort();
+
+    for path in entries {
+        if path.is_dir() {
+            let hidden = path
+                .file_name()
+                .is_some_and(|name| name.to_string_lossy().starts_with('.'));
+            if !hidden
+                && !excluded
+                    .iter()
+                    .any(|dir| same_file::is_same_file(dir, &path).unwrap_or(false))
+            {
+                discover(&path, excluded, tests)?;
+            }
+        } else if path
+            .file_name()
+            .is_some_and(|name| name.to_string_lossy().ends_with(TEST_SUFFIX))
+        {
+            tests.push(path);
+        }
+    }
+
+    Ok(())
+}
+
+/// The name of a test, which is its path relative to the test directory
+/// without the test suffix or extension.
+fn name(base: &Path, path: &Path) -> String {
+    let path = path
+        .strip_prefix(base)
+        .unwrap_or(path)
+        .to_string_lossy()
+        .replace('\\', "/");
+    match path.strip_suffix(TEST_SUFFIX) {
+        Some(name) => name.into(),
+        None => Path::new(&path).with_extension("").to_string_lossy().into(),
+    }
+}
+
+/// The result of running a single test.
+enum Outcome {
+    /// The rendering matched the reference image or the test has no visible
+    /// output.
+    Passed,
+    /// The reference image was written or removed.
+    Updated(PathBuf),
+    /// The test failed to compile, for example due to a failed assertion.
+    Errored(Vec<SourceDiagnostic>),
+    /// There is no reference image for the test.
+    MissingRef { live: PathBuf },
+    /// The rendering does not match the reference image.
+    Mismatched { live: PathBuf, reference: PathBuf, diff: Diff },
+}
+
+impl Outcome {
+    /// Print a report for the test with the given name.
+    fn print(&self, name: &str) -> io::Result<()> {
+        let mut out = terminal::out();
+        let (color, status) = match self {
+            Self::Passed => (Color::Green, "pass"),
+            Self::Updated(_) => (Color::Cyan, "update"),
+            _ => (Color::Red, "fail"),
+        };
+
+        out.set_color(ColorSpec::new().set_fg(Some(color)).set_bold(true))?;
+        write!(out, "{status:<6}")?;
+        out.reset()?;
+        writeln!(out, " {name}")?;
+
+        match self {
+            Self::Passed | Self::Errored(_) => {}
+            Self::Updated(path) => {
+                writeln!(out, "  reference | {}", path.display())?;
+            }
+            Self::MissingRef { live } => {
+                writeln!(
+                    out,
+                    "  missing reference image, run with `--update` to create it"
+                )?;
+                writeln!(out, "  live      | {}", live.display())?;
+            }
+            Self::Mismatched { live, reference, diff } => {
+                writeln!(out, "  mismatched rendering")?;
+                writeln!(out, "  live      | {}", live.display())?;
+                writeln!(out, "  reference | {}", reference.display())?;
+                match diff {
+                    Diff::Size { live, reference } => writeln!(
+                        out,
+                        "  size      | {}x{} (live) vs {}x{} (reference)",
+                        live.0, live.1, reference.0, reference.1,
+                    )?,
+                    Diff::Pixels { count, max, path, .. } => {
+                        writeln!(out, "  diff      | {}", path.display())?;
+                        writeln!(
+                            out,
+                            "  pixels    | {count} differ, by up to {max} per channel"
+                        )?;
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// How a rendering differs from its reference image.
+enum Diff {
+    /// The images have different dimensions.
+    Size { live: (u32, u32), reference: (u32, u32) },
+    /// Some pixels differ by more than the tolerance. The differences are
+    /// highlighted in `image`, which is stored at `path`.
+    Pixels { count: usize, max: u8, image: sk::Pixmap, path: PathBuf },
+}
+
+/// Check the rendered document against its reference image, or update the
+/// reference image if requested.
+fn check(
+    command: &TestCommand,
+    document: &Document,
+    name: &str,
+    ref_dir: &Path,
+    store_dir: &Path,
+) -> StrResult<Outcome> {
+    let ref_path = ref_dir.join(format!("{name}.png"));
+    let live_path = store_dir.join(format!("{name}.png"));
+    let diff_path = store_dir.join(format!("{name}.diff.png"));
+    let has_ref = ref_path.exists();
+
+    // Tests that only consist of assertions don't need a reference image.
+    let invisible = document.pages.iter().all(|page| is_invisible(&page.frame));
+    if invisible && !has_ref {
+        return Ok(Outcome::Passed);
+    }
+
+    let live = typst_render::render_merged(
+        document,
+        command.ppi / 72.0,
+        Abs::pt(1.0),
+        Some(typst::visualize::Color::BLACK),
+    );
+
+    let diff = if has_ref {
+        let data = fs::read(&ref_path)
+            .map_err(|err| eco_format!("failed to read reference image ({err})"))?;
+        let reference = sk::Pixmap::decode_png(&data)
+            .map_err(|err| eco_format!("failed to decode reference image ({err})"))?;
+        let live_size = (live.width(), live.height());
+        let ref_size = (reference.width(), reference.height());
+        if live_size != ref_size {
+            Some(Diff::Size { live: live_size, reference: ref_size })
+        } else {
+            compare(&live, &reference, command.tolerance).map(|(count, max, image)| {
+                Diff::Pixels { count, max, image, path: diff_path.clone() }
+            })
+        }
+    } else {
+        None
+    };
+
+    // A test that matches its reference doesn't need to be updated.
+    if has_ref && diff.is_none() {
+        // Clean up renderings from earlier failed runs.
+        fs::remove_file(&live_path).ok();
+        fs::remove_file(&diff_path).ok();
+        return Ok(Outcome::Passed);
+    }
+
+    if command.update {
+        if invisible {
+            fs::remove_file(&ref_path)
+                .map_err(|err| eco_format!("failed to remove reference image ({err})"))?;
+        } else {
+            write_png(&ref_path, &live)?;
+        }
+        fs::remove_file(&live_path).ok();
+        fs::remove_file(&diff_path).ok();
+        return Ok(Outcome::Updated(ref_path));
+    }
+
+    write_png(&live_path, &live)?;
+    match diff {
+        None => Ok(Outcome::MissingRef { live: live_path }),
+        Some(diff) => {
+            if let Diff::Pixels { image, path, .. } = &diff {
+                write_png(path, image)?;
+            }
+            Ok(Outcome::Mismatched { live: live_path, reference: ref_path, diff })
+        }
+    }
+}
+
+/// Compare two images of the same size.
+///
+/// If any channel of any pixel differs by more than the tolerance, returns
+/// the number of such pixels, the largest difference, and an image with the
+/// differing pixels marked in red on top of a faded version of the reference.
+fn compare(
+    live: &sk::Pixmap,
+    reference: &sk::Pixmap,
+    tolerance: u8,
+) -> Option<(usize, u8, sk::Pixmap)> {
+    let mut image = reference.clone();
+    let mut count = 0;
+    let mut max = 0;
+
+    for ((a, b), out) in
+        live.pixels().iter().zip(reference.pixels()).zip(image.pixels_mut())
+    {
+        let delta = [
+            a.red().abs_diff(b.red()),
+            a.green().abs_diff(b.green()),
+            a.blue().abs_diff(b.blue()),
+            a.alpha().abs_diff(b.alpha()),
+        ]
+        .into_iter()
+        .max()
+        .unwrap_or_default();
+
+        *out = if delta > tolerance {
+            count += 1;
+            max = max.max(delta);
+            sk::PremultipliedColorU8::from_rgba(255, 0, 0, 255).unwrap()
+        } else {
+            let luma = ((u16::from(b.red()) + u16::from(b.green()) + u16::from(b.blue()))
+                / 3) as u8;
+            let faded = 255 - (255 - luma) / 4;
+            sk::PremultipliedColorU8::from_rgba(faded, faded, faded, 255).unwrap()
+        };
+    }
+
+    (count > 0).then_some((count, max, image))
+}
+
+/// Whether a frame has no visible content.
+fn is_invisible(frame: &Frame) -> bool {
+    frame.items().all(|(_, item)| match item {
+        FrameItem::Group(group) => is_invisible(&group.frame),
+        FrameItem::Tag(_) => true,
+        _ => false,
+    })
+}
+
+/// Encode an image as PNG and write it to the given path, creating parent
+/// directories as needed.
+fn write_png(path: &Path, pixmap: &sk::Pixmap) -> StrResult<()> {
+    let data = pixmap
+        .encode_png()
+        .map_err(|err| eco_format!("failed to encode PNG file ({err})"))?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|err| eco_format!("failed to create directory ({err})"))?;
+    }
+    fs::write(path, data).map_err(|err| eco_format!("failed to write PNG file ({err})"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Create a fresh temporary directory with the given files.
+    fn setup(name: &str, files: &[&str]) -> tempfile::TempDir {
+        let dir = tempfile::Builder::new()
+            .prefix(&format!("typst-test-{name}-"))
+            .tempdir()
+            .unwrap();
+        for file in files {
+            let path = dir.path().join(file);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, "").unwrap();
+        }
+        dir
+    }
+
+    #[test]
+    fn test_discover_only_test_files() {
+        let temp = setup(
+            "discover",
+            &[
+                "b.test.typ",
+                "a.test.typ",
+                "template.typ",
+                "notes.txt",
+                "nested/c.test.typ",
+                "nested/util.typ",
+                ".hidden/d.test.typ",
+                "ref/e.test.typ",
+            ],
+        );
+
+        let dir = temp.path();
+        let mut tests = vec![];
+        discover(dir, &[&dir.join("ref")], &mut tests).unwrap();
+        let names: Vec<_> = tests.iter().map(|path| name(dir, path)).collect();
+        assert_eq!(names, ["a", "b", "nested/c"]);
+    }
+
+    #[test]
+    fn test_name() {
+        let base = Path::new("tests");
+        assert_eq!(name(base, Path::new("tests/a.test.typ")), "a");
+        assert_eq!(name(base, Path::new("tests/b/c.test.typ")), "b/c");
+        assert_eq!(name(base, Path::new("tests/single.typ")), "single");
+    }
+
+    #[test]
+    fn test_compare_tolerance() {
+        let mut live = sk::Pixmap::new(2, 1).unwrap();
+        let mut reference = live.clone();
+        live.fill(sk::Color::from_rgba8(100, 100, 100, 255));
+        reference.fill(sk::Color::from_rgba8(101, 100, 100, 255));
+        assert!(compare(&live, &reference, 1).is_none());
+
+        reference.pixels_mut()[1] =
+            sk::PremultipliedColorU8::from_rgba(110, 100, 100, 255).unwrap();
+        let (count, max, _) = compare(&live, &reference, 1).unwrap();
+        assert_eq!((count, max), (1, 10));
+    }
+}
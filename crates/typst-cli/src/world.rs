@@ -13,7 +13,7 @@ use typst::foundations::{Bytes, Datetime, Dict, IntoValue};
 use typst::syntax::{FileId, Source, VirtualPath};
 use typst::text::{Font, FontBook};
 use typst::utils::LazyHash;
-use typst::{Library, World};
+use typst::{Features, Library, World};
 use typst_kit::fonts::{FontSlot, Fonts};
 use typst_kit::package::PackageStorage;
 use typst_timing::{timed, TimingScope};
@@ -56,8 +56,11 @@ pub struct SystemWorld {
 }
 
 impl SystemWorld {
-    /// Create a new system world.
-    pub fn new(command: &SharedArgs) -> Result<Self, WorldCreationError> {
+    /// Create a new system world with the given opt-in compiler features.
+    pub fn new(
+        command: &SharedArgs,
+        features: Features,
+    ) -> Result<Self, WorldCreationError> {
         // Set up the thread pool.
         if let Some(jobs) = command.jobs {
             rayon::ThreadPoolBuilder::new()
@@ -113,7 +116,7 @@ impl SystemWorld {
                 .map(|(k, v)| (k.as_str().into(), v.as_str().into_value()))
                 .collect();
 
-            Library::builder().with_inputs(inputs).build()
+            Library::builder().with_inputs(inputs).with_features(features).build()
         };
 
         let fonts = Fonts::searcher()
@@ -144,6 +147,15 @@ impl SystemWorld {
         self.main
     }
 
+    /// Change the main source file to another file within the project root.
+    pub fn set_main(&mut self, path: &Path) -> Result<(), WorldCreationError> {
+        let path = path.canonicalize().map_err(WorldCreationError::Io)?;
+        let main_path = VirtualPath::within_root(&path, &self.root)
+            .ok_or(WorldCreationError::InputOutsideRoot)?;
+        self.main = FileId::new(None, main_path);
+        Ok(())
+    }
+
     /// The root relative to which absolute paths are resolved.
     pub fn root(&self) -> &Path {
         &self.root
@@ -9,6 +9,7 @@ use typst::foundations::{
     Scope, StyleChain, Styles, Type, Value,
 };
 use typst::model::Document;
+use typst::syntax::ast::AstNode;
 use typst::syntax::{
     ast, is_id_continue, is_id_start, is_ident, LinkedNode, Side, Source, SyntaxKind,
 };
@@ -435,13 +436,13 @@ fn field_access_completions(
         }
         Value::Func(func) => {
             // Autocomplete get rules.
-            if let Some((elem, styles)) = func.element().zip(styles.as_ref()) {
-                for param in elem.params().iter().filter(|param| !param.required) {
-                    if let Some(value) = elem.field_id(param.name).and_then(|id| {
-                        elem.field_from_styles(id, StyleChain::new(styles)).ok()
+            if let Some((params, styles)) = func.params().zip(styles.as_ref()) {
+                for param in params.iter().filter(|param| !param.required) {
+                    if let Some(value) = func.field_id(&param.name).and_then(|id| {
+                        func.field_from_styles(id, StyleChain::new(styles)).ok()
                     }) {
                         ctx.value_completion(
-                            Some(param.name.into()),
+                            Some(param.name.clone()),
                             &value,
                             false,
                             None,
@@ -721,7 +722,7 @@ fn param_completions<'a>(
     set: bool,
     args: ast::Args<'a>,
 ) {
-    let Some(func) = resolve_global_callee(ctx, callee) else { return };
+    let Some(func) = resolve_callee(ctx, callee) else { return };
     let Some(params) = func.params() else { return };
 
     // Exclude named arguments which are already present.
@@ -745,7 +746,7 @@ fn param_completions<'a>(
         if param.named {
             ctx.completions.push(Completion {
                 kind: CompletionKind::Param,
-                label: param.name.into(),
+                label: param.name.clone(),
                 apply: Some(eco_format!("{}: ${{}}", param.name)),
                 detail: Some(plain_docs_sentence(param.docs)),
             });
@@ -767,7 +768,7 @@ fn named_param_value_completions<'a>(
     callee: ast::Expr<'a>,
     name: &str,
 ) {
-    let Some(func) = resolve_global_callee(ctx, callee) else { return };
+    let Some(func) = resolve_callee(ctx, callee) else { return };
     let Some(param) = func.param(name) else { return };
     if !param.named {
         return;
@@ -783,6 +784,30 @@ fn named_param_value_completions<'a>(
     }
 }
 
+/// Resolve a callee expression to a function. Global functions are looked up
+/// directly while user-defined ones are found by analyzing the callee.
+fn resolve_callee<'a>(
+    ctx: &CompletionContext<'a>,
+    callee: ast::Expr<'a>,
+) -> Option<Func> {
+    if let Some(func) = resolve_global_callee(ctx, callee) {
+        return Some(func.clone());
+    }
+
+    let mut root = ctx.leaf.clone();
+    while let Some(parent) = root.parent() {
+        root = parent.clone();
+    }
+
+    let node = root.find(callee.span())?;
+    analyze_expr(ctx.world, &node)
+        .into_iter()
+        .find_map(|(value, _)| match value {
+            Value::Func(func) => Some(func),
+            _ => None,
+        })
+}
+
 /// Resolve a callee expression to a global function.
 fn resolve_global_callee<'a>(
     ctx: &CompletionContext<'a>,
@@ -1224,7 +1249,7 @@ impl<'a> CompletionContext<'a> {
     }
 
     /// Add completions for a castable.
-    fn cast_completions(&mut self, cast: &'a CastInfo) {
+    fn cast_completions(&mut self, cast: &CastInfo) {
         // Prevent duplicate completions from appearing.
         if !self.seen_casts.insert(typst::utils::hash128(cast)) {
             return;
@@ -1403,4 +1428,11 @@ mod tests {
         let s = "#{ let x = (1, 2, 3); x. }";
         test(s, s.len() - 2, &["at", "push", "pop"], &[]);
     }
+
+    #[test]
+    fn test_autocomplete_closure_params() {
+        let s = "#let f(x, size: length = 1pt) = x
+#f(1, )";
+        test(s, s.len() - 1, &["size"], &["x"]);
+    }
 }
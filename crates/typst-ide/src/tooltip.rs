@@ -7,6 +7,7 @@ use typst::eval::CapturesVisitor;
 use typst::foundations::{repr, Capturer, CastInfo, Repr, Value};
 use typst::layout::Length;
 use typst::model::Document;
+use typst::syntax::ast::AstNode;
 use typst::syntax::{ast, LinkedNode, Side, Source, SyntaxKind};
 use typst::utils::{round_2, Numeric};
 use typst::World;
@@ -184,8 +185,19 @@ fn named_param_tooltip(world: &dyn World, leaf: &LinkedNode) -> Option<Tooltip>
             _ => None,
         };
 
-        // Find metadata about the function.
-        if let Some(Value::Func(func)) = world.library().global.scope().get(&callee);
+        // Find metadata about the function, either in the global scope or,
+        // for user-defined functions, by analyzing the callee.
+        if let Some(func) = match world.library().global.scope().get(&callee) {
+            Some(Value::Func(func)) => Some(func.clone()),
+            _ => grand_grand.find(callee.span()).and_then(|node| {
+                analyze_expr(world, &node).into_iter().find_map(|(value, _)| {
+                    match value {
+                        Value::Func(func) => Some(func),
+                        _ => None,
+                    }
+                })
+            }),
+        };
         then { (func, named) }
         else { return None; }
     };
@@ -196,6 +208,12 @@ fn named_param_tooltip(world: &dyn World, leaf: &LinkedNode) -> Option<Tooltip>
         if let Some(ident) = leaf.cast::<ast::Ident>();
         if let Some(param) = func.param(&ident);
         then {
+            // Parameters of user-defined functions have no documentation, but
+            // may have a type annotation.
+            if param.docs.is_empty() && param.input != CastInfo::Any {
+                let annotation = annotation_repr(&param.input);
+                return Some(Tooltip::Code(eco_format!("{}: {annotation}", param.name)));
+            }
             return Some(Tooltip::Text(plain_docs_sentence(param.docs)));
         }
     }
@@ -213,6 +231,20 @@ fn named_param_tooltip(world: &dyn World, leaf: &LinkedNode) -> Option<Tooltip>
     None
 }
 
+/// Describe the types accepted by a parameter the way they are written in a
+/// type annotation: `int` or `(str, content)`.
+fn annotation_repr(info: &CastInfo) -> EcoString {
+    match info {
+        CastInfo::Type(ty) => ty.short_name().into(),
+        CastInfo::Union(options) => {
+            let parts: Vec<_> = options.iter().map(annotation_repr).collect();
+            eco_format!("({})", parts.join(", "))
+        }
+        CastInfo::Value(value, _) => value.repr(),
+        CastInfo::Any => "any".into(),
+    }
+}
+
 /// Find documentation for a castable string.
 fn find_string_doc(info: &CastInfo, string: &str) -> Option<&'static str> {
     match info {
@@ -280,6 +312,12 @@ mod tests {
         test("#let f(x) = x + y", 11, Side::Before, text("This closure captures `y`."));
     }
 
+    #[test]
+    fn test_tooltip_closure_param() {
+        let s = "#let f(x, size: (length, ratio) = 1pt) = x\n#f(1, size: 2pt)";
+        test(s, s.len() - 10, Side::After, code("size: (length, ratio)"));
+    }
+
     #[test]
     fn test_empty_contextual() {
         test("#{context}", 10, Side::Before, code("context()"));
@@ -708,7 +708,7 @@ fn create_param_info(field: &Field) -> TokenStream {
 
     quote! {
         #foundations::ParamInfo {
-            name: #name,
+            name: #name.into(),
             docs: #docs,
             input: <#ty as #foundations::Reflect>::input(),
             default: #default,
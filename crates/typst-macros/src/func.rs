@@ -412,7 +412,7 @@ fn create_param_info(param: &Param) -> TokenStream {
     }));
     quote! {
         #foundations::ParamInfo {
-            name: #name,
+            name: #name.into(),
             docs: #docs,
             input: <#ty as #foundations::Reflect>::input(),
             default: #default,
@@ -468,7 +468,7 @@ fn rewrite_fn_item(item: &syn::ItemFn) -> syn::ItemFn {
         Some(input)
     });
     let mut item = item.clone();
-    item.attrs.clear();
+    item.attrs.retain(|attr| attr.path().is_ident("allow"));
     item.sig.inputs = parse_quote! { #(#inputs),* };
     item
 }
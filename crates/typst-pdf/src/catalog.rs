@@ -84,7 +84,9 @@ pub fn write_catalog(
     }
 
     if let Some(date) = ctx.document.info.date.unwrap_or(timestamp) {
-        let tz = ctx.document.info.date.is_auto();
+        // Datetimes with an offset are written in UTC.
+        let tz = ctx.document.info.date.is_auto() || date.offset().is_some();
+        let date = date.to_utc();
         if let Some(pdf_date) = pdf_date(date, tz) {
             info.creation_date(pdf_date);
             info.modified_date(pdf_date);
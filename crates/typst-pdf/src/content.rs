@@ -9,9 +9,12 @@ use pdf_writer::{
     types::{ColorSpaceOperand, LineCapStyle, LineJoinStyle, TextRenderingMode},
     Content, Finish, Name, Rect, Str,
 };
+use typst::foundations::StyleChain;
+use typst::introspection::{Tag, TagKind};
 use typst::layout::{
     Abs, Em, Frame, FrameItem, GroupItem, Point, Ratio, Size, Transform,
 };
+use typst::math::EquationElem;
 use typst::model::Destination;
 use typst::text::{color::is_color_glyph, Font, TextItem, TextItemView};
 use typst::utils::{Deferred, Numeric, SliceExt};
@@ -23,6 +26,7 @@ use typst::visualize::{
 use crate::color_font::ColorFontMap;
 use crate::extg::ExtGState;
 use crate::image::deferred_image;
+use crate::properties::MarkedProperties;
 use crate::{color::PaintEncode, resources::Resources};
 use crate::{deflate_deferred, AbsExt, EmExt};
 
@@ -63,6 +67,11 @@ pub fn build(
     // Encode the frame into the content stream.
     write_frame(&mut ctx, frame);
 
+    // Close marked content that continues on the next page.
+    for _ in 0..ctx.marked {
+        ctx.content.end_marked_content();
+    }
+
     Encoded {
         size,
         content: deflate_deferred(ctx.content.finish()),
@@ -103,6 +112,8 @@ pub struct Builder<'a, R = ()> {
     uses_opacities: bool,
     /// All clickable links that are present in this content.
     links: Vec<(Destination, Rect)>,
+    /// The number of open marked content sequences.
+    marked: usize,
 }
 
 impl<'a, R> Builder<'a, R> {
@@ -115,6 +126,7 @@ impl<'a, R> Builder<'a, R> {
             state: State::new(size),
             saves: vec![],
             links: vec![],
+            marked: 0,
         }
     }
 }
@@ -356,8 +368,34 @@ pub(crate) fn write_frame(ctx: &mut Builder, frame: &Frame) {
             FrameItem::Shape(shape, _) => write_shape(ctx, pos, shape),
             FrameItem::Image(image, size, _) => write_image(ctx, x, y, image, *size),
             FrameItem::Link(dest, size) => write_link(ctx, pos, dest, *size),
-            FrameItem::Tag(_) => {}
+            FrameItem::Tag(tag) => write_tag(ctx, tag),
+        }
+    }
+}
+
+/// Encode a tag into the content stream.
+///
+/// Equations are wrapped into marked content with their alternative text and
+/// their MathML representation, so that they are accessible to assistive
+/// technology.
+fn write_tag(ctx: &mut Builder, tag: &Tag) {
+    let Some(equation) = tag.elem().to_packed::<EquationElem>() else { return };
+    match tag.kind() {
+        TagKind::Start => {
+            let Some(alt) = equation.alt(StyleChain::default()) else { return };
+            let mathml = equation.mathml().cloned().flatten();
+            let index = ctx.resources.properties.insert(MarkedProperties { alt, mathml });
+            let name = eco_format!("Mc{index}");
+            ctx.content
+                .begin_marked_content_with_properties(Name(b"Span"))
+                .properties_named(Name(name.as_bytes()));
+            ctx.marked += 1;
+        }
+        TagKind::End if ctx.marked > 0 => {
+            ctx.content.end_marked_content();
+            ctx.marked -= 1;
         }
+        TagKind::End => {}
     }
 }
 
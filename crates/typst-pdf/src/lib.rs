@@ -12,6 +12,7 @@ mod named_destination;
 mod outline;
 mod page;
 mod pattern;
+mod properties;
 mod resources;
 
 use std::collections::HashMap;
@@ -37,6 +38,7 @@ use crate::image::write_images;
 use crate::named_destination::{write_named_destinations, NamedDestinations};
 use crate::page::{alloc_page_refs, traverse_pages, write_page_tree, EncodedPage};
 use crate::pattern::{write_patterns, PdfPattern};
+use crate::properties::{write_marked_properties, MarkedProperties};
 use crate::resources::{
     alloc_resources_refs, write_resource_dictionaries, Resources, ResourcesRefs,
 };
@@ -85,6 +87,7 @@ pub fn pdf(
             gradients: builder.run(write_gradients),
             patterns: builder.run(write_patterns),
             ext_gs: builder.run(write_graphic_states),
+            properties: builder.run(write_marked_properties),
         })
         .phase(|builder| builder.run(write_page_tree))
         .phase(|builder| builder.run(write_resource_dictionaries))
@@ -220,6 +223,8 @@ struct References {
     patterns: HashMap<PdfPattern, Ref>,
     /// The IDs of written external graphics states.
     ext_gs: HashMap<ExtGState, Ref>,
+    /// The IDs of written marked-content property lists.
+    properties: HashMap<MarkedProperties, Ref>,
 }
 
 /// At this point, the references have been assigned to all resources. The page
@@ -0,0 +1,60 @@
+use std::collections::HashMap;
+
+use ecow::EcoString;
+use pdf_writer::{Finish, Name, Ref, Str, TextStr};
+
+use crate::{PdfChunk, WithGlobalRefs};
+
+/// The properties of a marked-content sequence that describes an equation to
+/// assistive technology.
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub struct MarkedProperties {
+    /// The human-readable alternative text.
+    pub alt: EcoString,
+    /// The MathML representation, which is attached as an associated file.
+    pub mathml: Option<EcoString>,
+}
+
+/// Embed all used marked-content property lists into the PDF.
+pub fn write_marked_properties(
+    context: &WithGlobalRefs,
+) -> (PdfChunk, HashMap<MarkedProperties, Ref>) {
+    let mut chunk = PdfChunk::new();
+    let mut out = HashMap::new();
+    context.resources.traverse(&mut |resources| {
+        for properties in resources.properties.items() {
+            if out.contains_key(properties) {
+                continue;
+            }
+
+            // Property lists that are referenced from the resources can hold
+            // indirect references, so the MathML can be associated with them.
+            let file_spec_id = properties.mathml.as_ref().map(|mathml| {
+                let file_id = chunk.alloc();
+                chunk
+                    .embedded_file(file_id, mathml.as_bytes())
+                    .subtype(Name(b"application/mathml+xml"));
+
+                let file_spec_id = chunk.alloc();
+                let mut file_spec = chunk.file_spec(file_spec_id);
+                file_spec.path(Str(b"equation.mml"));
+                file_spec.unic_file(TextStr("equation.mml"));
+                file_spec.description(TextStr("MathML representation"));
+                file_spec.embedded_file(file_id);
+                file_spec.pair(Name(b"AFRelationship"), Name(b"Supplement"));
+                file_spec_id
+            });
+
+            let id = chunk.alloc();
+            out.insert(properties.clone(), id);
+            let mut dict = chunk.indirect(id).dict();
+            dict.pair(Name(b"Alt"), TextStr(&properties.alt));
+            if let Some(file_spec_id) = file_spec_id {
+                dict.insert(Name(b"AF")).array().item(file_spec_id);
+            }
+            dict.finish();
+        }
+    });
+
+    (chunk, out)
+}
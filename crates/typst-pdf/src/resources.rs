@@ -17,8 +17,8 @@ use typst::{text::Font, utils::Deferred, visualize::Image};
 
 use crate::{
     color::ColorSpaces, color_font::ColorFontMap, extg::ExtGState, gradient::PdfGradient,
-    image::EncodedImage, pattern::PatternRemapper, PdfChunk, Renumber, WithEverything,
-    WithResources,
+    image::EncodedImage, pattern::PatternRemapper, properties::MarkedProperties,
+    PdfChunk, Renumber, WithEverything, WithResources,
 };
 
 /// All the resources that have been collected when traversing the document.
@@ -65,6 +65,8 @@ pub struct Resources<R = Ref> {
     pub patterns: Option<Box<PatternRemapper<R>>>,
     /// Deduplicates external graphics states used across the document.
     pub ext_gs: Remapper<ExtGState>,
+    /// Deduplicates marked-content property lists used across the document.
+    pub properties: Remapper<MarkedProperties>,
     /// Deduplicates color glyphs.
     pub color_fonts: Option<Box<ColorFontMap<R>>>,
 
@@ -119,6 +121,7 @@ impl Default for Resources<()> {
             gradients: Remapper::new("Gr"),
             patterns: None,
             ext_gs: Remapper::new("Gs"),
+            properties: Remapper::new("Mc"),
             color_fonts: None,
             languages: BTreeMap::new(),
             glyph_sets: HashMap::new(),
@@ -144,6 +147,7 @@ impl Resources<()> {
                 .zip(refs.patterns.as_ref())
                 .map(|(p, r)| Box::new(p.with_refs(r))),
             ext_gs: self.ext_gs,
+            properties: self.properties,
             color_fonts: self
                 .color_fonts
                 .zip(refs.color_fonts.as_ref())
@@ -235,6 +239,7 @@ pub fn write_resource_dictionaries(ctx: &WithEverything) -> (PdfChunk, ()) {
         let patterns_ref = chunk.alloc.bump();
         let ext_gs_states_ref = chunk.alloc.bump();
         let color_spaces_ref = chunk.alloc.bump();
+        let properties_ref = chunk.alloc.bump();
 
         let mut color_font_slices = Vec::new();
         let mut color_font_numbers = HashMap::new();
@@ -267,6 +272,11 @@ pub fn write_resource_dictionaries(ctx: &WithEverything) -> (PdfChunk, ()) {
             .ext_gs
             .write(&ctx.references.ext_gs, &mut chunk.indirect(ext_gs_states_ref).dict());
 
+        resources.properties.write(
+            &ctx.references.properties,
+            &mut chunk.indirect(properties_ref).dict(),
+        );
+
         let mut res_dict = chunk
             .indirect(resources.reference)
             .start::<pdf_writer::writers::Resources>();
@@ -274,6 +284,7 @@ pub fn write_resource_dictionaries(ctx: &WithEverything) -> (PdfChunk, ()) {
         res_dict.pair(Name(b"Pattern"), patterns_ref);
         res_dict.pair(Name(b"ExtGState"), ext_gs_states_ref);
         res_dict.pair(Name(b"ColorSpace"), color_spaces_ref);
+        res_dict.pair(Name(b"Properties"), properties_ref);
 
         // TODO: can't this be an indirect reference too?
         let mut fonts_dict = res_dict.fonts();
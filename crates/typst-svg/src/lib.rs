@@ -10,9 +10,11 @@ use std::fmt::{self, Display, Formatter, Write};
 
 use ecow::EcoString;
 use ttf_parser::OutlineBuilder;
+use typst::introspection::{Tag, TagKind};
 use typst::layout::{
     Abs, Frame, FrameItem, FrameKind, GroupItem, Page, Point, Ratio, Size, Transform,
 };
+use typst::math::EquationElem;
 use typst::model::Document;
 use typst::utils::hash128;
 use typst::visualize::{Geometry, Gradient, Pattern};
@@ -194,9 +196,14 @@ impl SVGRenderer {
         }
 
         for (pos, item) in frame.items() {
+            if let FrameItem::Tag(tag) = item {
+                self.render_tag(tag);
+                continue;
+            }
+
             // File size optimization.
             // TODO: SVGs could contain links, couldn't they?
-            if matches!(item, FrameItem::Link(_, _) | FrameItem::Tag(_)) {
+            if matches!(item, FrameItem::Link(_, _)) {
                 continue;
             }
 
@@ -227,6 +234,42 @@ impl SVGRenderer {
         self.xml.end_element();
     }
 
+    /// Render a tag. Equations are embedded as MathML metadata, so that they
+    /// are accessible to assistive technology and HTML pipelines.
+    fn render_tag(&mut self, tag: &Tag) {
+        if tag.kind() != TagKind::Start {
+            return;
+        }
+        let Some(equation) = tag.elem().to_packed::<EquationElem>() else { return };
+        let Some(Some(mathml)) = equation.mathml() else { return };
+        self.xml.start_element("metadata");
+        self.write_mathml(mathml);
+        self.xml.end_element();
+    }
+
+    /// Write MathML markup. It is tokenized and written element by element,
+    /// so that it nests properly into the SVG document.
+    fn write_mathml(&mut self, mathml: &str) {
+        for token in xmlparser::Tokenizer::from(mathml) {
+            match token {
+                Ok(xmlparser::Token::ElementStart { local, .. }) => {
+                    self.xml.start_element(local.as_str())
+                }
+                Ok(xmlparser::Token::Attribute { local, value, .. }) => {
+                    self.xml.write_attribute(local.as_str(), &unescape(&value))
+                }
+                Ok(xmlparser::Token::ElementEnd {
+                    end: xmlparser::ElementEnd::Close(..) | xmlparser::ElementEnd::Empty,
+                    ..
+                }) => self.xml.end_element(),
+                Ok(xmlparser::Token::Text { text }) => {
+                    self.xml.write_text(&unescape(&text))
+                }
+                _ => {}
+            }
+        }
+    }
+
     /// Render a group. If the group has `clips` set to true, a clip path will
     /// be created.
     fn render_group(&mut self, state: State, group: &GroupItem) {
@@ -457,3 +500,11 @@ impl ttf_parser::OutlineBuilder for SvgPathBuilder {
         write!(&mut self.0, "Z ").unwrap();
     }
 }
+
+/// Resolve the XML entities that MathML markup is escaped with.
+fn unescape(text: &str) -> String {
+    text.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&amp;", "&")
+}
@@ -1255,6 +1255,20 @@ impl<'a> Named<'a> {
         self.0.cast_last_match().unwrap_or_default()
     }
 
+    /// The type annotation of a parameter with a default value: `length` in
+    /// `thickness: length = 3pt`.
+    ///
+    /// This should only be accessed if this `Named` is contained in a `Param`.
+    pub fn annotation(self) -> Option<Expr<'a>> {
+        if !self.0.children().any(|node| node.kind() == SyntaxKind::Eq) {
+            return Option::None;
+        }
+        self.0
+            .children()
+            .skip_while(|node| node.kind() != SyntaxKind::Colon)
+            .find_map(SyntaxNode::cast)
+    }
+
     /// The right-hand side of the pair as a pattern.
     ///
     /// This should only be accessed if this `Named` is contained in a
@@ -1665,6 +1679,14 @@ impl<'a> Closure<'a> {
         self.0.cast_first_match().unwrap_or_default()
     }
 
+    /// The return type annotation: `content` in `(x) -> content => x`.
+    pub fn return_type(self) -> Option<Expr<'a>> {
+        self.0
+            .children()
+            .skip_while(|node| node.kind() != SyntaxKind::ThinArrow)
+            .find_map(SyntaxNode::cast)
+    }
+
     /// The body of the closure.
     pub fn body(self) -> Expr<'a> {
         self.0.cast_last_match().unwrap_or_default()
@@ -1681,12 +1703,26 @@ impl<'a> Params<'a> {
     pub fn children(self) -> impl DoubleEndedIterator<Item = Param<'a>> {
         self.0.children().filter_map(SyntaxNode::cast)
     }
+
+    /// The type annotations of the parameters, in the same order as
+    /// [`children`](Self::children): `int` in `x as int` or `x: int = 1`.
+    pub fn annotations(self) -> impl DoubleEndedIterator<Item = Option<Expr<'a>>> {
+        self.0
+            .children()
+            .filter(|node| node.cast::<Param>().is_some())
+            .map(|node| match node.kind() {
+                SyntaxKind::Typed => node.cast_last_match(),
+                SyntaxKind::Named => node.cast::<Named>().and_then(Named::annotation),
+                _ => Option::None,
+            })
+    }
 }
 
 /// A parameter to a closure.
 #[derive(Debug, Copy, Clone, Hash)]
 pub enum Param<'a> {
-    /// A positional parameter: `x`.
+    /// A positional parameter: `x`, optionally with a type annotation:
+    /// `x as int`.
     Pos(Pattern<'a>),
     /// A named parameter with a default value: `draw: false`.
     Named(Named<'a>),
@@ -1699,6 +1735,7 @@ impl<'a> AstNode<'a> for Param<'a> {
         match node.kind() {
             SyntaxKind::Named => node.cast().map(Self::Named),
             SyntaxKind::Spread => node.cast().map(Self::Spread),
+            SyntaxKind::Typed => node.cast_first_match().map(Self::Pos),
             _ => node.cast().map(Self::Pos),
         }
     }
@@ -158,6 +158,8 @@ pub enum SyntaxKind {
     Dots,
     /// An arrow between a closure's parameters and body: `=>`.
     Arrow,
+    /// An arrow before a function's return type annotation: `->`.
+    ThinArrow,
     /// A root: `√`, `∛` or `∜`.
     Root,
 
@@ -246,6 +248,8 @@ pub enum SyntaxKind {
     Closure,
     /// A closure's parameters: `(x, y)`.
     Params,
+    /// A positional parameter with a type annotation: `x as int`.
+    Typed,
     /// A let binding: `let x = 1`.
     LetBinding,
     /// A set rule: `set text(...)`.
@@ -441,6 +445,7 @@ impl SyntaxKind {
             Self::SlashEq => "divide-assign operator",
             Self::Dots => "dots",
             Self::Arrow => "arrow",
+            Self::ThinArrow => "thin arrow",
             Self::Root => "root",
             Self::Not => "operator `not`",
             Self::And => "operator `and`",
@@ -484,6 +489,7 @@ impl SyntaxKind {
             Self::Spread => "spread",
             Self::Closure => "closure",
             Self::Params => "closure parameters",
+            Self::Typed => "typed parameter",
             Self::LetBinding => "`let` expression",
             Self::SetRule => "`set` expression",
             Self::ShowRule => "`show` expression",
@@ -615,6 +615,7 @@ impl Lexer<'_> {
             '/' if self.s.eat_if('=') => SyntaxKind::SlashEq,
             '.' if self.s.eat_if('.') => SyntaxKind::Dots,
             '=' if self.s.eat_if('>') => SyntaxKind::Arrow,
+            '-' if self.s.eat_if('>') => SyntaxKind::ThinArrow,
 
             '{' => SyntaxKind::LeftBrace,
             '}' => SyntaxKind::RightBrace,
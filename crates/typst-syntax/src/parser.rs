@@ -685,7 +685,10 @@ fn code_expr_prec(p: &mut Parser, atomic: bool, min_prec: usize) {
         code_expr_prec(p, atomic, op.precedence());
         p.wrap(m, SyntaxKind::Unary);
     } else {
-        code_primary(p, atomic);
+        // A destructuring assignment is an assignment and thus must not bind
+        // more tightly than the surrounding expression allows.
+        let assignable = min_prec <= ast::BinOp::Assign.precedence();
+        code_primary(p, atomic, assignable);
     }
 
     loop {
@@ -747,7 +750,7 @@ fn code_expr_prec(p: &mut Parser, atomic: bool, min_prec: usize) {
 /// Parses an primary in a code expression. These are the atoms that unary and
 /// binary operations, functions calls, and field accesses start with / are
 /// composed of.
-fn code_primary(p: &mut Parser, atomic: bool) {
+fn code_primary(p: &mut Parser, atomic: bool, assignable: bool) {
     let m = p.marker();
     match p.current() {
         SyntaxKind::Ident => {
@@ -776,7 +779,7 @@ fn code_primary(p: &mut Parser, atomic: bool) {
 
         SyntaxKind::LeftBrace => code_block(p),
         SyntaxKind::LeftBracket => content_block(p),
-        SyntaxKind::LeftParen => expr_with_paren(p, atomic),
+        SyntaxKind::LeftParen => expr_with_paren(p, atomic, assignable),
         SyntaxKind::RawDelim => raw(p),
         SyntaxKind::Dollar => equation(p),
         SyntaxKind::Let => let_binding(p),
@@ -864,6 +867,7 @@ fn let_binding(p: &mut Parser) {
     if p.eat_if(SyntaxKind::Ident) {
         if p.directly_at(SyntaxKind::LeftParen) {
             params(p);
+            return_type(p);
             closure = true;
         }
     } else {
@@ -1071,11 +1075,14 @@ fn return_stmt(p: &mut Parser) {
 }
 
 /// An expression that starts with a parenthesis.
-fn expr_with_paren(p: &mut Parser, atomic: bool) {
+fn expr_with_paren(p: &mut Parser, atomic: bool, assignable: bool) {
     // If we've seen this position before and have a memoized result, just use
-    // it. See below for more explanation about this memoization.
+    // it. See below for more explanation about this memoization. A memoized
+    // destructuring assignment is only valid where assignments are allowed.
     let start = p.current_start();
-    if let Some((range, end_point)) = p.memo.get(&start).cloned() {
+    if let Some((range, end_point)) = p.memo.get(&start).cloned().filter(|(range, _)| {
+        assignable || p.memo_arena[range.start].kind() != SyntaxKind::DestructAssignment
+    }) {
         // Restore the end point first, so that it doesn't truncate our freshly
         // pushed nodes. If the current length of `p.nodes` doesn't match what
         // we had in the memoized run, this might otherwise happen.
@@ -1113,15 +1120,16 @@ fn expr_with_paren(p: &mut Parser, atomic: bool) {
     // again, we can then just restore this result. In this way, no
     // parenthesized expression is parsed more than twice, leading to a worst
     // case running time of O(2n).
-    if p.at(SyntaxKind::Arrow) {
+    if p.at(SyntaxKind::Arrow) || p.at(SyntaxKind::ThinArrow) {
         p.restore(checkpoint);
         params(p);
+        return_type(p);
         if !p.expect(SyntaxKind::Arrow) {
             return;
         }
         code_expr(p);
         p.wrap(m, SyntaxKind::Closure);
-    } else if p.at(SyntaxKind::Eq) && kind != SyntaxKind::Parenthesized {
+    } else if assignable && p.at(SyntaxKind::Eq) && kind != SyntaxKind::Parenthesized {
         p.restore(checkpoint);
         destructuring_or_parenthesized(p, true, &mut HashSet::new());
         if !p.expect(SyntaxKind::Eq) {
@@ -1343,6 +1351,15 @@ fn params(p: &mut Parser) {
     p.wrap(m, SyntaxKind::Params);
 }
 
+/// Parses a function's return type annotation, if any: `-> content`.
+fn return_type(p: &mut Parser) {
+    if p.eat_if(SyntaxKind::ThinArrow) {
+        // The annotation stays atomic so that the arrow of a closure's body is
+        // not mistaken for that of a nested closure: `(x) -> int => x`.
+        code_expr_prec(p, true, ast::BinOp::Assign.precedence() + 1);
+    }
+}
+
 /// Parses a single parameter in a parameter list.
 fn param<'s>(p: &mut Parser<'s>, seen: &mut HashSet<&'s str>, sink: &mut bool) {
     let m = p.marker();
@@ -1363,6 +1380,13 @@ fn param<'s>(p: &mut Parser<'s>, seen: &mut HashSet<&'s str>, sink: &mut bool) {
     let was_at_pat = p.at_set(set::PATTERN);
     pattern(p, false, seen, Some("parameter"));
 
+    // Parses a type annotation of a positional parameter: `x as int`.
+    if p.eat_if(SyntaxKind::As) {
+        code_expr_prec(p, false, ast::BinOp::Assign.precedence() + 1);
+        p.wrap(m, SyntaxKind::Typed);
+        return;
+    }
+
     // Parses a named parameter: `thickness: 12pt`.
     if p.eat_if(SyntaxKind::Colon) {
         // Recover from bad parameter name.
@@ -1370,7 +1394,13 @@ fn param<'s>(p: &mut Parser<'s>, seen: &mut HashSet<&'s str>, sink: &mut bool) {
             p[m].expected("identifier");
         }
 
-        code_expr(p);
+        // Parses a type annotation followed by a default value:
+        // `thickness: length = 12pt`.
+        code_expr_prec(p, false, ast::BinOp::Assign.precedence() + 1);
+        if p.eat_if(SyntaxKind::Eq) {
+            code_expr(p);
+        }
+
         p.wrap(m, SyntaxKind::Named);
     }
 }
@@ -46,9 +46,23 @@ pub(crate) fn destructure(
     pattern: ast::Pattern,
     value: Value,
 ) -> SourceResult<()> {
+    destructure_with(vm, pattern, value, &mut |vm, ident, value| vm.define(ident, value))
+}
+
+/// Destructures a value into a pattern, handing each bound identifier and its
+/// value to `bind` instead of defining it in the current scope.
+pub(crate) fn destructure_with<F>(
+    vm: &mut Vm,
+    pattern: ast::Pattern,
+    value: Value,
+    bind: &mut F,
+) -> SourceResult<()>
+where
+    F: FnMut(&mut Vm, ast::Ident, Value),
+{
     destructure_impl(vm, pattern, value, &mut |vm, expr, value| match expr {
         ast::Expr::Ident(ident) => {
-            vm.define(ident, value);
+            bind(vm, ident, value);
             Ok(())
         }
         _ => bail!(expr.span(), "cannot assign to this expression"),
@@ -63,7 +77,7 @@ fn destructure_impl<F>(
     f: &mut F,
 ) -> SourceResult<()>
 where
-    F: Fn(&mut Vm, ast::Expr, Value) -> SourceResult<()>,
+    F: FnMut(&mut Vm, ast::Expr, Value) -> SourceResult<()>,
 {
     match pattern {
         ast::Pattern::Normal(expr) => f(vm, expr, value)?,
@@ -87,7 +101,7 @@ fn destructure_array<F>(
     f: &mut F,
 ) -> SourceResult<()>
 where
-    F: Fn(&mut Vm, ast::Expr, Value) -> SourceResult<()>,
+    F: FnMut(&mut Vm, ast::Expr, Value) -> SourceResult<()>,
 {
     let len = value.as_slice().len();
     let mut i = 0;
@@ -153,7 +167,7 @@ fn destructure_dict<F>(
     f: &mut F,
 ) -> SourceResult<()>
 where
-    F: Fn(&mut Vm, ast::Expr, Value) -> SourceResult<()>,
+    F: FnMut(&mut Vm, ast::Expr, Value) -> SourceResult<()>,
 {
     let mut sink = None;
     let mut used = HashSet::new();
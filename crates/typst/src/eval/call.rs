@@ -6,10 +6,11 @@ use crate::diag::{
     Trace, Tracepoint,
 };
 use crate::engine::{Engine, Sink, Traced};
-use crate::eval::{Access, Eval, FlowEvent, Route, Vm};
+use crate::eval::{Access, Eval, FlowEvent, Frame, Route, Vm};
 use crate::foundations::{
-    call_method_mut, is_mutating_method, Arg, Args, Bytes, Capturer, Closure, Content,
-    Context, Func, IntoValue, NativeElement, Scope, Scopes, Value,
+    call_method_mut, is_mutating_method, Arg, Args, Capturer, CastInfo, Closure, Content,
+    Context, Func, IntoValue, NativeElement, ParamInfo, Scope, Scopes, TypeAnnotation,
+    Value,
 };
 use crate::introspection::Introspector;
 use crate::math::LrElem;
@@ -54,19 +55,29 @@ impl Eval for ast::FuncCall<'_> {
             .map_err(|err| hint_if_shadowed_std(vm, &self.callee(), err))
             .at(callee_span)?;
 
-        let point = || Tracepoint::Call(func.name().map(Into::into));
-        let f = || {
-            func.call(&mut vm.engine, vm.context, args)
-                .trace(vm.world(), point, span)
-        };
+        call_func(vm, &func, args, span)
+    }
+}
+
+/// Call a function from a call site, recording a tracepoint for errors.
+pub(crate) fn call_func(
+    vm: &mut Vm,
+    func: &Func,
+    args: Args,
+    span: Span,
+) -> SourceResult<Value> {
+    let point = || Tracepoint::Call(func.name().map(Into::into));
+    let f = || {
+        func.call(&mut vm.engine, vm.context, args)
+            .trace(vm.world(), point, span)
+    };
 
-        // Stacker is broken on WASM.
-        #[cfg(target_arch = "wasm32")]
-        return f();
+    // Stacker is broken on WASM.
+    #[cfg(target_arch = "wasm32")]
+    return f();
 
-        #[cfg(not(target_arch = "wasm32"))]
-        stacker::maybe_grow(32 * 1024, 2 * 1024 * 1024, f)
-    }
+    #[cfg(not(target_arch = "wasm32"))]
+    stacker::maybe_grow(32 * 1024, 2 * 1024 * 1024, f)
 }
 
 impl Eval for ast::Args<'_> {
@@ -125,14 +136,65 @@ impl Eval for ast::Closure<'_> {
     type Output = Value;
 
     fn eval(self, vm: &mut Vm) -> SourceResult<Self::Output> {
-        // Evaluate default values of named parameters.
+        // Evaluate type annotations and default values of named parameters.
         let mut defaults = Vec::new();
-        for param in self.params().children() {
-            if let ast::Param::Named(named) = param {
-                defaults.push(named.expr().eval(vm)?);
+        let mut annotations = Vec::new();
+        let mut params = Vec::new();
+        for (param, annotation) in
+            self.params().children().zip(self.params().annotations())
+        {
+            let annotation =
+                annotation.map(|expr| eval_annotation(vm, expr)).transpose()?;
+            let mut info = ParamInfo {
+                name: EcoString::new(),
+                docs: "",
+                input: CastInfo::Any,
+                default: None,
+                positional: false,
+                named: false,
+                variadic: false,
+                required: false,
+                settable: false,
+            };
+
+            match param {
+                ast::Param::Pos(pattern) => {
+                    // For destructuring patterns, tooling shows the pattern's
+                    // source text.
+                    info.name = pattern.to_untyped().clone().into_text();
+                    info.positional = true;
+                    info.required = true;
+                }
+                ast::Param::Named(named) => {
+                    info.name = named.name().get().clone();
+                    info.named = true;
+                    let mut default = named.expr().eval(vm)?;
+                    if let Some(annotation) = &annotation {
+                        default = annotation.check(default).at(named.expr().span())?;
+                    }
+                    defaults.push(default);
+                }
+                ast::Param::Spread(spread) => {
+                    info.name = spread
+                        .sink_ident()
+                        .map_or_else(|| "..".into(), |ident| ident.get().clone());
+                    info.positional = true;
+                    info.named = true;
+                    info.variadic = true;
+                }
+            }
+
+            if let Some(annotation) = &annotation {
+                info.input = annotation.info();
             }
+
+            annotations.push(annotation);
+            params.push(info);
         }
 
+        let returns =
+            self.return_type().map(|expr| eval_annotation(vm, expr)).transpose()?;
+
         // Collect captured variables.
         let captured = {
             let mut visitor = CapturesVisitor::new(Some(&vm.scopes), Capturer::Function);
@@ -145,17 +207,25 @@ impl Eval for ast::Closure<'_> {
             node: self.to_untyped().clone(),
             defaults,
             captured,
-            num_pos_params: self
-                .params()
-                .children()
-                .filter(|p| matches!(p, ast::Param::Pos(_)))
+            num_pos_params: params
+                .iter()
+                .filter(|info| info.positional && !info.variadic)
                 .count(),
+            annotations,
+            returns,
+            params,
+            compiled: Default::default(),
         };
 
         Ok(Value::Func(Func::from(closure).spanned(self.params().span())))
     }
 }
 
+/// Evaluate a type annotation of a closure's parameter or return value.
+fn eval_annotation(vm: &mut Vm, expr: ast::Expr) -> SourceResult<TypeAnnotation> {
+    expr.eval(vm)?.cast::<TypeAnnotation>().at(expr.span())
+}
+
 /// Call the function in the context with the arguments.
 #[comemo::memoize]
 #[allow(clippy::too_many_arguments)]
@@ -192,9 +262,20 @@ pub(crate) fn call_closure(
     // Prepare VM.
     let mut vm = Vm::new(engine, context, scopes, body.span());
 
+    // Run the compiled body unless a span is inspected, the tree-walking
+    // evaluator takes care of tracing values.
+    let mut frame = match vm.inspected {
+        None => closure.compiled.get(&closure.node).map(Frame::new),
+        Some(_) => None,
+    };
+    let mut define = |vm: &mut Vm, ident: ast::Ident, value: Value| match &mut frame {
+        Some(frame) => frame.bind(ident, value),
+        None => vm.define(ident, value),
+    };
+
     // Provide the closure itself for recursive calls.
     if let Some(name) = name {
-        vm.define(name, Value::Func(func.clone()));
+        define(&mut vm, name, Value::Func(func.clone()));
     }
 
     let num_pos_args = args.to_pos().len();
@@ -203,18 +284,24 @@ pub(crate) fn call_closure(
     let mut sink = None;
     let mut sink_pos_values = None;
     let mut defaults = closure.defaults.iter();
+    let mut annotations = closure.annotations.iter();
     for p in params.children() {
+        let annotation = annotations.next().and_then(Option::as_ref);
+        let check = |Spanned { v, span }: Spanned<Value>| match annotation {
+            Some(annotation) => annotation.check(v).at(span),
+            None => Ok(v),
+        };
+
         match p {
             ast::Param::Pos(pattern) => match pattern {
                 ast::Pattern::Normal(ast::Expr::Ident(ident)) => {
-                    vm.define(ident, args.expect::<Value>(&ident)?)
+                    let value = check(args.expect::<Spanned<Value>>(&ident)?)?;
+                    define(&mut vm, ident, value)
                 }
                 pattern => {
-                    crate::eval::destructure(
-                        &mut vm,
-                        pattern,
-                        args.expect::<Value>("pattern parameter")?,
-                    )?;
+                    let value =
+                        check(args.expect::<Spanned<Value>>("pattern parameter")?)?;
+                    crate::eval::destructure_with(&mut vm, pattern, value, &mut define)?;
                 }
             },
             ast::Param::Spread(spread) => {
@@ -226,9 +313,11 @@ pub(crate) fn call_closure(
             ast::Param::Named(named) => {
                 let name = named.name();
                 let default = defaults.next().unwrap();
-                let value =
-                    args.named::<Value>(&name)?.unwrap_or_else(|| default.clone());
-                vm.define(name, value);
+                let value = match args.named::<Spanned<Value>>(&name)? {
+                    Some(value) => check(value)?,
+                    None => default.clone(),
+                };
+                define(&mut vm, name, value);
             }
         }
     }
@@ -240,7 +329,7 @@ pub(crate) fn call_closure(
             if let Some(sink_pos_values) = sink_pos_values {
                 remaining_args.items.extend(sink_pos_values);
             }
-            vm.define(sink_name, remaining_args);
+            define(&mut vm, sink_name, remaining_args.into_value());
         }
     }
 
@@ -248,21 +337,27 @@ pub(crate) fn call_closure(
     args.finish()?;
 
     // Handle control flow.
-    let output = body.eval(&mut vm)?;
-    match vm.flow {
-        Some(FlowEvent::Return(_, Some(explicit))) => return Ok(explicit),
-        Some(FlowEvent::Return(_, None)) => {}
+    let output = match frame {
+        Some(frame) => frame.run(&mut vm)?,
+        None => body.eval(&mut vm)?,
+    };
+    let (output, span) = match vm.flow {
+        Some(FlowEvent::Return(span, Some(explicit))) => (explicit, span),
+        Some(FlowEvent::Return(_, None)) | None => (output, body.span()),
         Some(flow) => bail!(flow.forbidden()),
-        None => {}
-    }
+    };
 
-    Ok(output)
+    // Check the return value against the return type annotation.
+    match &closure.returns {
+        Some(returns) => returns.check(output).at(span),
+        None => Ok(output),
+    }
 }
 
 /// This used only as the return value of `eval_field_call`.
 /// - `Normal` means that we have a function to call and the arguments to call it with.
 /// - `Resolved` means that we have already resolved the call and have the value.
-enum FieldCall {
+pub(crate) enum FieldCall {
     Normal(Value, Args),
     Resolved(Value),
 }
@@ -288,7 +383,7 @@ fn eval_field_call(
     vm: &mut Vm,
 ) -> SourceResult<FieldCall> {
     // Evaluate the field-call's target and overall arguments.
-    let (target, mut args) = if is_mutating_method(&field) {
+    let (target, args) = if is_mutating_method(&field) {
         // If `field` looks like a mutating method, we evaluate the arguments first,
         // because `target_expr.access(vm)` mutably borrows the `vm`, so that we can't
         // evaluate the arguments after it.
@@ -298,12 +393,12 @@ fn eval_field_call(
         // a temporary which we disallow mutation on (returning an error).
         // Theoretically this could be observed if a method matching `is_mutating_method`
         // was added to some type in the future and we didn't update this function.
+        let world = vm.world();
         match target_expr.access(vm)? {
             // Only arrays and dictionaries have mutable methods.
             target @ (Value::Array(_) | Value::Dict(_)) => {
-                let value = call_method_mut(target, &field, args, span);
-                let point = || Tracepoint::Call(Some(field.get().clone()));
-                return Ok(FieldCall::Resolved(value.trace(vm.world(), point, span)?));
+                let value = call_mutating_method(world, target, field, args, span)?;
+                return Ok(FieldCall::Resolved(value));
             }
             target => (target.clone(), args),
         }
@@ -313,14 +408,38 @@ fn eval_field_call(
         (target, args)
     };
 
+    resolve_field_call(&mut vm.engine, target, target_expr.span(), field, args, span)
+}
+
+/// Call a mutating method on an array or dictionary in place.
+pub(crate) fn call_mutating_method(
+    world: Tracked<dyn World + '_>,
+    target: &mut Value,
+    field: Ident,
+    args: Args,
+    span: Span,
+) -> SourceResult<Value> {
+    let value = call_method_mut(target, &field, args, span);
+    let point = || Tracepoint::Call(Some(field.get().clone()));
+    value.trace(world, point, span)
+}
+
+/// Resolve a field call on an already evaluated target.
+pub(crate) fn resolve_field_call(
+    engine: &mut Engine,
+    target: Value,
+    target_span: Span,
+    field: Ident,
+    mut args: Args,
+    span: Span,
+) -> SourceResult<FieldCall> {
     if let Value::Plugin(plugin) = &target {
-        // Call plugins by converting args to bytes.
-        let bytes = args.all::<Bytes>()?;
-        args.finish()?;
-        let value = plugin.call(&field, bytes).at(span)?.into_value();
+        // Call plugins, which convert the arguments according to their
+        // protocol.
+        let value = plugin.call(engine, &field, args, span)?;
         Ok(FieldCall::Resolved(value))
     } else if let Some(callee) = target.ty().scope().get(&field) {
-        args.insert(0, target_expr.span(), target);
+        args.insert(0, target_span, target);
         Ok(FieldCall::Normal(callee.clone(), args))
     } else if matches!(
         target,
@@ -393,7 +512,7 @@ fn wrap_args_in_math(
 }
 
 /// Provide a hint if the callee is a shadowed standard library function.
-fn hint_if_shadowed_std(
+pub(crate) fn hint_if_shadowed_std(
     vm: &mut Vm,
     callee: &ast::Expr,
     mut err: HintedString,
@@ -465,12 +584,20 @@ impl<'a> CapturesVisitor<'a> {
             // body is evaluated. Care must be taken so that the default values
             // of named parameters cannot access previous parameter bindings.
             Some(ast::Expr::Closure(expr)) => {
+                for annotation in expr.params().annotations().flatten() {
+                    self.visit(annotation.to_untyped());
+                }
+
                 for param in expr.params().children() {
                     if let ast::Param::Named(named) = param {
                         self.visit(named.expr().to_untyped());
                     }
                 }
 
+                if let Some(returns) = expr.return_type() {
+                    self.visit(returns.to_untyped());
+                }
+
                 self.internal.enter();
                 if let Some(name) = expr.name() {
                     self.bind(name);
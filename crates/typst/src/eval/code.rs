@@ -303,33 +303,39 @@ impl Eval for ast::FieldAccess<'_> {
 
     fn eval(self, vm: &mut Vm) -> SourceResult<Self::Output> {
         let value = self.target().eval(vm)?;
-        let field = self.field();
-
-        let err = match value.field(&field).at(field.span()) {
-            Ok(value) => return Ok(value),
-            Err(err) => err,
-        };
+        access_field(vm, value, self.field())
+    }
+}
 
-        // Check whether this is a get rule field access.
-        if_chain::if_chain! {
-            if let Value::Func(func) = &value;
-            if let Some(element) = func.element();
-            if let Some(id) = element.field_id(&field);
-            let styles = vm.context.styles().at(field.span());
-            if let Ok(value) = element.field_from_styles(
-                id,
-                styles.as_ref().map(|&s| s).unwrap_or_default(),
-            );
-            then {
-                // Only validate the context once we know that this is indeed
-                // a field from the style chain.
-                let _ = styles?;
-                return Ok(value);
-            }
+/// Access a field on an already evaluated value.
+pub(crate) fn access_field(
+    vm: &mut Vm,
+    value: Value,
+    field: ast::Ident,
+) -> SourceResult<Value> {
+    let err = match value.field(&field).at(field.span()) {
+        Ok(value) => return Ok(value),
+        Err(err) => err,
+    };
+
+    // Check whether this is a get rule field access.
+    if_chain::if_chain! {
+        if let Value::Func(func) = &value;
+        if let Some(id) = func.field_id(&field);
+        let styles = vm.context.styles().at(field.span());
+        if let Ok(value) = func.field_from_styles(
+            id,
+            styles.as_ref().map(|&s| s).unwrap_or_default(),
+        );
+        then {
+            // Only validate the context once we know that this is indeed
+            // a field from the style chain.
+            let _ = styles?;
+            return Ok(value);
         }
-
-        Err(err)
     }
+
+    Err(err)
 }
 
 impl Eval for ast::Contextual<'_> {
@@ -351,6 +357,10 @@ impl Eval for ast::Contextual<'_> {
             defaults: vec![],
             captured,
             num_pos_params: 0,
+            annotations: vec![],
+            returns: None,
+            params: vec![],
+            compiled: Default::default(),
         };
 
         let func = Func::from(closure).spanned(body.span());
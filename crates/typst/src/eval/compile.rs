@@ -0,0 +1,875 @@
+use std::fmt::{self, Debug, Formatter};
+use std::hash::{Hash, Hasher};
+use std::sync::OnceLock;
+
+use ecow::EcoString;
+
+use crate::eval::{can_diverge, is_invariant};
+use crate::foundations::{is_mutating_method, Value};
+use crate::syntax::ast::{self, AstNode};
+use crate::syntax::{Span, SyntaxKind, SyntaxNode};
+use crate::text::{SpaceElem, TextElem};
+
+/// A closure or module body compiled to bytecode.
+///
+/// Bindings in nested scopes are resolved to numbered slots at compile time,
+/// so that reading and writing them doesn't go through scope lookups. Any
+/// expression that the compiler doesn't handle natively (markup, math, nested
+/// closures, ...) is kept as a syntax node and evaluated with the tree-walking
+/// evaluator. The locals it mentions are temporarily moved into a scope for
+/// that.
+#[derive(Debug)]
+pub(crate) struct Program {
+    /// The instructions.
+    pub(super) ops: Vec<Op>,
+    /// Constant values referenced by instructions.
+    pub(super) consts: Vec<Value>,
+    /// Syntax nodes referenced by instructions.
+    pub(super) nodes: Vec<SyntaxNode>,
+    /// Names of variables and fields referenced by instructions.
+    pub(super) names: Vec<EcoString>,
+    /// Lists of locals referenced by instructions.
+    pub(super) lists: Vec<Vec<Local>>,
+    /// The slots of the parameters, identified by their binding's span.
+    pub(super) params: Vec<(Span, u32)>,
+    /// The number of local slots.
+    pub(super) slots: usize,
+}
+
+/// A local binding resolved to a slot.
+#[derive(Debug, Clone)]
+pub(super) struct Local {
+    /// The name of the binding.
+    pub name: EcoString,
+    /// The slot holding the binding's value.
+    pub slot: u32,
+    /// The span of the binding's identifier.
+    pub span: Span,
+}
+
+/// A single bytecode instruction.
+///
+/// Instructions operate on a stack of values. Indices refer into the tables
+/// of the [`Program`], jump targets are instruction indices.
+#[derive(Debug, Copy, Clone)]
+pub(super) enum Op {
+    /// Push `none`.
+    None,
+    /// Push a constant.
+    Const(u32),
+    /// Push the value of a local slot.
+    Load(u32, Span),
+    /// Push the value of a variable from the scopes by name.
+    LoadName(u32, Span),
+    /// Pop a value into a local slot.
+    Store(u32),
+    /// Pop a value and define it in the current scope. Refers to an
+    /// identifier node.
+    Define(u32),
+    /// Pop a value and destructure it into the slots of a list. Refers to a
+    /// pattern node.
+    Destructure(u32, u32),
+    /// Pop a value and destructure it into the current scope. Refers to a
+    /// pattern node.
+    DestructureDefine(u32),
+    /// Discard the topmost value.
+    Pop,
+    /// Attach a span to the topmost value.
+    Spanned(Span),
+    /// Apply a unary operator to the topmost value.
+    Unary(ast::UnOp, Span),
+    /// Apply a binary operator to the two topmost values.
+    Binary(ast::BinOp, Span),
+    /// Jump if the topmost value decides the boolean operator on its own,
+    /// keeping the value.
+    ShortCircuit(ast::BinOp, u32),
+    /// Pop a value and assign it to a local slot with an operator.
+    Assign(ast::BinOp, u32, Span),
+    /// Push an empty array with a capacity.
+    Array(u32),
+    /// Pop a value and push it onto the array below.
+    ArrayPush,
+    /// Pop a value and spread it into the array below.
+    ArraySpread(Span),
+    /// Push an empty dictionary.
+    Dict,
+    /// Pop a value and insert it into the dictionary below under a name.
+    DictInsert(u32),
+    /// Pop a value and spread it into the dictionary below.
+    DictSpread(Span),
+    /// Push empty arguments.
+    Args,
+    /// Pop a value and add it as a positional argument. Has the spans of the
+    /// argument and its expression.
+    ArgPos(Span, Span),
+    /// Pop a value and add it as a named argument.
+    ArgNamed(u32, Span, Span),
+    /// Pop a value and spread it into the arguments.
+    ArgSpread(Span),
+    /// Pop a value and access a field on it. Refers to a field access node.
+    Field(u32),
+    /// Check that the maximum call depth is not exceeded.
+    CheckDepth(Span),
+    /// Pop arguments and a callee and call it. Refers to the callee's node
+    /// and knows whether the callee is a local.
+    Call(u32, Span, bool),
+    /// Pop arguments and a target and call a method or field on the target.
+    /// Refers to the callee's field access node.
+    FieldCall(u32, Span),
+    /// Pop arguments and call a mutating method on a local slot. Refers to
+    /// the callee's field access node.
+    MutCall(u32, u32, Span),
+    /// Jump unconditionally.
+    Jump(u32),
+    /// Pop a condition and jump if it is false.
+    JumpIfFalse(u32, Span),
+    /// Jump if a control flow event is happening.
+    JumpIfFlow(u32),
+    /// Pop a value and join it into the value below.
+    Join(Span),
+    /// Set the control flow event aside while a block runs.
+    SaveFlow,
+    /// Restore the control flow event set aside by the last `SaveFlow`.
+    RestoreFlow,
+    /// Start breaking out of a loop.
+    Break(Span),
+    /// Start continuing a loop.
+    Continue(Span),
+    /// Start returning from the function, with an explicit value popped
+    /// from the stack if set.
+    Return(Span, bool),
+    /// Handle control flow at the end of a loop iteration, jumping to the
+    /// loop's end to leave it.
+    LoopFlow(u32),
+    /// Count an iteration of a while loop in a slot. Refers to the loop's
+    /// node and knows whether the loop's condition is invariant.
+    WhileCheck(u32, u32, bool),
+    /// Pop an iterable and start iterating over it. Refers to a for loop's
+    /// node.
+    IterStart(u32),
+    /// Push the next item of the current iteration or jump when it is
+    /// exhausted.
+    IterNext(u32),
+    /// Stop the current iteration.
+    IterEnd,
+    /// Evaluate a syntax node with the tree-walking evaluator, moving the
+    /// locals of the list into a scope for it.
+    Eval(u32, u32),
+    /// Evaluate a set or show rule like `Eval` and keep its styling for the
+    /// rest of the block. Jumps if a control flow event happens.
+    Style(u32, u32, u32),
+    /// Pop a value and apply the last styling to it.
+    ApplyStyle,
+    /// Start a sequence of markup.
+    SeqStart,
+    /// Pop a value and add it to the current sequence as markup.
+    SeqPush(Span),
+    /// Pop content and add it to the current sequence as is.
+    SeqAppend,
+    /// Finish the current sequence and push it as content.
+    SeqEnd,
+}
+
+/// A lazily compiled program for a closure.
+///
+/// This is excluded from the closure's hash since it is fully determined by
+/// the closure's syntax node.
+#[derive(Default)]
+pub(crate) struct Compiled(OnceLock<Option<Program>>);
+
+impl Compiled {
+    /// Get the program for the closure, compiling it on first use.
+    pub fn get(&self, node: &SyntaxNode) -> Option<&Program> {
+        self.0.get_or_init(|| compile_closure(node)).as_ref()
+    }
+}
+
+impl Debug for Compiled {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.pad("Compiled(..)")
+    }
+}
+
+impl Hash for Compiled {
+    fn hash<H: Hasher>(&self, _: &mut H) {}
+}
+
+/// Compile the body of a closure.
+///
+/// The node must be castable to `ast::Closure` or `ast::Expr`, like the node
+/// of a [`Closure`](crate::foundations::Closure). Returns `None` if the body
+/// can't be compiled.
+fn compile_closure(node: &SyntaxNode) -> Option<Program> {
+    let mut compiler = Compiler::new();
+    compiler.scopes.push(vec![]);
+
+    let body = match node.cast::<ast::Closure>() {
+        Some(closure) => {
+            // Parameters are bound in the same order as in `call_closure`,
+            // so that later ones shadow earlier ones with the same name.
+            let mut sink = None;
+            compiler.param(closure.name());
+            for param in closure.params().children() {
+                match param {
+                    ast::Param::Pos(pattern) => {
+                        pattern.bindings().into_iter().for_each(|ident| {
+                            compiler.param(Some(ident));
+                        });
+                    }
+                    ast::Param::Named(named) => compiler.param(Some(named.name())),
+                    ast::Param::Spread(spread) => sink = spread.sink_ident(),
+                }
+            }
+            compiler.param(sink);
+            closure.body()
+        }
+        None => node.cast::<ast::Expr>()?,
+    };
+
+    compiler.expr(body).ok()?;
+    Some(compiler.finish())
+}
+
+/// Compile the markup of a module.
+///
+/// Top-level bindings are defined in the module's scope by name, only nested
+/// scopes use slots.
+pub(crate) fn compile_module(markup: ast::Markup) -> Option<Program> {
+    let mut compiler = Compiler::new();
+    compiler.markup(&mut markup.exprs()).ok()?;
+    Some(compiler.finish())
+}
+
+/// Signals that an expression can't be compiled.
+struct Unsupported;
+
+/// The result of compiling an expression.
+type CompileResult = Result<(), Unsupported>;
+
+/// Compiles syntax into a program.
+struct Compiler {
+    /// The program under construction.
+    program: Program,
+    /// The stack of nested scopes with slot-resolved bindings. When empty,
+    /// bindings are defined by name.
+    scopes: Vec<Vec<Local>>,
+}
+
+/// The compiler state to return to when compiling an expression fails.
+struct Mark {
+    ops: usize,
+    scopes: usize,
+    locals: usize,
+}
+
+impl Compiler {
+    /// Create a new compiler.
+    fn new() -> Self {
+        Self {
+            program: Program {
+                ops: vec![],
+                consts: vec![],
+                nodes: vec![],
+                names: vec![],
+                lists: vec![],
+                params: vec![],
+                slots: 0,
+            },
+            scopes: vec![],
+        }
+    }
+
+    /// Finish compilation.
+    fn finish(self) -> Program {
+        self.program
+    }
+
+    /// Compile an expression, falling back to the tree-walking evaluator for
+    /// anything not compiled natively.
+    fn expr(&mut self, expr: ast::Expr) -> CompileResult {
+        let mark = self.mark();
+        match self.expr_inner(expr) {
+            // Bindings and imports in nested scopes must stay visible to
+            // later expressions, so they can't be evaluated in isolation.
+            Err(Unsupported)
+                if !self.scopes.is_empty()
+                    && matches!(expr, ast::Expr::Let(_) | ast::Expr::Import(_)) =>
+            {
+                Err(Unsupported)
+            }
+            Err(Unsupported) => {
+                self.reset(mark);
+                self.fallback(expr);
+                Ok(())
+            }
+            Ok(()) => Ok(()),
+        }
+    }
+
+    /// Compile an expression natively.
+    fn expr_inner(&mut self, expr: ast::Expr) -> CompileResult {
+        let span = expr.span();
+        match expr {
+            ast::Expr::None(_) => {
+                self.emit(Op::None);
+            }
+            ast::Expr::Text(text) => {
+                self.constant(Value::Content(TextElem::packed(text.get().clone())))
+            }
+            ast::Expr::Space(_) => {
+                self.constant(Value::Content(SpaceElem::shared().clone()))
+            }
+            ast::Expr::Auto(_) => self.constant(Value::Auto),
+            ast::Expr::Bool(v) => self.constant(Value::Bool(v.get())),
+            ast::Expr::Int(v) => self.constant(Value::Int(v.get())),
+            ast::Expr::Float(v) => self.constant(Value::Float(v.get())),
+            ast::Expr::Numeric(v) => self.constant(Value::numeric(v.get())),
+            ast::Expr::Str(v) => self.constant(Value::Str(v.get().into())),
+            ast::Expr::Ident(ident) => self.load(ident),
+            ast::Expr::Code(block) => {
+                self.scopes.push(vec![]);
+                self.code(&mut block.body().exprs())?;
+                self.scopes.pop();
+                self.emit(Op::Spanned(span));
+            }
+            ast::Expr::Content(block) => {
+                self.scopes.push(vec![]);
+                self.markup(&mut block.body().exprs())?;
+                self.scopes.pop();
+                self.emit(Op::Spanned(span));
+            }
+            ast::Expr::Parenthesized(v) => self.expr(v.expr())?,
+            ast::Expr::Array(array) => self.array(array)?,
+            ast::Expr::Dict(dict) => self.dict(dict)?,
+            ast::Expr::Unary(unary) => {
+                self.expr(unary.expr())?;
+                self.emit(Op::Unary(unary.op(), span));
+            }
+            ast::Expr::Binary(binary) => self.binary(binary)?,
+            ast::Expr::FieldAccess(access) => {
+                self.expr(access.target())?;
+                let node = self.node(access.to_untyped());
+                self.emit(Op::Field(node));
+            }
+            ast::Expr::FuncCall(call) => self.call(call)?,
+            ast::Expr::Let(binding) => self.binding(binding)?,
+            ast::Expr::Conditional(conditional) => {
+                let condition = conditional.condition();
+                self.expr(condition)?;
+                let jump = self.emit(Op::JumpIfFalse(0, condition.span()));
+                self.expr(conditional.if_body())?;
+                let exit = self.emit(Op::Jump(0));
+                self.patch(jump);
+                match conditional.else_body() {
+                    Some(else_body) => self.expr(else_body)?,
+                    None => {
+                        self.emit(Op::None);
+                    }
+                }
+                self.patch(exit);
+                self.emit(Op::Spanned(span));
+            }
+            ast::Expr::While(while_loop) => self.while_loop(while_loop)?,
+            ast::Expr::For(for_loop) => self.for_loop(for_loop)?,
+            ast::Expr::Break(_) => {
+                self.emit(Op::Break(span));
+            }
+            ast::Expr::Continue(_) => {
+                self.emit(Op::Continue(span));
+            }
+            ast::Expr::Return(ret) => {
+                let body = ret.body();
+                if let Some(body) = body {
+                    self.expr(body)?;
+                }
+                self.emit(Op::Return(span, body.is_some()));
+            }
+            _ => return Err(Unsupported),
+        }
+        Ok(())
+    }
+
+    /// Compile a stream of code, joining the values of its expressions.
+    fn code<'a>(
+        &mut self,
+        exprs: &mut impl Iterator<Item = ast::Expr<'a>>,
+    ) -> CompileResult {
+        self.emit(Op::SaveFlow);
+        self.code_tail(exprs)?;
+        self.emit(Op::RestoreFlow);
+        Ok(())
+    }
+
+    /// Compile the rest of a stream of code.
+    fn code_tail<'a>(
+        &mut self,
+        exprs: &mut impl Iterator<Item = ast::Expr<'a>>,
+    ) -> CompileResult {
+        self.emit(Op::None);
+        let mut exits = vec![];
+        while let Some(expr) = exprs.next() {
+            if matches!(expr, ast::Expr::Set(_) | ast::Expr::Show(_)) {
+                exits.push(self.style(expr));
+                self.code_tail(exprs)?;
+                self.emit(Op::ApplyStyle);
+            } else {
+                self.expr(expr)?;
+            }
+            self.emit(Op::Join(expr.span()));
+            exits.push(self.emit(Op::JumpIfFlow(0)));
+        }
+        exits.into_iter().for_each(|exit| self.patch(exit));
+        Ok(())
+    }
+
+    /// Compile a stream of markup into a sequence.
+    fn markup<'a>(
+        &mut self,
+        exprs: &mut impl Iterator<Item = ast::Expr<'a>>,
+    ) -> CompileResult {
+        self.emit(Op::SaveFlow);
+        self.markup_tail(exprs)?;
+        self.emit(Op::RestoreFlow);
+        Ok(())
+    }
+
+    /// Compile the rest of a stream of markup.
+    fn markup_tail<'a>(
+        &mut self,
+        exprs: &mut impl Iterator<Item = ast::Expr<'a>>,
+    ) -> CompileResult {
+        self.emit(Op::SeqStart);
+        let mut exits = vec![];
+        while let Some(expr) = exprs.next() {
+            if matches!(expr, ast::Expr::Set(_) | ast::Expr::Show(_)) {
+                exits.push(self.style(expr));
+                self.markup_tail(exprs)?;
+                self.emit(Op::ApplyStyle);
+                self.emit(Op::SeqAppend);
+            } else {
+                self.expr(expr)?;
+                self.emit(Op::SeqPush(expr.span()));
+            }
+            exits.push(self.emit(Op::JumpIfFlow(0)));
+        }
+        exits.into_iter().for_each(|exit| self.patch(exit));
+        self.emit(Op::SeqEnd);
+        Ok(())
+    }
+
+    /// Compile a set or show rule, returning the instruction to patch with
+    /// the block's exit.
+    fn style(&mut self, expr: ast::Expr) -> usize {
+        let node = self.node(expr.to_untyped());
+        let list = self.referenced(expr.to_untyped());
+        self.emit(Op::Style(node, list, 0))
+    }
+
+    /// Compile an array literal.
+    fn array(&mut self, array: ast::Array) -> CompileResult {
+        let items = array.items();
+        self.emit(Op::Array(items.size_hint().0 as u32));
+        for item in items {
+            match item {
+                ast::ArrayItem::Pos(expr) => {
+                    self.expr(expr)?;
+                    self.emit(Op::ArrayPush);
+                }
+                ast::ArrayItem::Spread(spread) => {
+                    self.expr(spread.expr())?;
+                    self.emit(Op::ArraySpread(spread.span()));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Compile a dictionary literal.
+    fn dict(&mut self, dict: ast::Dict) -> CompileResult {
+        // Keyed items collect errors for invalid keys across the whole
+        // dictionary, leave them to the evaluator.
+        if dict.items().any(|item| matches!(item, ast::DictItem::Keyed(_))) {
+            return Err(Unsupported);
+        }
+
+        self.emit(Op::Dict);
+        for item in dict.items() {
+            match item {
+                ast::DictItem::Named(named) => {
+                    self.expr(named.expr())?;
+                    let name = self.name(named.name().get());
+                    self.emit(Op::DictInsert(name));
+                }
+                ast::DictItem::Spread(spread) => {
+                    self.expr(spread.expr())?;
+                    self.emit(Op::DictSpread(spread.span()));
+                }
+                ast::DictItem::Keyed(_) => unreachable!(),
+            }
+        }
+        Ok(())
+    }
+
+    /// Compile a binary operation.
+    fn binary(&mut self, binary: ast::Binary) -> CompileResult {
+        let op = binary.op();
+        let span = binary.span();
+
+        if crate::eval::ops::is_assignment(op) {
+            // Only assignments to locals are compiled, everything else needs
+            // mutable access through the scopes.
+            let ast::Expr::Ident(ident) = binary.lhs() else { return Err(Unsupported) };
+            let Some(local) = self.resolve(&ident) else { return Err(Unsupported) };
+            self.expr(binary.rhs())?;
+            self.emit(Op::Assign(op, local.slot, span));
+            self.emit(Op::None);
+            return Ok(());
+        }
+
+        self.expr(binary.lhs())?;
+        let jump = matches!(op, ast::BinOp::And | ast::BinOp::Or)
+            .then(|| self.emit(Op::ShortCircuit(op, 0)));
+        self.expr(binary.rhs())?;
+        self.emit(Op::Binary(op, span));
+        if let Some(jump) = jump {
+            self.patch(jump);
+        }
+        Ok(())
+    }
+
+    /// Compile a function call.
+    fn call(&mut self, call: ast::FuncCall) -> CompileResult {
+        let span = call.span();
+        let callee = call.callee();
+        self.emit(Op::CheckDepth(span));
+
+        match callee {
+            ast::Expr::FieldAccess(access) if is_mutating_method(&access.field()) => {
+                // Mutating methods are only compiled on locals, other targets
+                // need mutable access through the scopes.
+                let ast::Expr::Ident(ident) = access.target() else {
+                    return Err(Unsupported);
+                };
+                let Some(local) = self.resolve(&ident) else { return Err(Unsupported) };
+                self.args(call.args())?;
+                let node = self.node(access.to_untyped());
+                self.emit(Op::MutCall(local.slot, node, span));
+            }
+            ast::Expr::FieldAccess(access) => {
+                self.expr(access.target())?;
+                self.args(call.args())?;
+                let node = self.node(access.to_untyped());
+                self.emit(Op::FieldCall(node, span));
+            }
+            ast::Expr::MathIdent(_) => return Err(Unsupported),
+            _ => {
+                let local = match callee {
+                    ast::Expr::Ident(ident) => self.resolve(&ident).is_some(),
+                    _ => false,
+                };
+                self.expr(callee)?;
+                self.args(call.args())?;
+                let node = self.node(callee.to_untyped());
+                self.emit(Op::Call(node, span, local));
+            }
+        }
+        Ok(())
+    }
+
+    /// Compile the arguments of a call.
+    fn args(&mut self, args: ast::Args) -> CompileResult {
+        self.emit(Op::Args);
+        for arg in args.items() {
+            let span = arg.span();
+            match arg {
+                ast::Arg::Pos(expr) => {
+                    self.expr(expr)?;
+                    self.emit(Op::ArgPos(span, expr.span()));
+                }
+                ast::Arg::Named(named) => {
+                    let expr = named.expr();
+                    self.expr(expr)?;
+                    let name = self.name(named.name().get());
+                    self.emit(Op::ArgNamed(name, span, expr.span()));
+                }
+                ast::Arg::Spread(spread) => {
+                    self.expr(spread.expr())?;
+                    self.emit(Op::ArgSpread(span));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Compile a let binding.
+    fn binding(&mut self, binding: ast::LetBinding) -> CompileResult {
+        match binding.init() {
+            Some(init) => self.expr(init)?,
+            None => {
+                self.emit(Op::None);
+            }
+        }
+
+        // A binding is skipped if its initializer breaks or returns.
+        let skip = binding
+            .init()
+            .filter(|init| can_diverge(init.to_untyped()))
+            .map(|_| self.emit(Op::JumpIfFlow(0)));
+
+        match binding.kind() {
+            ast::LetBindingKind::Normal(ast::Pattern::Normal(ast::Expr::Ident(
+                ident,
+            )))
+            | ast::LetBindingKind::Closure(ident) => self.bind(ident),
+            ast::LetBindingKind::Normal(pattern) => self.destructure(pattern),
+        }
+
+        if let Some(skip) = skip {
+            let exit = self.emit(Op::Jump(0));
+            self.patch(skip);
+            self.emit(Op::Pop);
+            self.patch(exit);
+        }
+
+        self.emit(Op::None);
+        Ok(())
+    }
+
+    /// Compile a while loop.
+    fn while_loop(&mut self, while_loop: ast::WhileLoop) -> CompileResult {
+        let condition = while_loop.condition();
+        let body = while_loop.body();
+        let invariant =
+            is_invariant(condition.to_untyped()) && !can_diverge(body.to_untyped());
+
+        self.emit(Op::SaveFlow);
+        let counter = self.slot();
+        self.constant(Value::Int(0));
+        self.emit(Op::Store(counter));
+        self.emit(Op::None);
+
+        let start = self.program.ops.len();
+        self.expr(condition)?;
+        let exit = self.emit(Op::JumpIfFalse(0, condition.span()));
+        let node = self.node(while_loop.to_untyped());
+        self.emit(Op::WhileCheck(counter, node, invariant));
+        self.expr(body)?;
+        self.emit(Op::Join(body.span()));
+        let flow = self.emit(Op::LoopFlow(0));
+        self.emit(Op::Jump(start as u32));
+
+        self.patch(exit);
+        self.patch(flow);
+        self.emit(Op::RestoreFlow);
+        self.emit(Op::Spanned(while_loop.span()));
+        Ok(())
+    }
+
+    /// Compile a for loop.
+    fn for_loop(&mut self, for_loop: ast::ForLoop) -> CompileResult {
+        let body = for_loop.body();
+
+        self.emit(Op::SaveFlow);
+        self.emit(Op::None);
+        self.expr(for_loop.iterable())?;
+        let node = self.node(for_loop.to_untyped());
+        self.emit(Op::IterStart(node));
+
+        self.scopes.push(vec![]);
+        let start = self.program.ops.len();
+        let next = self.emit(Op::IterNext(0));
+        match for_loop.pattern() {
+            ast::Pattern::Normal(ast::Expr::Ident(ident)) => self.bind(ident),
+            pattern => self.destructure(pattern),
+        }
+        self.expr(body)?;
+        self.emit(Op::Join(body.span()));
+        let flow = self.emit(Op::LoopFlow(0));
+        self.emit(Op::Jump(start as u32));
+        self.scopes.pop();
+
+        self.patch(next);
+        self.patch(flow);
+        self.emit(Op::IterEnd);
+        self.emit(Op::RestoreFlow);
+        self.emit(Op::Spanned(for_loop.span()));
+        Ok(())
+    }
+
+    /// Compile a read of a variable.
+    fn load(&mut self, ident: ast::Ident) {
+        let span = ident.span();
+        match self.resolve(&ident) {
+            Some(local) => self.emit(Op::Load(local.slot, span)),
+            None => {
+                let name = self.name(ident.get());
+                self.emit(Op::LoadName(name, span))
+            }
+        };
+    }
+
+    /// Pop the topmost value into a new binding.
+    fn bind(&mut self, ident: ast::Ident) {
+        if self.scopes.is_empty() {
+            let node = self.node(ident.to_untyped());
+            self.emit(Op::Define(node));
+        } else {
+            let local = self.local(ident);
+            self.emit(Op::Store(local.slot));
+        }
+    }
+
+    /// Pop the topmost value and destructure it into new bindings.
+    fn destructure(&mut self, pattern: ast::Pattern) {
+        let node = self.node(pattern.to_untyped());
+        if self.scopes.is_empty() {
+            self.emit(Op::DestructureDefine(node));
+        } else {
+            let locals = pattern
+                .bindings()
+                .into_iter()
+                .map(|ident| self.local(ident))
+                .collect();
+            let list = self.list(locals);
+            self.emit(Op::Destructure(node, list));
+        }
+    }
+
+    /// Evaluate an expression with the tree-walking evaluator.
+    fn fallback(&mut self, expr: ast::Expr) {
+        let node = self.node(expr.to_untyped());
+        let list = self.referenced(expr.to_untyped());
+        self.emit(Op::Eval(node, list));
+    }
+
+    /// Collect the locals that a syntax node may refer to into a list.
+    fn referenced(&mut self, node: &SyntaxNode) -> u32 {
+        let mut locals: Vec<Local> = vec![];
+        if !self.scopes.is_empty() {
+            let mut names = vec![];
+            collect_names(node, &mut names);
+            for name in names {
+                if let Some(local) = self.resolve(name) {
+                    if !locals.iter().any(|prev| prev.slot == local.slot) {
+                        locals.push(local);
+                    }
+                }
+            }
+        }
+        self.list(locals)
+    }
+
+    /// Define a parameter of a closure.
+    fn param(&mut self, ident: Option<ast::Ident>) {
+        if let Some(ident) = ident {
+            let local = self.local(ident);
+            self.program.params.push((ident.span(), local.slot));
+        }
+    }
+
+    /// Resolve a name to a local in the current scopes.
+    fn resolve(&self, name: &str) -> Option<Local> {
+        self.scopes
+            .iter()
+            .rev()
+            .find_map(|scope| scope.iter().rev().find(|local| local.name == name))
+            .cloned()
+    }
+
+    /// Define a new local in the innermost scope.
+    fn local(&mut self, ident: ast::Ident) -> Local {
+        let local = Local {
+            name: ident.get().clone(),
+            slot: self.slot(),
+            span: ident.span(),
+        };
+        self.scopes.last_mut().unwrap().push(local.clone());
+        local
+    }
+
+    /// Allocate a new slot.
+    fn slot(&mut self) -> u32 {
+        self.program.slots += 1;
+        self.program.slots as u32 - 1
+    }
+
+    /// Push a constant.
+    fn constant(&mut self, value: Value) {
+        self.program.consts.push(value);
+        self.emit(Op::Const(self.program.consts.len() as u32 - 1));
+    }
+
+    /// Add a syntax node to the program.
+    fn node(&mut self, node: &SyntaxNode) -> u32 {
+        self.program.nodes.push(node.clone());
+        self.program.nodes.len() as u32 - 1
+    }
+
+    /// Add a name to the program.
+    fn name(&mut self, name: &EcoString) -> u32 {
+        match self.program.names.iter().position(|prev| prev == name) {
+            Some(i) => i as u32,
+            None => {
+                self.program.names.push(name.clone());
+                self.program.names.len() as u32 - 1
+            }
+        }
+    }
+
+    /// Add a list of locals to the program.
+    fn list(&mut self, locals: Vec<Local>) -> u32 {
+        self.program.lists.push(locals);
+        self.program.lists.len() as u32 - 1
+    }
+
+    /// Emit an instruction, returning its index.
+    fn emit(&mut self, op: Op) -> usize {
+        self.program.ops.push(op);
+        self.program.ops.len() - 1
+    }
+
+    /// Let the jump at the given index target the next instruction.
+    fn patch(&mut self, at: usize) {
+        let target = self.program.ops.len() as u32;
+        match &mut self.program.ops[at] {
+            Op::ShortCircuit(_, t)
+            | Op::Jump(t)
+            | Op::JumpIfFalse(t, _)
+            | Op::JumpIfFlow(t)
+            | Op::LoopFlow(t)
+            | Op::IterNext(t)
+            | Op::Style(_, _, t) => *t = target,
+            op => unreachable!("cannot patch {op:?}"),
+        }
+    }
+
+    /// Remember the current state.
+    fn mark(&self) -> Mark {
+        Mark {
+            ops: self.program.ops.len(),
+            scopes: self.scopes.len(),
+            locals: self.scopes.last().map_or(0, Vec::len),
+        }
+    }
+
+    /// Return to a remembered state.
+    fn reset(&mut self, mark: Mark) {
+        self.program.ops.truncate(mark.ops);
+        self.scopes.truncate(mark.scopes);
+        if let Some(scope) = self.scopes.last_mut() {
+            scope.truncate(mark.locals);
+        }
+    }
+}
+
+/// Collect the names of all identifiers in a syntax node.
+fn collect_names<'a>(node: &'a SyntaxNode, names: &mut Vec<&'a EcoString>) {
+    match node.kind() {
+        SyntaxKind::Ident | SyntaxKind::MathIdent => {
+            if !names.contains(&node.text()) {
+                names.push(node.text());
+            }
+        }
+        _ => node.children().for_each(|child| collect_names(child, names)),
+    }
+}
@@ -0,0 +1,535 @@
+use ecow::{eco_format, EcoVec};
+use unicode_segmentation::UnicodeSegmentation;
+
+use crate::diag::{bail, At, SourceResult};
+use crate::eval::call::{
+    call_func, call_mutating_method, hint_if_shadowed_std, resolve_field_call, FieldCall,
+};
+use crate::eval::code::access_field;
+use crate::eval::compile::{Local, Op, Program};
+use crate::eval::markup::attach_label;
+use crate::eval::ops::{binary_op, unary_op};
+use crate::eval::{destructure, destructure_with, Eval, FlowEvent, Vm, MAX_ITERATIONS};
+use crate::foundations::{
+    Arg, Args, Array, Content, Dict, Func, IntoValue, Recipe, Styles, Value,
+};
+use crate::syntax::ast::{self, AstNode};
+use crate::syntax::{Span, Spanned};
+
+/// The local slots of a program that is about to run.
+pub(crate) struct Frame<'p> {
+    program: &'p Program,
+    locals: Vec<Value>,
+}
+
+impl<'p> Frame<'p> {
+    /// Create a frame with empty slots for a program.
+    pub fn new(program: &'p Program) -> Self {
+        Self { program, locals: vec![Value::None; program.slots] }
+    }
+
+    /// Bind a parameter of a closure.
+    pub fn bind(&mut self, ident: ast::Ident, value: Value) {
+        let span = ident.span();
+        let (_, slot) = self
+            .program
+            .params
+            .iter()
+            .find(|(param, _)| *param == span)
+            .expect("parameter was not compiled");
+        self.locals[*slot as usize] = value;
+    }
+
+    /// Run the program to completion, returning the value it produces.
+    ///
+    /// Like with the tree-walking evaluator, a control flow event that
+    /// escapes the program is left in the VM.
+    pub fn run(self, vm: &mut Vm) -> SourceResult<Value> {
+        let mut machine = Machine {
+            program: self.program,
+            locals: self.locals,
+            stack: vec![],
+            flows: vec![],
+            iters: vec![],
+            styles: vec![],
+            seqs: vec![],
+        };
+        machine.run(vm)
+    }
+}
+
+/// Styling established by a set or show rule for the rest of a block.
+enum Styling {
+    Styles(Styles),
+    Recipe(Recipe),
+}
+
+/// Executes a program.
+struct Machine<'p> {
+    program: &'p Program,
+    locals: Vec<Value>,
+    stack: Vec<Value>,
+    flows: Vec<Option<FlowEvent>>,
+    iters: Vec<ecow::vec::IntoIter<Value>>,
+    styles: Vec<Styling>,
+    seqs: Vec<Vec<Content>>,
+}
+
+impl Machine<'_> {
+    fn run(&mut self, vm: &mut Vm) -> SourceResult<Value> {
+        let program = self.program;
+        let mut pc = 0;
+
+        while let Some(&op) = program.ops.get(pc) {
+            pc += 1;
+            match op {
+                Op::None => self.push(Value::None),
+                Op::Const(i) => self.push(program.consts[i as usize].clone()),
+                Op::Load(slot, span) => {
+                    let value = self.locals[slot as usize].clone();
+                    self.push(value.spanned(span));
+                }
+                Op::LoadName(name, span) => {
+                    let name = &program.names[name as usize];
+                    let value = vm.scopes.get(name).cloned().at(span)?;
+                    self.push(value.spanned(span));
+                }
+                Op::Store(slot) => self.locals[slot as usize] = self.pop(),
+                Op::Define(node) => {
+                    let ident = program.node::<ast::Ident>(node);
+                    let value = self.pop();
+                    vm.define(ident, value);
+                }
+                Op::Destructure(node, list) => {
+                    let pattern = program.node::<ast::Pattern>(node);
+                    let value = self.pop();
+                    let list = &program.lists[list as usize];
+                    let locals = &mut self.locals;
+                    destructure_with(vm, pattern, value, &mut |_, ident, value| {
+                        if let Some(local) = list.iter().find(|l| l.span == ident.span())
+                        {
+                            locals[local.slot as usize] = value;
+                        }
+                    })?;
+                }
+                Op::DestructureDefine(node) => {
+                    let pattern = program.node::<ast::Pattern>(node);
+                    let value = self.pop();
+                    destructure(vm, pattern, value)?;
+                }
+                Op::Pop => {
+                    self.pop();
+                }
+                Op::Spanned(span) => {
+                    let value = self.pop();
+                    self.push(value.spanned(span));
+                }
+                Op::Unary(op, span) => {
+                    let value = self.pop();
+                    self.push(unary_op(op)(value).at(span)?.spanned(span));
+                }
+                Op::Binary(op, span) => {
+                    let rhs = self.pop();
+                    let lhs = self.pop();
+                    self.push(binary_op(op)(lhs, rhs).at(span)?.spanned(span));
+                }
+                Op::ShortCircuit(op, target) => {
+                    let decided = match self.stack.last() {
+                        Some(Value::Bool(lhs)) => match op {
+                            ast::BinOp::And => !lhs,
+                            _ => *lhs,
+                        },
+                        _ => false,
+                    };
+                    if decided {
+                        pc = target as usize;
+                    }
+                }
+                Op::Assign(op, slot, span) => {
+                    let rhs = self.pop();
+                    let location = &mut self.locals[slot as usize];
+                    let lhs = std::mem::take(location);
+                    *location = binary_op(op)(lhs, rhs).at(span)?;
+                }
+                Op::Array(capacity) => self
+                    .push(Value::Array(EcoVec::with_capacity(capacity as usize).into())),
+                Op::ArrayPush => {
+                    let value = self.pop();
+                    self.array().push(value);
+                }
+                Op::ArraySpread(span) => match self.pop() {
+                    Value::None => {}
+                    Value::Array(array) => self.array().extend(array),
+                    v => bail!(span, "cannot spread {} into array", v.ty()),
+                },
+                Op::Dict => self.push(Value::Dict(Dict::new())),
+                Op::DictInsert(name) => {
+                    let value = self.pop();
+                    let key = program.names[name as usize].clone().into();
+                    self.dict().insert(key, value);
+                }
+                Op::DictSpread(span) => match self.pop() {
+                    Value::None => {}
+                    Value::Dict(dict) => self.dict().extend(dict),
+                    v => bail!(span, "cannot spread {} into dictionary", v.ty()),
+                },
+                Op::Args => self.push(Value::Args(Args {
+                    span: Span::detached(),
+                    items: EcoVec::new(),
+                })),
+                Op::ArgPos(span, expr_span) => {
+                    let value = self.pop();
+                    self.args().items.push(Arg {
+                        span,
+                        name: None,
+                        value: Spanned::new(value, expr_span),
+                    });
+                }
+                Op::ArgNamed(name, span, expr_span) => {
+                    let value = self.pop();
+                    self.args().items.push(Arg {
+                        span,
+                        name: Some(program.names[name as usize].clone().into()),
+                        value: Spanned::new(value, expr_span),
+                    });
+                }
+                Op::ArgSpread(span) => {
+                    let value = self.pop();
+                    let items = &mut self.args().items;
+                    match value {
+                        Value::None => {}
+                        Value::Array(array) => {
+                            items.extend(array.into_iter().map(|value| Arg {
+                                span,
+                                name: None,
+                                value: Spanned::new(value, span),
+                            }));
+                        }
+                        Value::Dict(dict) => {
+                            items.extend(dict.into_iter().map(|(key, value)| Arg {
+                                span,
+                                name: Some(key),
+                                value: Spanned::new(value, span),
+                            }));
+                        }
+                        Value::Args(args) => items.extend(args.items),
+                        v => bail!(span, "cannot spread {}", v.ty()),
+                    }
+                }
+                Op::Field(node) => {
+                    let access = program.node::<ast::FieldAccess>(node);
+                    let value = self.pop();
+                    let value = access_field(vm, value, access.field())?;
+                    self.push(value.spanned(access.span()));
+                }
+                Op::CheckDepth(span) => vm.engine.route.check_call_depth().at(span)?,
+                Op::Call(node, span, local) => {
+                    let callee = program.node::<ast::Expr>(node);
+                    let args = self.pop_args(span);
+                    let func = self
+                        .pop()
+                        .cast::<Func>()
+                        .map_err(|mut err| match callee {
+                            // Locals aren't in the scopes, so check whether
+                            // they shadow the standard library here.
+                            ast::Expr::Ident(ident) if local => {
+                                if vm.scopes.base.is_some_and(|base| {
+                                    base.global.scope().get(&ident).is_some()
+                                }) {
+                                    err.hint(eco_format!(
+                                        "use `std.{}` to access the shadowed \
+                                         standard library function",
+                                        ident.as_str(),
+                                    ));
+                                }
+                                err
+                            }
+                            _ => hint_if_shadowed_std(vm, &callee, err),
+                        })
+                        .at(callee.span())?;
+                    let value = call_func(vm, &func, args, span)?;
+                    self.push(value.spanned(span));
+                }
+                Op::FieldCall(node, span) => {
+                    let access = program.node::<ast::FieldAccess>(node);
+                    let args = self.pop_args(span);
+                    let target = self.pop();
+                    let value = match resolve_field_call(
+                        &mut vm.engine,
+                        target,
+                        access.target().span(),
+                        access.field(),
+                        args,
+                        span,
+                    )? {
+                        FieldCall::Normal(callee, args) => {
+                            let func = callee.cast::<Func>().at(access.span())?;
+                            call_func(vm, &func, args, span)?
+                        }
+                        FieldCall::Resolved(value) => value,
+                    };
+                    self.push(value.spanned(span));
+                }
+                Op::MutCall(slot, node, span) => {
+                    let access = program.node::<ast::FieldAccess>(node);
+                    let args = self.pop_args(span);
+                    let value = match &mut self.locals[slot as usize] {
+                        target @ (Value::Array(_) | Value::Dict(_)) => {
+                            call_mutating_method(
+                                vm.world(),
+                                target,
+                                access.field(),
+                                args,
+                                span,
+                            )?
+                        }
+                        target => match resolve_field_call(
+                            &mut vm.engine,
+                            target.clone(),
+                            access.target().span(),
+                            access.field(),
+                            args,
+                            span,
+                        )? {
+                            FieldCall::Normal(callee, args) => {
+                                let func = callee.cast::<Func>().at(access.span())?;
+                                call_func(vm, &func, args, span)?
+                            }
+                            FieldCall::Resolved(value) => value,
+                        },
+                    };
+                    self.push(value.spanned(span));
+                }
+                Op::Jump(target) => pc = target as usize,
+                Op::JumpIfFalse(target, span) => {
+                    if !self.pop().cast::<bool>().at(span)? {
+                        pc = target as usize;
+                    }
+                }
+                Op::JumpIfFlow(target) => {
+                    if vm.flow.is_some() {
+                        pc = target as usize;
+                    }
+                }
+                Op::Join(span) => {
+                    let rhs = self.pop();
+                    let lhs = self.pop();
+                    self.push(crate::eval::ops::join(lhs, rhs).at(span)?);
+                }
+                Op::SaveFlow => self.flows.push(vm.flow.take()),
+                Op::RestoreFlow => {
+                    let flow = self.flows.pop().flatten();
+                    if flow.is_some() {
+                        vm.flow = flow;
+                    }
+                }
+                Op::Break(span) => {
+                    if vm.flow.is_none() {
+                        vm.flow = Some(FlowEvent::Break(span));
+                    }
+                    self.push(Value::None);
+                }
+                Op::Continue(span) => {
+                    if vm.flow.is_none() {
+                        vm.flow = Some(FlowEvent::Continue(span));
+                    }
+                    self.push(Value::None);
+                }
+                Op::Return(span, explicit) => {
+                    let value = explicit.then(|| self.pop());
+                    if vm.flow.is_none() {
+                        vm.flow = Some(FlowEvent::Return(span, value));
+                    }
+                    self.push(Value::None);
+                }
+                Op::LoopFlow(target) => match vm.flow {
+                    Some(FlowEvent::Break(_)) => {
+                        vm.flow = None;
+                        pc = target as usize;
+                    }
+                    Some(FlowEvent::Continue(_)) => vm.flow = None,
+                    Some(FlowEvent::Return(..)) => pc = target as usize,
+                    None => {}
+                },
+                Op::WhileCheck(slot, node, invariant) => {
+                    let while_loop = program.node::<ast::WhileLoop>(node);
+                    let counter = &mut self.locals[slot as usize];
+                    let Value::Int(i) = *counter else { unreachable!() };
+                    if i == 0 && invariant {
+                        bail!(while_loop.condition().span(), "condition is always true");
+                    } else if i as usize >= MAX_ITERATIONS {
+                        bail!(while_loop.span(), "loop seems to be infinite");
+                    }
+                    *counter = Value::Int(i + 1);
+                }
+                Op::IterStart(node) => {
+                    let for_loop = program.node::<ast::ForLoop>(node);
+                    let iterable = self.pop();
+                    let iter = iterate(for_loop, iterable)?;
+                    self.iters.push(iter);
+                }
+                Op::IterNext(target) => {
+                    match self.iters.last_mut().and_then(Iterator::next) {
+                        Some(value) => self.push(value),
+                        None => pc = target as usize,
+                    }
+                }
+                Op::IterEnd => {
+                    self.iters.pop();
+                }
+                Op::Eval(node, list) => {
+                    let expr = program.node::<ast::Expr>(node);
+                    let list = &program.lists[list as usize];
+                    self.enter(vm, list);
+                    let value = expr.eval(vm)?;
+                    self.exit(vm, list);
+                    self.push(value);
+                }
+                Op::Style(node, list, target) => {
+                    let expr = program.node::<ast::Expr>(node);
+                    let list = &program.lists[list as usize];
+                    self.enter(vm, list);
+                    let styling = match expr {
+                        ast::Expr::Set(set) => Styling::Styles(set.eval(vm)?),
+                        ast::Expr::Show(show) => Styling::Recipe(show.eval(vm)?),
+                        _ => unreachable!(),
+                    };
+                    self.exit(vm, list);
+                    if vm.flow.is_some() {
+                        pc = target as usize;
+                    } else {
+                        self.styles.push(styling);
+                    }
+                }
+                Op::ApplyStyle => {
+                    let tail = self.pop().display();
+                    let content = match self.styles.pop().unwrap() {
+                        Styling::Styles(styles) => tail.styled_with_map(styles),
+                        Styling::Recipe(recipe) => {
+                            tail.styled_with_recipe(&mut vm.engine, vm.context, recipe)?
+                        }
+                    };
+                    self.push(Value::Content(content));
+                }
+                Op::SeqStart => self.seqs.push(vec![]),
+                Op::SeqPush(span) => {
+                    let value = self.pop();
+                    let seq = self.seqs.last_mut().unwrap();
+                    match value {
+                        Value::Label(label) => attach_label(vm, seq, label, span),
+                        value => seq.push(value.display().spanned(span)),
+                    }
+                }
+                Op::SeqAppend => {
+                    let content = self.pop().display();
+                    self.seqs.last_mut().unwrap().push(content);
+                }
+                Op::SeqEnd => {
+                    let seq = self.seqs.pop().unwrap();
+                    self.push(Value::Content(Content::sequence(seq)));
+                }
+            }
+        }
+
+        Ok(self.pop())
+    }
+
+    /// Move the listed locals into a new scope for the evaluator.
+    fn enter(&mut self, vm: &mut Vm, list: &[Local]) {
+        if list.is_empty() {
+            return;
+        }
+
+        vm.scopes.enter();
+        for local in list {
+            let value = std::mem::take(&mut self.locals[local.slot as usize]);
+            vm.scopes.top.define_spanned(local.name.clone(), value, local.span);
+        }
+    }
+
+    /// Move the listed locals back from the scope.
+    fn exit(&mut self, vm: &mut Vm, list: &[Local]) {
+        if list.is_empty() {
+            return;
+        }
+
+        for local in list {
+            if let Some(Ok(value)) = vm.scopes.top.get_mut(&local.name) {
+                self.locals[local.slot as usize] = std::mem::take(value);
+            }
+        }
+        vm.scopes.exit();
+    }
+
+    fn push(&mut self, value: Value) {
+        self.stack.push(value);
+    }
+
+    fn pop(&mut self) -> Value {
+        self.stack.pop().unwrap_or_default()
+    }
+
+    fn pop_args(&mut self, span: Span) -> Args {
+        match self.pop() {
+            Value::Args(args) => args.spanned(span),
+            _ => unreachable!(),
+        }
+    }
+
+    fn array(&mut self) -> &mut Array {
+        match self.stack.last_mut() {
+            Some(Value::Array(array)) => array,
+            _ => unreachable!(),
+        }
+    }
+
+    fn dict(&mut self) -> &mut Dict {
+        match self.stack.last_mut() {
+            Some(Value::Dict(dict)) => dict,
+            _ => unreachable!(),
+        }
+    }
+
+    fn args(&mut self) -> &mut Args {
+        match self.stack.last_mut() {
+            Some(Value::Args(args)) => args,
+            _ => unreachable!(),
+        }
+    }
+}
+
+impl Program {
+    /// Get a syntax node referenced by an instruction.
+    fn node<'a, T: AstNode<'a>>(&'a self, i: u32) -> T {
+        self.nodes[i as usize].cast().unwrap()
+    }
+}
+
+/// Turn the iterable of a for loop into an iterator over its items.
+fn iterate(
+    for_loop: ast::ForLoop,
+    iterable: Value,
+) -> SourceResult<ecow::vec::IntoIter<Value>> {
+    let pattern = for_loop.pattern();
+    let iterable_type = iterable.ty();
+
+    use ast::Pattern;
+    let items: EcoVec<Value> = match (pattern, iterable) {
+        (_, Value::Array(array)) => return Ok(array.into_iter()),
+        (_, Value::Dict(dict)) => dict.iter().map(IntoValue::into_value).collect(),
+        (Pattern::Normal(_) | Pattern::Placeholder(_), Value::Str(str)) => {
+            str.as_str().graphemes(true).map(IntoValue::into_value).collect()
+        }
+        (Pattern::Normal(_) | Pattern::Placeholder(_), Value::Bytes(bytes)) => {
+            bytes.as_slice().iter().map(|&byte| byte.into_value()).collect()
+        }
+        (Pattern::Destructuring(_), Value::Str(_) | Value::Bytes(_)) => {
+            bail!(pattern.span(), "cannot destructure values of {}", iterable_type);
+        }
+        _ => {
+            bail!(for_loop.iterable().span(), "cannot loop over {}", iterable_type);
+        }
+    };
+
+    Ok(items.into_iter())
+}
@@ -7,7 +7,7 @@ use crate::syntax::ast::{self, AstNode};
 use crate::syntax::{Span, SyntaxKind, SyntaxNode};
 
 /// The maximum number of loop iterations.
-const MAX_ITERATIONS: usize = 10_000;
+pub(crate) const MAX_ITERATIONS: usize = 10_000;
 
 /// A control flow event that occurred during evaluation.
 #[derive(Debug, Clone, PartialEq)]
@@ -207,7 +207,7 @@ impl Eval for ast::FuncReturn<'_> {
 }
 
 /// Whether the expression always evaluates to the same value.
-fn is_invariant(expr: &SyntaxNode) -> bool {
+pub(crate) fn is_invariant(expr: &SyntaxNode) -> bool {
     match expr.cast() {
         Some(ast::Expr::Ident(_)) => false,
         Some(ast::Expr::MathIdent(_)) => false,
@@ -223,7 +223,7 @@ fn is_invariant(expr: &SyntaxNode) -> bool {
 }
 
 /// Whether the expression contains a break or return.
-fn can_diverge(expr: &SyntaxNode) -> bool {
+pub(crate) fn can_diverge(expr: &SyntaxNode) -> bool {
     matches!(expr.kind(), SyntaxKind::Break | SyntaxKind::Return)
         || expr.children().any(can_diverge)
 }
@@ -10,6 +10,7 @@ use crate::model::{
 };
 use crate::symbols::Symbol;
 use crate::syntax::ast::{self, AstNode};
+use crate::syntax::Span;
 use crate::text::{
     LinebreakElem, RawContent, RawElem, SmartQuoteElem, SpaceElem, TextElem,
 };
@@ -50,26 +51,7 @@ fn eval_markup<'a>(
                 seq.push(tail.styled_with_recipe(&mut vm.engine, vm.context, recipe)?)
             }
             expr => match expr.eval(vm)? {
-                Value::Label(label) => {
-                    if let Some(elem) =
-                        seq.iter_mut().rev().find(|node| !node.can::<dyn Unlabellable>())
-                    {
-                        if elem.label().is_some() {
-                            vm.engine.sink.warn(warning!(
-                                elem.span(), "content labelled multiple times";
-                                hint: "only the last label is used, the rest are ignored",
-                            ));
-                        }
-
-                        *elem = std::mem::take(elem).labelled(label);
-                    } else {
-                        vm.engine.sink.warn(warning!(
-                            expr.span(),
-                            "label `{}` is not attached to anything",
-                            label.repr()
-                        ));
-                    }
-                }
+                Value::Label(label) => attach_label(vm, &mut seq, label, expr.span()),
                 value => seq.push(value.display().spanned(expr.span())),
             },
         }
@@ -86,6 +68,27 @@ fn eval_markup<'a>(
     Ok(Content::sequence(seq))
 }
 
+/// Attach a label to the last labellable element of a markup sequence.
+pub(crate) fn attach_label(vm: &mut Vm, seq: &mut [Content], label: Label, span: Span) {
+    if let Some(elem) = seq.iter_mut().rev().find(|node| !node.can::<dyn Unlabellable>())
+    {
+        if elem.label().is_some() {
+            vm.engine.sink.warn(warning!(
+                elem.span(), "content labelled multiple times";
+                hint: "only the last label is used, the rest are ignored",
+            ));
+        }
+
+        *elem = std::mem::take(elem).labelled(label);
+    } else {
+        vm.engine.sink.warn(warning!(
+            span,
+            "label `{}` is not attached to anything",
+            label.repr()
+        ));
+    }
+}
+
 impl Eval for ast::Text<'_> {
     type Output = Content;
 
@@ -6,6 +6,8 @@ mod access;
 mod binding;
 mod call;
 mod code;
+mod compile;
+mod exec;
 mod flow;
 mod import;
 mod markup;
@@ -19,6 +21,8 @@ pub use self::vm::*;
 
 pub(crate) use self::access::*;
 pub(crate) use self::binding::*;
+pub(crate) use self::compile::{compile_module, Compiled};
+pub(crate) use self::exec::Frame;
 pub(crate) use self::flow::*;
 
 use comemo::{Track, Tracked, TrackedMut};
@@ -69,9 +73,13 @@ pub fn eval(
         return Err(errors.into_iter().map(Into::into).collect());
     }
 
-    // Evaluate the module.
+    // Evaluate the module. Like for closures, the compiled program is only
+    // used if no span is inspected.
     let markup = root.cast::<ast::Markup>().unwrap();
-    let output = markup.eval(&mut vm)?;
+    let output = match vm.inspected.is_none().then(|| compile_module(markup)).flatten() {
+        Some(program) => Frame::new(&program).run(&mut vm)?.display(),
+        None => markup.eval(&mut vm)?,
+    };
 
     // Handle control flow.
     if let Some(flow) = vm.flow {
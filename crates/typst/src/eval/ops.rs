@@ -18,12 +18,7 @@ impl Eval for ast::Unary<'_> {
 
     fn eval(self, vm: &mut Vm) -> SourceResult<Self::Output> {
         let value = self.expr().eval(vm)?;
-        let result = match self.op() {
-            ast::UnOp::Pos => pos(value),
-            ast::UnOp::Neg => neg(value),
-            ast::UnOp::Not => not(value),
-        };
-        result.at(self.span())
+        unary_op(self.op())(value).at(self.span())
     }
 }
 
@@ -31,30 +26,58 @@ impl Eval for ast::Binary<'_> {
     type Output = Value;
 
     fn eval(self, vm: &mut Vm) -> SourceResult<Self::Output> {
-        match self.op() {
-            ast::BinOp::Add => apply_binary(self, vm, add),
-            ast::BinOp::Sub => apply_binary(self, vm, sub),
-            ast::BinOp::Mul => apply_binary(self, vm, mul),
-            ast::BinOp::Div => apply_binary(self, vm, div),
-            ast::BinOp::And => apply_binary(self, vm, and),
-            ast::BinOp::Or => apply_binary(self, vm, or),
-            ast::BinOp::Eq => apply_binary(self, vm, eq),
-            ast::BinOp::Neq => apply_binary(self, vm, neq),
-            ast::BinOp::Lt => apply_binary(self, vm, lt),
-            ast::BinOp::Leq => apply_binary(self, vm, leq),
-            ast::BinOp::Gt => apply_binary(self, vm, gt),
-            ast::BinOp::Geq => apply_binary(self, vm, geq),
-            ast::BinOp::In => apply_binary(self, vm, in_),
-            ast::BinOp::NotIn => apply_binary(self, vm, not_in),
-            ast::BinOp::Assign => apply_assignment(self, vm, |_, b| Ok(b)),
-            ast::BinOp::AddAssign => apply_assignment(self, vm, add),
-            ast::BinOp::SubAssign => apply_assignment(self, vm, sub),
-            ast::BinOp::MulAssign => apply_assignment(self, vm, mul),
-            ast::BinOp::DivAssign => apply_assignment(self, vm, div),
+        let op = binary_op(self.op());
+        if is_assignment(self.op()) {
+            apply_assignment(self, vm, op)
+        } else {
+            apply_binary(self, vm, op)
         }
     }
 }
 
+/// Whether the binary operator assigns to its left-hand side.
+pub(crate) fn is_assignment(op: ast::BinOp) -> bool {
+    matches!(
+        op,
+        ast::BinOp::Assign
+            | ast::BinOp::AddAssign
+            | ast::BinOp::SubAssign
+            | ast::BinOp::MulAssign
+            | ast::BinOp::DivAssign
+    )
+}
+
+/// The operation performed by a unary operator.
+pub(crate) fn unary_op(op: ast::UnOp) -> fn(Value) -> HintedStrResult<Value> {
+    match op {
+        ast::UnOp::Pos => pos,
+        ast::UnOp::Neg => neg,
+        ast::UnOp::Not => not,
+    }
+}
+
+/// The operation performed by a binary operator. For assignment operators,
+/// this computes the new value from the old one and the right-hand side.
+pub(crate) fn binary_op(op: ast::BinOp) -> fn(Value, Value) -> HintedStrResult<Value> {
+    match op {
+        ast::BinOp::Add | ast::BinOp::AddAssign => add,
+        ast::BinOp::Sub | ast::BinOp::SubAssign => sub,
+        ast::BinOp::Mul | ast::BinOp::MulAssign => mul,
+        ast::BinOp::Div | ast::BinOp::DivAssign => div,
+        ast::BinOp::And => and,
+        ast::BinOp::Or => or,
+        ast::BinOp::Eq => eq,
+        ast::BinOp::Neq => neq,
+        ast::BinOp::Lt => lt,
+        ast::BinOp::Leq => leq,
+        ast::BinOp::Gt => gt,
+        ast::BinOp::Geq => geq,
+        ast::BinOp::In => in_,
+        ast::BinOp::NotIn => not_in,
+        ast::BinOp::Assign => |_, b| Ok(b),
+    }
+}
+
 /// Apply a basic binary operation.
 fn apply_binary(
     binary: ast::Binary,
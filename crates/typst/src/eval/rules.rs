@@ -18,7 +18,7 @@ impl Eval for ast::SetRule<'_> {
             .eval(vm)?
             .cast::<Func>()
             .and_then(|func| {
-                func.element().ok_or_else(|| {
+                func.element().map(|_| func).ok_or_else(|| {
                     "only element functions can be used in set rules".into()
                 })
             })
@@ -14,7 +14,7 @@ use smallvec::smallvec;
 use crate::diag::{SourceResult, StrResult};
 use crate::engine::Engine;
 use crate::foundations::{
-    elem, func, scope, ty, Context, Dict, Element, Fields, IntoValue, Label,
+    elem, func, scope, ty, Context, Dict, Element, Fields, Func, IntoValue, Label,
     NativeElement, Recipe, RecipeIndex, Repr, Selector, Str, Style, StyleChain, Styles,
     Value,
 };
@@ -215,7 +215,7 @@ impl Content {
                 return Ok(label.into_value());
             }
         }
-        let id = self.elem().field_id(name).ok_or(FieldAccessError::Unknown)?;
+        let id = self.func().field_id(name).ok_or(FieldAccessError::Unknown)?;
         self.get(id, None)
     }
 
@@ -226,7 +226,7 @@ impl Content {
     /// generated by the `#[elem]` macro.
     pub fn field(&self, id: u8) -> StrResult<Value> {
         self.get(id, None)
-            .map_err(|e| e.message(self, self.elem().field_name(id).unwrap()))
+            .map_err(|e| e.message(self, self.func().field_name(id).unwrap()))
     }
 
     /// Get a field by name, returning a missing field error if it does not
@@ -530,8 +530,8 @@ impl Content {
     /// a specific
     /// kind of element.
     #[func]
-    pub fn func(&self) -> Element {
-        self.elem()
+    pub fn func(&self) -> Func {
+        self.inner.elem.dyn_func()
     }
 
     /// Whether the content has the specified field.
@@ -545,7 +545,7 @@ impl Content {
             return self.label().is_some();
         }
 
-        let Some(id) = self.elem().field_id(&field) else {
+        let Some(id) = self.func().field_id(&field) else {
             return false;
         };
 
@@ -698,7 +698,7 @@ impl Serialize for Content {
         S: Serializer,
     {
         serializer.collect_map(
-            iter::once(("func".into(), self.func().name().into_value()))
+            iter::once(("func".into(), self.elem().name().into_value()))
                 .chain(self.fields()),
         )
     }
@@ -708,6 +708,7 @@ impl Serialize for Content {
 trait Bounds: Debug + Repr + Fields + Send + Sync + 'static {
     fn dyn_type_id(&self) -> TypeId;
     fn dyn_elem(&self) -> Element;
+    fn dyn_func(&self) -> Func;
     fn dyn_clone(&self, inner: &Inner<dyn Bounds>, span: Span) -> Content;
     fn dyn_hash(&self, hasher: &mut dyn Hasher);
     fn dyn_eq(&self, other: &Content) -> bool;
@@ -719,7 +720,11 @@ impl<T: NativeElement> Bounds for T {
     }
 
     fn dyn_elem(&self) -> Element {
-        Self::elem()
+        self.instance_elem()
+    }
+
+    fn dyn_func(&self) -> Func {
+        self.instance_func()
     }
 
     fn dyn_clone(&self, inner: &Inner<dyn Bounds>, span: Span) -> Content {
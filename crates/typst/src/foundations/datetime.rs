@@ -2,16 +2,20 @@ use std::cmp::Ordering;
 use std::hash::Hash;
 use std::ops::{Add, Sub};
 
+use comemo::Tracked;
 use ecow::{eco_format, EcoString, EcoVec};
 use time::error::{Format, InvalidFormatDescription};
+use time::format_description::modifier::{MonthRepr, WeekdayRepr};
+use time::format_description::{Component, OwnedFormatItem};
 use time::macros::format_description;
-use time::{format_description, Month, PrimitiveDateTime};
+use time::{format_description, Month, PrimitiveDateTime, UtcOffset};
 
 use crate::diag::{bail, StrResult};
 use crate::engine::Engine;
 use crate::foundations::{
-    cast, func, repr, scope, ty, Dict, Duration, Repr, Smart, Str, Value,
+    cast, func, repr, scope, ty, Cast, Context, Dict, Duration, Repr, Smart, Str, Value,
 };
+use crate::text::{Lang, Region, TextElem};
 use crate::World;
 
 /// Represents a date, a time, or a combination of both.
@@ -93,9 +97,8 @@ use crate::World;
 ///   - `padding`: Can be either `zero`, `space` or `none`. Specifies how the
 ///     month is padded.
 ///   - `repr`: Can be either `numerical`, `long` or `short`. Specifies if the
-///     month should be displayed as a number or a word. Unfortunately, when
-///     choosing the word representation, it can currently only display the
-///     English version. In the future, it is planned to support localization.
+///     month should be displayed as a number or a word. The word is displayed
+///     in the [language]($datetime.display.lang) of the datetime's display.
 /// - `day`: Displays the day of the datetime.
 ///   - `padding`: Can be either `zero`, `space` or `none`. Specifies how the
 ///     day is padded.
@@ -107,10 +110,10 @@ use crate::World;
 ///      and 53.
 /// - `weekday`: Displays the weekday of the date.
 ///   - `repr` Can be either `long`, `short`, `sunday` or `monday`. In the case
-///     of `long` and `short`, the corresponding English name will be displayed
-///     (same as for the month, other languages are currently not supported). In
-///     the case of `sunday` and `monday`, the numerical value will be displayed
-///     (assuming Sunday and Monday as the first day of the week, respectively).
+///     of `long` and `short`, the corresponding name will be displayed (in the
+///     same language as the month). In the case of `sunday` and `monday`, the
+///     numerical value will be displayed (assuming Sunday and Monday as the
+///     first day of the week, respectively).
 ///   - `one_indexed`: Can be either `true` or `false`. Defines whether the
 ///     numerical representation of the week starts with 0 or 1.
 /// - `hour`: Displays the hour of the date.
@@ -127,12 +130,49 @@ use crate::World;
 /// - `second`: Displays the second of the date.
 ///   - `padding`: Can be either `zero`, `space` or `none`. Specifies how the
 ///     second is padded.
+/// - `offset_hour`: Displays the hour part of the UTC offset.
+///   - `sign`: Can be either `automatic` or `mandatory`. Specifies when the
+///     sign should be displayed.
+/// - `offset_minute`: Displays the minute part of the UTC offset.
 ///
 /// Keep in mind that not always all components can be used. For example, if you
 /// create a new datetime with `{datetime(year: 2023, month: 10, day: 13)}`, it
 /// will be stored as a plain date internally, meaning that you cannot use
 /// components such as `hour` or `minute`, which would only work on datetimes
-/// that have a specified time.
+/// that have a specified time. Likewise, the `offset_hour` and `offset_minute`
+/// components are only available for datetimes with a UTC offset.
+///
+/// # Localization
+/// Without a pattern, a datetime is displayed in an ISO 8601-like format. By
+/// specifying a [`length`]($datetime.display.length) instead, you can use the
+/// customary format of the current [text language]($text.lang) and
+/// [region]($text.region). Month and weekday names are localized, both in
+/// these formats and in custom patterns.
+///
+/// ```example
+/// #let date = datetime(year: 2026, month: 10, day: 17)
+/// #context date.display(length: "long") \
+/// #set text(lang: "de")
+/// #context date.display(length: "long") \
+/// #context date.display(length: "full") \
+/// #context date.display("[weekday repr:short], [month repr:long]")
+/// ```
+///
+/// # UTC offsets
+/// A datetime can be tied to a fixed offset from UTC, either by specifying an
+/// `offset` in the constructor or by parsing an ISO 8601 string that ends in
+/// `Z` or an offset like `+02:00`. Such datetimes can be converted to other
+/// offsets with [`to-offset`]($datetime.to-offset). Named time zones are not
+/// supported.
+///
+/// ```example
+/// #let meeting = datetime("2026-10-17T09:30:00+02:00")
+/// #meeting.display() \
+/// #meeting.to-offset(0).display() \
+/// #meeting.to-offset(-5).display(
+///   "[hour]:[minute] UTC[offset_hour sign:mandatory]"
+/// )
+/// ```
 #[ty(scope, cast)]
 #[derive(Debug, Clone, Copy, PartialEq, Hash)]
 pub enum Datetime {
@@ -142,6 +182,9 @@ pub enum Datetime {
     Time(time::Time),
     /// Representation as a combination of date and time.
     Datetime(time::PrimitiveDateTime),
+    /// Representation as a combination of date and time at a fixed offset
+    /// from UTC.
+    Offset(time::PrimitiveDateTime, UtcOffset),
 }
 
 impl Datetime {
@@ -221,12 +264,101 @@ impl Datetime {
         }
     }
 
+    /// Parse a datetime from an ISO 8601 string.
+    ///
+    /// Supports dates (`2024-10-17`), times (`09:30` or `09:30:00`) and
+    /// combinations of both, separated by `T` or a space. A combined datetime
+    /// may end in `Z` or an offset like `+02:00`. Fractional seconds are
+    /// truncated.
+    pub fn from_iso(text: &str) -> StrResult<Self> {
+        let text = text.trim();
+        let (date, rest) = match text.find(['T', 't', ' ']) {
+            Some(i) => (Some(&text[..i]), Some(&text[i + 1..])),
+            None if text.contains(':') => (None, Some(text)),
+            None => (Some(text), None),
+        };
+
+        let date = date
+            .map(|date| {
+                time::Date::parse(date, &format_description!("[year]-[month]-[day]"))
+                    .map_err(|_| eco_format!("invalid date: {date}"))
+            })
+            .transpose()?;
+
+        let (time, offset) = match rest {
+            Some(rest) => {
+                let (time, offset) = split_offset(rest)?;
+                (Some(parse_iso_time(time)?), offset)
+            }
+            None => (None, None),
+        };
+
+        Ok(match (date, time, offset) {
+            (Some(date), Some(time), Some(offset)) => {
+                Self::Offset(PrimitiveDateTime::new(date, time), offset)
+            }
+            (Some(date), Some(time), None) => {
+                Self::Datetime(PrimitiveDateTime::new(date, time))
+            }
+            (Some(date), None, _) => Self::Date(date),
+            (None, Some(time), None) => Self::Time(time),
+            (None, Some(_), Some(_)) => {
+                bail!("a time without a date cannot have an offset")
+            }
+            (None, None, _) => unreachable!(),
+        })
+    }
+
     /// Which kind of variant this datetime stores.
     pub fn kind(&self) -> &'static str {
         match self {
             Datetime::Datetime(_) => "datetime",
             Datetime::Date(_) => "date",
             Datetime::Time(_) => "time",
+            Datetime::Offset(..) => "datetime with offset",
+        }
+    }
+
+    /// The date part, if any.
+    fn date_part(&self) -> Option<time::Date> {
+        match self {
+            Self::Date(date) => Some(*date),
+            Self::Time(_) => None,
+            Self::Datetime(datetime) | Self::Offset(datetime, _) => Some(datetime.date()),
+        }
+    }
+
+    /// The time part, if any.
+    fn time_part(&self) -> Option<time::Time> {
+        match self {
+            Self::Date(_) => None,
+            Self::Time(time) => Some(*time),
+            Self::Datetime(datetime) | Self::Offset(datetime, _) => Some(datetime.time()),
+        }
+    }
+
+    /// Attaches a UTC offset to a datetime with both a date and a time.
+    fn with_offset(self, offset: UtcOffset) -> StrResult<Self> {
+        match self {
+            Self::Datetime(datetime) | Self::Offset(datetime, _) => {
+                Ok(Self::Offset(datetime, offset))
+            }
+            _ => bail!("only a datetime with both a date and a time can have an offset"),
+        }
+    }
+
+    /// Converts a datetime with a UTC offset to UTC. Other datetimes are
+    /// returned unchanged.
+    pub fn to_utc(self) -> Self {
+        match self {
+            Self::Offset(datetime, offset) => {
+                let utc = datetime.assume_offset(offset).to_offset(UtcOffset::UTC);
+                Self::Offset(
+                    PrimitiveDateTime::new(utc.date(), utc.time()),
+                    UtcOffset::UTC,
+                )
+            }
+            other => other,
         }
     }
 }
@@ -248,15 +380,25 @@ impl Datetime {
     /// Depending on how it is stored, the [`display`]($datetime.display) method
     /// will choose a different formatting by default.
     ///
+    /// Alternatively, you can parse a datetime from an ISO 8601 string. Dates
+    /// like `{"2024-10-17"}`, times like `{"09:30:00"}` and combinations like
+    /// `{"2024-10-17T09:30:00+02:00"}` are supported.
+    ///
     /// ```example
     /// #datetime(
     ///   year: 2012,
     ///   month: 8,
     ///   day: 3,
-    /// ).display()
+    /// ).display() \
+    /// #datetime("2012-08-03T14:15:00").display()
     /// ```
     #[func(constructor)]
+    #[allow(clippy::too_many_arguments)]
     pub fn construct(
+        /// An ISO 8601 string to parse the datetime from. Cannot be combined
+        /// with the other arguments.
+        #[default]
+        text: Option<Str>,
         /// The year of the datetime.
         #[named]
         year: Option<i32>,
@@ -275,7 +417,29 @@ impl Datetime {
         /// The second of the datetime.
         #[named]
         second: Option<u8>,
+        /// The offset of the datetime from UTC, as a number of hours, a
+        /// [duration], or a string like `{"+05:30"}`. Can only be specified
+        /// along with both a date and a time.
+        #[named]
+        offset: Option<UtcOffset>,
     ) -> StrResult<Datetime> {
+        if let Some(text) = text {
+            if year.is_some()
+                || month.is_some()
+                || day.is_some()
+                || hour.is_some()
+                || minute.is_some()
+                || second.is_some()
+            {
+                bail!("cannot specify components along with an ISO 8601 string");
+            }
+            let datetime = Datetime::from_iso(&text)?;
+            return match offset {
+                Some(offset) => datetime.with_offset(offset),
+                None => Ok(datetime),
+            };
+        }
+
         let time = match (hour, minute, second) {
             (Some(hour), Some(minute), Some(second)) => {
                 match time::Time::from_hms(hour, minute, second) {
@@ -298,7 +462,7 @@ impl Datetime {
             _ => bail!("date is incomplete"),
         };
 
-        Ok(match (date, time) {
+        let datetime = match (date, time) {
             (Some(date), Some(time)) => {
                 Datetime::Datetime(PrimitiveDateTime::new(date, time))
             }
@@ -307,7 +471,12 @@ impl Datetime {
             (None, None) => {
                 bail!("at least one of date or time must be fully specified")
             }
-        })
+        };
+
+        match offset {
+            Some(offset) => datetime.with_offset(offset),
+            None => Ok(datetime),
+        }
     }
 
     /// Returns the current date.
@@ -338,31 +507,83 @@ impl Datetime {
     /// default format will be different. If you specified a date, it will be
     /// `[[year]-[month]-[day]]`. If you specified a time, it will be
     /// `[[hour]:[minute]:[second]]`. In the case of a datetime, it will be
-    /// `[[year]-[month]-[day] [hour]:[minute]:[second]]`.
+    /// `[[year]-[month]-[day] [hour]:[minute]:[second]]`, followed by the
+    /// offset for datetimes with a UTC offset.
     ///
     /// See the [format syntax]($datetime/#format) for more information.
     #[func]
     pub fn display(
         &self,
+        /// The callsite context.
+        context: Tracked<Context>,
         /// The format used to display the datetime.
         #[default]
         pattern: Smart<DisplayPattern>,
+        /// Selects the customary format of the language and region instead of
+        /// an explicit pattern. Ranges from the numeric `{"short"}` format to
+        /// the `{"full"}` format, which includes the weekday.
+        ///
+        /// ```example
+        /// #let date = datetime(year: 2026, month: 10, day: 17)
+        /// #for length in ("short", "medium", "long", "full") [
+        ///   #context date.display(length: length) \
+        /// ]
+        /// ```
+        #[named]
+        #[default]
+        length: Smart<DateLength>,
+        /// The language in which to display month and weekday names.
+        ///
+        /// If `{auto}`, the [text language]($text.lang) and
+        /// [region]($text.region) are used when the context is known, and
+        /// English otherwise.
+        #[named]
+        #[default]
+        lang: Smart<Lang>,
     ) -> StrResult<EcoString> {
-        let pat = |s| format_description::parse_borrowed::<2>(s).unwrap();
-        let result = match pattern {
-            Smart::Auto => match self {
-                Self::Date(date) => date.format(&pat("[year]-[month]-[day]")),
-                Self::Time(time) => time.format(&pat("[hour]:[minute]:[second]")),
-                Self::Datetime(datetime) => {
-                    datetime.format(&pat("[year]-[month]-[day] [hour]:[minute]:[second]"))
-                }
+        let (lang, region) = match lang {
+            Smart::Custom(lang) => (lang, None),
+            Smart::Auto => match context.styles() {
+                Ok(styles) => (TextElem::lang_in(styles), TextElem::region_in(styles)),
+                Err(_) => (Lang::ENGLISH, None),
             },
+        };
 
-            Smart::Custom(DisplayPattern(_, format)) => match self {
-                Self::Date(date) => date.format(&format),
-                Self::Time(time) => time.format(&format),
-                Self::Datetime(datetime) => datetime.format(&format),
-            },
+        let format = match (pattern, length) {
+            (Smart::Custom(DisplayPattern(_, format)), Smart::Auto) => format,
+            (Smart::Auto, Smart::Custom(length)) => {
+                let pattern = default_pattern(self, length, lang, region);
+                format_description::parse_owned::<2>(&pattern).unwrap()
+            }
+            (Smart::Auto, Smart::Auto) => {
+                let pattern = match self {
+                    Self::Date(_) => "[year]-[month]-[day]",
+                    Self::Time(_) => "[hour]:[minute]:[second]",
+                    Self::Datetime(_) => "[year]-[month]-[day] [hour]:[minute]:[second]",
+                    Self::Offset(..) => {
+                        "[year]-[month]-[day] [hour]:[minute]:[second] \
+                         [offset_hour sign:mandatory]:[offset_minute]"
+                    }
+                };
+                format_description::parse_owned::<2>(pattern).unwrap()
+            }
+            (Smart::Custom(_), Smart::Custom(_)) => {
+                bail!("cannot specify both a pattern and a length")
+            }
+        };
+
+        let format = match (self.date_part(), DateNames::get(lang)) {
+            (Some(date), Some(names)) => names.localize(format, date),
+            _ => format,
+        };
+
+        let result = match self {
+            Self::Date(date) => date.format(&format),
+            Self::Time(time) => time.format(&format),
+            Self::Datetime(datetime) => datetime.format(&format),
+            Self::Offset(datetime, offset) => {
+                datetime.assume_offset(*offset).format(&format)
+            }
         };
         result.map(EcoString::from).map_err(format_time_format_error)
     }
@@ -370,82 +591,89 @@ impl Datetime {
     /// The year if it was specified, or `{none}` for times without a date.
     #[func]
     pub fn year(&self) -> Option<i32> {
-        match self {
-            Self::Date(date) => Some(date.year()),
-            Self::Time(_) => None,
-            Self::Datetime(datetime) => Some(datetime.year()),
-        }
+        self.date_part().map(|date| date.year())
     }
 
     /// The month if it was specified, or `{none}` for times without a date.
     #[func]
     pub fn month(&self) -> Option<u8> {
-        match self {
-            Self::Date(date) => Some(date.month().into()),
-            Self::Time(_) => None,
-            Self::Datetime(datetime) => Some(datetime.month().into()),
-        }
+        self.date_part().map(|date| date.month().into())
     }
 
     /// The weekday (counting Monday as 1) or `{none}` for times without a date.
     #[func]
     pub fn weekday(&self) -> Option<u8> {
-        match self {
-            Self::Date(date) => Some(date.weekday().number_from_monday()),
-            Self::Time(_) => None,
-            Self::Datetime(datetime) => Some(datetime.weekday().number_from_monday()),
-        }
+        self.date_part().map(|date| date.weekday().number_from_monday())
     }
 
     /// The day if it was specified, or `{none}` for times without a date.
     #[func]
     pub fn day(&self) -> Option<u8> {
-        match self {
-            Self::Date(date) => Some(date.day()),
-            Self::Time(_) => None,
-            Self::Datetime(datetime) => Some(datetime.day()),
-        }
+        self.date_part().map(|date| date.day())
     }
 
     /// The hour if it was specified, or `{none}` for dates without a time.
     #[func]
     pub fn hour(&self) -> Option<u8> {
-        match self {
-            Self::Date(_) => None,
-            Self::Time(time) => Some(time.hour()),
-            Self::Datetime(datetime) => Some(datetime.hour()),
-        }
+        self.time_part().map(|time| time.hour())
     }
 
     /// The minute if it was specified, or `{none}` for dates without a time.
     #[func]
     pub fn minute(&self) -> Option<u8> {
-        match self {
-            Self::Date(_) => None,
-            Self::Time(time) => Some(time.minute()),
-            Self::Datetime(datetime) => Some(datetime.minute()),
-        }
+        self.time_part().map(|time| time.minute())
     }
 
     /// The second if it was specified, or `{none}` for dates without a time.
     #[func]
     pub fn second(&self) -> Option<u8> {
-        match self {
-            Self::Date(_) => None,
-            Self::Time(time) => Some(time.second()),
-            Self::Datetime(datetime) => Some(datetime.second()),
-        }
+        self.time_part().map(|time| time.second())
     }
 
     /// The ordinal (day of the year), or `{none}` for times without a date.
     #[func]
     pub fn ordinal(&self) -> Option<u16> {
+        self.date_part().map(|date| date.ordinal())
+    }
+
+    /// The offset from UTC, or `{none}` for datetimes without an offset.
+    ///
+    /// ```example
+    /// #datetime("2026-10-17T09:30:00+05:30").offset().hours()
+    /// ```
+    #[func]
+    pub fn offset(&self) -> Option<Duration> {
         match self {
-            Self::Datetime(datetime) => Some(datetime.ordinal()),
-            Self::Date(date) => Some(date.ordinal()),
-            Self::Time(_) => None,
+            Self::Offset(_, offset) => {
+                Some(time::Duration::seconds(offset.whole_seconds().into()).into())
+            }
+            _ => None,
         }
     }
+
+    /// Converts a datetime with a UTC offset to the same point in time at
+    /// another offset.
+    ///
+    /// ```example
+    /// #let flight = datetime("2026-10-17T22:15:00-04:00")
+    /// #flight.to-offset(1).display()
+    /// ```
+    #[func]
+    pub fn to_offset(
+        &self,
+        /// The new offset from UTC, as a number of hours, a [duration], or a
+        /// string like `{"+05:30"}`.
+        offset: UtcOffset,
+    ) -> StrResult<Datetime> {
+        let Self::Offset(datetime, current) = self else {
+            bail!("cannot convert a {} without an offset to another offset", self.kind());
+        };
+        let converted = datetime.assume_offset(*current).to_offset(offset);
+        Ok(Self::Offset(
+            PrimitiveDateTime::new(converted.date(), converted.time()),
+            offset,
+        ))
+    }
 }
 
 impl Repr for Datetime {
@@ -456,7 +684,8 @@ impl Repr for Datetime {
         let hour = self.hour().map(|h| eco_format!("hour: {}", (h as i64).repr()));
         let minute = self.minute().map(|m| eco_format!("minute: {}", (m as i64).repr()));
         let second = self.second().map(|s| eco_format!("second: {}", (s as i64).repr()));
-        let filtered = [year, month, day, hour, minute, second]
+        let offset = self.offset().map(|o| eco_format!("offset: {}", o.repr()));
+        let filtered = [year, month, day, hour, minute, second, offset]
             .into_iter()
             .flatten()
             .collect::<EcoVec<_>>();
@@ -471,6 +700,12 @@ impl PartialOrd for Datetime {
             (Self::Datetime(a), Self::Datetime(b)) => a.partial_cmp(b),
             (Self::Date(a), Self::Date(b)) => a.partial_cmp(b),
             (Self::Time(a), Self::Time(b)) => a.partial_cmp(b),
+            // Datetimes describing the same instant at different offsets are
+            // not equal, so they are ordered by their offsets to stay
+            // consistent with equality.
+            (Self::Offset(a, x), Self::Offset(b, y)) => {
+                Some(a.assume_offset(*x).cmp(&b.assume_offset(*y)).then_with(|| x.cmp(y)))
+            }
             _ => None,
         }
     }
@@ -485,6 +720,7 @@ impl Add<Duration> for Datetime {
             Self::Datetime(datetime) => Self::Datetime(datetime + rhs),
             Self::Date(date) => Self::Date(date + rhs),
             Self::Time(time) => Self::Time(time + rhs),
+            Self::Offset(datetime, offset) => Self::Offset(datetime + rhs, offset),
         }
     }
 }
@@ -498,6 +734,7 @@ impl Sub<Duration> for Datetime {
             Self::Datetime(datetime) => Self::Datetime(datetime - rhs),
             Self::Date(date) => Self::Date(date - rhs),
             Self::Time(time) => Self::Time(time - rhs),
+            Self::Offset(datetime, offset) => Self::Offset(datetime - rhs, offset),
         }
     }
 }
@@ -510,6 +747,9 @@ impl Sub for Datetime {
             (Self::Datetime(a), Self::Datetime(b)) => Ok((a - b).into()),
             (Self::Date(a), Self::Date(b)) => Ok((a - b).into()),
             (Self::Time(a), Self::Time(b)) => Ok((a - b).into()),
+            (Self::Offset(a, x), Self::Offset(b, y)) => {
+                Ok((a.assume_offset(x) - b.assume_offset(y)).into())
+            }
             (a, b) => bail!("cannot subtract {} from {}", b.kind(), a.kind()),
         }
     }
@@ -533,6 +773,585 @@ cast! {
     v: u8 => Self::try_from(v).map_err(|_| "month is invalid")?
 }
 
+cast! {
+    UtcOffset,
+    v: i64 => i32::try_from(v)
+        .ok()
+        .and_then(|hours| hours.checked_mul(3600))
+        .and_then(|seconds| Self::from_whole_seconds(seconds).ok())
+        .ok_or("offset is invalid")?,
+    v: Duration => i32::try_from(time::Duration::from(v).whole_seconds())
+        .ok()
+        .and_then(|seconds| Self::from_whole_seconds(seconds).ok())
+        .ok_or("offset is invalid")?,
+    v: Str => parse_offset(&v)?,
+}
+
+/// How detailed the customary format of a datetime is.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Cast)]
+pub enum DateLength {
+    /// A numeric format, like `10/17/26`.
+    Short,
+    /// A format with an abbreviated month name, like `Oct 17, 2026`.
+    Medium,
+    /// A format with the full month name, like `October 17, 2026`.
+    Long,
+    /// A format that also includes the weekday, like
+    /// `Saturday, October 17, 2026`.
+    Full,
+}
+
+/// The customary pattern for displaying a datetime in a language and region.
+fn default_pattern(
+    datetime: &Datetime,
+    length: DateLength,
+    lang: Lang,
+    region: Option<Region>,
+) -> String {
+    let region = region.as_ref().map(Region::as_str);
+    let us = lang == Lang::ENGLISH && matches!(region, None | Some("US"));
+
+    let [short, medium, long, full] = match lang.as_str() {
+        "en" if us => [
+            "[month padding:none]/[day padding:none]/[year repr:last_two]",
+            "[month repr:short] [day padding:none], [year]",
+            "[month repr:long] [day padding:none], [year]",
+            "[weekday repr:long], [month repr:long] [day padding:none], [year]",
+        ],
+        "en" => [
+            "[day]/[month]/[year]",
+            "[day padding:none] [month repr:short] [year]",
+            "[day padding:none] [month repr:long] [year]",
+            "[weekday repr:long], [day padding:none] [month repr:long] [year]",
+        ],
+        "de" => [
+            "[day].[month].[year repr:last_two]",
+            "[day].[month].[year]",
+            "[day padding:none]. [month repr:long] [year]",
+            "[weekday repr:long], [day padding:none]. [month repr:long] [year]",
+        ],
+        "fr" => [
+            "[day]/[month]/[year]",
+            "[day padding:none] [month repr:short] [year]",
+            "[day padding:none] [month repr:long] [year]",
+            "[weekday repr:long] [day padding:none] [month repr:long] [year]",
+        ],
+        "es" | "pt" => [
+            "[day]/[month]/[year repr:last_two]",
+            "[day padding:none] [month repr:short] [year]",
+            "[day padding:none] de [month repr:long] de [year]",
+            "[weekday repr:long], [day padding:none] de [month repr:long] de [year]",
+        ],
+        "it" => [
+            "[day]/[month]/[year repr:last_two]",
+            "[day padding:none] [month repr:short] [year]",
+            "[day padding:none] [month repr:long] [year]",
+            "[weekday repr:long] [day padding:none] [month repr:long] [year]",
+        ],
+        "nl" => [
+            "[day]-[month]-[year]",
+            "[day padding:none] [month repr:short] [year]",
+            "[day padding:none] [month repr:long] [year]",
+            "[weekday repr:long] [day padding:none] [month repr:long] [year]",
+        ],
+        "sv" => [
+            "[year]-[month]-[day]",
+            "[day padding:none] [month repr:short] [year]",
+            "[day padding:none] [month repr:long] [year]",
+            "[weekday repr:long] [day padding:none] [month repr:long] [year]",
+        ],
+        "da" | "nb" | "nn" | "no" => [
+            "[day].[month].[year]",
+            "[day padding:none]. [month repr:short] [year]",
+            "[day padding:none]. [month repr:long] [year]",
+            "[weekday repr:long] [day padding:none]. [month repr:long] [year]",
+        ],
+        "ja" | "zh" => [
+            "[year]/[month]/[day]",
+            "[year]/[month]/[day]",
+            "[year]年[month padding:none]月[day padding:none]日",
+            "[year]年[month padding:none]月[day padding:none]日[weekday repr:long]",
+        ],
+        _ => [
+            "[year]-[month]-[day]",
+            "[year]-[month]-[day]",
+            "[day padding:none] [month repr:long] [year]",
+            "[weekday repr:long], [day padding:none] [month repr:long] [year]",
+        ],
+    };
+
+    let date = match length {
+        DateLength::Short => short,
+        DateLength::Medium => medium,
+        DateLength::Long => long,
+        DateLength::Full => full,
+    };
+
+    let time = match (length, us) {
+        (DateLength::Short, true) => "[hour repr:12 padding:none]:[minute] [period]",
+        (_, true) => "[hour repr:12 padding:none]:[minute]:[second] [period]",
+        (DateLength::Short, false) => "[hour]:[minute]",
+        (_, false) => "[hour]:[minute]:[second]",
+    };
+
+    let separator = if lang == Lang::ENGLISH { ", " } else { " " };
+    match datetime {
+        Datetime::Date(_) => date.into(),
+        Datetime::Time(_) => time.into(),
+        Datetime::Datetime(_) => format!("{date}{separator}{time}"),
+        Datetime::Offset(..) => match length {
+            DateLength::Short | DateLength::Medium => format!("{date}{separator}{time}"),
+            DateLength::Long | DateLength::Full => format!(
+                "{date}{separator}{time} UTC[offset_hour sign:mandatory]:[offset_minute]"
+            ),
+        },
+    }
+}
+
+/// Localized month and weekday names.
+struct DateNames {
+    /// The full month names.
+    months: [&'static str; 12],
+    /// The abbreviated month names.
+    months_short: [&'static str; 12],
+    /// The full weekday names, starting with Monday.
+    weekdays: [&'static str; 7],
+    /// The abbreviated weekday names, starting with Monday.
+    weekdays_short: [&'static str; 7],
+}
+
+impl DateNames {
+    /// Get the names for a language.
+    ///
+    /// Returns `None` for English and unknown languages, for which the English
+    /// names built into the time crate are used.
+    fn get(lang: Lang) -> Option<Self> {
+        Some(match lang.as_str() {
+            "de" => Self {
+                months: [
+                    "Januar",
+                    "Februar",
+                    "März",
+                    "April",
+                    "Mai",
+                    "Juni",
+                    "Juli",
+                    "August",
+                    "September",
+                    "Oktober",
+                    "November",
+                    "Dezember",
+                ],
+                months_short: [
+                    "Jan.", "Feb.", "März", "Apr.", "Mai", "Juni", "Juli", "Aug.",
+                    "Sept.", "Okt.", "Nov.", "Dez.",
+                ],
+                weekdays: [
+                    "Montag",
+                    "Dienstag",
+                    "Mittwoch",
+                    "Donnerstag",
+                    "Freitag",
+                    "Samstag",
+                    "Sonntag",
+                ],
+                weekdays_short: ["Mo.", "Di.", "Mi.", "Do.", "Fr.", "Sa.", "So."],
+            },
+            "fr" => Self {
+                months: [
+                    "janvier",
+                    "février",
+                    "mars",
+                    "avril",
+                    "mai",
+                    "juin",
+                    "juillet",
+                    "août",
+                    "septembre",
+                    "octobre",
+                    "novembre",
+                    "décembre",
+                ],
+                months_short: [
+                    "janv.", "févr.", "mars", "avr.", "mai", "juin", "juil.", "août",
+                    "sept.", "oct.", "nov.", "déc.",
+                ],
+                weekdays: [
+                    "lundi", "mardi", "mercredi", "jeudi", "vendredi", "samedi",
+                    "dimanche",
+                ],
+                weekdays_short: ["lun.", "mar.", "mer.", "jeu.", "ven.", "sam.", "dim."],
+            },
+            "es" => Self {
+                months: [
+                    "enero",
+                    "febrero",
+                    "marzo",
+                    "abril",
+                    "mayo",
+                    "junio",
+                    "julio",
+                    "agosto",
+                    "septiembre",
+                    "octubre",
+                    "noviembre",
+                    "diciembre",
+                ],
+                months_short: [
+                    "ene", "feb", "mar", "abr", "may", "jun", "jul", "ago", "sept",
+                    "oct", "nov", "dic",
+                ],
+                weekdays: [
+                    "lunes",
+                    "martes",
+                    "miércoles",
+                    "jueves",
+                    "viernes",
+                    "sábado",
+                    "domingo",
+                ],
+                weekdays_short: ["lun", "mar", "mié", "jue", "vie", "sáb", "dom"],
+            },
+            "it" => Self {
+                months: [
+                    "gennaio",
+                    "febbraio",
+                    "marzo",
+                    "aprile",
+                    "maggio",
+                    "giugno",
+                    "luglio",
+                    "agosto",
+                    "settembre",
+                    "ottobre",
+                    "novembre",
+                    "dicembre",
+                ],
+                months_short: [
+                    "gen", "feb", "mar", "apr", "mag", "giu", "lug", "ago", "set", "ott",
+                    "nov", "dic",
+                ],
+                weekdays: [
+                    "lunedì",
+                    "martedì",
+                    "mercoledì",
+                    "giovedì",
+                    "venerdì",
+                    "sabato",
+                    "domenica",
+                ],
+                weekdays_short: ["lun", "mar", "mer", "gio", "ven", "sab", "dom"],
+            },
+            "pt" => Self {
+                months: [
+                    "janeiro",
+                    "fevereiro",
+                    "março",
+                    "abril",
+                    "maio",
+                    "junho",
+                    "julho",
+                    "agosto",
+                    "setembro",
+                    "outubro",
+                    "novembro",
+                    "dezembro",
+                ],
+                months_short: [
+                    "jan.", "fev.", "mar.", "abr.", "mai.", "jun.", "jul.", "ago.",
+                    "set.", "out.", "nov.", "dez.",
+                ],
+                weekdays: [
+                    "segunda-feira",
+                    "terça-feira",
+                    "quarta-feira",
+                    "quinta-feira",
+                    "sexta-feira",
+                    "sábado",
+                    "domingo",
+                ],
+                weekdays_short: ["seg.", "ter.", "qua.", "qui.", "sex.", "sáb.", "dom."],
+            },
+            "nl" => Self {
+                months: [
+                    "januari",
+                    "februari",
+                    "maart",
+                    "april",
+                    "mei",
+                    "juni",
+                    "juli",
+                    "augustus",
+                    "september",
+                    "oktober",
+                    "november",
+                    "december",
+                ],
+                months_short: [
+                    "jan", "feb", "mrt", "apr", "mei", "jun", "jul", "aug", "sep", "okt",
+                    "nov", "dec",
+                ],
+                weekdays: [
+                    "maandag",
+                    "dinsdag",
+                    "woensdag",
+                    "donderdag",
+                    "vrijdag",
+                    "zaterdag",
+                    "zondag",
+                ],
+                weekdays_short: ["ma", "di", "wo", "do", "vr", "za", "zo"],
+            },
+            "sv" => Self {
+                months: [
+                    "januari",
+                    "februari",
+                    "mars",
+                    "april",
+                    "maj",
+                    "juni",
+                    "juli",
+                    "augusti",
+                    "september",
+                    "oktober",
+                    "november",
+                    "december",
+                ],
+                months_short: [
+                    "jan.", "feb.", "mars", "apr.", "maj", "juni", "juli", "aug.",
+                    "sep.", "okt.", "nov.", "dec.",
+                ],
+                weekdays: [
+                    "måndag", "tisdag", "onsdag", "torsdag", "fredag", "lördag", "söndag",
+                ],
+                weekdays_short: ["mån", "tis", "ons", "tors", "fre", "lör", "sön"],
+            },
+            "da" => Self {
+                months: [
+                    "januar",
+                    "februar",
+                    "marts",
+                    "april",
+                    "maj",
+                    "juni",
+                    "juli",
+                    "august",
+                    "september",
+                    "oktober",
+                    "november",
+                    "december",
+                ],
+                months_short: [
+                    "jan.", "feb.", "mar.", "apr.", "maj", "jun.", "jul.", "aug.",
+                    "sep.", "okt.", "nov.", "dec.",
+                ],
+                weekdays: [
+                    "mandag", "tirsdag", "onsdag", "torsdag", "fredag", "lørdag",
+                    "søndag",
+                ],
+                weekdays_short: [
+                    "man.", "tirs.", "ons.", "tors.", "fre.", "lør.", "søn.",
+                ],
+            },
+            "nb" | "nn" | "no" => Self {
+                months: [
+                    "januar",
+                    "februar",
+                    "mars",
+                    "april",
+                    "mai",
+                    "juni",
+                    "juli",
+                    "august",
+                    "september",
+                    "oktober",
+                    "november",
+                    "desember",
+                ],
+                months_short: [
+                    "jan.", "feb.", "mar.", "apr.", "mai", "jun.", "jul.", "aug.",
+                    "sep.", "okt.", "nov.", "des.",
+                ],
+                weekdays: [
+                    "mandag", "tirsdag", "onsdag", "torsdag", "fredag", "lørdag",
+                    "søndag",
+                ],
+                weekdays_short: ["man.", "tir.", "ons.", "tor.", "fre.", "lør.", "søn."],
+            },
+            "ja" => Self {
+                months: [
+                    "1月", "2月", "3月", "4月", "5月", "6月", "7月", "8月", "9月",
+                    "10月", "11月", "12月",
+                ],
+                months_short: [
+                    "1月", "2月", "3月", "4月", "5月", "6月", "7月", "8月", "9月",
+                    "10月", "11月", "12月",
+                ],
+                weekdays: [
+                    "月曜日",
+                    "火曜日",
+                    "水曜日",
+                    "木曜日",
+                    "金曜日",
+                    "土曜日",
+                    "日曜日",
+                ],
+                weekdays_short: ["月", "火", "水", "木", "金", "土", "日"],
+            },
+            "zh" => Self {
+                months: [
+                    "一月",
+                    "二月",
+                    "三月",
+                    "四月",
+                    "五月",
+                    "六月",
+                    "七月",
+                    "八月",
+                    "九月",
+                    "十月",
+                    "十一月",
+                    "十二月",
+                ],
+                months_short: [
+                    "1月", "2月", "3月", "4月", "5月", "6月", "7月", "8月", "9月",
+                    "10月", "11月", "12月",
+                ],
+                weekdays: [
+                    "星期一",
+                    "星期二",
+                    "星期三",
+                    "星期四",
+                    "星期五",
+                    "星期六",
+                    "星期日",
+                ],
+                weekdays_short: ["周一", "周二", "周三", "周四", "周五", "周六", "周日"],
+            },
+            _ => return None,
+        })
+    }
+
+    /// Replaces the month and weekday name components in a format with the
+    /// localized names for a date.
+    fn localize(&self, item: OwnedFormatItem, date: time::Date) -> OwnedFormatItem {
+        let month = usize::from(u8::from(date.month())) - 1;
+        let weekday = usize::from(date.weekday().number_days_from_monday());
+        let name = match &item {
+            OwnedFormatItem::Component(Component::Month(modifier)) => {
+                match modifier.repr {
+                    MonthRepr::Long => Some(self.months[month]),
+                    MonthRepr::Short => Some(self.months_short[month]),
+                    _ => None,
+                }
+            }
+            OwnedFormatItem::Component(Component::Weekday(modifier)) => {
+                match modifier.repr {
+                    WeekdayRepr::Long => Some(self.weekdays[weekday]),
+                    WeekdayRepr::Short => Some(self.weekdays_short[weekday]),
+                    _ => None,
+                }
+            }
+            _ => None,
+        };
+
+        if let Some(name) = name {
+            return OwnedFormatItem::Literal(name.as_bytes().into());
+        }
+
+        let localize_all = |items: Box<[OwnedFormatItem]>| {
+            items
+                .into_vec()
+                .into_iter()
+                .map(|item| self.localize(item, date))
+                .collect()
+        };
+
+        match item {
+            OwnedFormatItem::Compound(items) => {
+                OwnedFormatItem::Compound(localize_all(items))
+            }
+            OwnedFormatItem::Optional(item) => {
+                OwnedFormatItem::Optional(Box::new(self.localize(*item, date)))
+            }
+            OwnedFormatItem::First(items) => OwnedFormatItem::First(localize_all(items)),
+            item => item,
+        }
+    }
+}
+
+/// Splits the UTC offset off the end of an ISO 8601 time.
+fn split_offset(text: &str) -> StrResult<(&str, Option<UtcOffset>)> {
+    if let Some(time) = text.strip_suffix(['Z', 'z']) {
+        return Ok((time, Some(UtcOffset::UTC)));
+    }
+
+    match text.rfind(['+', '-', '−']) {
+        Some(i) => Ok((&text[..i], Some(parse_offset(&text[i..])?))),
+        None => Ok((text, None)),
+    }
+}
+
+/// Parses a UTC offset like `Z`, `+02`, `+0200` or `+02:00`.
+fn parse_offset(text: &str) -> StrResult<UtcOffset> {
+    if matches!(text, "Z" | "z") {
+        return Ok(UtcOffset::UTC);
+    }
+
+    let invalid = || eco_format!("invalid offset: {text}");
+    let (sign, rest) = if let Some(rest) = text.strip_prefix('+') {
+        (1, rest)
+    } else if let Some(rest) = text.strip_prefix(['-', '−']) {
+        (-1, rest)
+    } else {
+        return Err(invalid());
+    };
+
+    let digits: String = rest.chars().filter(|&c| c != ':').collect();
+    let valid = digits.bytes().all(|b| b.is_ascii_digit())
+        && match rest.len() {
+            2 | 4 => digits.len() == rest.len(),
+            5 => rest.as_bytes()[2] == b':' && digits.len() == 4,
+            _ => false,
+        };
+    if !valid {
+        return Err(invalid());
+    }
+
+    let hours: i8 = digits[..2].parse().map_err(|_| invalid())?;
+    let minutes: i8 = match digits.get(2..) {
+        Some(minutes) if !minutes.is_empty() => minutes.parse().map_err(|_| invalid())?,
+        _ => 0,
+    };
+    UtcOffset::from_hms(sign * hours, sign * minutes, 0).map_err(|_| invalid())
+}
+
+/// Parses an ISO 8601 time like `09:30` or `09:30:15.25`, truncating
+/// fractional seconds.
+fn parse_iso_time(text: &str) -> StrResult<time::Time> {
+    let invalid = || eco_format!("invalid time: {text}");
+    let whole = text.split_once(['.', ',']).map_or(text, |(whole, _)| whole);
+    let mut parts = whole.split(':').map(|part| {
+        if part.len() == 2 && part.bytes().all(|b| b.is_ascii_digit()) {
+            part.parse::<u8>().ok()
+        } else {
+            None
+        }
+    });
+
+    let hour = parts.next().flatten().ok_or_else(invalid)?;
+    let minute = parts.next().flatten().ok_or_else(invalid)?;
+    let second = match parts.next() {
+        Some(second) => second.ok_or_else(invalid)?,
+        None => 0,
+    };
+    if parts.next().is_some() {
+        return Err(invalid());
+    }
+
+    time::Time::from_hms(hour, minute, second).map_err(|_| invalid())
+}
+
 /// Format the `Format` error of the time crate in an appropriate way.
 fn format_time_format_error(error: Format) -> EcoString {
     match error {
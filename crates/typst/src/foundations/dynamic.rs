@@ -0,0 +1,552 @@
+use std::any::TypeId;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+use std::ptr::NonNull;
+use std::sync::{Arc, RwLock};
+
+use comemo::Track;
+
+use ecow::{eco_format, EcoString, EcoVec};
+use once_cell::sync::Lazy;
+
+use crate::diag::{bail, At, HintedStrResult, SourceResult, StrResult};
+use crate::engine::Engine;
+use crate::foundations::{
+    cast, func, repr, Args, CastInfo, Construct, Content, Context, Dict, Element,
+    FieldAccessError, Fields, Func, NativeElement, NativeElementData, Packed, ParamInfo,
+    Property, Repr, Scope, Set, Show, Str, StyleChain, Styles, Type, TypeAnnotation,
+    Value,
+};
+use crate::introspection::Locatable;
+use crate::syntax::{is_ident, Span, Spanned};
+use crate::utils::hash128;
+
+/// Defines a new element.
+///
+/// User-defined elements behave just like built-in ones: They are constructed
+/// by calling them, their fields can be configured with set rules, they can
+/// be targeted by show rules and selectors (including `.where(..)`), and they
+/// are locatable, so they can be queried and referenced through their
+/// location.
+///
+/// Each entry of the `fields` dictionary declares one field. It is either
+/// just a type or a dictionary with the following keys, all of which are
+/// optional:
+/// - `type`: The type or an array of types the field accepts, like in a
+///   [type annotation]($function/#type-annotations). If omitted, any value
+///   is accepted.
+/// - `default`: The default value of the field. Defaults to `{none}`.
+/// - `required`: Whether the field must be given when constructing the
+///   element. Required fields cannot be configured with set rules.
+/// - `positional`: Whether the field is given positionally instead of by
+///   name. Defaults to `{true}` for required fields and `{false}` otherwise.
+///
+/// ```example
+/// #let note = element(
+///   "note",
+///   fields: (
+///     body: (type: content, required: true),
+///     kind: (type: str, default: "Note"),
+///   ),
+///   display: it => block(
+///     stroke: 0.5pt,
+///     inset: 6pt,
+///   )[*#it.kind:* #it.body],
+/// )
+///
+/// #note[Elements are extensible.]
+///
+/// #set note(kind: "Tip")
+/// #note[Set rules work, too.]
+///
+/// #show note.where(kind: "Tip"): set text(blue)
+/// #note[So do show rules.]
+/// ```
+#[func(name = "element")]
+pub fn define_element(
+    /// The callsite span.
+    span: Span,
+    /// The element's name, as used in its representation and error messages.
+    name: Str,
+    /// The element's fields, in order.
+    #[named]
+    #[default]
+    fields: Dict,
+    /// A function that turns an instance of the element into content.
+    ///
+    /// It receives the element with all settable fields resolved, so they
+    /// can be accessed with field access syntax. If omitted, the element
+    /// displays as nothing unless a show rule is applied to it.
+    #[named]
+    display: Option<Func>,
+) -> StrResult<Func> {
+    if !is_ident(&name) {
+        bail!("element name must be a valid identifier");
+    }
+
+    let mut specs = vec![];
+    for (key, value) in fields {
+        if key.as_str() == "label" {
+            bail!("field name `label` is reserved");
+        } else if !is_ident(&key) {
+            bail!("field name `{key}` is not a valid identifier");
+        }
+        let spec = value.cast::<FieldSpec>().map_err(|err| {
+            eco_format!("invalid specification for field `{key}`: {}", err.message())
+        })?;
+        specs.push((key, spec));
+    }
+
+    if specs.len() >= 255 {
+        bail!("an element can have at most 254 fields");
+    }
+
+    let elem = intern_elem(span, &name);
+    Ok(Arc::new(DynamicElementData::new(elem, specs, display)).into())
+}
+
+/// Elements that were defined at runtime, keyed by a hash of the span and
+/// name of their definition.
+///
+/// Defining an element at the same place repeatedly (e.g. because the
+/// defining function is called repeatedly) yields the same element, just like
+/// for built-in ones, so that show rules and selectors apply to all of its
+/// instances. The definitions themselves live in the element functions and
+/// instances instead. Thus, only one element is leaked per place of
+/// definition.
+static ELEMENTS: Interner<NativeElementData> = Lazy::new(Default::default);
+
+/// The names of the fields of user-defined elements.
+static NAMES: Interner<str> = Lazy::new(Default::default);
+
+/// The parameter information of user-defined elements, keyed by a hash of
+/// their fields' names and types.
+static PARAMS: Interner<[ParamInfo]> = Lazy::new(Default::default);
+
+/// Static data derived from element definitions. Each distinct piece of data
+/// is leaked only once.
+type Interner<T> = Lazy<RwLock<HashMap<u128, &'static T>>>;
+
+/// Retrieve the data for the given key or leak new data.
+fn intern<T: ?Sized + Sync>(
+    interner: &Interner<T>,
+    key: u128,
+    leak: impl FnOnce() -> &'static T,
+) -> &'static T {
+    if let Some(&data) = interner.read().unwrap().get(&key) {
+        return data;
+    }
+    interner.write().unwrap().entry(key).or_insert_with(leak)
+}
+
+/// Retrieve the element defined at the given place, creating it if necessary.
+fn intern_elem(span: Span, name: &str) -> Element {
+    let data = intern(&ELEMENTS, hash128(&(span, name)), || {
+        let name: &'static str = Box::leak(name.into());
+        Box::leak(Box::new(NativeElementData { name, title: name, ..base() }))
+    });
+    data.into()
+}
+
+/// Retrieve the static version of a field name.
+fn intern_name(name: &str) -> &'static str {
+    intern(&NAMES, hash128(name), || Box::leak(name.into()))
+}
+
+/// The runtime counterpart of the data the `#[elem]` macro generates for
+/// native elements.
+///
+/// The function of a user-defined element carries this data, and so does
+/// each of its instances ([`DynamicElem`]), which thus keep the definition
+/// they were created with.
+#[derive(Debug)]
+pub struct DynamicElementData {
+    /// The element this is a definition of.
+    elem: Element,
+    /// The element's fields. A field's index is its ID.
+    fields: Vec<DynamicField>,
+    /// Turns an instance into content.
+    display: Option<Func>,
+    /// Parameter information for each field.
+    params: &'static [ParamInfo],
+    /// A hash of the definition.
+    hash: u128,
+}
+
+/// A field of a user-defined element.
+#[derive(Debug, Clone)]
+struct DynamicField {
+    /// The field's name.
+    name: &'static str,
+    /// The types the field accepts, if restricted.
+    annotation: Option<TypeAnnotation>,
+    /// The field's default value, if it is settable.
+    default: Option<Value>,
+    /// Whether the field is given positionally.
+    positional: bool,
+}
+
+impl DynamicElementData {
+    /// Create a definition from the specification of its fields.
+    fn new(elem: Element, specs: Vec<(Str, FieldSpec)>, display: Option<Func>) -> Self {
+        let hash = hash128(&(elem, &specs, &display));
+        let fields: Vec<_> = specs
+            .into_iter()
+            .map(|(key, spec)| DynamicField {
+                name: intern_name(&key),
+                positional: spec.positional.unwrap_or(spec.required),
+                default: (!spec.required).then(|| spec.default.unwrap_or(Value::None)),
+                annotation: spec.annotation,
+            })
+            .collect();
+
+        // The parameter information only depends on the fields' names, types
+        // and kinds, so it is shared between definitions that agree on them.
+        let signature = hash128(
+            &fields
+                .iter()
+                .map(|field| {
+                    (
+                        field.name,
+                        &field.annotation,
+                        field.positional,
+                        field.default.is_some(),
+                    )
+                })
+                .collect::<Vec<_>>(),
+        );
+        let params = intern(&PARAMS, signature, || {
+            fields
+                .iter()
+                .map(|field| ParamInfo {
+                    name: field.name.into(),
+                    docs: "",
+                    input: field.input(),
+                    default: None,
+                    positional: field.positional,
+                    named: !field.positional,
+                    variadic: false,
+                    required: field.default.is_none(),
+                    settable: field.default.is_some(),
+                })
+                .collect::<Vec<_>>()
+                .leak()
+        });
+
+        Self { elem, fields, display, params, hash }
+    }
+
+    /// The element this is a definition of.
+    pub fn elem(&self) -> Element {
+        self.elem
+    }
+
+    /// Details about the element's fields.
+    pub fn params(&self) -> &'static [ParamInfo] {
+        self.params
+    }
+
+    /// Extract the field ID for the given field name.
+    pub fn field_id(&self, name: &str) -> Option<u8> {
+        self.fields
+            .iter()
+            .position(|field| field.name == name)
+            .map(|i| i as u8)
+    }
+
+    /// Extract the field name for the given field ID.
+    pub fn field_name(&self, id: u8) -> Option<&'static str> {
+        self.fields.get(usize::from(id)).map(|field| field.name)
+    }
+
+    /// Extract the value of a settable field from the styles.
+    pub fn field_from_styles(
+        &self,
+        id: u8,
+        styles: StyleChain,
+    ) -> Result<Value, FieldAccessError> {
+        let field = self.fields.get(usize::from(id)).ok_or(FieldAccessError::Unknown)?;
+        let default = field.default.as_ref().ok_or(FieldAccessError::Unknown)?;
+        Ok(styles.get(self.elem, id, None, || default.clone()))
+    }
+
+    /// Construct an instance of the element.
+    pub fn construct(self: &Arc<Self>, args: &mut Args) -> SourceResult<Content> {
+        let mut values = EcoVec::with_capacity(self.fields.len());
+        for field in &self.fields {
+            let value = field.parse(args)?;
+            if value.is_none() && field.default.is_none() {
+                bail!(args.span, "missing argument: {}", field.name);
+            }
+            values.push(value);
+        }
+        Ok(DynamicElem { definition: self.clone(), values }
+            .pack()
+            .spanned(args.span))
+    }
+
+    /// Parse the arguments of a set rule into style properties.
+    pub fn set(&self, args: &mut Args) -> SourceResult<Styles> {
+        let mut styles = Styles::new();
+        for (id, field) in self.fields.iter().enumerate() {
+            if field.default.is_none() {
+                continue;
+            }
+            if let Some(value) = field.parse(args)? {
+                styles.set(Property::dynamic(self.elem, id as u8, value));
+            }
+        }
+        Ok(styles)
+    }
+}
+
+impl PartialEq for DynamicElementData {
+    fn eq(&self, other: &Self) -> bool {
+        self.hash == other.hash
+    }
+}
+
+impl Hash for DynamicElementData {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        state.write_u128(self.hash);
+    }
+}
+
+impl DynamicField {
+    /// Describes what values the field accepts.
+    fn input(&self) -> CastInfo {
+        self.annotation.as_ref().map_or(CastInfo::Any, TypeAnnotation::info)
+    }
+
+    /// Take the field's value from the arguments, if present, and check it.
+    fn parse(&self, args: &mut Args) -> SourceResult<Option<Value>> {
+        let value = if self.positional {
+            args.find::<Spanned<Value>>()?
+        } else {
+            args.named::<Spanned<Value>>(self.name)?
+        };
+        value
+            .map(|Spanned { v, span }| {
+                check(self.annotation.as_ref(), self.default.as_ref(), v).at(span)
+            })
+            .transpose()
+    }
+}
+
+/// Check that a value fits a field's type annotation, coercing it if
+/// necessary. A `none` value is also accepted if the field defaults to
+/// `{none}`.
+fn check(
+    annotation: Option<&TypeAnnotation>,
+    default: Option<&Value>,
+    value: Value,
+) -> HintedStrResult<Value> {
+    match annotation {
+        Some(_) if matches!((&value, default), (Value::None, Some(Value::None))) => {
+            Ok(value)
+        }
+        Some(annotation) => annotation.check(value),
+        None => Ok(value),
+    }
+}
+
+/// The specification of a field in an element definition.
+#[derive(Debug, Clone, PartialEq, Hash)]
+struct FieldSpec {
+    annotation: Option<TypeAnnotation>,
+    default: Option<Value>,
+    required: bool,
+    positional: Option<bool>,
+}
+
+cast! {
+    FieldSpec,
+    ty: Type => Self {
+        annotation: Some(ty.into()),
+        default: None,
+        required: false,
+        positional: None,
+    },
+    mut dict: Dict => {
+        let annotation = dict.take("type").ok().map(Value::cast).transpose()?;
+        let default = dict.take("default").ok();
+        let required = dict.take("required").ok().map(Value::cast).transpose()?.unwrap_or(false);
+        let positional = dict.take("positional").ok().map(Value::cast).transpose()?;
+        dict.finish(&["type", "default", "required", "positional"])?;
+
+        if required && default.is_some() {
+            bail!("a required field cannot have a default value");
+        }
+        let default = match default {
+            Some(value) => Some(check(annotation.as_ref(), None, value).map_err(|err| {
+                eco_format!("default value does not match type: {}", err.message())
+            })?),
+            None => None,
+        };
+
+        Self { annotation, default, required, positional }
+    },
+}
+
+/// An instance of a user-defined element.
+///
+/// All user-defined elements share this Rust type. The definition an instance
+/// was created with, which also determines its element, is stored alongside
+/// its field values.
+#[derive(Debug, Clone, PartialEq, Hash)]
+pub struct DynamicElem {
+    /// The definition of the element this is an instance of.
+    definition: Arc<DynamicElementData>,
+    /// The values of the fields, indexed by field ID. Settable fields that
+    /// were not given are `None` until materialization.
+    values: EcoVec<Option<Value>>,
+}
+
+impl NativeElement for DynamicElem {
+    fn data() -> &'static NativeElementData {
+        static DATA: NativeElementData = base();
+        &DATA
+    }
+
+    fn instance_elem(&self) -> Element {
+        self.definition.elem
+    }
+
+    fn instance_func(&self) -> Func {
+        self.definition.clone().into()
+    }
+}
+
+/// The native data shared by all user-defined elements. Their actual behaviour
+/// is determined by the dynamic data.
+const fn base() -> NativeElementData {
+    NativeElementData {
+        name: "element",
+        title: "Element",
+        docs: "",
+        keywords: &[],
+        construct: <DynamicElem as Construct>::construct,
+        set: <DynamicElem as Set>::set,
+        vtable: <DynamicElem as crate::foundations::Capable>::vtable,
+        field_id: |_| None,
+        field_name: |_| None,
+        field_from_styles: <DynamicElem as Fields>::field_from_styles,
+        local_name: None,
+        scope: Lazy::new(Scope::new),
+        params: Lazy::new(Vec::new),
+    }
+}
+
+impl Construct for DynamicElem {
+    fn construct(_: &mut Engine, args: &mut Args) -> SourceResult<Content> {
+        bail!(args.span, "cannot construct a user-defined element without its definition")
+    }
+}
+
+impl Set for DynamicElem {
+    fn set(_: &mut Engine, args: &mut Args) -> SourceResult<Styles> {
+        bail!(args.span, "cannot configure a user-defined element without its definition")
+    }
+}
+
+// Safety: The vtables are those of `Packed<DynamicElem>` w.r.t. the
+// respective traits.
+unsafe impl crate::foundations::Capable for DynamicElem {
+    fn vtable(capability: TypeId) -> Option<NonNull<()>> {
+        let dangling = NonNull::<Packed<DynamicElem>>::dangling().as_ptr();
+        if capability == TypeId::of::<dyn Show>() {
+            // Safety: The vtable function doesn't require initialized
+            // data, so it's fine to use a dangling pointer.
+            return Some(unsafe {
+                crate::utils::fat::vtable(dangling as *const dyn Show)
+            });
+        }
+        if capability == TypeId::of::<dyn Locatable>() {
+            // Safety: See above.
+            return Some(unsafe {
+                crate::utils::fat::vtable(dangling as *const dyn Locatable)
+            });
+        }
+        None
+    }
+}
+
+impl Fields for DynamicElem {
+    type Enum = u8;
+
+    fn has(&self, id: u8) -> bool {
+        matches!(self.values.get(usize::from(id)), Some(Some(_)))
+    }
+
+    fn field(&self, id: u8) -> Result<Value, FieldAccessError> {
+        match self.values.get(usize::from(id)) {
+            Some(Some(value)) => Ok(value.clone()),
+            Some(None) => Err(FieldAccessError::Unset),
+            None => Err(FieldAccessError::Unknown),
+        }
+    }
+
+    fn field_with_styles(
+        &self,
+        id: u8,
+        styles: StyleChain,
+    ) -> Result<Value, FieldAccessError> {
+        match self.values.get(usize::from(id)) {
+            Some(Some(value)) => Ok(value.clone()),
+            Some(None) => self.definition.field_from_styles(id, styles),
+            None => Err(FieldAccessError::Unknown),
+        }
+    }
+
+    fn field_from_styles(_: u8, _: StyleChain) -> Result<Value, FieldAccessError> {
+        Err(FieldAccessError::Unknown)
+    }
+
+    fn materialize(&mut self, styles: StyleChain) {
+        let definition = self.definition.clone();
+        for (id, slot) in self.values.make_mut().iter_mut().enumerate() {
+            if slot.is_none() {
+                *slot = definition.field_from_styles(id as u8, styles).ok();
+            }
+        }
+    }
+
+    fn fields(&self) -> Dict {
+        self.definition
+            .fields
+            .iter()
+            .zip(&self.values)
+            .filter_map(|(field, value)| Some((field.name.into(), value.clone()?)))
+            .collect()
+    }
+}
+
+impl Repr for DynamicElem {
+    fn repr(&self) -> EcoString {
+        let fields = self
+            .fields()
+            .into_iter()
+            .map(|(name, value)| eco_format!("{}: {}", name, value.repr()))
+            .collect::<Vec<_>>();
+        eco_format!(
+            "{}{}",
+            self.definition.elem.name(),
+            repr::pretty_array_like(&fields, false)
+        )
+    }
+}
+
+impl Locatable for Packed<DynamicElem> {}
+
+impl Show for Packed<DynamicElem> {
+    fn show(&self, engine: &mut Engine, styles: StyleChain) -> SourceResult<Content> {
+        let Some(display) = &self.definition.display else {
+            return Ok(Content::empty());
+        };
+        let context = Context::new(self.location(), Some(styles));
+        Ok(display
+            .call(engine, context.track(), [self.clone().pack()])?
+            .display()
+            .spanned(self.span()))
+    }
+}
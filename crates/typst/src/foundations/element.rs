@@ -191,6 +191,29 @@ pub trait NativeElement:
         Element::from(Self::data())
     }
 
+    /// Get the element of this instance.
+    ///
+    /// This is the same as [`elem`](Self::elem) for all native elements.
+    /// Instances of user-defined elements share a single Rust type and
+    /// override this to return the element they belong to.
+    fn instance_elem(&self) -> Element
+    where
+        Self: Sized,
+    {
+        Self::elem()
+    }
+
+    /// Get the element function of this instance.
+    ///
+    /// User-defined elements override this to return a function that carries
+    /// the definition the instance was created with.
+    fn instance_func(&self) -> Func
+    where
+        Self: Sized,
+    {
+        self.instance_elem().into()
+    }
+
     /// Pack the element into type-erased content.
     fn pack(self) -> Content
     where
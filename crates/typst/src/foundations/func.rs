@@ -7,12 +7,14 @@ use once_cell::sync::Lazy;
 
 use crate::diag::{bail, SourceResult, StrResult};
 use crate::engine::Engine;
+use crate::eval::Compiled;
 use crate::foundations::{
-    cast, repr, scope, ty, Args, CastInfo, Content, Context, Element, IntoArgs, Scope,
-    Selector, Type, Value,
+    cast, repr, scope, ty, Args, CastInfo, Content, Context, DynamicElementData, Element,
+    FieldAccessError, IntoArgs, Scope, Selector, StyleChain, Styles, Type,
+    TypeAnnotation, Value,
 };
 use crate::syntax::{ast, Span, SyntaxNode};
-use crate::utils::{singleton, LazyHash, Static};
+use crate::utils::{LazyHash, Static};
 
 #[doc(inline)]
 pub use typst_macros::func;
@@ -106,6 +108,33 @@ pub use typst_macros::func;
 /// ]
 /// ```
 ///
+/// # Type annotations
+/// A function can declare which types its parameters and its return value
+/// have. A positional parameter is annotated as `x as int` and a named
+/// parameter as `x: int = 1`, with its default value after the type. Writing
+/// `-> type` after the parameter list annotates the return value. An
+/// annotation is either a single type or an array of types, any of which is
+/// accepted.
+///
+/// Positional parameters can't be annotated as `x: int` because that syntax
+/// already declares a named parameter `x` whose default value is the type
+/// `int` itself. Existing functions rely on this, so it keeps its meaning.
+///
+/// The arguments and the return value are checked whenever the function is
+/// called. Just like for built-in functions, strings are accepted as
+/// [content] and integers as [floats]($float).
+///
+/// ```example
+/// #let badge(body as content, fill: color = aqua) -> content = box(
+///   fill: fill,
+///   inset: 3pt,
+///   radius: 2pt,
+///   body,
+/// )
+///
+/// #badge[New] #badge("Beta", fill: yellow)
+/// ```
+///
 /// # Importing functions
 /// Functions can be imported from one file ([`module`]($scripting/#modules)) into
 /// another using `{import}`. For example, assume that we have defined the `alert`
@@ -150,6 +179,8 @@ enum Repr {
     Native(Static<NativeFuncData>),
     /// A function for an element.
     Element(Element),
+    /// A function for a user-defined element, along with its definition.
+    DynamicElement(Arc<DynamicElementData>),
     /// A user-defined closure.
     Closure(Arc<LazyHash<Closure>>),
     /// A nested function with pre-applied arguments.
@@ -164,6 +195,7 @@ impl Func {
         match &self.repr {
             Repr::Native(native) => Some(native.name),
             Repr::Element(elem) => Some(elem.name()),
+            Repr::DynamicElement(data) => Some(data.elem().name()),
             Repr::Closure(closure) => closure.name(),
             Repr::With(with) => with.0.name(),
         }
@@ -176,6 +208,7 @@ impl Func {
         match &self.repr {
             Repr::Native(native) => Some(native.title),
             Repr::Element(elem) => Some(elem.title()),
+            Repr::DynamicElement(data) => Some(data.elem().title()),
             Repr::Closure(_) => None,
             Repr::With(with) => with.0.title(),
         }
@@ -186,6 +219,7 @@ impl Func {
         match &self.repr {
             Repr::Native(native) => Some(native.docs),
             Repr::Element(elem) => Some(elem.docs()),
+            Repr::DynamicElement(data) => Some(data.elem().docs()),
             Repr::Closure(_) => None,
             Repr::With(with) => with.0.docs(),
         }
@@ -200,28 +234,29 @@ impl Func {
     }
 
     /// Get details about this function's parameters if available.
-    pub fn params(&self) -> Option<&'static [ParamInfo]> {
+    pub fn params(&self) -> Option<&[ParamInfo]> {
         match &self.repr {
             Repr::Native(native) => Some(&native.0.params),
             Repr::Element(elem) => Some(elem.params()),
-            Repr::Closure(_) => None,
+            Repr::DynamicElement(data) => Some(data.params()),
+            Repr::Closure(closure) => Some(&closure.params),
             Repr::With(with) => with.0.params(),
         }
     }
 
     /// Get the parameter info for a parameter with the given name if it exist.
-    pub fn param(&self, name: &str) -> Option<&'static ParamInfo> {
+    pub fn param(&self, name: &str) -> Option<&ParamInfo> {
         self.params()?.iter().find(|param| param.name == name)
     }
 
     /// Get details about the function's return type.
-    pub fn returns(&self) -> Option<&'static CastInfo> {
+    pub fn returns(&self) -> Option<CastInfo> {
         match &self.repr {
-            Repr::Native(native) => Some(&native.0.returns),
-            Repr::Element(_) => {
-                Some(singleton!(CastInfo, CastInfo::Type(Type::of::<Content>())))
+            Repr::Native(native) => Some(native.0.returns.clone()),
+            Repr::Element(_) | Repr::DynamicElement(_) => {
+                Some(CastInfo::Type(Type::of::<Content>()))
             }
-            Repr::Closure(_) => None,
+            Repr::Closure(closure) => closure.returns.as_ref().map(TypeAnnotation::info),
             Repr::With(with) => with.0.returns(),
         }
     }
@@ -231,6 +266,7 @@ impl Func {
         match &self.repr {
             Repr::Native(native) => native.keywords,
             Repr::Element(elem) => elem.keywords(),
+            Repr::DynamicElement(data) => data.elem().keywords(),
             Repr::Closure(_) => &[],
             Repr::With(with) => with.0.keywords(),
         }
@@ -241,6 +277,7 @@ impl Func {
         match &self.repr {
             Repr::Native(native) => Some(&native.0.scope),
             Repr::Element(elem) => Some(elem.scope()),
+            Repr::DynamicElement(data) => Some(data.elem().scope()),
             Repr::Closure(_) => None,
             Repr::With(with) => with.0.scope(),
         }
@@ -261,12 +298,61 @@ impl Func {
 
     /// Extract the element function, if it is one.
     pub fn element(&self) -> Option<Element> {
-        match self.repr {
-            Repr::Element(func) => Some(func),
+        match &self.repr {
+            Repr::Element(func) => Some(*func),
+            Repr::DynamicElement(data) => Some(data.elem()),
             _ => None,
         }
     }
 
+    /// Extract the field ID for the given field name, if this is an element
+    /// function with such a field.
+    pub fn field_id(&self, name: &str) -> Option<u8> {
+        match &self.repr {
+            Repr::DynamicElement(data) if name != "label" => data.field_id(name),
+            _ => self.element()?.field_id(name),
+        }
+    }
+
+    /// Extract the field name for the given field ID, if this is an element
+    /// function with such a field.
+    pub fn field_name(&self, id: u8) -> Option<&'static str> {
+        match &self.repr {
+            Repr::DynamicElement(data) if id != 255 => data.field_name(id),
+            _ => self.element()?.field_name(id),
+        }
+    }
+
+    /// Extract the value of the field for the given field ID and style chain,
+    /// if this is an element function.
+    pub fn field_from_styles(
+        &self,
+        id: u8,
+        styles: StyleChain,
+    ) -> Result<Value, FieldAccessError> {
+        match &self.repr {
+            Repr::DynamicElement(data) => data.field_from_styles(id, styles),
+            _ => self
+                .element()
+                .ok_or(FieldAccessError::Unknown)?
+                .field_from_styles(id, styles),
+        }
+    }
+
+    /// Execute the set rule for the element function and return the resulting
+    /// style map.
+    pub fn set(&self, engine: &mut Engine, mut args: Args) -> SourceResult<Styles> {
+        match &self.repr {
+            Repr::DynamicElement(data) => {
+                let styles = data.set(&mut args)?;
+                args.finish()?;
+                Ok(styles)
+            }
+            Repr::Element(elem) => elem.set(engine, args),
+            _ => bail!(args.span, "only element functions can be used in set rules"),
+        }
+    }
+
     /// Call the function with the given context and arguments.
     pub fn call<A: IntoArgs>(
         &self,
@@ -296,6 +382,11 @@ impl Func {
                 args.finish()?;
                 Ok(Value::Content(value))
             }
+            Repr::DynamicElement(data) => {
+                let value = data.construct(&mut args)?;
+                args.finish()?;
+                Ok(Value::Content(value))
+            }
             Repr::Closure(closure) => crate::eval::call_closure(
                 self,
                 closure,
@@ -379,7 +470,7 @@ impl Func {
         let fields = fields
             .into_iter()
             .map(|(key, value)| {
-                element.field_id(&key).map(|id| (id, value)).ok_or_else(|| {
+                self.field_id(&key).map(|id| (id, value)).ok_or_else(|| {
                     eco_format!(
                         "element `{}` does not have field `{}`",
                         element.name(),
@@ -435,6 +526,12 @@ impl From<Element> for Func {
     }
 }
 
+impl From<Arc<DynamicElementData>> for Func {
+    fn from(data: Arc<DynamicElementData>) -> Self {
+        Repr::DynamicElement(data).into()
+    }
+}
+
 /// A Typst function that is defined by a native Rust type that shadows a
 /// native Rust function.
 pub trait NativeFunc {
@@ -481,10 +578,10 @@ cast! {
 }
 
 /// Describes a function parameter.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Hash)]
 pub struct ParamInfo {
     /// The parameter's name.
-    pub name: &'static str,
+    pub name: EcoString,
     /// Documentation for the parameter.
     pub docs: &'static str,
     /// Describe what values this parameter accepts.
@@ -519,6 +616,14 @@ pub struct Closure {
     pub captured: Scope,
     /// The number of positional parameters in the closure.
     pub num_pos_params: usize,
+    /// Type annotations of the parameters, in order.
+    pub annotations: Vec<Option<TypeAnnotation>>,
+    /// The type annotation of the return value.
+    pub returns: Option<TypeAnnotation>,
+    /// Details about the parameters.
+    pub params: Vec<ParamInfo>,
+    /// The closure's body, compiled on first call.
+    pub(crate) compiled: Compiled,
 }
 
 impl Closure {
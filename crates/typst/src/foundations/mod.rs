@@ -18,6 +18,7 @@ mod datetime;
 mod decimal;
 mod dict;
 mod duration;
+mod dynamic;
 mod element;
 mod fields;
 mod float;
@@ -27,6 +28,7 @@ mod label;
 mod methods;
 mod module;
 mod none;
+mod number;
 mod plugin;
 mod scope;
 mod selector;
@@ -47,6 +49,7 @@ pub use self::datetime::*;
 pub use self::decimal::*;
 pub use self::dict::*;
 pub use self::duration::*;
+pub use self::dynamic::*;
 pub use self::element::*;
 pub use self::fields::*;
 pub use self::float::*;
@@ -56,6 +59,7 @@ pub use self::label::*;
 pub(crate) use self::methods::*;
 pub use self::module::*;
 pub use self::none::*;
+pub use self::number::*;
 pub use self::plugin::*;
 pub use self::repr::Repr;
 pub use self::scope::*;
@@ -116,6 +120,8 @@ pub(super) fn define(global: &mut Scope, inputs: Dict) {
     global.define_func::<assert>();
     global.define_func::<eval>();
     global.define_func::<style>();
+    global.define_func::<define_element>();
+    global.define_func::<format_number>();
     global.define_module(calc::module());
     global.define_module(sys::module(inputs));
 }
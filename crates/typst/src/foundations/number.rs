@@ -0,0 +1,527 @@
+use comemo::Tracked;
+use ecow::EcoString;
+
+use crate::diag::{bail, HintedStrResult};
+use crate::foundations::calc::DecNum;
+use crate::foundations::{func, repr, Cast, Context, Repr, Smart, Str};
+use crate::text::{Lang, Region, TextElem};
+
+/// Formats a number as a string, following the conventions of the current
+/// language and region.
+///
+/// The decimal separator, digit grouping, percent sign and currency placement
+/// default to those customary for the [text language]($text.lang) and
+/// [region]($text.region) at the call site. Each of them can also be specified
+/// explicitly. Since the result depends on the surrounding styles, this
+/// function is [contextual]($context).
+///
+/// # Example
+/// ```example
+/// #context format-number(1234567.891, precision: 2) \
+/// #context format-number(0.256, style: "percent") \
+/// #context format-number(
+///   299792458,
+///   notation: "scientific",
+///   precision: 3,
+///   unit: "m/s",
+/// )
+///
+/// #set text(lang: "de", region: "de")
+/// #context format-number(1234567.891, precision: 2) \
+/// #context format-number(decimal("19.9"), style: "currency")
+/// ```
+#[func(contextual)]
+#[allow(clippy::too_many_arguments)]
+pub fn format_number(
+    /// The callsite context.
+    context: Tracked<Context>,
+    /// The number to format.
+    number: DecNum,
+    /// The number of digits to show after the decimal separator.
+    ///
+    /// If `{auto}`, all digits of the number are shown, except for currency
+    /// amounts, which show the customary number of minor digits of their
+    /// currency.
+    #[named]
+    #[default]
+    precision: Smart<u8>,
+    /// How to round the number to the requested precision.
+    #[named]
+    #[default]
+    rounding: Rounding,
+    /// Whether to separate the digits of the integer part into groups.
+    #[named]
+    #[default(true)]
+    grouping: bool,
+    /// Whether to show the number in standard, scientific or engineering
+    /// notation.
+    ///
+    /// ```example
+    /// #context format-number(0.000123, notation: "scientific") \
+    /// #context format-number(0.000123, notation: "engineering")
+    /// ```
+    #[named]
+    #[default]
+    notation: Notation,
+    /// Whether the number is a plain decimal number, a percentage or a
+    /// currency amount.
+    #[named]
+    #[default]
+    style: NumberStyle,
+    /// The currency of a currency amount, as an ISO 4217 code like `{"EUR"}`
+    /// or as a symbol.
+    ///
+    /// If `{auto}`, the currency is derived from the
+    /// [text region]($text.region).
+    #[named]
+    #[default]
+    currency: Smart<Str>,
+    /// A unit to show after the number, separated by a narrow no-break space.
+    #[named]
+    #[default]
+    unit: Option<Str>,
+    /// The separator between the integer and the fractional part.
+    ///
+    /// If `{auto}`, it is derived from the text language and region.
+    #[named]
+    #[default]
+    decimal_separator: Smart<Str>,
+    /// The separator between digit groups.
+    ///
+    /// If `{auto}`, it is derived from the text language and region.
+    ///
+    /// ```example
+    /// #context format-number(1234567, group-separator: "'")
+    /// ```
+    #[named]
+    #[default]
+    group_separator: Smart<Str>,
+) -> HintedStrResult<Str> {
+    let styles = context.styles()?;
+    let lang = TextElem::lang_in(styles);
+    let region = TextElem::region_in(styles);
+    let locale = NumberLocale::get(lang, region);
+
+    let mut digits = match number {
+        DecNum::Int(v) => Digits::parse(&v.to_string()),
+        DecNum::Float(v) if !v.is_finite() => {
+            bail!("cannot format {}", v.repr())
+        }
+        DecNum::Float(v) => Digits::parse(&format!("{v:e}")),
+        DecNum::Decimal(v) => Digits::parse(&v.to_string()),
+    };
+
+    let mut precision = precision.custom().map(usize::from);
+    let (mut prefix, mut suffix) = (EcoString::new(), EcoString::new());
+    match style {
+        NumberStyle::Decimal => {}
+        NumberStyle::Percent => {
+            digits.point += 2;
+            prefix.push_str(locale.percent.0);
+            suffix.push_str(locale.percent.1);
+        }
+        NumberStyle::Currency => {
+            let code = match &currency {
+                Smart::Custom(code) => code.as_str(),
+                Smart::Auto => match region.and_then(default_currency) {
+                    Some(code) => code,
+                    None => bail!(
+                        "cannot determine the currency";
+                        hint: "specify it with the `currency` argument \
+                               or set the text region"
+                    ),
+                },
+            };
+            let (symbol, minor) = currency_symbol(code);
+            precision.get_or_insert(minor);
+            if locale.currency_prefix {
+                prefix.push_str(symbol);
+                if locale.currency_space || symbol.chars().all(char::is_alphabetic) {
+                    prefix.push('\u{A0}');
+                }
+            } else {
+                suffix.push('\u{A0}');
+                suffix.push_str(symbol);
+            }
+        }
+    }
+
+    let mut exponent = match notation {
+        Notation::Standard => None,
+        _ if digits.is_zero() => {
+            digits.point = 0;
+            Some(0)
+        }
+        Notation::Scientific => Some(digits.point - 1),
+        Notation::Engineering => Some((digits.point - 1).div_euclid(3) * 3),
+    };
+
+    if let Some(e) = exponent {
+        digits.point -= e;
+    }
+
+    if let Some(precision) = precision {
+        digits.round(precision as isize, rounding);
+    }
+
+    // Rounding may carry over into a new integer digit, which must be moved
+    // into the exponent.
+    if let Some(e) = &mut exponent {
+        let step = if notation == Notation::Engineering { 3 } else { 1 };
+        if digits.point > step {
+            digits.point -= step;
+            *e += step;
+        }
+    }
+
+    let decimal = decimal_separator.as_ref().map_or(locale.decimal, Str::as_str);
+    let group = group_separator.as_ref().map_or(locale.group, Str::as_str);
+
+    let mut out = EcoString::new();
+    if digits.negative && !digits.is_zero() {
+        out.push_str(repr::MINUS_SIGN);
+    }
+
+    out.push_str(&prefix);
+
+    let integer = digits.integer();
+    if grouping {
+        out.push_str(&group_digits(&integer, group, locale.indian));
+    } else {
+        out.push_str(&integer);
+    }
+
+    let fraction = digits.fraction(precision);
+    if !fraction.is_empty() {
+        out.push_str(decimal);
+        out.push_str(&fraction);
+    }
+
+    if let Some(e) = exponent {
+        out.push_str("\u{A0}×\u{A0}10");
+        out.extend(e.to_string().chars().map(superscript));
+    }
+
+    out.push_str(&suffix);
+
+    if let Some(unit) = unit {
+        out.push('\u{202F}');
+        out.push_str(&unit);
+    }
+
+    Ok(out.into())
+}
+
+/// How to round a number to the requested precision.
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq, Hash, Cast)]
+pub enum Rounding {
+    /// Round to the nearest value, with ties away from zero.
+    #[default]
+    HalfUp,
+    /// Round to the nearest value, with ties towards zero.
+    HalfDown,
+    /// Round to the nearest value, with ties towards an even last digit.
+    HalfEven,
+    /// Round away from zero.
+    Up,
+    /// Round towards zero.
+    Down,
+    /// Round towards positive infinity.
+    Ceiling,
+    /// Round towards negative infinity.
+    Floor,
+}
+
+/// How to show the magnitude of a number.
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq, Hash, Cast)]
+pub enum Notation {
+    /// Show all integer digits, as in `12300`.
+    #[default]
+    Standard,
+    /// Show one integer digit and a power of ten, as in `1.23 × 10⁴`.
+    Scientific,
+    /// Show one to three integer digits and a power of ten whose exponent is
+    /// a multiple of three, as in `12.3 × 10³`.
+    Engineering,
+}
+
+/// What kind of quantity a number represents.
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq, Hash, Cast)]
+pub enum NumberStyle {
+    /// A plain number.
+    #[default]
+    Decimal,
+    /// A percentage. The number is multiplied by 100 and shown with a percent
+    /// sign.
+    Percent,
+    /// An amount of money, shown with a currency symbol.
+    Currency,
+}
+
+/// A number as a sequence of decimal digits.
+struct Digits {
+    /// Whether the number is negative.
+    negative: bool,
+    /// The decimal digits, without leading zeros.
+    digits: Vec<u8>,
+    /// The number of digits before the decimal point. May be negative or
+    /// exceed the number of digits.
+    point: isize,
+}
+
+impl Digits {
+    /// Parses a number from its textual representation, with an optional
+    /// exponent like `1.5e-7`.
+    fn parse(s: &str) -> Self {
+        let (negative, s) =
+            match s.strip_prefix('-').or_else(|| s.strip_prefix(repr::MINUS_SIGN)) {
+                Some(rest) => (true, rest),
+                None => (false, s),
+            };
+
+        let (mantissa, exp) = match s.split_once('e') {
+            Some((mantissa, exp)) => (mantissa, exp.parse().unwrap_or(0)),
+            None => (s, 0),
+        };
+
+        let (integer, fraction) = mantissa.split_once('.').unwrap_or((mantissa, ""));
+        let mut digits: Vec<u8> =
+            integer.bytes().chain(fraction.bytes()).map(|b| b - b'0').collect();
+        let mut point = integer.len() as isize + exp;
+
+        let leading = digits.iter().take_while(|&&d| d == 0).count();
+        digits.drain(..leading);
+        point -= leading as isize;
+
+        Self { negative, digits, point }
+    }
+
+    /// Whether all digits are zero.
+    fn is_zero(&self) -> bool {
+        self.digits.iter().all(|&d| d == 0)
+    }
+
+    /// The digit at the given position, relative to the first digit.
+    fn digit(&self, i: isize) -> char {
+        let d = usize::try_from(i).ok().and_then(|i| self.digits.get(i));
+        char::from(b'0' + d.copied().unwrap_or(0))
+    }
+
+    /// The digits before the decimal point.
+    fn integer(&self) -> String {
+        if self.point <= 0 {
+            return "0".into();
+        }
+        (0..self.point).map(|i| self.digit(i)).collect()
+    }
+
+    /// The digits after the decimal point, padded to the given precision or
+    /// all remaining digits if there is none.
+    fn fraction(&self, precision: Option<usize>) -> String {
+        let len = precision.map_or_else(
+            || (self.digits.len() as isize - self.point).max(0),
+            |p| p as isize,
+        );
+        (self.point..self.point + len).map(|i| self.digit(i)).collect()
+    }
+
+    /// Rounds to the given number of digits after the decimal point.
+    fn round(&mut self, precision: isize, mode: Rounding) {
+        let mut keep = self.point + precision;
+        if keep >= self.digits.len() as isize {
+            return;
+        }
+
+        // If all digits are dropped, pad with leading zeros so that the
+        // increment below lands at the right position.
+        if keep < 0 {
+            self.digits.splice(0..0, std::iter::repeat(0).take((-keep) as usize));
+            self.point -= keep;
+            keep = 0;
+        }
+
+        let dropped = self.digits.split_off(keep as usize);
+        let rest_zero = dropped[1..].iter().all(|&d| d == 0);
+        if dropped[0] == 0 && rest_zero {
+            return;
+        }
+
+        let half = match dropped[0] {
+            0..=4 => std::cmp::Ordering::Less,
+            5 if rest_zero => std::cmp::Ordering::Equal,
+            _ => std::cmp::Ordering::Greater,
+        };
+
+        let odd = self.digits.last().is_some_and(|d| d % 2 == 1);
+        let up = match mode {
+            Rounding::HalfUp => half.is_ge(),
+            Rounding::HalfDown => half.is_gt(),
+            Rounding::HalfEven => half.is_gt() || (half.is_eq() && odd),
+            Rounding::Up => true,
+            Rounding::Down => false,
+            Rounding::Ceiling => !self.negative,
+            Rounding::Floor => self.negative,
+        };
+
+        if up {
+            self.increment();
+        }
+    }
+
+    /// Adds one to the last digit, carrying over as necessary.
+    fn increment(&mut self) {
+        for d in self.digits.iter_mut().rev() {
+            if *d == 9 {
+                *d = 0;
+            } else {
+                *d += 1;
+                return;
+            }
+        }
+        self.digits.insert(0, 1);
+        self.point += 1;
+    }
+}
+
+/// Inserts separators between groups of integer digits. With Indian grouping,
+/// only the last group has three digits and all others have two.
+fn group_digits(integer: &str, separator: &str, indian: bool) -> String {
+    let len = integer.len();
+    let mut out = String::new();
+    for (i, c) in integer.chars().enumerate() {
+        let remaining = len - i;
+        let boundary = if indian {
+            remaining == 3 || (remaining > 3 && (remaining - 3) % 2 == 0)
+        } else {
+            remaining % 3 == 0
+        };
+        if i > 0 && boundary {
+            out.push_str(separator);
+        }
+        out.push(c);
+    }
+    out
+}
+
+/// Converts a character of an exponent into a superscript.
+fn superscript(c: char) -> char {
+    match c {
+        '-' => '⁻',
+        '1' => '¹',
+        '2' => '²',
+        '3' => '³',
+        _ => char::from_u32(0x2070 + c.to_digit(10).unwrap_or(0)).unwrap_or(c),
+    }
+}
+
+/// The number formatting conventions of a language and region.
+struct NumberLocale {
+    /// The separator between the integer and the fractional part.
+    decimal: &'static str,
+    /// The separator between digit groups.
+    group: &'static str,
+    /// Whether to group digits in the Indian way (lakh and crore).
+    indian: bool,
+    /// The text before and after a percentage.
+    percent: (&'static str, &'static str),
+    /// Whether the currency symbol precedes the amount.
+    currency_prefix: bool,
+    /// Whether a preceding currency symbol is always separated by a space.
+    currency_space: bool,
+}
+
+impl NumberLocale {
+    /// Get the number formatting conventions for a language and region.
+    ///
+    /// For unknown languages, the English conventions are used as fallback.
+    fn get(lang: Lang, region: Option<Region>) -> Self {
+        let region = region.as_ref().map(Region::as_str);
+        let lang = lang.as_str();
+
+        let (decimal, group) = match lang {
+            "de" | "it" if matches!(region, Some("CH" | "LI")) => (".", "’"),
+            "es" if matches!(region, Some("MX" | "US")) => (".", ","),
+            "ca" | "da" | "de" | "el" | "es" | "gl" | "gr" | "hr" | "id" | "is"
+            | "it" | "nl" | "pt" | "ro" | "sl" | "sr" | "tr" | "vi" => (",", "."),
+            "fr" => (",", "\u{202F}"),
+            "bg" | "cs" | "et" | "fi" | "hu" | "lt" | "lv" | "nb" | "nn" | "no"
+            | "pl" | "ru" | "sk" | "sq" | "sv" | "ua" | "uk" => (",", "\u{A0}"),
+            _ => (".", ","),
+        };
+
+        let percent = match lang {
+            "tr" => ("%", ""),
+            "fr" => ("", "\u{202F}%"),
+            "bg" | "cs" | "da" | "de" | "es" | "et" | "fi" | "lt" | "lv" | "nb"
+            | "nn" | "no" | "ru" | "sk" | "sl" | "sv" | "ua" | "uk" => ("", "\u{A0}%"),
+            _ => ("", "%"),
+        };
+
+        let currency_prefix = match lang {
+            "de" | "it" | "fr" => matches!(region, Some("CH" | "LI")),
+            "en" | "he" | "hi" | "ja" | "ko" | "nl" | "tl" | "zh" => true,
+            _ => decimal == ".",
+        };
+
+        Self {
+            decimal,
+            group,
+            indian: lang == "hi" || region == Some("IN"),
+            percent,
+            currency_prefix,
+            currency_space: lang == "nl" || region == Some("CH"),
+        }
+    }
+}
+
+/// The ISO 4217 code of the currency used in a region.
+fn default_currency(region: Region) -> Option<&'static str> {
+    Some(match region.as_str() {
+        "US" | "EC" | "PR" | "SV" => "USD",
+        "AT" | "BE" | "CY" | "DE" | "EE" | "ES" | "FI" | "FR" | "GR" | "HR" | "IE"
+        | "IT" | "LT" | "LU" | "LV" | "MT" | "NL" | "PT" | "SI" | "SK" => "EUR",
+        "GB" => "GBP",
+        "CH" | "LI" => "CHF",
+        "JP" => "JPY",
+        "CN" => "CNY",
+        "IN" => "INR",
+        "KR" => "KRW",
+        "CA" => "CAD",
+        "AU" => "AUD",
+        "NZ" => "NZD",
+        "BR" => "BRL",
+        "MX" => "MXN",
+        "SE" => "SEK",
+        "NO" => "NOK",
+        "DK" => "DKK",
+        "PL" => "PLN",
+        "CZ" => "CZK",
+        "HU" => "HUF",
+        "TR" => "TRY",
+        "UA" => "UAH",
+        "IL" => "ILS",
+        _ => return None,
+    })
+}
+
+/// The symbol and the number of minor digits of a currency. Unknown codes are
+/// used as their own symbol.
+fn currency_symbol(code: &str) -> (&str, usize) {
+    match code {
+        "USD" => ("$", 2),
+        "EUR" => ("€", 2),
+        "GBP" => ("£", 2),
+        "JPY" => ("¥", 0),
+        "CNY" => ("¥", 2),
+        "INR" => ("₹", 2),
+        "KRW" => ("₩", 0),
+        "ILS" => ("₪", 2),
+        "UAH" => ("₴", 2),
+        "TRY" => ("₺", 2),
+        "PLN" => ("zł", 2),
+        "CZK" => ("Kč", 2),
+        "HUF" => ("Ft", 2),
+        "SEK" | "NOK" | "DKK" => ("kr", 2),
+        _ => (code, 2),
+    }
+}
@@ -2,13 +2,17 @@ use std::fmt::{self, Debug, Formatter};
 use std::hash::{Hash, Hasher};
 use std::sync::{Arc, Mutex};
 
-use ecow::{eco_format, EcoString};
+use comemo::Tracked;
+use ecow::{eco_format, EcoString, EcoVec};
 use wasmi::{AsContext, AsContextMut};
 
-use crate::diag::{bail, At, SourceResult, StrResult};
+use crate::diag::{bail, warning, At, SourceResult, StrResult};
 use crate::engine::Engine;
-use crate::foundations::{func, repr, scope, ty, Bytes};
-use crate::syntax::Spanned;
+use crate::foundations::{
+    dict, func, repr, scope, ty, Args, Array, Bytes, IntoValue, Value,
+};
+use crate::syntax::{Span, Spanned};
+use crate::utils::PicoStr;
 use crate::World;
 
 /// A WebAssembly plugin.
@@ -20,13 +24,16 @@ use crate::World;
 /// and bytes.
 ///
 /// Plugins run in isolation from your system, which means that printing,
-/// reading files, or anything like that will not be supported for security
-/// reasons. To run as a plugin, a program needs to be compiled to a 32-bit
-/// shared WebAssembly library. Many compilers will use the
-/// [WASI ABI](https://wasi.dev/) by default or as their only option (e.g.
-/// emscripten), which allows printing, reading files, etc. This ABI will not
-/// directly work with Typst. You will either need to compile to a different
-/// target or [stub all functions](https://github.com/astrale-sharp/wasm-minimal-protocol/blob/master/wasi-stub).
+/// reading arbitrary files, or anything like that will not be supported for
+/// security reasons. Instead, the host provides a few
+/// [functions](#host-functions) through which plugins can read files from the
+/// project, query fonts, and emit warnings. To run as a plugin, a program
+/// needs to be compiled to a 32-bit shared WebAssembly library. Many
+/// compilers will use the [WASI ABI](https://wasi.dev/) by default or as
+/// their only option (e.g. emscripten), which allows printing, reading files,
+/// etc. This ABI will not directly work with Typst. You will either need to
+/// compile to a different target or
+/// [stub all functions](https://github.com/astrale-sharp/wasm-minimal-protocol/blob/master/wasi-stub).
 ///
 /// # Plugins and Packages
 /// Plugins are distributed as packages. A package can make use of a plugin
@@ -105,6 +112,63 @@ use crate::World;
 ///   immediately after this function returns. If the message should be
 ///   interpreted as an error message, it should be encoded as UTF-8.
 ///
+/// # Protocol version 2
+/// Version 2 of the protocol removes the need to convert between Typst values
+/// and bytes by hand. A plugin opts into it by exporting a function
+/// `typst_protocol_version` that takes no arguments and returns `2`. Plugins
+/// that don't export this function follow version 1, which is described
+/// above.
+///
+/// ## Exports of version 2
+/// Exported functions are called in the same way as in version 1, with one
+/// difference: Plugin functions accept arbitrary Typst values instead of just
+/// byte buffers. Each argument is encoded as [CBOR]($cbor.encode) and the
+/// buffer sent to the host on success is decoded as CBOR into a Typst value.
+/// This means that a plugin function can directly return dictionaries and
+/// arrays. Error messages are still UTF-8 encoded.
+///
+/// # Host functions
+/// Plugins of both versions may import the following functions from the
+/// runtime in addition to the two described for version 1. Functions that retrieve data store
+/// it in a response buffer on the host side, which the plugin can then copy
+/// into its own memory.
+///
+/// - `(import "typst_env" "typst_read_file" (func (param i32 i32) (result i32)))`
+///
+///   Reads the file at the UTF-8 encoded path given by a pointer and a length.
+///   Relative paths are resolved relative to the file from which the plugin
+///   function was called, just like with [`read`]($read). Returns `0` on
+///   success, in which case the response holds the file's contents, and `1`
+///   on failure, in which case the response holds a UTF-8 encoded error
+///   message.
+///
+/// - `(import "typst_env" "typst_query_fonts" (func (param i32 i32) (result i32)))`
+///
+///   Looks up the fonts of the family whose UTF-8 encoded name is given by a
+///   pointer and a length. Returns `0`. The response holds a CBOR encoded
+///   array with one dictionary per font, with the keys `family`, `style`,
+///   `weight`, and `stretch`.
+///
+/// - `(import "typst_env" "typst_response_len" (func (result i32)))`
+///
+///   Returns the length of the current response.
+///
+/// - `(import "typst_env" "typst_write_response" (func (param i32)))`
+///
+///   Writes the current response into a plugin-allocated buffer, whose
+///   capacity must be at least the response's length.
+///
+/// - `(import "typst_env" "typst_warn" (func (param i32 i32)))`
+///
+///   Emits a warning with the UTF-8 encoded message given by a pointer and a
+///   length at the place where the plugin function was called.
+///
+/// # Resource limits
+/// To ensure that a faulty plugin cannot hang the compiler, each call to a
+/// plugin function may execute a limited number of WebAssembly instructions
+/// and a plugin's memory may grow to at most 256 MiB. A plugin that exceeds
+/// either budget fails with an error.
+///
 /// # Resources
 /// For more resources, check out the
 /// [wasm-minimal-protocol repository](https://github.com/astrale-sharp/wasm-minimal-protocol).
@@ -117,12 +181,21 @@ use crate::World;
 /// - A stubber for WASI
 #[ty(scope, cast)]
 #[derive(Clone)]
-pub struct Plugin(Arc<Repr>);
+pub struct Plugin {
+    /// The loaded WebAssembly module, shared between plugins with the same
+    /// bytes.
+    repr: Arc<Repr>,
+    /// A name for the plugin in timings, typically the path it was loaded
+    /// from.
+    name: EcoString,
+}
 
 /// The internal representation of a plugin.
 struct Repr {
     /// The raw WebAssembly bytes.
     bytes: Bytes,
+    /// The version of the protocol the plugin conforms to.
+    protocol: Protocol,
     /// The function defined by the WebAssembly module.
     functions: Vec<(EcoString, wasmi::Func)>,
     /// Owns all data associated with the WebAssembly module.
@@ -132,6 +205,26 @@ struct Repr {
 /// Owns all data associated with the WebAssembly module.
 type Store = wasmi::Store<StoreData>;
 
+/// The version of the plugin protocol.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+enum Protocol {
+    /// Arguments and results are raw byte buffers.
+    V1,
+    /// Arguments and results are CBOR encoded Typst values.
+    V2,
+}
+
+/// The name of the function through which a plugin declares its protocol
+/// version.
+const PROTOCOL_VERSION: &str = "typst_protocol_version";
+
+/// The amount of fuel available to each call of a plugin function. Most
+/// WebAssembly instructions consume one unit of fuel.
+const MAX_FUEL: u64 = 100_000_000;
+
+/// The maximum size of a plugin's memory in bytes.
+const MAX_MEMORY: usize = 1 << 28;
+
 /// If there was an error reading/writing memory, keep the offset + length to
 /// display an error message.
 struct MemoryError {
@@ -139,14 +232,73 @@ struct MemoryError {
     length: u32,
     write: bool,
 }
+
 /// The persistent store data used for communication between store and host.
 #[derive(Default)]
 struct StoreData {
     args: Vec<Bytes>,
     output: Vec<u8>,
+    response: Vec<u8>,
+    warnings: EcoVec<EcoString>,
     memory_error: Option<MemoryError>,
+    limiter: Limiter,
+}
+
+/// Limits the memory of a plugin and remembers whether the limit was hit.
+#[derive(Default)]
+struct Limiter {
+    exceeded: bool,
+}
+
+impl wasmi::ResourceLimiter for Limiter {
+    fn memory_growing(
+        &mut self,
+        _: usize,
+        desired: usize,
+        maximum: Option<usize>,
+    ) -> Result<bool, wasmi::errors::MemoryError> {
+        if desired > MAX_MEMORY {
+            self.exceeded = true;
+            return Ok(false);
+        }
+        Ok(maximum.map_or(true, |max| desired <= max))
+    }
+
+    fn table_growing(
+        &mut self,
+        _: u32,
+        desired: u32,
+        maximum: Option<u32>,
+    ) -> Result<bool, wasmi::errors::TableError> {
+        Ok(maximum.map_or(true, |max| desired <= max))
+    }
+}
+
+/// A request from the plugin that the host has to serve with access to the
+/// world.
+///
+/// Host functions raise these as errors, which suspends the plugin. The
+/// request is then served outside of the WebAssembly runtime and the plugin
+/// is resumed with the status code.
+#[derive(Debug)]
+enum Request {
+    /// Read a file relative to the calling file.
+    ReadFile(EcoString),
+    /// Look up the fonts of a family.
+    QueryFonts(EcoString),
+}
+
+impl fmt::Display for Request {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            Self::ReadFile(path) => write!(f, "read file {path}"),
+            Self::QueryFonts(family) => write!(f, "query fonts of family {family}"),
+        }
+    }
 }
 
+impl wasmi::core::HostError for Request {}
+
 #[scope]
 impl Plugin {
     /// Creates a new plugin from a WebAssembly file.
@@ -162,7 +314,8 @@ impl Plugin {
         let Spanned { v: path, span } = path;
         let id = span.resolve_path(&path).at(span)?;
         let data = engine.world.file(id).at(span)?;
-        Plugin::new(data).at(span)
+        let plugin = Plugin::new(data).at(span)?;
+        Ok(plugin.with_name(eco_format!("{id:?}")))
     }
 }
 
@@ -171,7 +324,9 @@ impl Plugin {
     #[comemo::memoize]
     #[typst_macros::time(name = "load plugin")]
     pub fn new(bytes: Bytes) -> StrResult<Plugin> {
-        let engine = wasmi::Engine::default();
+        let mut config = wasmi::Config::default();
+        config.consume_fuel(true);
+        let engine = wasmi::Engine::new(&config);
         let module = wasmi::Module::new(&engine, bytes.as_slice())
             .map_err(|err| format!("failed to load WebAssembly module ({err})"))?;
 
@@ -191,11 +346,27 @@ impl Plugin {
             )
             .unwrap();
 
+        linker
+            .func_wrap("typst_env", "typst_read_file", typst_read_file)
+            .unwrap();
+        linker
+            .func_wrap("typst_env", "typst_query_fonts", typst_query_fonts)
+            .unwrap();
+        linker
+            .func_wrap("typst_env", "typst_response_len", typst_response_len)
+            .unwrap();
+        linker
+            .func_wrap("typst_env", "typst_write_response", typst_write_response)
+            .unwrap();
+        linker.func_wrap("typst_env", "typst_warn", typst_warn).unwrap();
+
         let mut store = Store::new(&engine, StoreData::default());
+        store.limiter(|data| &mut data.limiter);
+        store.set_fuel(MAX_FUEL).unwrap();
         let instance = linker
             .instantiate(&mut store, &module)
             .and_then(|pre_instance| pre_instance.start(&mut store))
-            .map_err(|e| eco_format!("{e}"))?;
+            .map_err(|err| runtime_error(&mut store, err, |err| eco_format!("{err}")))?;
 
         // Ensure that the plugin exports its memory.
         if !matches!(
@@ -205,25 +376,120 @@ impl Plugin {
             bail!("plugin does not export its memory");
         }
 
+        // Determine the protocol version.
+        let protocol = match instance.get_func(&store, PROTOCOL_VERSION) {
+            None => Protocol::V1,
+            Some(func) => {
+                let mut version = wasmi::Val::I32(0);
+                func.call(&mut store, &[], std::slice::from_mut(&mut version))
+                    .map_err(|err| {
+                        runtime_error(&mut store, err, |err| {
+                            eco_format!(
+                                "failed to determine plugin protocol version ({err})"
+                            )
+                        })
+                    })?;
+                match version {
+                    wasmi::Val::I32(1) => Protocol::V1,
+                    wasmi::Val::I32(2) => Protocol::V2,
+                    wasmi::Val::I32(v) => {
+                        bail!("plugin requires unsupported protocol version {v}")
+                    }
+                    _ => bail!("plugin protocol version must be a 32-bit integer"),
+                }
+            }
+        };
+
         // Collect exported functions.
         let functions = instance
             .exports(&store)
+            .filter(|export| export.name() != PROTOCOL_VERSION)
             .filter_map(|export| {
                 let name = export.name().into();
                 export.into_func().map(|func| (name, func))
             })
             .collect();
 
-        Ok(Plugin(Arc::new(Repr { bytes, functions, store: Mutex::new(store) })))
+        Ok(Plugin {
+            repr: Arc::new(Repr {
+                bytes,
+                protocol,
+                functions,
+                store: Mutex::new(store),
+            }),
+            name: EcoString::new(),
+        })
+    }
+
+    /// Set the name that identifies the plugin in timings.
+    pub fn with_name(mut self, name: EcoString) -> Self {
+        self.name = name;
+        self
     }
 
     /// Call the plugin function with the given `name`.
+    ///
+    /// The arguments and the result are converted according to the plugin's
+    /// protocol version: For version 1, they are byte buffers, for version 2,
+    /// they are arbitrary values that are exchanged as CBOR.
+    pub fn call(
+        &self,
+        engine: &mut Engine,
+        name: &str,
+        mut args: Args,
+        span: Span,
+    ) -> SourceResult<Value> {
+        let buffers = match self.repr.protocol {
+            Protocol::V1 => {
+                let buffers = args.all::<Bytes>()?;
+                args.finish()?;
+                buffers
+            }
+            Protocol::V2 => {
+                let values = args.all::<Spanned<Value>>()?;
+                args.finish()?;
+                values
+                    .into_iter()
+                    .map(|Spanned { v, span }| encode(&v).map(Bytes::from).at(span))
+                    .collect::<SourceResult<_>>()?
+            }
+        };
+
+        let (output, warnings) =
+            self.call_raw(engine.world, span, name, buffers).at(span)?;
+        for message in warnings {
+            engine.sink.warn(warning!(
+                span, "{message}";
+                hint: "this warning was emitted by the plugin function `{name}`",
+            ));
+        }
+
+        match self.repr.protocol {
+            Protocol::V1 => Ok(output.into_value()),
+            Protocol::V2 => ciborium::from_reader(output.as_slice())
+                .map_err(|err| {
+                    eco_format!("plugin returned a value that is not valid CBOR ({err})")
+                })
+                .at(span),
+        }
+    }
+
+    /// Call the plugin function with the given `name` on raw byte buffers.
+    ///
+    /// Returns the output buffer and the warnings emitted by the plugin.
+    /// Requests that the plugin makes to the host are served with the given
+    /// world, resolving paths relative to the file of the `span`.
     #[comemo::memoize]
-    #[typst_macros::time(name = "call plugin")]
-    pub fn call(&self, name: &str, args: Vec<Bytes>) -> StrResult<Bytes> {
+    pub fn call_raw(
+        &self,
+        world: Tracked<dyn World + '_>,
+        span: Span,
+        name: &str,
+        args: Vec<Bytes>,
+    ) -> StrResult<(Bytes, EcoVec<EcoString>)> {
         // Find the function with the given name.
         let func = self
-            .0
+            .repr
             .functions
             .iter()
             .find(|(v, _)| v == name)
@@ -232,7 +498,15 @@ impl Plugin {
                 eco_format!("plugin does not contain a function called {name}")
             })?;
 
-        let mut store = self.0.store.lock().unwrap();
+        // Record a separate timing entry for each plugin function.
+        let _scope = typst_timing::is_enabled()
+            .then(|| {
+                let label = eco_format!("call plugin {} ({name})", self.name);
+                PicoStr::new(&label).resolve()
+            })
+            .and_then(|label| typst_timing::TimingScope::new(label, Some(span)));
+
+        let mut store = self.repr.store.lock().unwrap();
         let ty = func.ty(store.as_context());
 
         // Check function signature.
@@ -262,13 +536,42 @@ impl Plugin {
             .map(|a| wasmi::Val::I32(a.len() as i32))
             .collect::<Vec<_>>();
 
-        // Store the input data.
+        // Store the input data and refill the budget.
         store.data_mut().args = args;
+        store.data_mut().limiter.exceeded = false;
+        store.set_fuel(MAX_FUEL).unwrap();
 
-        // Call the function.
+        // Call the function, serving requests to the host whenever it is
+        // suspended.
         let mut code = wasmi::Val::I32(-1);
-        func.call(store.as_context_mut(), &lengths, std::slice::from_mut(&mut code))
-            .map_err(|err| eco_format!("plugin panicked: {err}"))?;
+        let mut call = func.call_resumable(
+            store.as_context_mut(),
+            &lengths,
+            std::slice::from_mut(&mut code),
+        );
+        loop {
+            let call_result = call.map_err(|err| {
+                runtime_error(&mut store, err, |err| {
+                    eco_format!("plugin panicked: {err}")
+                })
+            });
+            match call_result? {
+                wasmi::ResumableCall::Finished => break,
+                wasmi::ResumableCall::Resumable(invocation) => {
+                    let Some(request) = invocation.host_error().downcast_ref::<Request>()
+                    else {
+                        bail!("plugin panicked: {}", invocation.host_error());
+                    };
+                    let (status, response) = serve(world, span, request);
+                    store.data_mut().response = response;
+                    call = invocation.resume(
+                        store.as_context_mut(),
+                        &[wasmi::Val::I32(status)],
+                        std::slice::from_mut(&mut code),
+                    );
+                }
+            }
+        }
         if let Some(MemoryError { offset, length, write }) =
             store.data_mut().memory_error.take()
         {
@@ -280,6 +583,8 @@ impl Plugin {
 
         // Extract the returned data.
         let output = std::mem::take(&mut store.data_mut().output);
+        let warnings = std::mem::take(&mut store.data_mut().warnings);
+        store.data_mut().response = Vec::new();
 
         // Parse the functions return value.
         match code {
@@ -293,12 +598,12 @@ impl Plugin {
             _ => bail!("plugin did not respect the protocol"),
         };
 
-        Ok(output.into())
+        Ok((output.into(), warnings))
     }
 
     /// An iterator over all the function names defined by the plugin.
     pub fn iter(&self) -> impl Iterator<Item = &EcoString> {
-        self.0.functions.as_slice().iter().map(|(func_name, _)| func_name)
+        self.repr.functions.as_slice().iter().map(|(func_name, _)| func_name)
     }
 }
 
@@ -316,13 +621,13 @@ impl repr::Repr for Plugin {
 
 impl PartialEq for Plugin {
     fn eq(&self, other: &Self) -> bool {
-        self.0.bytes == other.0.bytes
+        self.repr.bytes == other.repr.bytes
     }
 }
 
 impl Hash for Plugin {
     fn hash<H: Hasher>(&self, state: &mut H) {
-        self.0.bytes.hash(state);
+        self.repr.bytes.hash(state);
     }
 }
 
@@ -363,3 +668,131 @@ fn wasm_minimal_protocol_send_result_to_host(
     }
     caller.data_mut().output = buffer;
 }
+
+/// Turns an error of the WebAssembly runtime into a message, reporting
+/// exceeded budgets specially and formatting other errors with `describe`.
+fn runtime_error(
+    store: &mut Store,
+    err: wasmi::Error,
+    describe: impl FnOnce(wasmi::Error) -> EcoString,
+) -> EcoString {
+    if err.as_trap_code() == Some(wasmi::core::TrapCode::OutOfFuel) {
+        "plugin exceeded its instruction budget".into()
+    } else if std::mem::take(&mut store.data_mut().limiter.exceeded) {
+        "plugin exceeded its memory budget".into()
+    } else {
+        describe(err)
+    }
+}
+
+/// Reads a UTF-8 string that the plugin passed to a host function.
+///
+/// Records a memory error and returns `None` if the string is out of bounds.
+fn read_str(
+    caller: &mut wasmi::Caller<StoreData>,
+    ptr: u32,
+    len: u32,
+) -> Option<EcoString> {
+    let memory = caller.get_export("memory").unwrap().into_memory().unwrap();
+    let mut buffer = vec![0; len as usize];
+    if memory.read(&*caller, ptr as _, &mut buffer).is_err() {
+        caller.data_mut().memory_error =
+            Some(MemoryError { offset: ptr, length: len, write: false });
+        return None;
+    }
+    Some(String::from_utf8_lossy(&buffer).into())
+}
+
+/// Requests a file from the host.
+fn typst_read_file(
+    mut caller: wasmi::Caller<StoreData>,
+    ptr: u32,
+    len: u32,
+) -> Result<i32, wasmi::Error> {
+    match read_str(&mut caller, ptr, len) {
+        Some(path) => Err(wasmi::Error::host(Request::ReadFile(path))),
+        None => Ok(1),
+    }
+}
+
+/// Requests information about the fonts of a family from the host.
+fn typst_query_fonts(
+    mut caller: wasmi::Caller<StoreData>,
+    ptr: u32,
+    len: u32,
+) -> Result<i32, wasmi::Error> {
+    match read_str(&mut caller, ptr, len) {
+        Some(family) => Err(wasmi::Error::host(Request::QueryFonts(family))),
+        None => Ok(1),
+    }
+}
+
+/// Returns the length of the current response.
+fn typst_response_len(caller: wasmi::Caller<StoreData>) -> u32 {
+    caller.data().response.len() as u32
+}
+
+/// Writes the current response into the plugin's memory.
+fn typst_write_response(mut caller: wasmi::Caller<StoreData>, ptr: u32) {
+    let memory = caller.get_export("memory").unwrap().into_memory().unwrap();
+    let response = std::mem::take(&mut caller.data_mut().response);
+    if memory.write(&mut caller, ptr as _, &response).is_err() {
+        caller.data_mut().memory_error = Some(MemoryError {
+            offset: ptr,
+            length: response.len() as u32,
+            write: true,
+        });
+    }
+    caller.data_mut().response = response;
+}
+
+/// Records a warning emitted by the plugin.
+fn typst_warn(mut caller: wasmi::Caller<StoreData>, ptr: u32, len: u32) {
+    if let Some(message) = read_str(&mut caller, ptr, len) {
+        caller.data_mut().warnings.push(message);
+    }
+}
+
+/// Serves a request of the plugin, returning the status code and response.
+fn serve(
+    world: Tracked<dyn World + '_>,
+    span: Span,
+    request: &Request,
+) -> (i32, Vec<u8>) {
+    let result = match request {
+        Request::ReadFile(path) => span
+            .resolve_path(path)
+            .and_then(|id| world.file(id).map_err(|err| err.to_string().into()))
+            .map(|data| data.to_vec()),
+        Request::QueryFonts(family) => {
+            let book = world.book();
+            let fonts: Array = book
+                .select_family(&family.to_lowercase())
+                .filter_map(|index| book.info(index))
+                .map(|info| {
+                    dict! {
+                        "family" => info.family.clone(),
+                        "style" => info.variant.style,
+                        "weight" => info.variant.weight.to_number(),
+                        "stretch" => info.variant.stretch.to_ratio().get(),
+                    }
+                    .into_value()
+                })
+                .collect();
+            encode(&fonts.into_value())
+        }
+    };
+
+    match result {
+        Ok(response) => (0, response),
+        Err(message) => (1, message.as_bytes().to_vec()),
+    }
+}
+
+/// Encodes a value as CBOR.
+fn encode(value: &Value) -> StrResult<Vec<u8>> {
+    let mut buffer = Vec::new();
+    ciborium::into_writer(value, &mut buffer)
+        .map_err(|err| eco_format!("failed to encode value as CBOR ({err})"))?;
+    Ok(buffer)
+}
@@ -131,7 +131,7 @@ impl Selector {
                     })
             }
             Self::Label(label) => target.label() == Some(*label),
-            Self::Can(cap) => target.func().can_type_id(*cap),
+            Self::Can(cap) => target.elem().can_type_id(*cap),
             Self::Or(selectors) => {
                 selectors.iter().any(move |sel| sel.matches(target, styles))
             }
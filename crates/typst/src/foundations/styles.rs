@@ -351,6 +351,22 @@ impl Property {
         }
     }
 
+    /// Create a new property for a field of an element that is not known
+    /// statically, like a user-defined one.
+    pub fn dynamic<T>(elem: Element, id: u8, value: T) -> Self
+    where
+        T: Debug + Clone + Hash + Send + Sync + 'static,
+    {
+        Self {
+            elem,
+            id,
+            value: Block::new(value),
+            span: Span::detached(),
+            liftable: false,
+            outside: false,
+        }
+    }
+
     /// Whether this property is the given one.
     pub fn is(&self, elem: Element, id: u8) -> bool {
         self.elem == elem && self.id == id
@@ -500,7 +516,7 @@ impl Recipe {
             Transformation::Func(func) => {
                 let mut result = func.call(engine, context, [content.clone()]);
                 if self.selector.is_some() {
-                    let point = || Tracepoint::Show(content.func().name().into());
+                    let point = || Tracepoint::Show(content.elem().name().into());
                     result = result.trace(engine.world, point, content.span());
                 }
                 result?.display()
@@ -1,12 +1,13 @@
 use std::cmp::Ordering;
 use std::fmt::{self, Debug, Display, Formatter};
 
-use ecow::{eco_format, EcoString};
+use ecow::{eco_format, eco_vec, EcoString, EcoVec};
 use once_cell::sync::Lazy;
 
-use crate::diag::StrResult;
+use crate::diag::{bail, HintedStrResult, StrResult};
 use crate::foundations::{
-    cast, func, AutoValue, Func, NativeFuncData, NoneValue, Repr, Scope, Value,
+    cast, func, Array, AutoValue, CastInfo, Content, Func, IntoValue, NativeFuncData,
+    NoneValue, Reflect, Repr, Scope, Value,
 };
 use crate::utils::Static;
 
@@ -222,3 +223,79 @@ cast! {
     &'static NativeTypeData,
     self => Type::from(self).into_value(),
 }
+
+/// The types a value may have, as given in a type annotation: `int`,
+/// `(str, content)`, or `none`.
+///
+/// Used to check the arguments and return values of functions with typed
+/// signatures and the fields of user-defined elements.
+#[derive(Debug, Clone, PartialEq, Hash)]
+pub struct TypeAnnotation(EcoVec<Type>);
+
+impl TypeAnnotation {
+    /// The annotated types.
+    pub fn types(&self) -> &[Type] {
+        &self.0
+    }
+
+    /// Describes what values the annotation accepts.
+    pub fn info(&self) -> CastInfo {
+        match self.0.as_slice() {
+            [ty] => CastInfo::Type(*ty),
+            types => {
+                CastInfo::Union(types.iter().map(|&ty| CastInfo::Type(ty)).collect())
+            }
+        }
+    }
+
+    /// Check that the value has one of the annotated types.
+    ///
+    /// This performs the same coercions as native functions do for their
+    /// parameters: Strings and symbols are accepted as content and integers
+    /// as floats.
+    pub fn check(&self, value: Value) -> HintedStrResult<Value> {
+        if self.0.contains(&value.ty()) {
+            return Ok(value);
+        }
+
+        if self.0.contains(&Type::of::<Content>()) && Content::castable(&value) {
+            return Ok(value.cast::<Content>()?.into_value());
+        }
+
+        if let Value::Int(int) = value {
+            if self.0.contains(&Type::of::<f64>()) {
+                return Ok(Value::Float(int as f64));
+            }
+        }
+
+        Err(self.info().error(&value))
+    }
+}
+
+impl From<Type> for TypeAnnotation {
+    fn from(ty: Type) -> Self {
+        Self(eco_vec![ty])
+    }
+}
+
+cast! {
+    TypeAnnotation,
+    self => match self.0.as_slice() {
+        [ty] => ty.into_value(),
+        types => types.iter().copied().map(IntoValue::into_value).collect::<Array>().into_value(),
+    },
+    ty: Type => ty.into(),
+    _: NoneValue => Self(eco_vec![Type::of::<NoneValue>()]),
+    _: AutoValue => Self(eco_vec![Type::of::<AutoValue>()]),
+    array: Array => {
+        let mut types = EcoVec::with_capacity(array.len());
+        for value in array {
+            let TypeAnnotation(more) = value.cast()?;
+            types.extend(more);
+        }
+        if types.is_empty() {
+            bail!("expected at least one type");
+        }
+        Self(types)
+    },
+}
@@ -308,6 +308,7 @@ impl Counter {
         let mut page = NonZeroUsize::ONE;
         let mut stops = eco_vec![(state.clone(), page)];
 
+        let plain = matches!(&self.0, CounterKey::Selector(Selector::Elem(_, None)));
         for elem in introspector.query(&self.selector()) {
             if self.is_page() {
                 let prev = page;
@@ -320,6 +321,7 @@ impl Counter {
             }
 
             if let Some(update) = match elem.with::<dyn Count>() {
+                Some(countable) if plain && !countable.in_plain_counter() => None,
                 Some(countable) => countable.update(),
                 None => Some(CounterUpdate::Step(NonZeroUsize::ONE)),
             } {
@@ -648,6 +650,13 @@ cast! {
 pub trait Count {
     /// Get the counter update for this element.
     fn update(&self) -> Option<CounterUpdate>;
+
+    /// Whether the element is counted by the plain counter of its element
+    /// function (e.g. `{counter(footnote)}`). If not, it is only counted by
+    /// counters with a more specific selector.
+    fn in_plain_counter(&self) -> bool {
+        true
+    }
 }
 
 /// Counts through elements with different levels.
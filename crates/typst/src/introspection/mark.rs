@@ -0,0 +1,180 @@
+use comemo::Tracked;
+
+use crate::diag::{At, SourceResult};
+use crate::engine::Engine;
+use crate::foundations::{
+    elem, func, scope, select_where, Content, Context, Packed, Show, Str, StyleChain,
+    Value,
+};
+use crate::introspection::Locatable;
+use crate::syntax::Span;
+
+/// Sets a named mark for running headers and footers.
+///
+/// Marks are invisible and don't affect layout. Instead, the [header]($page.header)
+/// and [footer]($page.footer) of a page can ask for the [first]($mark.first),
+/// [last]($mark.last), or [inherited]($mark.top) mark of a name on that page.
+/// This is useful for dictionary-style headers, which show the first and last
+/// entry of a page, or for chapter-style headers, which show the chapter that
+/// is in effect at the top of the page.
+///
+/// Marks with different names are tracked independently. Within a page, marks
+/// are ordered in the order in which they appear in the document.
+///
+/// # Example
+/// ```example
+/// >>> #set page(width: 120pt, height: 120pt)
+/// #set page(header: context {
+///   let first = mark.first("entry")
+///   let last = mark.last("entry")
+///   if first != none [*#first -- #last*]
+/// })
+///
+/// #let entry(term, body) = {
+///   mark("entry", term)
+///   [*#term:* #body]
+///   parbreak()
+/// }
+///
+/// #entry[Apple][A fruit.]
+/// #entry[Banana][Also a fruit.]
+/// #entry[Cherry][Yet another fruit.]
+/// #entry[Date][A fruit, too.]
+/// ```
+///
+/// # Marks from headings
+/// To get a running header with the current chapter, you can set a mark in a
+/// show rule. In contrast to querying for headings before `{here()}`, the
+/// heading at the very top of a page is considered correctly.
+///
+/// ```example
+/// >>> #set page(width: 120pt, height: 120pt)
+/// #set page(header: context {
+///   let chapter = mark.first("chapter")
+///   if chapter != none [_ #chapter _]
+/// })
+///
+/// #show heading.where(level: 1): it => {
+///   mark("chapter", it.body)
+///   it
+/// }
+///
+/// = Introduction
+/// #lorem(10)
+///
+/// = Background
+/// #lorem(20)
+/// ```
+#[elem(scope, Show, Locatable)]
+pub struct MarkElem {
+    /// The name of the mark.
+    #[required]
+    pub name: Str,
+
+    /// The value of the mark.
+    #[required]
+    pub value: Value,
+}
+
+#[scope]
+impl MarkElem {
+    /// Retrieves the value of the first mark with the given name on the
+    /// current page.
+    ///
+    /// If there is no such mark on the page, this returns the value of the
+    /// [inherited]($mark.top) mark instead.
+    #[func(contextual)]
+    pub fn first(
+        /// The engine.
+        engine: &mut Engine,
+        /// The callsite context.
+        context: Tracked<Context>,
+        /// The callsite span.
+        span: Span,
+        /// The name of the mark.
+        name: Str,
+    ) -> SourceResult<Value> {
+        let marks = Marks::on_current_page(engine, context, span, name)?;
+        Ok(marks.page.into_iter().next().or(marks.top).unwrap_or_default())
+    }
+
+    /// Retrieves the value of the last mark with the given name on the current
+    /// page.
+    ///
+    /// If there is no such mark on the page, this returns the value of the
+    /// [inherited]($mark.top) mark instead.
+    #[func(contextual)]
+    pub fn last(
+        /// The engine.
+        engine: &mut Engine,
+        /// The callsite context.
+        context: Tracked<Context>,
+        /// The callsite span.
+        span: Span,
+        /// The name of the mark.
+        name: Str,
+    ) -> SourceResult<Value> {
+        let marks = Marks::on_current_page(engine, context, span, name)?;
+        Ok(marks.page.into_iter().last().or(marks.top).unwrap_or_default())
+    }
+
+    /// Retrieves the value of the mark with the given name that is in effect
+    /// at the top of the current page, that is, the last such mark on any of
+    /// the previous pages.
+    ///
+    /// Returns `{none}` if there is no such mark.
+    #[func(contextual)]
+    pub fn top(
+        /// The engine.
+        engine: &mut Engine,
+        /// The callsite context.
+        context: Tracked<Context>,
+        /// The callsite span.
+        span: Span,
+        /// The name of the mark.
+        name: Str,
+    ) -> SourceResult<Value> {
+        let marks = Marks::on_current_page(engine, context, span, name)?;
+        Ok(marks.top.unwrap_or_default())
+    }
+}
+
+impl Show for Packed<MarkElem> {
+    fn show(&self, _: &mut Engine, _styles: StyleChain) -> SourceResult<Content> {
+        Ok(Content::empty())
+    }
+}
+
+/// The values of the marks with a given name relevant for a page.
+struct Marks {
+    /// The value of the last mark on a previous page.
+    top: Option<Value>,
+    /// The values of the marks on the page, in document order.
+    page: Vec<Value>,
+}
+
+impl Marks {
+    /// Determines the marks for the page of the current location.
+    fn on_current_page(
+        engine: &mut Engine,
+        context: Tracked<Context>,
+        span: Span,
+        name: Str,
+    ) -> SourceResult<Self> {
+        let loc = context.location().at(span)?;
+        let page = engine.introspector.page(loc);
+
+        let mut marks = Self { top: None, page: vec![] };
+        for elem in engine.introspector.query(&select_where!(MarkElem, Name => name)) {
+            let Some(mark) = elem.to_packed::<MarkElem>() else { continue };
+            let mark_page = engine.introspector.page(mark.location().unwrap());
+            if mark_page < page {
+                marks.top = Some(mark.value().clone());
+            } else if mark_page == page {
+                marks.page.push(mark.value().clone());
+            }
+        }
+
+        Ok(marks)
+    }
+}
@@ -8,6 +8,7 @@ mod introspector;
 mod locate_;
 mod location;
 mod locator;
+mod mark;
 mod metadata;
 #[path = "query.rs"]
 mod query_;
@@ -20,6 +21,7 @@ pub use self::introspector::*;
 pub use self::locate_::*;
 pub use self::location::*;
 pub use self::locator::*;
+pub use self::mark::*;
 pub use self::metadata::*;
 pub use self::query_::*;
 pub use self::state::*;
@@ -47,6 +49,7 @@ pub fn define(global: &mut Scope) {
     global.define_type::<Counter>();
     global.define_type::<State>();
     global.define_elem::<MetadataElem>();
+    global.define_elem::<MarkElem>();
     global.define_func::<here>();
     global.define_func::<query>();
     global.define_func::<locate>();
@@ -7,10 +7,10 @@ use crate::foundations::{
     cast, elem, Args, AutoValue, Construct, Content, NativeElement, Packed, Resolve,
     Smart, StyleChain, Value,
 };
-use crate::introspection::Locator;
+use crate::introspection::{Locator, SplitLocator};
 use crate::layout::{
     layout_fragment, layout_frame, Abs, Axes, Corners, Em, Fr, Fragment, Frame,
-    FrameKind, Length, Region, Regions, Rel, Sides, Size, Spacing,
+    FrameKind, Length, Point, Region, Regions, Rel, Sides, Size, Spacing,
 };
 use crate::utils::Numeric;
 use crate::visualize::{clip_rect, Paint, Stroke};
@@ -362,6 +362,61 @@ pub struct BlockElem {
     #[fold]
     pub outset: Sides<Option<Rel<Length>>>,
 
+    /// How to stroke the edges at which a breakable block is split across
+    /// regions.
+    ///
+    /// By default, the edges at a split stay open such that the fragments of
+    /// the block visually belong together. When `{auto}`, the split edges are
+    /// stroked like the others.
+    ///
+    /// ```example
+    /// #set page(height: 100pt)
+    /// #block(
+    ///   stroke: 1pt,
+    ///   radius: 4pt,
+    ///   inset: 6pt,
+    ///   split-stroke: (dash: "dashed"),
+    ///   lorem(20),
+    /// )
+    /// ```
+    #[resolve]
+    #[default(Smart::Custom(None))]
+    pub split_stroke: Smart<Option<Stroke>>,
+
+    /// How much to round the corners at the edges at which a breakable block
+    /// is split across regions. By default, these corners are not rounded.
+    /// When `{auto}`, they are rounded like the others.
+    #[resolve]
+    #[default(Smart::Custom(Rel::zero()))]
+    pub split_radius: Smart<Rel<Length>>,
+
+    /// How much to pad the block's content at the edges at which a breakable
+    /// block is split across regions. When `{auto}`, uses the
+    /// [`inset`]($block.inset) of the respective side.
+    #[resolve]
+    pub split_inset: Smart<Rel<Length>>,
+
+    /// Content to show at the bottom of each fragment of a breakable block
+    /// that is continued in the next region.
+    ///
+    /// ```example
+    /// #set page(height: 100pt)
+    /// #block(
+    ///   fill: luma(235),
+    ///   inset: 6pt,
+    ///   split-before: align(right, emph[Continued ...]),
+    ///   split-after: emph[... continued],
+    ///   lorem(20),
+    /// )
+    /// ```
+    #[borrowed]
+    pub split_before: Option<Content>,
+
+    /// Content to show at the top of each fragment of a breakable block that
+    /// continues a previous fragment.
+    #[borrowed]
+    pub split_after: Option<Content>,
+
     /// The spacing around the block. When `{auto}`, inherits the paragraph
     /// [`spacing`]($par.spacing).
     ///
@@ -582,73 +637,115 @@ impl Packed<BlockElem> {
             breakable_pod(&width.into(), &height, &inset, styles, regions, &mut buf);
 
         // Layout the body.
-        let body = self.body(styles);
-        let mut fragment = match body {
-            // If we have no body, just create one frame plus one per backlog
-            // region. We create them zero-sized; if necessary, their size will
-            // be adjusted below.
-            None => {
-                let mut frames = vec![];
-                frames.push(Frame::hard(Size::zero()));
-                if pod.expand.y {
-                    let mut iter = pod;
-                    while !iter.backlog.is_empty() {
-                        frames.push(Frame::hard(Size::zero()));
-                        iter.next();
-                    }
-                }
-                Fragment::frames(frames)
+        let mut locator = locator.split();
+        let body_locator = locator.next(&());
+        let mut fragment =
+            self.layout_body(engine, body_locator.relayout(), styles, pod, regions)?;
+
+        // If the block is split, its split edges may need a different amount
+        // of space than the normal ones. Then, we lay it out again into regions
+        // that leave room for that.
+        let mut split_buf = vec![];
+        let mut pod = pod;
+        if fragment.len() > 1 {
+            if let Some(split_pod) =
+                self.split_pod(engine, &mut locator, styles, pod, &inset, &mut split_buf)?
+            {
+                pod = split_pod;
+                fragment =
+                    self.layout_body(engine, body_locator, styles, pod, regions)?;
             }
+        }
 
-            // If we have content as our body, just layout it.
-            Some(BlockBody::Content(body)) => {
-                let mut fragment =
-                    layout_fragment(engine, body, locator.relayout(), styles, pod)?;
+        // Apply insets, markers, clipping, fills, and strokes.
+        self.finalize_fragment(engine, locator, styles, pod, &inset, &mut fragment)?;
 
-                // If the body is automatically sized and produced more than one
-                // fragment, ensure that the width was consistent across all
-                // regions. If it wasn't, we need to relayout with expansion.
-                if !pod.expand.x
-                    && fragment
-                        .as_slice()
-                        .windows(2)
-                        .any(|w| !w[0].width().approx_eq(w[1].width()))
-                {
-                    let max_width = fragment
-                        .iter()
-                        .map(|frame| frame.width())
-                        .max()
-                        .unwrap_or_default();
-                    let pod = Regions {
-                        size: Size::new(max_width, pod.size.y),
-                        expand: Axes::new(true, pod.expand.y),
-                        ..pod
-                    };
-                    fragment = layout_fragment(engine, body, locator, styles, pod)?;
-                }
+        Ok(fragment)
+    }
 
-                fragment
-            }
+    /// Builds pod regions that leave room for the split edges of the block if
+    /// they need a different amount of space than the normal edges.
+    fn split_pod<'a>(
+        &self,
+        engine: &mut Engine,
+        locator: &mut SplitLocator,
+        styles: StyleChain,
+        pod: Regions,
+        inset: &Sides<Rel<Abs>>,
+        buf: &'a mut Vec<Abs>,
+    ) -> SourceResult<Option<Regions<'a>>> {
+        let split = self.split_edges(styles, inset);
+        let split_before = self.split_before(styles);
+        let split_after = self.split_after(styles);
+        if split.top == inset.top
+            && split.bottom == inset.bottom
+            && split_before.is_none()
+            && split_after.is_none()
+        {
+            return Ok(None);
+        }
 
-            // If we have a child that wants to layout with just access to the
-            // base region, give it that.
-            Some(BlockBody::SingleLayouter(callback)) => {
-                let pod = Region::new(pod.base(), pod.expand);
-                callback.call(engine, locator, styles, pod).map(Fragment::frame)?
+        // Measure the markers.
+        let base = Size::new(pod.size.x, pod.base().y);
+        let mut markers = Sides::splat(Abs::zero());
+        for (marker, amount) in
+            [(split_after, &mut markers.top), (split_before, &mut markers.bottom)]
+        {
+            if let Some(marker) = marker {
+                *amount =
+                    layout_split_marker(engine, marker, locator.next(&()), styles, base)?
+                        .height();
             }
+        }
 
-            // If we have a child that wants to layout with full region access,
-            // we layout it.
-            //
-            // For auto-sized multi-layouters, we propagate the outer expansion
-            // so that they can decide for themselves. We also ensure again to
-            // only expand if the size is finite.
-            Some(BlockBody::MultiLayouter(callback)) => {
-                let expand = (pod.expand | regions.expand) & pod.size.map(Abs::is_finite);
-                let pod = Regions { expand, ..pod };
-                callback.call(engine, locator, styles, pod)?
-            }
+        // Shrink the regions by the difference between the split and the
+        // normal edges.
+        let extra = |height: Abs, split: Rel<Abs>, normal: Rel<Abs>| {
+            split.relative_to(height) - normal.relative_to(height)
         };
+        let top = |h: Abs| extra(h, split.top, inset.top) + markers.top;
+        let bottom = |h: Abs| extra(h, split.bottom, inset.bottom) + markers.bottom;
+
+        buf.extend(pod.backlog.iter().map(|&h| h - top(h) - bottom(h)));
+        Ok(Some(Regions {
+            size: Size::new(pod.size.x, pod.size.y - bottom(pod.size.y)),
+            full: pod.full,
+            backlog: buf,
+            last: pod.last.map(|h| h - top(h) - bottom(h)),
+            expand: pod.expand,
+        }))
+    }
+
+    /// The insets at the top and bottom edges at which the block is split.
+    fn split_edges(
+        &self,
+        styles: StyleChain,
+        inset: &Sides<Rel<Abs>>,
+    ) -> Sides<Rel<Abs>> {
+        let split_inset = self.split_inset(styles);
+        Sides::new(
+            Rel::zero(),
+            split_inset.unwrap_or(inset.top),
+            Rel::zero(),
+            split_inset.unwrap_or(inset.bottom),
+        )
+    }
+
+    /// Post-processes the frames of a laid out breakable block to apply insets,
+    /// markers, clipping, fills, and strokes.
+    fn finalize_fragment(
+        &self,
+        engine: &mut Engine,
+        mut locator: SplitLocator,
+        styles: StyleChain,
+        pod: Regions,
+        inset: &Sides<Rel<Abs>>,
+        fragment: &mut Fragment,
+    ) -> SourceResult<()> {
+        let body = self.body(styles);
+        let split = self.split_edges(styles, inset);
+        let split_before = self.split_before(styles).as_ref();
+        let split_after = self.split_after(styles).as_ref();
 
         // Prepare fill and stroke.
         let fill = self.fill(styles);
@@ -656,27 +753,31 @@ impl Packed<BlockElem> {
             .stroke(styles)
             .unwrap_or_default()
             .map(|s| s.map(Stroke::unwrap_or_default));
+        let split_stroke =
+            self.split_stroke(styles).map(|s| s.map(Stroke::unwrap_or_default));
 
         // Only fetch these if necessary (for clipping or filling/stroking).
         let outset = Lazy::new(|| self.outset(styles).unwrap_or_default());
         let radius = Lazy::new(|| self.radius(styles).unwrap_or_default());
+        let split_radius = self.split_radius(styles);
 
         // Fetch/compute these outside of the loop.
         let clip = self.clip(styles);
         let has_fill_or_stroke = fill.is_some() || stroke.iter().any(Option::is_some);
-        let has_inset = !inset.is_zero();
         let is_explicit = matches!(body, None | Some(BlockBody::Content(_)));
 
-        // Skip filling/stroking the first frame if it is empty and a non-empty
-        // one follows.
-        let mut skip_first = false;
+        // If the first frame is empty and a non-empty one follows, the block
+        // effectively starts in the second frame. We then skip filling and
+        // stroking the first frame.
+        let mut empty_first = false;
         if let [first, rest @ ..] = fragment.as_slice() {
-            skip_first = has_fill_or_stroke
-                && first.is_empty()
-                && rest.iter().any(|frame| !frame.is_empty());
+            empty_first = first.is_empty() && rest.iter().any(|frame| !frame.is_empty());
         }
+        let skip_first = has_fill_or_stroke && empty_first;
+        let start = usize::from(empty_first);
+        let count = fragment.len();
 
-        // Post-process to apply insets, clipping, fills, and strokes.
+        // Post-process to apply insets, markers, clipping, fills, and strokes.
         for (i, (frame, region)) in fragment.iter_mut().zip(pod.iter()).enumerate() {
             // Explicit blocks are boundaries for gradient relativeness.
             if is_explicit {
@@ -687,8 +788,63 @@ impl Packed<BlockElem> {
             // applying the inset, since the pod shrunk.
             frame.set_size(pod.expand.select(region, frame.size()));
 
+            // Whether this frame continues a previous one and whether it is
+            // continued by the next one.
+            let continues = i > start;
+            let continued = i >= start && i + 1 < count;
+
+            // Add the markers at the split edges.
+            let base = Size::new(frame.width(), region.y);
+            if let Some(marker) = split_after.filter(|_| continues) {
+                let marker =
+                    layout_split_marker(engine, marker, locator.next(&()), styles, base)?;
+                let amount = marker.height().into();
+                crate::layout::grow(
+                    frame,
+                    &Sides::new(Rel::zero(), amount, Rel::zero(), Rel::zero()),
+                );
+                frame.push_frame(Point::zero(), marker);
+            }
+            if let Some(marker) = split_before.filter(|_| continued) {
+                let marker =
+                    layout_split_marker(engine, marker, locator.next(&()), styles, base)?;
+                let amount = marker.height().into();
+                crate::layout::grow(
+                    frame,
+                    &Sides::new(Rel::zero(), Rel::zero(), Rel::zero(), amount),
+                );
+                let y = frame.height() - marker.height();
+                frame.push_frame(Point::with_y(y), marker);
+            }
+
+            // Determine the inset, stroke, and radius of this frame, which
+            // differ at the split edges.
+            let mut inset = *inset;
+            let mut stroke = stroke.clone();
+            let mut radius = *radius;
+            if continues {
+                inset.top = split.top;
+                if let Smart::Custom(split_stroke) = &split_stroke {
+                    stroke.top = split_stroke.clone();
+                }
+                if let Smart::Custom(split_radius) = split_radius {
+                    radius.top_left = split_radius;
+                    radius.top_right = split_radius;
+                }
+            }
+            if continued {
+                inset.bottom = split.bottom;
+                if let Smart::Custom(split_stroke) = &split_stroke {
+                    stroke.bottom = split_stroke.clone();
+                }
+                if let Smart::Custom(split_radius) = split_radius {
+                    radius.bottom_left = split_radius;
+                    radius.bottom_right = split_radius;
+                }
+            }
+
             // Apply the inset.
-            if has_inset {
+            if !inset.is_zero() {
                 crate::layout::grow(frame, &inset);
             }
 
@@ -717,7 +873,85 @@ impl Packed<BlockElem> {
             }
         }
 
-        Ok(fragment)
+        Ok(())
+    }
+
+    /// Lays out the block's body into the pod regions.
+    fn layout_body(
+        &self,
+        engine: &mut Engine,
+        locator: Locator,
+        styles: StyleChain,
+        pod: Regions,
+        regions: Regions,
+    ) -> SourceResult<Fragment> {
+        let body = self.body(styles);
+        Ok(match body {
+            // If we have no body, just create one frame plus one per backlog
+            // region. We create them zero-sized; if necessary, their size will
+            // be adjusted below.
+            None => {
+                let mut frames = vec![];
+                frames.push(Frame::hard(Size::zero()));
+                if pod.expand.y {
+                    let mut iter = pod;
+                    while !iter.backlog.is_empty() {
+                        frames.push(Frame::hard(Size::zero()));
+                        iter.next();
+                    }
+                }
+                Fragment::frames(frames)
+            }
+
+            // If we have content as our body, just layout it.
+            Some(BlockBody::Content(body)) => {
+                let mut fragment =
+                    layout_fragment(engine, body, locator.relayout(), styles, pod)?;
+
+                // If the body is automatically sized and produced more than one
+                // fragment, ensure that the width was consistent across all
+                // regions. If it wasn't, we need to relayout with expansion.
+                if !pod.expand.x
+                    && fragment
+                        .as_slice()
+                        .windows(2)
+                        .any(|w| !w[0].width().approx_eq(w[1].width()))
+                {
+                    let max_width = fragment
+                        .iter()
+                        .map(|frame| frame.width())
+                        .max()
+                        .unwrap_or_default();
+                    let pod = Regions {
+                        size: Size::new(max_width, pod.size.y),
+                        expand: Axes::new(true, pod.expand.y),
+                        ..pod
+                    };
+                    fragment = layout_fragment(engine, body, locator, styles, pod)?;
+                }
+
+                fragment
+            }
+
+            // If we have a child that wants to layout with just access to the
+            // base region, give it that.
+            Some(BlockBody::SingleLayouter(callback)) => {
+                let pod = Region::new(pod.base(), pod.expand);
+                callback.call(engine, locator, styles, pod).map(Fragment::frame)?
+            }
+
+            // If we have a child that wants to layout with full region access,
+            // we layout it.
+            //
+            // For auto-sized multi-layouters, we propagate the outer expansion
+            // so that they can decide for themselves. We also ensure again to
+            // only expand if the size is finite.
+            Some(BlockBody::MultiLayouter(callback)) => {
+                let expand = (pod.expand | regions.expand) & pod.size.map(Abs::is_finite);
+                let pod = Regions { expand, ..pod };
+                callback.call(engine, locator, styles, pod)?
+            }
+        })
     }
 }
 
@@ -809,6 +1043,23 @@ cast! {
     v: Fr => Self::Fr(v),
 }
 
+/// Lays out a marker shown at an edge at which a block is split.
+fn layout_split_marker(
+    engine: &mut Engine,
+    marker: &Content,
+    locator: Locator,
+    styles: StyleChain,
+    base: Size,
+) -> SourceResult<Frame> {
+    layout_frame(
+        engine,
+        marker,
+        locator,
+        styles,
+        Region::new(base, Axes::new(true, false)),
+    )
+}
+
 /// Builds the pod region for an unbreakable sized container.
 fn unbreakable_pod(
     width: &Sizing,
@@ -9,11 +9,12 @@ use once_cell::unsync::Lazy;
 use crate::diag::{bail, SourceResult};
 use crate::engine::Engine;
 use crate::foundations::{Packed, Resolve, Smart, StyleChain};
-use crate::introspection::{Locator, SplitLocator, Tag, TagElem};
+use crate::introspection::{Location, Locator, SplitLocator, Tag, TagElem};
 use crate::layout::{
-    layout_frame, Abs, AlignElem, Alignment, Axes, BlockElem, ColbreakElem,
-    FixedAlignment, FlushElem, Fr, Fragment, Frame, PagebreakElem, PlaceElem,
-    PlacementScope, Ratio, Region, Regions, Rel, Size, Sizing, Spacing, VElem,
+    layout_frame, Abs, AlignElem, Alignment, Axes, BlockElem, ColbreakElem, Exclusion,
+    FixedAlignment, FloatPolicy, FlushElem, Fr, Fragment, Frame, PagebreakElem,
+    PlaceElem, PlacementScope, PlacementWrap, Ratio, Region, Regions, Rel, Size, Sizing,
+    Spacing, VElem,
 };
 use crate::model::ParElem;
 use crate::realize::Pair;
@@ -39,6 +40,7 @@ pub fn collect<'a>(
         expand,
         output: Vec::with_capacity(children.len()),
         last_was_par: false,
+        wrapping: Abs::zero(),
     }
     .run()
 }
@@ -53,6 +55,9 @@ struct Collector<'a, 'x, 'y> {
     locator: SplitLocator<'a>,
     output: Vec<Child<'a>>,
     last_was_par: bool,
+    /// How much more paragraph height may still have to wrap around a
+    /// preceding placed element. Zero if no wrapping is in effect.
+    wrapping: Abs,
 }
 
 impl<'a> Collector<'a, '_, '_> {
@@ -72,6 +77,8 @@ impl<'a> Collector<'a, '_, '_> {
             } else if child.is::<FlushElem>() {
                 self.output.push(Child::Flush);
             } else if let Some(elem) = child.to_packed::<ColbreakElem>() {
+                // Wrapping never carries over into the next region.
+                self.wrapping = Abs::zero();
                 self.output.push(Child::Break(elem.weak(styles)));
             } else if child.is::<PagebreakElem>() {
                 bail!(
@@ -79,7 +86,7 @@ impl<'a> Collector<'a, '_, '_> {
                     hint: "try using a `#colbreak()` instead",
                 );
             } else {
-                bail!(child.span(), "{} is not allowed here", child.func().name());
+                bail!(child.span(), "{} is not allowed here", child.elem().name());
             }
         }
 
@@ -96,66 +103,65 @@ impl<'a> Collector<'a, '_, '_> {
 
     /// Collect a paragraph into [`LineChild`]ren. This already performs line
     /// layout since it is not dependent on the concrete regions.
+    ///
+    /// If a preceding placed element requested wrapping and the paragraphs
+    /// since then have not yet passed it, the paragraph is instead collected
+    /// into a [`ParChild`] whose line layout is deferred until it is known
+    /// which areas its lines must avoid.
     fn par(
         &mut self,
         elem: &'a Packed<ParElem>,
         styles: StyleChain<'a>,
     ) -> SourceResult<()> {
-        let align = AlignElem::alignment_in(styles).resolve(styles);
-        let leading = ParElem::leading_in(styles);
         let spacing = ParElem::spacing_in(styles);
-        let costs = TextElem::costs_in(styles);
-
-        let lines = crate::layout::layout_inline(
-            self.engine,
-            &elem.children,
-            self.locator.next(&elem.span()),
-            styles,
-            self.last_was_par,
-            self.base,
-            self.expand,
-        )?
-        .into_frames();
+        let locator = self.locator.next(&elem.span());
+        let consecutive = self.last_was_par;
 
         self.output.push(Child::Rel(spacing.into(), 4));
 
-        // Determine whether to prevent widow and orphans.
-        let len = lines.len();
-        let prevent_orphans =
-            costs.orphan() > Ratio::zero() && len >= 2 && !lines[1].is_empty();
-        let prevent_widows =
-            costs.widow() > Ratio::zero() && len >= 2 && !lines[len - 2].is_empty();
-        let prevent_all = len == 3 && prevent_orphans && prevent_widows;
-
-        // Store the heights of lines at the edges because we'll potentially
-        // need these later when `lines` is already moved.
-        let height_at = |i| lines.get(i).map(Frame::height).unwrap_or_default();
-        let front_1 = height_at(0);
-        let front_2 = height_at(1);
-        let back_2 = height_at(len.saturating_sub(2));
-        let back_1 = height_at(len.saturating_sub(1));
-
-        for (i, frame) in lines.into_iter().enumerate() {
-            if i > 0 {
-                self.output.push(Child::Rel(leading.into(), 5));
-            }
-
-            // To prevent widows and orphans, we require enough space for
-            // - all lines if it's just three
-            // - the first two lines if we're at the first line
-            // - the last two lines if we're at the second to last line
-            let need = if prevent_all && i == 0 {
-                front_1 + leading + front_2 + leading + back_1
-            } else if prevent_orphans && i == 0 {
-                front_1 + leading + front_2
-            } else if prevent_widows && i >= 2 && i + 2 == len {
-                back_2 + leading + back_1
-            } else {
-                frame.height()
+        if self.wrapping > Abs::zero() {
+            let leading = ParElem::leading_in(styles);
+            let child = ParChild {
+                leading,
+                elem,
+                styles,
+                locator,
+                consecutive,
+                base: self.base,
+                expand: self.expand,
+                cell: CachedCell::new(),
             };
 
-            self.output
-                .push(Child::Line(self.boxed(LineChild { frame, align, need })));
+            // Without exclusions, the paragraph is at most as high as when
+            // wrapping, so once the unwrapped heights exceed the placed
+            // element's extent, later paragraphs are certainly below it.
+            let lines = child.layout(self.engine, &[])?;
+            let height = lines.iter().map(|line| line.frame.height()).sum::<Abs>()
+                + leading * lines.len().saturating_sub(1) as f64;
+            self.wrapping = (self.wrapping - height).max(Abs::zero());
+
+            self.output.push(Child::Par(self.boxed(child)));
+        } else {
+            let lines = crate::layout::layout_inline(
+                self.engine,
+                &elem.children,
+                locator,
+                styles,
+                consecutive,
+                self.base,
+                self.expand,
+                &[],
+            )?
+            .into_frames();
+
+            let leading = ParElem::leading_in(styles);
+            for (i, line) in line_children(lines, styles).enumerate() {
+                if i > 0 {
+                    self.output.push(Child::Rel(leading.into(), 5));
+                }
+
+                self.output.push(Child::Line(self.boxed(line)));
+            }
         }
 
         self.output.push(Child::Rel(spacing.into(), 4));
@@ -247,15 +253,46 @@ impl<'a> Collector<'a, '_, '_> {
             );
         }
 
+        let wrap = elem.wrap(styles);
+        if wrap != PlacementWrap::None {
+            if float {
+                bail!(
+                    elem.span(),
+                    "wrapping is currently only available for non-floating placement"
+                );
+            }
+
+            if align_x == FixedAlignment::Center {
+                bail!(
+                    elem.span(),
+                    "wrapping placement must be aligned to the left or right";
+                    hint: "try using `left` or `right` as the horizontal alignment"
+                );
+            }
+        }
+
+        let policy = elem.policy(styles);
+        if !float && policy != FloatPolicy::Edge {
+            bail!(
+                elem.span(),
+                "float policies are only available for floating placement";
+                hint: "you can enable floating placement with `place(float: true, ..)`"
+            );
+        }
+
         let locator = self.locator.next(&elem.span());
         let clearance = elem.clearance(styles);
         let delta = Axes::new(elem.dx(styles), elem.dy(styles)).resolve(styles);
-        self.output.push(Child::Placed(self.boxed(PlacedChild {
+        let child = PlacedChild {
             idx,
             align_x,
             align_y,
             scope,
             float,
+            wrap,
+            policy,
+            max_fraction: elem.max_fraction(styles),
+            anchor: elem.anchor().copied().flatten(),
             clearance,
             delta,
             elem,
@@ -263,7 +300,22 @@ impl<'a> Collector<'a, '_, '_> {
             locator,
             alignment,
             cell: CachedCell::new(),
-        })));
+        };
+
+        if wrap != PlacementWrap::None {
+            // Content placed at the current position or at the top of the
+            // region extends at most its own height below the current
+            // position. Content aligned further down may be anywhere below.
+            let extent = match align_y {
+                Smart::Custom(None | Some(FixedAlignment::Start)) => {
+                    child.layout(self.engine, self.base)?.height() + clearance
+                }
+                _ => Abs::inf(),
+            };
+            self.wrapping.set_max(extent);
+        }
+
+        self.output.push(Child::Placed(self.boxed(child)));
 
         Ok(())
     }
@@ -288,6 +340,8 @@ pub enum Child<'a> {
     Fr(Fr),
     /// An already layouted line of a paragraph.
     Line(BumpBox<'a, LineChild>),
+    /// A paragraph that may have to wrap around placed content.
+    Par(BumpBox<'a, ParChild<'a>>),
     /// An unbreakable block.
     Single(BumpBox<'a, SingleChild<'a>>),
     /// A breakable block.
@@ -301,13 +355,103 @@ pub enum Child<'a> {
 }
 
 /// A child that encapsulates a layouted line of a paragraph.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct LineChild {
     pub frame: Frame,
     pub align: Axes<FixedAlignment>,
     pub need: Abs,
 }
 
+/// Turns the lines of a paragraph into [`LineChild`]ren, determining how much
+/// space each of them needs to prevent widows and orphans.
+fn line_children(
+    lines: Vec<Frame>,
+    styles: StyleChain,
+) -> impl Iterator<Item = LineChild> {
+    let align = AlignElem::alignment_in(styles).resolve(styles);
+    let leading = ParElem::leading_in(styles);
+    let costs = TextElem::costs_in(styles);
+
+    // Determine whether to prevent widow and orphans.
+    let len = lines.len();
+    let prevent_orphans =
+        costs.orphan() > Ratio::zero() && len >= 2 && !lines[1].is_empty();
+    let prevent_widows =
+        costs.widow() > Ratio::zero() && len >= 2 && !lines[len - 2].is_empty();
+    let prevent_all = len == 3 && prevent_orphans && prevent_widows;
+
+    // Store the heights of lines at the edges because we'll potentially
+    // need these later when `lines` is already moved.
+    let height_at = |i| lines.get(i).map(Frame::height).unwrap_or_default();
+    let front_1 = height_at(0);
+    let front_2 = height_at(1);
+    let back_2 = height_at(len.saturating_sub(2));
+    let back_1 = height_at(len.saturating_sub(1));
+
+    lines.into_iter().enumerate().map(move |(i, frame)| {
+        // To prevent widows and orphans, we require enough space for
+        // - all lines if it's just three
+        // - the first two lines if we're at the first line
+        // - the last two lines if we're at the second to last line
+        let need = if prevent_all && i == 0 {
+            front_1 + leading + front_2 + leading + back_1
+        } else if prevent_orphans && i == 0 {
+            front_1 + leading + front_2
+        } else if prevent_widows && i >= 2 && i + 2 == len {
+            back_2 + leading + back_1
+        } else {
+            frame.height()
+        };
+
+        LineChild { frame, align, need }
+    })
+}
+
+/// A child that encapsulates a paragraph whose lines may have to wrap around
+/// placed content.
+#[derive(Debug)]
+pub struct ParChild<'a> {
+    pub leading: Abs,
+    elem: &'a Packed<ParElem>,
+    styles: StyleChain<'a>,
+    locator: Locator<'a>,
+    consecutive: bool,
+    base: Size,
+    expand: bool,
+    cell: CachedCell<SourceResult<Vec<LineChild>>>,
+}
+
+impl ParChild<'_> {
+    /// Build the paragraph's lines such that they avoid the given exclusions.
+    pub fn layout(
+        &self,
+        engine: &mut Engine,
+        exclusions: &[Exclusion],
+    ) -> SourceResult<Vec<LineChild>> {
+        self.cell.get_or_init(exclusions, |exclusions| {
+            let lines = crate::layout::layout_inline(
+                engine,
+                &self.elem.children,
+                self.locator.relayout(),
+                self.styles,
+                self.consecutive,
+                self.base,
+                self.expand,
+                exclusions,
+            )?
+            .into_frames();
+            Ok(line_children(lines, self.styles).collect())
+        })
+    }
+}
+
+/// The remaining lines of a [`ParChild`] that broke across two regions.
+#[derive(Debug, Clone)]
+pub struct ParSpill {
+    pub lines: std::vec::IntoIter<LineChild>,
+    pub leading: Abs,
+}
+
 /// A child that encapsulates a prepared unbreakable block.
 #[derive(Debug)]
 pub struct SingleChild<'a> {
@@ -463,6 +607,10 @@ pub struct PlacedChild<'a> {
     pub align_y: Smart<Option<FixedAlignment>>,
     pub scope: PlacementScope,
     pub float: bool,
+    pub wrap: PlacementWrap,
+    pub policy: FloatPolicy,
+    pub max_fraction: Ratio,
+    pub anchor: Option<Location>,
     pub clearance: Abs,
     pub delta: Axes<Rel<Abs>>,
     elem: &'a Packed<PlaceElem>,
@@ -3,20 +3,22 @@ use std::num::NonZeroUsize;
 use super::{distribute, Config, FlowResult, PlacedChild, Skip, Stop, Work};
 use crate::diag::SourceResult;
 use crate::engine::Engine;
-use crate::foundations::{Content, NativeElement, Packed, Resolve, Smart};
+use crate::foundations::{
+    Content, NativeElement, Packed, Resolve, Smart, Str, StyleChain,
+};
 use crate::introspection::{
-    Counter, CounterDisplayElem, CounterState, CounterUpdate, Locator, SplitLocator,
-    TagKind,
+    Counter, CounterDisplayElem, CounterState, CounterUpdate, Location, Locator,
+    SplitLocator, TagKind,
 };
 use crate::layout::{
-    layout_fragment, layout_frame, Abs, Axes, Dir, FixedAlignment, Frame, FrameItem,
-    OuterHAlignment, PlacementScope, Point, Region, Regions, Rel, Size,
+    layout_fragment, layout_frame, Abs, Axes, Dir, FixedAlignment, FloatPolicy, Frame,
+    FrameItem, OuterHAlignment, PlacementScope, Point, Region, Regions, Rel, Size,
 };
 use crate::model::{
     FootnoteElem, FootnoteEntry, LineNumberingScope, Numbering, ParLine, ParLineMarker,
 };
 use crate::syntax::Span;
-use crate::utils::NonZeroExt;
+use crate::utils::{NonZeroExt, Numeric};
 
 /// Composes the contents of a single page/region. A region can have multiple
 /// columns/subregions.
@@ -69,13 +71,43 @@ pub struct Composer<'a, 'b, 'x, 'y> {
     // footnotes otherwise). For floats, we revisit them anyway, so it's okay to
     // use `work.floats` directly. This is not super clean; probably there's a
     // better way.
-    footnote_spill: Option<std::vec::IntoIter<Frame>>,
+    footnote_spill: Option<FootnoteSpill>,
     footnote_queue: Vec<Packed<FootnoteElem>>,
 }
 
+/// Spilled frames of a footnote that didn't fully fit into a region.
+#[derive(Debug, Clone)]
+pub struct FootnoteSpill {
+    /// The series of the footnote.
+    series: Option<Str>,
+    /// The separator of the footnote's series.
+    separator: Content,
+    /// The remaining frames.
+    frames: std::vec::IntoIter<Frame>,
+}
+
 impl<'a, 'b> Composer<'a, 'b, '_, '_> {
     /// Lay out a container/page region, including container/page insertions.
     fn page(mut self, locator: Locator, regions: Regions) -> SourceResult<Frame> {
+        // Release held back floats if their anchors can't show up anymore
+        // because the flow is exhausted.
+        if self.work.children.is_empty() && self.work.spill.is_none() {
+            for placed in std::mem::take(&mut self.work.awaiting) {
+                self.work.anchors.extend(placed.anchor);
+                if placed.policy == FloatPolicy::Page && self.config.root {
+                    self.work.float_pages.push(placed);
+                } else {
+                    self.work.floats.push(placed);
+                }
+            }
+        }
+
+        // Dedicate the region to pending floats with the page policy, if there
+        // are any.
+        if self.config.root && !self.work.float_pages.is_empty() {
+            return self.float_page(regions);
+        }
+
         // This loop can restart region layout when requested to do so by a
         // `Stop`. This happens when there is a parent-scoped float.
         let checkpoint = self.work.clone();
@@ -101,6 +133,97 @@ impl<'a, 'b> Composer<'a, 'b, '_, '_> {
         Ok(self.page_insertions.finalize(self.work, self.config, output))
     }
 
+    /// Lay out a float page, which holds as many of the pending page floats
+    /// as fit, stacked and vertically centered.
+    ///
+    /// Like for in-flow floats, footnotes in the floats are laid out at the
+    /// bottom of the page and held back floats anchored in them are released.
+    fn float_page(mut self, regions: Regions) -> SourceResult<Frame> {
+        let base = regions.base();
+        let mut frames = vec![];
+        let mut used = Abs::zero();
+        let mut skips = vec![];
+
+        let mut pending = std::mem::take(&mut self.work.float_pages).into_iter();
+        for placed in pending.by_ref() {
+            let frame = placed.layout(self.engine, base)?;
+            let gap = if frames.is_empty() { Abs::zero() } else { placed.clearance };
+
+            // The first float always goes onto the page, even if it
+            // doesn't fit.
+            if !frames.is_empty() && !regions.size.y.fits(used + gap + frame.height()) {
+                self.work.float_pages.push(placed);
+                break;
+            }
+
+            used += gap + frame.height();
+            skips.push(Skip::Placed(placed.idx));
+            frames.push((placed, gap, frame));
+        }
+
+        // Keep the remaining floats for the next float page.
+        self.work.float_pages.extend(pending);
+        self.work.extend_skips(&skips);
+
+        // Release held back floats anchored in the floats.
+        let mut locations = vec![];
+        for (_, _, frame) in &frames {
+            find_locations_in_frame(&mut locations, frame);
+        }
+        for placed in std::mem::take(&mut self.work.awaiting) {
+            let anchor = placed.anchor.unwrap();
+            if !locations.contains(&anchor) {
+                self.work.awaiting.push(placed);
+            } else if placed.policy == FloatPolicy::Page {
+                self.work.anchors.push(anchor);
+                self.work.float_pages.push(placed);
+            } else {
+                self.work.anchors.push(anchor);
+                self.work.floats.push(placed);
+            }
+        }
+
+        // Lay out footnotes in the floats below them. If earlier footnotes
+        // are still pending, these are queued after them to keep the order.
+        let notes: Vec<_> = frames
+            .iter()
+            .flat_map(|(_, _, frame)| find_in_frame::<FootnoteElem>(frame))
+            .map(|(_, note)| note)
+            .collect();
+        if !self.work.footnotes.is_empty() || self.work.footnote_spill.is_some() {
+            self.work.footnotes.extend(notes);
+        } else {
+            let mut pod = regions;
+            for note in notes {
+                match self.footnote(note, &mut pod, used, false) {
+                    Ok(()) | Err(Stop::Relayout(_) | Stop::Finish(_)) => {}
+                    Err(Stop::Error(err)) => return Err(err),
+                }
+            }
+            self.work.footnotes.extend(self.footnote_queue.drain(..));
+            self.work.footnote_spill = self.footnote_spill.take();
+        }
+
+        let height = if regions.expand.y {
+            regions.size.y - self.column_insertions.height()
+        } else {
+            used
+        };
+        let mut output = Frame::soft(Size::new(regions.size.x, height));
+        let mut offset = (height - used) / 2.0;
+        for (placed, gap, frame) in frames {
+            offset += gap;
+            let x = placed.align_x.position(regions.size.x - frame.width());
+            let delta = placed.delta.zip_map(output.size(), Rel::relative_to).to_point();
+            let y = offset;
+            offset += frame.height();
+            output.push_frame(Point::new(x, y) + delta, frame);
+        }
+
+        let insertions = std::mem::take(&mut self.column_insertions);
+        Ok(insertions.finalize(self.work, self.config, output))
+    }
+
     /// Lay out the inner contents of a container/page.
     fn page_contents(&mut self, locator: Locator, regions: Regions) -> FlowResult<Frame> {
         // No point in create column regions, if there's just one!
@@ -236,6 +359,10 @@ impl<'a, 'b> Composer<'a, 'b, '_, '_> {
     /// When the float does not fit, it is queued into `work.floats`. The
     /// value of `clearance` that between the float and flow content is needed
     /// --- it is set if there are already distributed items.
+    ///
+    /// Floats that wait for their anchor or that go onto a float page are
+    /// instead collected in `work.awaiting` and `work.float_pages`,
+    /// respectively.
     pub fn float(
         &mut self,
         placed: &'b PlacedChild<'a>,
@@ -247,6 +374,22 @@ impl<'a, 'b> Composer<'a, 'b, '_, '_> {
             return Ok(());
         }
 
+        // If the float's anchor wasn't laid out yet, hold it back. It is
+        // released once the anchor shows up in the flow.
+        if placed
+            .anchor
+            .is_some_and(|anchor| !self.work.anchors.contains(&anchor))
+        {
+            self.work.awaiting.push(placed);
+            return Ok(());
+        }
+
+        // Floats for float pages are collected until the current page is done.
+        if placed.policy == FloatPolicy::Page && self.config.root {
+            self.work.float_pages.push(placed);
+            return Ok(());
+        }
+
         // If there is already a queued float, queue this one as well. We
         // don't want to disrupt the order.
         if !self.work.floats.is_empty() {
@@ -281,6 +424,24 @@ impl<'a, 'b> Composer<'a, 'b, '_, '_> {
         let clearance = if clearance { Abs::zero() } else { placed.clearance };
         let need = frame.height() + clearance;
 
+        // If the floats in the scope would exceed the maximum fraction, queue
+        // the float for the next region. If it exceeds the fraction on its
+        // own, that won't help, so we put it onto a float page instead. In an
+        // infinite region, the fraction is unbounded.
+        let used = match placed.scope {
+            PlacementScope::Column => self.column_insertions.float_height(),
+            PlacementScope::Parent => self.page_insertions.float_height(),
+        };
+        if base.y.is_finite() && !placed.max_fraction.of(base.y).fits(used + need) {
+            if used > Abs::zero() {
+                self.work.floats.push(placed);
+                return Ok(());
+            } else if self.config.root {
+                self.work.float_pages.push(placed);
+                return Ok(());
+            }
+        }
+
         // If the float doesn't fit, queue it for the next region.
         if !remaining.fits(need) && !regions.in_last() {
             self.work.floats.push(placed);
@@ -320,6 +481,40 @@ impl<'a, 'b> Composer<'a, 'b, '_, '_> {
         Err(Stop::Relayout(placed.scope))
     }
 
+    /// Releases held back floats whose anchors are in the `frame`. The value
+    /// of `flow_need` is the amount of space used by the in-flow content that
+    /// produced the frame.
+    pub fn anchors(
+        &mut self,
+        regions: &Regions,
+        frame: &Frame,
+        flow_need: Abs,
+    ) -> FlowResult<()> {
+        if self.work.awaiting.is_empty() {
+            return Ok(());
+        }
+
+        let mut locations = vec![];
+        find_locations_in_frame(&mut locations, frame);
+
+        let mut pod = *regions;
+        pod.size.y -= flow_need;
+
+        // A successfully placed float triggers a relayout, which restores the
+        // work, so we don't need to worry about the floats processed after it.
+        for placed in std::mem::take(&mut self.work.awaiting) {
+            let anchor = placed.anchor.unwrap();
+            if locations.contains(&anchor) {
+                self.work.anchors.push(anchor);
+                self.float(placed, &pod, false)?;
+            } else {
+                self.work.awaiting.push(placed);
+            }
+        }
+
+        Ok(())
+    }
+
     /// Lays out footnotes in the `frame` if this is the root flow and there are
     /// any. The value of `breakable` indicates whether the element that
     /// produced the frame is breakable. If not, the frame is treated as atomic.
@@ -386,9 +581,12 @@ impl<'a, 'b> Composer<'a, 'b, '_, '_> {
         flow_need: Abs,
         migratable: bool,
     ) -> FlowResult<()> {
-        // Ignore reference footnotes and already processed ones.
+        // Ignore reference footnotes, endnotes, and already processed ones.
         let loc = elem.location().unwrap();
-        if elem.is_ref() || self.skipped(Skip::Footnote(loc)) {
+        if elem.is_ref()
+            || elem.endnote(StyleChain::default())
+            || self.skipped(Skip::Footnote(loc))
+        {
             return Ok(());
         }
 
@@ -400,13 +598,19 @@ impl<'a, 'b> Composer<'a, 'b, '_, '_> {
             return Ok(());
         }
 
-        // If there weren't any footnotes so far, account for the footnote
-        // separator.
+        // If there weren't any footnotes of this series so far, account for
+        // the series' footnote separator.
+        let series = elem.series(StyleChain::default());
+        let separator_content = self.config.footnote.separator_of(&elem);
         let mut separator = None;
         let mut separator_need = Abs::zero();
-        if area.footnotes.is_empty() {
-            let frame =
-                layout_footnote_separator(self.engine, self.config, regions.base())?;
+        if !area.has_footnotes(&series) {
+            let frame = layout_footnote_separator(
+                self.engine,
+                self.config,
+                &separator_content,
+                regions.base(),
+            )?;
             separator_need += self.config.footnote.clearance + frame.height();
             separator = Some(frame);
         }
@@ -449,18 +653,22 @@ impl<'a, 'b> Composer<'a, 'b, '_, '_> {
 
         // Save the separator.
         if let Some(frame) = separator {
-            area.push_footnote_separator(self.config, frame);
+            area.push_footnote_separator(self.config, series.clone(), frame);
             regions.size.y -= separator_need;
         }
 
         // Save the footnote's frame.
-        area.push_footnote(self.config, first);
+        area.push_footnote(self.config, &series, first);
         area.skips.push(Skip::Footnote(loc));
         regions.size.y -= note_need;
 
         // Save the spill.
         if !iter.as_slice().is_empty() {
-            self.footnote_spill = Some(iter);
+            self.footnote_spill = Some(FootnoteSpill {
+                series,
+                separator: separator_content,
+                frames: iter,
+            });
         }
 
         // Lay out nested footnotes.
@@ -475,22 +683,23 @@ impl<'a, 'b> Composer<'a, 'b, '_, '_> {
     /// Handles spillover from a footnote.
     fn footnote_spill(
         &mut self,
-        mut iter: std::vec::IntoIter<Frame>,
+        mut spill: FootnoteSpill,
         base: Size,
     ) -> SourceResult<()> {
         let area = &mut self.column_insertions;
 
         // Create and save the separator.
-        let separator = layout_footnote_separator(self.engine, self.config, base)?;
-        area.push_footnote_separator(self.config, separator);
+        let separator =
+            layout_footnote_separator(self.engine, self.config, &spill.separator, base)?;
+        area.push_footnote_separator(self.config, spill.series.clone(), separator);
 
         // Save the footnote's frame.
-        let frame = iter.next().unwrap();
-        area.push_footnote(self.config, frame);
+        let frame = spill.frames.next().unwrap();
+        area.push_footnote(self.config, &spill.series, frame);
 
         // Save the spill.
-        if !iter.as_slice().is_empty() {
-            self.footnote_spill = Some(iter);
+        if !spill.frames.as_slice().is_empty() {
+            self.footnote_spill = Some(spill);
         }
 
         Ok(())
@@ -510,15 +719,16 @@ impl<'a, 'b> Composer<'a, 'b, '_, '_> {
     }
 }
 
-/// Lay out the footnote separator, typically a line.
+/// Lay out a footnote separator, typically a line.
 fn layout_footnote_separator(
     engine: &mut Engine,
     config: &Config,
+    separator: &Content,
     base: Size,
 ) -> SourceResult<Frame> {
     layout_frame(
         engine,
-        &config.footnote.separator,
+        separator,
         Locator::root(),
         config.shared,
         Region::new(base, Axes::new(config.footnote.expand, false)),
@@ -530,8 +740,7 @@ fn layout_footnote_separator(
 struct Insertions<'a, 'b> {
     top_floats: Vec<(&'b PlacedChild<'a>, Frame)>,
     bottom_floats: Vec<(&'b PlacedChild<'a>, Frame)>,
-    footnotes: Vec<Frame>,
-    footnote_separator: Option<Frame>,
+    footnotes: Vec<FootnoteGroup>,
     top_size: Abs,
     bottom_size: Abs,
     width: Abs,
@@ -560,18 +769,42 @@ impl<'a, 'b> Insertions<'a, 'b> {
         }
     }
 
-    /// Add a footnote to the bottom area.
-    fn push_footnote(&mut self, config: &Config, frame: Frame) {
+    /// Whether the bottom area already contains footnotes of the given series.
+    fn has_footnotes(&self, series: &Option<Str>) -> bool {
+        self.footnotes.iter().any(|group| group.series == *series)
+    }
+
+    /// Add a footnote of the given series to the bottom area. The series'
+    /// separator must have been added before.
+    fn push_footnote(&mut self, config: &Config, series: &Option<Str>, frame: Frame) {
         self.width.set_max(frame.width());
         self.bottom_size += config.footnote.gap + frame.height();
-        self.footnotes.push(frame);
+        if let Some(group) = self.footnotes.iter_mut().find(|g| g.series == *series) {
+            group.notes.push(frame);
+        }
     }
 
-    /// Add a footnote separator to the bottom area.
-    fn push_footnote_separator(&mut self, config: &Config, frame: Frame) {
+    /// Add the footnote separator of a series to the bottom area, starting
+    /// the listing of the series.
+    fn push_footnote_separator(
+        &mut self,
+        config: &Config,
+        series: Option<Str>,
+        frame: Frame,
+    ) {
         self.width.set_max(frame.width());
         self.bottom_size += config.footnote.clearance + frame.height();
-        self.footnote_separator = Some(frame);
+        self.footnotes
+            .push(FootnoteGroup { series, separator: frame, notes: vec![] });
+    }
+
+    /// The combined height of the floats (including clearances).
+    fn float_height(&self) -> Abs {
+        self.top_floats
+            .iter()
+            .chain(&self.bottom_floats)
+            .map(|(placed, frame)| frame.height() + placed.clearance)
+            .sum()
     }
 
     /// The combined height of the top and bottom area (includings clearances).
@@ -588,7 +821,6 @@ impl<'a, 'b> Insertions<'a, 'b> {
 
         if self.top_floats.is_empty()
             && self.bottom_floats.is_empty()
-            && self.footnote_separator.is_none()
             && self.footnotes.is_empty()
         {
             return inner;
@@ -610,18 +842,18 @@ impl<'a, 'b> Insertions<'a, 'b> {
 
         output.push_frame(Point::with_y(self.top_size), inner);
 
-        if let Some(frame) = self.footnote_separator {
+        for group in self.footnotes {
             offset_bottom += config.footnote.clearance;
             let y = offset_bottom;
-            offset_bottom += frame.height();
-            output.push_frame(Point::with_y(y), frame);
-        }
-
-        for frame in self.footnotes {
-            offset_bottom += config.footnote.gap;
-            let y = offset_bottom;
-            offset_bottom += frame.height();
-            output.push_frame(Point::with_y(y), frame);
+            offset_bottom += group.separator.height();
+            output.push_frame(Point::with_y(y), group.separator);
+
+            for frame in group.notes {
+                offset_bottom += config.footnote.gap;
+                let y = offset_bottom;
+                offset_bottom += frame.height();
+                output.push_frame(Point::with_y(y), frame);
+            }
         }
 
         for (placed, frame) in self.bottom_floats {
@@ -637,6 +869,16 @@ impl<'a, 'b> Insertions<'a, 'b> {
     }
 }
 
+/// The footnotes of a single series in an insertion area.
+struct FootnoteGroup {
+    /// The series of the footnotes.
+    series: Option<Str>,
+    /// The separator above the footnotes.
+    separator: Frame,
+    /// The frames of the footnote entries.
+    notes: Vec<Frame>,
+}
+
 /// Lay out the given collected lines' line numbers to an output frame.
 ///
 /// The numbers are placed either on the left margin (left border of the frame)
@@ -823,6 +1065,19 @@ fn find_in_frames<T: NativeElement>(frames: &[Frame]) -> Vec<(Abs, Packed<T>)> {
     output
 }
 
+/// Collect the locations of all elements that start in the frame.
+fn find_locations_in_frame(output: &mut Vec<Location>, frame: &Frame) {
+    for (_, item) in frame.items() {
+        match item {
+            FrameItem::Group(group) => find_locations_in_frame(output, &group.frame),
+            FrameItem::Tag(tag) if tag.kind() == TagKind::Start => {
+                output.push(tag.location());
+            }
+            _ => {}
+        }
+    }
+}
+
 fn find_in_frame_impl<T: NativeElement>(
     output: &mut Vec<(Abs, Packed<T>)>,
     frame: &Frame,
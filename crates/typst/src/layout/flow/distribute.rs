@@ -1,12 +1,18 @@
 use super::{
-    Child, Composer, FlowResult, LineChild, MultiChild, MultiSpill, PlacedChild,
-    SingleChild, Stop, Work,
+    Child, Composer, FlowResult, LineChild, MultiChild, MultiSpill, ParChild, ParSpill,
+    PlacedChild, SingleChild, Stop, Work,
 };
 use crate::introspection::Tag;
 use crate::layout::{
-    Abs, Axes, FixedAlignment, Fr, Frame, FrameItem, Point, Region, Regions, Rel, Size,
+    Abs, Axes, Exclusion, FixedAlignment, FloatPolicy, Fr, Frame, FrameItem,
+    PlacementWrap, Point, Region, Regions, Rel, Size, Transform,
 };
 use crate::utils::Numeric;
+use crate::visualize::{Geometry, PathItem};
+
+/// The height (in points) of the slices in which the contour of placed content is
+/// approximated for wrapping.
+const CONTOUR_SLICE: f64 = 2.0;
 
 /// Distributes as many children as fit from `composer.work` into the first
 /// region and returns the resulting frame.
@@ -15,6 +21,7 @@ pub fn distribute(composer: &mut Composer, regions: Regions) -> FlowResult<Frame
         composer,
         regions,
         items: vec![],
+        exclusions: vec![],
         sticky: None,
         stickable: false,
     };
@@ -38,6 +45,9 @@ struct Distributor<'a, 'b, 'x, 'y, 'z> {
     regions: Regions<'z>,
     /// Already laid out items, not yet aligned.
     items: Vec<Item<'a, 'b>>,
+    /// Areas that following paragraphs must avoid because placed content
+    /// wraps them. Vertically relative to the top of the region.
+    exclusions: Vec<Exclusion>,
     /// A snapshot which can be restored to migrate a suffix of sticky blocks to
     /// the next region.
     sticky: Option<DistributionSnapshot<'a, 'b>>,
@@ -50,6 +60,7 @@ struct Distributor<'a, 'b, 'x, 'y, 'z> {
 struct DistributionSnapshot<'a, 'b> {
     work: Work<'a, 'b>,
     items: usize,
+    exclusions: usize,
 }
 
 /// A laid out item in a distribution.
@@ -89,6 +100,11 @@ impl<'a, 'b> Distributor<'a, 'b, '_, '_, '_> {
             self.multi_spill(spill)?;
         }
 
+        // Then, handle spill of a wrapping paragraph.
+        if let Some(spill) = self.composer.work.par_spill.take() {
+            self.par_spill(spill)?;
+        }
+
         // If spill are taken care of, process children until no space is left
         // or no children are left.
         while let Some(child) = self.composer.work.head() {
@@ -112,6 +128,7 @@ impl<'a, 'b> Distributor<'a, 'b, '_, '_, '_> {
             Child::Rel(amount, weakness) => self.rel(*amount, *weakness),
             Child::Fr(fr) => self.fr(*fr),
             Child::Line(line) => self.line(line)?,
+            Child::Par(par) => self.par(par)?,
             Child::Single(single) => self.single(single)?,
             Child::Multi(multi) => self.multi(multi)?,
             Child::Placed(placed) => self.placed(placed)?,
@@ -192,8 +209,22 @@ impl<'a, 'b> Distributor<'a, 'b, '_, '_, '_> {
         Abs::zero()
     }
 
+    /// The vertical position at which the next item will be placed.
+    ///
+    /// Does not take fractional spacing into account.
+    fn offset(&self) -> Abs {
+        self.items
+            .iter()
+            .map(|item| match item {
+                Item::Abs(amount, _) => *amount,
+                Item::Frame(frame, _) => frame.height(),
+                Item::Fr(..) | Item::Placed(..) => Abs::zero(),
+            })
+            .sum()
+    }
+
     /// Processes a line of a paragraph.
-    fn line(&mut self, line: &'b LineChild) -> FlowResult<()> {
+    fn line(&mut self, line: &LineChild) -> FlowResult<()> {
         // If the line doesn't fit and we're allowed to break, finish the
         // region.
         if !self.regions.size.y.fits(line.frame.height()) && !self.regions.in_last() {
@@ -217,6 +248,61 @@ impl<'a, 'b> Distributor<'a, 'b, '_, '_, '_> {
         self.frame(line.frame.clone(), line.align, false, false)
     }
 
+    /// Processes a paragraph that may have to wrap around placed content.
+    fn par(&mut self, par: &'b ParChild<'a>) -> FlowResult<()> {
+        // Determine the exclusions that are still relevant below the current
+        // position, relative to the paragraph's top edge.
+        let mut exclusions = vec![];
+        if !self.exclusions.is_empty() {
+            let top = self.offset();
+            exclusions.extend(
+                self.exclusions.iter().filter(|e| e.bottom > top).map(|e| Exclusion {
+                    top: e.top - top,
+                    bottom: e.bottom - top,
+                    ..*e
+                }),
+            );
+        }
+
+        // Lay out the paragraph's lines.
+        let mut lines = par.layout(self.composer.engine, &exclusions)?.into_iter();
+
+        // If the first line doesn't fit, we finish the region without
+        // advancing such that the paragraph is laid out anew with the
+        // exclusions of the next region.
+        if let Some(first) = lines.next() {
+            self.line(&first)?;
+        }
+
+        // If the remaining lines don't fully fit, they were saved into the
+        // `par_spill` and we must advance past the paragraph.
+        let spill = ParSpill { lines, leading: par.leading };
+        if let Err(stop) = self.par_spill(spill) {
+            self.composer.work.advance();
+            return Err(stop);
+        }
+
+        Ok(())
+    }
+
+    /// Processes the remaining lines of a wrapping paragraph.
+    fn par_spill(&mut self, mut spill: ParSpill) -> FlowResult<()> {
+        while let Some(line) = spill.lines.as_slice().first().cloned() {
+            self.rel(spill.leading.into(), 5);
+
+            // If the line doesn't fit, save the remaining lines into the
+            // `par_spill`.
+            if let Err(stop) = self.line(&line) {
+                self.composer.work.par_spill = Some(spill);
+                return Err(stop);
+            }
+
+            spill.lines.next();
+        }
+
+        Ok(())
+    }
+
     /// Processes an unbreakable block.
     fn single(&mut self, single: &'b SingleChild<'a>) -> FlowResult<()> {
         // Handle fractionally sized blocks.
@@ -317,6 +403,9 @@ impl<'a, 'b> Distributor<'a, 'b, '_, '_, '_> {
             self.composer
                 .footnotes(&self.regions, &frame, frame.height(), breakable)?;
 
+            // Release floats that were held back until now.
+            self.composer.anchors(&self.regions, &frame, frame.height())?;
+
             // Clear the drained tags _after_ the footnotes are handled because
             // a [`Stop::Finish`] could otherwise lose them.
             self.composer.work.tags.clear();
@@ -330,7 +419,9 @@ impl<'a, 'b> Distributor<'a, 'b, '_, '_, '_> {
 
     /// Processes an absolutely or floatingly placed child.
     fn placed(&mut self, placed: &'b PlacedChild<'a>) -> FlowResult<()> {
-        if placed.float {
+        if placed.float && placed.policy == FloatPolicy::Here {
+            self.here(placed)?;
+        } else if placed.float {
             // If the element is floatingly placed, let the composer handle it.
             // It might require relayout because the area available for
             // distribution shrinks. We make the spacing occupied by weak
@@ -343,11 +434,92 @@ impl<'a, 'b> Distributor<'a, 'b, '_, '_, '_> {
         } else {
             let frame = placed.layout(self.composer.engine, self.regions.base())?;
             self.composer.footnotes(&self.regions, &frame, Abs::zero(), true)?;
+            if placed.wrap != PlacementWrap::None {
+                self.wrap(placed, &frame);
+            }
             self.items.push(Item::Placed(frame, placed));
         }
         Ok(())
     }
 
+    /// Processes a floatingly placed child that should stay in-flow if it
+    /// fits.
+    fn here(&mut self, placed: &'b PlacedChild<'a>) -> FlowResult<()> {
+        // If the float must wait for its anchor or other floats are queued,
+        // it can't stay here anyway, so we let the composer handle it.
+        if placed.anchor.is_some() || !self.composer.work.floats.is_empty() {
+            return self.composer.float(placed, &self.regions, self.items.is_empty());
+        }
+
+        // If it doesn't fit, queue it such that it floats into the next
+        // region.
+        let frame = placed.layout(self.composer.engine, self.regions.base())?;
+        if !self.regions.size.y.fits(frame.height()) && !self.regions.in_last() {
+            self.composer.work.floats.push(placed);
+            return Ok(());
+        }
+
+        // Otherwise, lay it out like a block, surrounded by weak spacing.
+        let spacing = Rel::from(placed.clearance);
+        self.rel(spacing, 4);
+        self.frame(
+            frame,
+            Axes::new(placed.align_x, FixedAlignment::Start),
+            false,
+            false,
+        )?;
+        self.rel(spacing, 4);
+        Ok(())
+    }
+
+    /// Registers the areas that following paragraphs must avoid to wrap around
+    /// the frame of a placed child.
+    fn wrap(&mut self, placed: &PlacedChild, frame: &Frame) {
+        // Determine where the frame will end up. This mirrors the positioning
+        // in `finalize`, except for the offsets by `dx` and `dy`, which don't
+        // affect the layout of in-flow content.
+        let width = self.regions.size.x;
+        let offset = self.offset();
+        let x = placed.align_x.position(width - frame.width());
+        let y = match placed.align_y.unwrap_or_default() {
+            Some(FixedAlignment::Start) => Abs::zero(),
+            Some(align) if self.regions.expand.y => {
+                align.position(offset + self.regions.size.y - frame.height())
+            }
+            _ => offset,
+        };
+
+        // Determine the horizontal extent of the frame's contents in slices
+        // or, when wrapping around the bounding box, in a single slice.
+        let slices = match placed.wrap {
+            PlacementWrap::Shape => contour(frame, Abs::pt(CONTOUR_SLICE)),
+            _ => vec![Some((Abs::zero(), frame.width()))],
+        };
+
+        let count = slices.len();
+        let clearance = placed.clearance;
+        for (i, slice) in slices.into_iter().enumerate() {
+            let Some((min, max)) = slice else { continue };
+
+            // The last slice is extended by the clearance to keep the space
+            // below the content free.
+            let top = y + frame.height() * (i as f64 / count as f64);
+            let mut bottom = y + frame.height() * ((i + 1) as f64 / count as f64);
+            if i + 1 == count {
+                bottom += clearance;
+            }
+
+            // Take away the space at the side on which the content sits.
+            let (left, right) = if placed.align_x == FixedAlignment::Start {
+                (x + max + clearance, Abs::zero())
+            } else {
+                (Abs::zero(), width - x - min + clearance)
+            };
+
+            self.exclusions.push(Exclusion { top, bottom, left, right });
+        }
+    }
+
     /// Processes a float flush.
     fn flush(&mut self) -> FlowResult<()> {
         // If there are still pending floats, finish the region instead of
@@ -501,6 +673,7 @@ impl<'a, 'b> Distributor<'a, 'b, '_, '_, '_> {
         DistributionSnapshot {
             work: self.composer.work.clone(),
             items: self.items.len(),
+            exclusions: self.exclusions.len(),
         }
     }
 
@@ -508,5 +681,159 @@ impl<'a, 'b> Distributor<'a, 'b, '_, '_, '_> {
     fn restore(&mut self, snapshot: DistributionSnapshot<'a, 'b>) {
         *self.composer.work = snapshot.work;
         self.items.truncate(snapshot.items);
+        self.exclusions.truncate(snapshot.exclusions);
+    }
+}
+
+/// Determines the horizontal extent of a frame's visible contents in
+/// horizontal slices of roughly the given height.
+///
+/// Returns one entry per slice, which is `None` if the slice is empty. Curves
+/// are approximated by line segments and text and images by their bounding
+/// boxes.
+fn contour(frame: &Frame, slice: Abs) -> Vec<Option<(Abs, Abs)>> {
+    let count = (frame.height() / slice).ceil().max(1.0) as usize;
+    let mut slices = vec![None; count];
+    contour_impl(&mut slices, frame.height(), frame, Transform::identity());
+    slices
+}
+
+/// Adds the extent of the frame's contents to the slices.
+fn contour_impl(
+    slices: &mut [Option<(Abs, Abs)>],
+    height: Abs,
+    frame: &Frame,
+    ts: Transform,
+) {
+    for (pos, item) in frame.items() {
+        let ts = ts.pre_concat(Transform::translate(pos.x, pos.y));
+        match item {
+            FrameItem::Group(group) => {
+                let ts = ts.pre_concat(group.transform);
+                contour_impl(slices, height, &group.frame, ts);
+            }
+            FrameItem::Shape(shape, _) => {
+                let pad = shape
+                    .stroke
+                    .as_ref()
+                    .map(|stroke| stroke.thickness / 2.0)
+                    .unwrap_or_default();
+
+                let mut add = |points: &[Point]| {
+                    for pair in points.windows(2) {
+                        let (a, b) = (pair[0].transform(ts), pair[1].transform(ts));
+                        contour_segment(slices, height, a, b, pad);
+                    }
+                };
+
+                match &shape.geometry {
+                    Geometry::Line(to) => add(&[Point::zero(), *to]),
+                    Geometry::Rect(size) => add(&rect_outline(*size)),
+                    Geometry::Path(path) => {
+                        let mut points = vec![];
+                        let mut start = Point::zero();
+                        for item in &path.0 {
+                            match *item {
+                                PathItem::MoveTo(p) => {
+                                    add(&points);
+                                    points.clear();
+                                    points.push(p);
+                                    start = p;
+                                }
+                                PathItem::LineTo(p) => points.push(p),
+                                PathItem::CubicTo(c1, c2, p) => {
+                                    let p0 = points.last().copied().unwrap_or(start);
+                                    points.extend(
+                                        (1..=8).map(|i| {
+                                            cubic(p0, c1, c2, p, i as f64 / 8.0)
+                                        }),
+                                    );
+                                }
+                                PathItem::ClosePath => points.push(start),
+                            }
+                        }
+                        add(&points);
+                    }
+                }
+            }
+            FrameItem::Text(text) => {
+                let metrics = text.font.metrics();
+                let top = -metrics.ascender.at(text.size);
+                let bottom = -metrics.descender.at(text.size);
+                let size = Size::new(text.width(), bottom - top);
+                for pair in rect_outline(size).windows(2) {
+                    let shift = Point::with_y(top);
+                    let a = (pair[0] + shift).transform(ts);
+                    let b = (pair[1] + shift).transform(ts);
+                    contour_segment(slices, height, a, b, Abs::zero());
+                }
+            }
+            FrameItem::Image(_, size, _) => {
+                for pair in rect_outline(*size).windows(2) {
+                    let (a, b) = (pair[0].transform(ts), pair[1].transform(ts));
+                    contour_segment(slices, height, a, b, Abs::zero());
+                }
+            }
+            FrameItem::Link(..) | FrameItem::Tag(..) => {}
+        }
+    }
+}
+
+/// Adds the horizontal extent of a line segment (padded by `pad`) to all
+/// slices it crosses.
+fn contour_segment(
+    slices: &mut [Option<(Abs, Abs)>],
+    height: Abs,
+    a: Point,
+    b: Point,
+    pad: Abs,
+) {
+    let count = slices.len();
+    let slice = height / count as f64;
+    let top = a.y.min(b.y) - pad;
+    let bottom = a.y.max(b.y) + pad;
+
+    for (i, extent) in slices.iter_mut().enumerate() {
+        let start = slice * i as f64;
+        let end = start + slice;
+        if bottom < start || top > end {
+            continue;
+        }
+
+        // Determine the horizontal range of the part of the segment that lies
+        // within the slice.
+        let (min, max) = if (b.y - a.y).approx_empty() {
+            (a.x.min(b.x), a.x.max(b.x))
+        } else {
+            let at = |y: Abs| {
+                let t = ((y - a.y) / (b.y - a.y)).clamp(0.0, 1.0);
+                a.x + (b.x - a.x) * t
+            };
+            let (x1, x2) = (at(start), at(end));
+            (x1.min(x2), x1.max(x2))
+        };
+
+        let (min, max) = (min - pad, max + pad);
+        *extent = Some(match *extent {
+            Some((prev_min, prev_max)) => (prev_min.min(min), prev_max.max(max)),
+            None => (min, max),
+        });
     }
 }
+
+/// The closed outline of a rectangle with its origin in the top-left corner.
+fn rect_outline(size: Size) -> [Point; 5] {
+    [
+        Point::zero(),
+        Point::with_x(size.x),
+        size.to_point(),
+        Point::with_y(size.y),
+        Point::zero(),
+    ]
+}
+
+/// Evaluates a cubic bezier curve at `t`.
+fn cubic(p0: Point, p1: Point, p2: Point, p3: Point, t: f64) -> Point {
+    let u = 1.0 - t;
+    p0 * (u * u * u) + p1 * (3.0 * u * u * t) + p2 * (3.0 * u * t * t) + p3 * (t * t * t)
+}
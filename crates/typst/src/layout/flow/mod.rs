@@ -13,9 +13,10 @@ use comemo::{Track, Tracked, TrackedMut};
 use ecow::EcoVec;
 
 use self::collect::{
-    collect, Child, LineChild, MultiChild, MultiSpill, PlacedChild, SingleChild,
+    collect, Child, LineChild, MultiChild, MultiSpill, ParChild, ParSpill, PlacedChild,
+    SingleChild,
 };
-use self::compose::{compose, Composer};
+use self::compose::{compose, Composer, FootnoteSpill};
 use self::distribute::distribute;
 use crate::diag::{bail, At, SourceDiagnostic, SourceResult};
 use crate::engine::{Engine, Route, Sink, Traced};
@@ -232,12 +233,20 @@ struct Work<'a, 'b> {
     children: &'b [Child<'a>],
     /// Leftovers from a breakable block.
     spill: Option<MultiSpill<'a, 'b>>,
+    /// Leftover lines from a paragraph that wraps around placed content.
+    par_spill: Option<ParSpill>,
     /// Queued floats that didn't fit in previous regions.
     floats: EcoVec<&'b PlacedChild<'a>>,
+    /// Floats that are held back until their anchor was laid out.
+    awaiting: EcoVec<&'b PlacedChild<'a>>,
+    /// Anchors of held back floats that were already laid out.
+    anchors: EcoVec<Location>,
+    /// Floats that go onto the next float page.
+    float_pages: EcoVec<&'b PlacedChild<'a>>,
     /// Queued footnotes that didn't fit in previous regions.
     footnotes: EcoVec<Packed<FootnoteElem>>,
     /// Spilled frames of a footnote that didn't fully fit. Similar to `spill`.
-    footnote_spill: Option<std::vec::IntoIter<Frame>>,
+    footnote_spill: Option<FootnoteSpill>,
     /// Queued tags that will be attached to the next frame.
     tags: EcoVec<&'a Tag>,
     /// Identifies floats and footnotes that can be skipped if visited because
@@ -263,7 +272,11 @@ impl<'a, 'b> Work<'a, 'b> {
         Self {
             children,
             spill: None,
+            par_spill: None,
             floats: EcoVec::new(),
+            awaiting: EcoVec::new(),
+            anchors: EcoVec::new(),
+            float_pages: EcoVec::new(),
             footnotes: EcoVec::new(),
             footnote_spill: None,
             tags: EcoVec::new(),
@@ -285,7 +298,10 @@ impl<'a, 'b> Work<'a, 'b> {
     fn done(&self) -> bool {
         self.children.is_empty()
             && self.spill.is_none()
+            && self.par_spill.is_none()
             && self.floats.is_empty()
+            && self.awaiting.is_empty()
+            && self.float_pages.is_empty()
             && self.footnote_spill.is_none()
             && self.footnotes.is_empty()
     }
@@ -325,6 +341,14 @@ struct FootnoteConfig {
     expand: bool,
 }
 
+impl FootnoteConfig {
+    /// The separator to use for the series of the given footnote.
+    fn separator_of(&self, note: &Packed<FootnoteElem>) -> Content {
+        note.separator(StyleChain::default())
+            .unwrap_or_else(|| self.separator.clone())
+    }
+}
+
 /// Configuration of columns.
 struct ColumnConfig {
     /// The number of columns.
@@ -362,6 +362,34 @@ impl Frame {
         });
     }
 
+    /// Remove all items for which `f` returns `false`.
+    ///
+    /// The closure receives the position of each item relative to this frame.
+    /// The items of a retained group are visited as well, unless the group is
+    /// transformed.
+    pub fn retain(&mut self, f: &mut impl FnMut(Point, &FrameItem) -> bool) {
+        self.retain_at(Point::zero(), f);
+    }
+
+    /// Remove items, given this frame's offset from the outermost frame.
+    fn retain_at(
+        &mut self,
+        offset: Point,
+        f: &mut impl FnMut(Point, &FrameItem) -> bool,
+    ) {
+        Arc::make_mut(&mut self.items).retain_mut(|(pos, item)| {
+            if !f(offset + *pos, item) {
+                return false;
+            }
+            if let FrameItem::Group(group) = item {
+                if group.transform.is_identity() {
+                    group.frame.retain_at(offset + *pos, f);
+                }
+            }
+            true
+        });
+    }
+
     /// Add a background fill.
     pub fn fill(&mut self, fill: Paint) {
         self.prepend(
@@ -6,6 +6,7 @@ use ecow::eco_format;
 
 use super::lines::Line;
 use super::repeated::{Footer, Header, Repeatable};
+use super::CharAlignedElem;
 use crate::diag::{bail, At, Hint, HintedStrResult, HintedString, SourceResult};
 use crate::engine::Engine;
 use crate::foundations::{
@@ -14,8 +15,8 @@ use crate::foundations::{
 };
 use crate::introspection::Locator;
 use crate::layout::{
-    layout_fragment, Abs, Alignment, Axes, Fragment, Length, LinePosition, Regions, Rel,
-    Sides, Sizing,
+    layout_fragment, layout_frame, Abs, Alignment, Axes, Fragment, Length, LinePosition,
+    Region, Regions, Rel, Sides, Size, Sizing,
 };
 use crate::syntax::Span;
 use crate::utils::NonZeroExt;
@@ -187,6 +188,10 @@ pub struct Cell<'a> {
     /// By default, a cell spanning only fixed-size rows is unbreakable, while
     /// a cell spanning at least one `auto`-sized row is breakable.
     pub breakable: bool,
+    /// The character the cell is aligned on, if any, and copies of the cell
+    /// holding the parts of its body before and after the character. These
+    /// are measured to line up the character across the cell's column.
+    pub char_parts: Option<(char, Content, Content)>,
 }
 
 impl<'a> Cell<'a> {
@@ -201,6 +206,7 @@ impl<'a> Cell<'a> {
             stroke: Sides::splat(None),
             stroke_overridden: Sides::splat(false),
             breakable: true,
+            char_parts: None,
         }
     }
 
@@ -238,7 +244,7 @@ pub(super) enum Entry<'a> {
 
 impl<'a> Entry<'a> {
     /// Obtains the cell inside this entry, if this is not a merged cell.
-    fn as_cell(&self) -> Option<&Cell<'a>> {
+    pub(super) fn as_cell(&self) -> Option<&Cell<'a>> {
         match self {
             Self::Cell(cell) => Some(cell),
             Self::Merged { .. } => None,
@@ -281,7 +287,7 @@ pub enum ResolvableGridItem<T: ResolvableCell> {
 
 /// Any grid child, which can be either a header or an item.
 pub enum ResolvableGridChild<T: ResolvableCell, I> {
-    Header { repeat: bool, span: Span, items: I },
+    Header { repeat: bool, level: NonZeroUsize, span: Span, items: I },
     Footer { repeat: bool, span: Span, items: I },
     Item(ResolvableGridItem<T>),
 }
@@ -290,8 +296,8 @@ pub enum ResolvableGridChild<T: ResolvableCell, I> {
 /// the table, and may have property overrides.
 pub trait ResolvableCell {
     /// Resolves the cell's fields, given its coordinates and default grid-wide
-    /// fill, align, inset and stroke properties, plus the expected value of
-    /// the `breakable` field.
+    /// fill, align, char-align, inset and stroke properties, plus the expected
+    /// value of the `breakable` field.
     /// Returns a final Cell.
     #[allow(clippy::too_many_arguments)]
     fn resolve_cell<'a>(
@@ -300,6 +306,7 @@ pub trait ResolvableCell {
         y: usize,
         fill: &Option<Paint>,
         align: Smart<Alignment>,
+        char_align: Option<char>,
         inset: Sides<Option<Rel<Length>>>,
         stroke: Sides<Option<Option<Arc<Stroke<Abs>>>>>,
         breakable: bool,
@@ -341,6 +348,9 @@ pub struct CellGrid<'a> {
     pub(super) hlines: Vec<Vec<Line>>,
     /// The repeatable header of this grid.
     pub(super) header: Option<Repeatable<Header>>,
+    /// The headers of a level greater than 1 which start after the first row,
+    /// sorted by their first row.
+    pub(super) subheaders: Vec<Repeatable<Header>>,
     /// The repeatable footer of this grid.
     pub(super) footer: Option<Repeatable<Footer>>,
     /// Whether this grid has gutters.
@@ -355,7 +365,7 @@ impl<'a> CellGrid<'a> {
         cells: impl IntoIterator<Item = Cell<'a>>,
     ) -> Self {
         let entries = cells.into_iter().map(Entry::Cell).collect();
-        Self::new_internal(tracks, gutter, vec![], vec![], None, None, entries)
+        Self::new_internal(tracks, gutter, vec![], vec![], None, vec![], None, entries)
     }
 
     /// Resolves and positions all cells in the grid before creating it.
@@ -372,6 +382,7 @@ impl<'a> CellGrid<'a> {
         children: C,
         fill: &Celled<Option<Paint>>,
         align: &Celled<Smart<Alignment>>,
+        char_align: &Celled<Option<Smart<char>>>,
         inset: &Celled<Sides<Option<Rel<Length>>>>,
         stroke: &ResolvedCelled<Sides<Option<Option<Arc<Stroke>>>>>,
         engine: &mut Engine,
@@ -406,6 +417,10 @@ impl<'a> CellGrid<'a> {
         let mut header: Option<Header> = None;
         let mut repeat_header = false;
 
+        // Stores the sub-headers along with whether they repeat and their
+        // spans.
+        let mut subheaders: Vec<(Header, bool, Span)> = vec![];
+
         // Stores where the footer is supposed to end, its span, and the
         // actual footer structure.
         let mut footer: Option<(usize, Span, Footer)> = None;
@@ -460,6 +475,8 @@ impl<'a> CellGrid<'a> {
         let mut resolved_cells: Vec<Option<Entry>> = Vec::with_capacity(child_count);
         for child in children {
             let mut is_header = false;
+            let mut header_level = NonZeroUsize::ONE;
+            let mut header_repeat = false;
             let mut is_footer = false;
             let mut child_start = usize::MAX;
             let mut child_end = 0;
@@ -469,21 +486,20 @@ impl<'a> CellGrid<'a> {
             let mut first_index_of_non_top_hlines = usize::MAX;
 
             let (header_footer_items, simple_item) = match child {
-                ResolvableGridChild::Header { repeat, span, items, .. } => {
-                    if header.is_some() {
+                ResolvableGridChild::Header { repeat, level, span, items } => {
+                    if level == NonZeroUsize::ONE && header.is_some() {
                         bail!(span, "cannot have more than one header");
                     }
 
                     is_header = true;
+                    header_level = level;
                     child_span = span;
-                    repeat_header = repeat;
+                    header_repeat = repeat;
 
                     // If any cell in the header is automatically positioned,
                     // have it skip to the next row. This is to avoid having a
                     // header after a partially filled row just add cells to
                     // that row instead of starting a new one.
-                    // FIXME: Revise this approach when headers can start from
-                    // arbitrary rows.
                     start_new_row = true;
 
                     // Any hlines at the top of the header will start at this
@@ -686,6 +702,7 @@ impl<'a> CellGrid<'a> {
                     y,
                     &fill.resolve(engine, styles, x, y)?,
                     align.resolve(engine, styles, x, y)?,
+                    char_align.resolve(engine, styles, x, y)?.map(|c| c.unwrap_or('.')),
                     inset.resolve(engine, styles, x, y)?,
                     stroke.resolve(engine, styles, x, y)?,
                     resolve_breakable(y, rowspan),
@@ -798,7 +815,19 @@ impl<'a> CellGrid<'a> {
                 }
             }
 
-            if is_header {
+            if is_header && header_level > NonZeroUsize::ONE {
+                // Like the main header, sub-headers are converted to
+                // post-gutter coordinates once they have fully expanded.
+                subheaders.push((
+                    Header {
+                        start: child_start,
+                        end: child_end,
+                        level: header_level,
+                    },
+                    header_repeat,
+                    child_span,
+                ));
+            } else if is_header {
                 if child_start != 0 {
                     bail!(
                         child_span,
@@ -807,12 +836,15 @@ impl<'a> CellGrid<'a> {
                     );
                 }
 
+                repeat_header = header_repeat;
                 header = Some(Header {
+                    start: 0,
                     // Later on, we have to correct this number in case there
                     // is gutter. But only once all cells have been analyzed
                     // and the header has fully expanded in the fixup loop
                     // below.
                     end: child_end,
+                    level: header_level,
                 });
             }
 
@@ -862,8 +894,6 @@ impl<'a> CellGrid<'a> {
                 // placed at the first row that is fully empty or something).
                 // Nothing we can do when both 'x' and 'y' were overridden, of
                 // course.
-                // None of the above are concerns for now, as headers must
-                // start at the first row.
                 auto_index = auto_index.max(c * child_end);
             }
         }
@@ -885,7 +915,7 @@ impl<'a> CellGrid<'a> {
         // creation, ensure the header expands enough to accommodate them
         // across all of their spanned rows. Same for the footer.
         // 4. If any cells before the footer try to span it, error.
-        let resolved_cells = resolved_cells
+        let mut resolved_cells = resolved_cells
             .into_iter()
             .chain(std::iter::repeat_with(|| None).take(missing_cells))
             .enumerate()
@@ -909,6 +939,27 @@ impl<'a> CellGrid<'a> {
                             }
                         }
 
+                        for (subheader, _, subheader_span) in &mut subheaders {
+                            let x = i % c;
+                            let y = i / c;
+                            let cell_end = y + parent_cell.rowspan.get();
+                            if y < subheader.start && cell_end > subheader.start {
+                                // As with footers, don't allow a cell before
+                                // the sub-header to span it, since it would
+                                // not be repeated with it.
+                                bail!(
+                                    *subheader_span,
+                                    "header would conflict with a cell placed before it at column {x} row {y}";
+                                    hint: "try reducing that cell's rowspan or moving the header"
+                                );
+                            }
+                            if y >= subheader.start && y < subheader.end {
+                                // Expand the sub-header to include all rows
+                                // spanned by this cell, as it is inside it.
+                                subheader.end = subheader.end.max(cell_end);
+                            }
+                        }
+
                         if let Some((end, footer_span, footer)) = &mut footer {
                             let x = i % c;
                             let y = i / c;
@@ -948,6 +999,9 @@ impl<'a> CellGrid<'a> {
                         y,
                         &fill.resolve(engine, styles, x, y)?,
                         align.resolve(engine, styles, x, y)?,
+                        char_align
+                        .resolve(engine, styles, x, y)?
+                        .map(|c| c.unwrap_or('.')),
                         inset.resolve(engine, styles, x, y)?,
                         stroke.resolve(engine, styles, x, y)?,
                         resolve_breakable(y, 1),
@@ -959,6 +1013,10 @@ impl<'a> CellGrid<'a> {
             })
             .collect::<SourceResult<Vec<Entry>>>()?;
 
+        // Line up the cells which are aligned on a character within each of
+        // their columns.
+        align_on_chars(&mut resolved_cells, c, engine, styles)?;
+
         // Populate the final lists of lines.
         // For each line type (horizontal or vertical), we keep a vector for
         // every group of lines with the same index.
@@ -1077,14 +1135,38 @@ impl<'a> CellGrid<'a> {
                 }
             });
 
+        // Sub-headers also repeat the gutter below them.
+        subheaders.sort_by_key(|(subheader, ..)| subheader.start);
+        let mut headers_end = header.as_ref().map_or(0, |header| header.unwrap().end);
+        let subheaders = subheaders
+            .into_iter()
+            .map(|(mut subheader, repeat, subheader_span)| {
+                if has_gutter {
+                    subheader.start *= 2;
+                    subheader.end =
+                        (2 * subheader.end).min((2 * row_amount).saturating_sub(1));
+                }
+
+                if subheader.start < headers_end {
+                    bail!(subheader_span, "headers must not have common rows");
+                }
+                headers_end = subheader.end;
+
+                Ok(if repeat {
+                    Repeatable::Repeated(subheader)
+                } else {
+                    Repeatable::NotRepeated(subheader)
+                })
+            })
+            .collect::<SourceResult<Vec<_>>>()?;
+
         let footer = footer
             .map(|(footer_end, footer_span, mut footer)| {
                 if footer_end != row_amount {
                     bail!(footer_span, "footer must end at the last row");
                 }
 
-                let header_end =
-                    header.as_ref().map(Repeatable::unwrap).map(|header| header.end);
+                let header_end = (headers_end > 0).then_some(headers_end);
 
                 if has_gutter {
                     // Convert the footer's start index to post-gutter coordinates.
@@ -1119,18 +1201,21 @@ impl<'a> CellGrid<'a> {
             vlines,
             hlines,
             header,
+            subheaders,
             footer,
             resolved_cells,
         ))
     }
 
     /// Generates the cell grid, given the tracks and resolved entries.
+    #[allow(clippy::too_many_arguments)]
     pub(super) fn new_internal(
         tracks: Axes<&[Sizing]>,
         gutter: Axes<&[Sizing]>,
         vlines: Vec<Vec<Line>>,
         hlines: Vec<Vec<Line>>,
         header: Option<Repeatable<Header>>,
+        subheaders: Vec<Repeatable<Header>>,
         footer: Option<Repeatable<Footer>>,
         entries: Vec<Entry<'a>>,
     ) -> Self {
@@ -1185,6 +1270,7 @@ impl<'a> CellGrid<'a> {
             vlines,
             hlines,
             header,
+            subheaders,
             footer,
             has_gutter,
         }
@@ -1308,6 +1394,24 @@ impl<'a> CellGrid<'a> {
             cell.rowspan.get()
         }
     }
+
+    /// Returns the sub-header starting at the given row, if any.
+    pub(super) fn subheader_at(&self, y: usize) -> Option<&Header> {
+        self.subheaders
+            .binary_search_by_key(&y, |subheader| subheader.unwrap().start)
+            .ok()
+            .map(|i| self.subheaders[i].unwrap())
+    }
+
+    /// Whether the given row belongs to the main header or to a sub-header
+    /// which repeat in upcoming regions.
+    pub(super) fn is_repeated_header_row(&self, y: usize) -> bool {
+        self.header
+            .iter()
+            .chain(&self.subheaders)
+            .filter_map(Repeatable::as_repeated)
+            .any(|header| (header.start..header.end).contains(&y))
+    }
 }
 
 /// Given a cell's requested x and y, the vector with the resolved cell
@@ -1477,3 +1581,46 @@ fn skip_auto_index_through_fully_merged_rows(
         }
     }
 }
+
+/// Measures the parts of the cells which are aligned on a character and pads
+/// them to the widest such parts in each column, such that the characters of
+/// all these cells line up. Cells aligned on different characters are lined
+/// up separately.
+///
+/// Only cells spanning a single column take part in this.
+fn align_on_chars(
+    entries: &mut [Entry],
+    columns: usize,
+    engine: &mut Engine,
+    styles: StyleChain,
+) -> SourceResult<()> {
+    let region = Region::new(Size::splat(Abs::inf()), Axes::splat(false));
+    for x in 0..columns {
+        let mut widths: Vec<(char, Axes<Abs>)> = vec![];
+        for entry in entries.iter().skip(x).step_by(columns) {
+            let Some(cell) = entry.as_cell() else { continue };
+            let Some((c, before, after)) = &cell.char_parts else { continue };
+            let mut locator = cell.locator.relayout().split();
+            let before = layout_frame(engine, before, locator.next(&()), styles, region)?;
+            let after = layout_frame(engine, after, locator.next(&()), styles, region)?;
+            let measured = Axes::new(before.width(), after.width());
+            match widths.iter_mut().find(|(other, _)| other == c) {
+                Some((_, widths)) => *widths = widths.max(measured),
+                None => widths.push((*c, measured)),
+            }
+        }
+
+        for entry in entries.iter_mut().skip(x).step_by(columns) {
+            let Entry::Cell(cell) = entry else { continue };
+            let Some((c, ..)) = cell.char_parts else { continue };
+            let Some((_, widths)) = widths.iter().find(|(other, _)| *other == c) else {
+                continue;
+            };
+            cell.body = std::mem::take(&mut cell.body).styled(
+                CharAlignedElem::set_widths(Smart::Custom(widths.map(Length::from))),
+            );
+        }
+    }
+
+    Ok(())
+}
@@ -4,7 +4,7 @@ use super::lines::{
     generate_line_segments, hline_stroke_at_column, vline_stroke_at_row, LinePosition,
     LineSegment,
 };
-use super::repeated::Repeatable;
+use super::repeated::{Continuation, Header, Repeatable};
 use super::rowspans::{Rowspan, UnbreakableRowGroup};
 use crate::diag::{bail, SourceResult};
 use crate::engine::Engine;
@@ -48,7 +48,7 @@ pub struct GridLayouter<'a> {
     /// Whether this is an RTL grid.
     pub(super) is_rtl: bool,
     /// The simulated header height.
-    /// This field is reset in `layout_header` and properly updated by
+    /// This field is reset in `layout_headers` and properly updated by
     /// `layout_auto_row` and `layout_relative_row`, and should not be read
     /// before all header rows are fully laid out. It is usually fine because
     /// header rows themselves are unbreakable, and unbreakable rows do not
@@ -57,6 +57,17 @@ pub struct GridLayouter<'a> {
     /// The simulated footer height for this region.
     /// The simulation occurs before any rows are laid out for a region.
     pub(super) footer_height: Abs,
+    /// The sub-headers repeated on upcoming regions, ordered by level.
+    pub(super) repeating_subheaders: Vec<&'a Header>,
+    /// The amount of rows at the top of the current region which belong to
+    /// repeated headers.
+    pub(super) header_rows: usize,
+    /// Whether the rows being laid out belong to a continued header or
+    /// footer.
+    pub(super) continuation: Option<Continuation>,
+    /// If the grid may break horizontally, the number of leading columns to
+    /// repeat on every slice.
+    pub(super) column_split: Option<usize>,
     /// The span of the grid element.
     pub(super) span: Span,
 }
@@ -122,13 +133,26 @@ impl<'a> GridLayouter<'a> {
             is_rtl: TextElem::dir_in(styles) == Dir::RTL,
             header_height: Abs::zero(),
             footer_height: Abs::zero(),
+            repeating_subheaders: vec![],
+            header_rows: 0,
+            continuation: None,
+            column_split: None,
             span,
         }
     }
 
+    /// Allow the grid to break horizontally, splitting its columns into
+    /// slices that each fit the available width. The given number of leading
+    /// columns is repeated on every slice.
+    pub fn with_column_split(mut self, repeat: usize) -> Self {
+        self.column_split = Some(repeat);
+        self
+    }
+
     /// Determines the columns sizes and then layouts the grid row-by-row.
     pub fn layout(mut self, engine: &mut Engine) -> SourceResult<Fragment> {
         self.measure_columns(engine)?;
+        let slices = self.column_slices();
 
         if let Some(Repeatable::Repeated(footer)) = &self.grid.footer {
             // Ensure rows in the first region will be aware of the possible
@@ -140,11 +164,14 @@ impl<'a> GridLayouter<'a> {
             }
         }
 
+        let grid = self.grid;
+        let mut subheaders = grid.subheaders.iter().peekable();
+        let mut subheader_end = 0;
         for y in 0..self.grid.rows.len() {
             if let Some(Repeatable::Repeated(header)) = &self.grid.header {
                 if y < header.end {
                     if y == 0 {
-                        self.layout_header(header, engine, 0)?;
+                        self.layout_headers(engine, 0)?;
                         self.regions.size.y -= self.footer_height;
                     }
                     // Skip header rows during normal layout.
@@ -152,10 +179,23 @@ impl<'a> GridLayouter<'a> {
                 }
             }
 
+            if y < subheader_end {
+                // Sub-header rows were already laid out.
+                continue;
+            }
+
+            if let Some(subheader) =
+                subheaders.next_if(|subheader| subheader.unwrap().start == y)
+            {
+                self.layout_subheader(subheader, engine)?;
+                subheader_end = subheader.unwrap().end;
+                continue;
+            }
+
             if let Some(Repeatable::Repeated(footer)) = &self.grid.footer {
                 if y >= footer.start {
                     if y == footer.start {
-                        self.layout_footer(footer, engine, self.finished.len())?;
+                        self.layout_footer(footer, engine, self.finished.len(), false)?;
                     }
                     continue;
                 }
@@ -181,7 +221,11 @@ impl<'a> GridLayouter<'a> {
             self.layout_rowspan(rowspan, None, engine)?;
         }
 
-        self.render_fills_strokes()
+        let fragment = self.render_fills_strokes()?;
+        Ok(match slices {
+            Some(slices) => slices.apply(fragment),
+            None => fragment,
+        })
     }
 
     /// Layout the given row.
@@ -703,7 +747,14 @@ impl<'a> GridLayouter<'a> {
 
         // Size that is not used by fixed-size columns.
         let available = self.regions.size.x - rel;
-        if available >= Abs::zero() {
+        if self.column_split.is_some() {
+            // If the grid may break horizontally, auto columns keep their
+            // natural size instead of shrinking, and fractional columns only
+            // receive what remains of the first slice. Individual columns are
+            // still limited to the available width.
+            let (auto, _) = self.measure_auto_columns(engine, self.regions.size.x)?;
+            self.grow_fractional_columns((available - auto).max(Abs::zero()), fr);
+        } else if available >= Abs::zero() {
             // Determine size of auto columns.
             let (auto, count) = self.measure_auto_columns(engine, available)?;
 
@@ -1086,8 +1137,9 @@ impl<'a> GridLayouter<'a> {
                 pod
             };
 
-            let frames =
-                cell.layout(engine, disambiguator, self.styles, pod)?.into_frames();
+            let frames = self
+                .layout_cell(cell, engine, disambiguator, self.continuation, pod)?
+                .into_frames();
 
             // Skip the first region if one cell in it is empty. Then,
             // remeasure.
@@ -1253,8 +1305,8 @@ impl<'a> GridLayouter<'a> {
                         // rows.
                         pod.full = self.regions.full;
                     }
-                    let frame = cell
-                        .layout(engine, disambiguator, self.styles, pod)?
+                    let frame = self
+                        .layout_cell(cell, engine, disambiguator, self.continuation, pod)?
                         .into_frame();
                     let mut pos = pos;
                     if self.is_rtl {
@@ -1310,8 +1362,13 @@ impl<'a> GridLayouter<'a> {
                     pod.size.x = width;
 
                     // Push the layouted frames into the individual output frames.
-                    let fragment =
-                        cell.layout(engine, disambiguator, self.styles, pod)?;
+                    let fragment = self.layout_cell(
+                        cell,
+                        engine,
+                        disambiguator,
+                        self.continuation,
+                        pod,
+                    )?;
                     for (output, frame) in outputs.iter_mut().zip(fragment) {
                         let mut pos = pos;
                         if self.is_rtl {
@@ -1369,25 +1426,28 @@ impl<'a> GridLayouter<'a> {
                 .and_then(Repeatable::as_repeated)
                 .is_some_and(|footer| footer.start != 0);
 
-        if let Some(Repeatable::Repeated(header)) = &self.grid.header {
-            if self.grid.rows.len() > header.end
-                && self
-                    .grid
-                    .footer
-                    .as_ref()
-                    .and_then(Repeatable::as_repeated)
-                    .map_or(true, |footer| footer.start != header.end)
-                && self.lrows.last().is_some_and(|row| row.index() < header.end)
-                && !in_last_with_offset(
-                    self.regions,
-                    self.header_height + self.footer_height,
-                )
-            {
-                // Header and footer would be alone in this region, but there are more
-                // rows beyond the header and the footer. Push an empty region.
-                self.lrows.clear();
-                footer_would_be_orphan = true;
-            }
+        let header_end = self
+            .grid
+            .header
+            .as_ref()
+            .and_then(Repeatable::as_repeated)
+            .map_or(0, |header| header.end);
+        if self.header_rows > 0
+            && self.grid.rows.len() > header_end
+            && self
+                .grid
+                .footer
+                .as_ref()
+                .and_then(Repeatable::as_repeated)
+                .map_or(true, |footer| footer.start != header_end)
+            && self.lrows.len() <= self.header_rows
+            && !in_last_with_offset(self.regions, self.header_height + self.footer_height)
+        {
+            // Headers and footer would be alone in this region, but there are
+            // more rows beyond the headers and the footer. Push an empty
+            // region.
+            self.lrows.clear();
+            footer_would_be_orphan = true;
         }
 
         let mut laid_out_footer_start = None;
@@ -1398,7 +1458,7 @@ impl<'a> GridLayouter<'a> {
                 && self.lrows.iter().all(|row| row.index() < footer.start)
             {
                 laid_out_footer_start = Some(footer.start);
-                self.layout_footer(footer, engine, self.finished.len())?;
+                self.layout_footer(footer, engine, self.finished.len(), !last)?;
             }
         }
 
@@ -1534,9 +1594,11 @@ impl<'a> GridLayouter<'a> {
                 self.prepare_footer(footer, engine, disambiguator)?;
             }
 
-            if let Some(Repeatable::Repeated(header)) = &self.grid.header {
-                // Add a header to the new region.
-                self.layout_header(header, engine, disambiguator)?;
+            if matches!(self.grid.header, Some(Repeatable::Repeated(_)))
+                || !self.repeating_subheaders.is_empty()
+            {
+                // Add the headers to the new region.
+                self.layout_headers(engine, disambiguator)?;
             }
 
             // Ensure rows don't try to overrun the footer.
@@ -1557,6 +1619,26 @@ impl<'a> GridLayouter<'a> {
         self.rrows.push(resolved_rows);
         self.regions.next();
         self.initial = self.regions.size;
+        self.header_rows = 0;
+    }
+
+    /// Lays out a cell, marking it as part of a continued header or footer if
+    /// requested.
+    pub(super) fn layout_cell(
+        &self,
+        cell: &Cell,
+        engine: &mut Engine,
+        disambiguator: usize,
+        continuation: Option<Continuation>,
+        regions: Regions,
+    ) -> SourceResult<Fragment> {
+        match continuation {
+            Some(continuation) => {
+                let local = continuation.styles();
+                cell.layout(engine, disambiguator, self.styles.chain(&local), regions)
+            }
+            None => cell.layout(engine, disambiguator, self.styles, regions),
+        }
     }
 }
 
@@ -616,6 +616,7 @@ mod test {
             stroke: Sides::splat(Some(Arc::new(Stroke::default()))),
             stroke_overridden: Sides::splat(false),
             breakable: true,
+            char_parts: None,
         }
     }
 
@@ -629,6 +630,7 @@ mod test {
             stroke: Sides::splat(Some(Arc::new(Stroke::default()))),
             stroke_overridden: Sides::splat(false),
             breakable: true,
+            char_parts: None,
         }
     }
 
@@ -677,6 +679,7 @@ mod test {
             vec![],
             vec![],
             None,
+            vec![],
             None,
             entries,
         )
@@ -1215,6 +1218,7 @@ mod test {
             vec![],
             vec![],
             None,
+            vec![],
             None,
             entries,
         )
@@ -3,6 +3,7 @@ mod layout;
 mod lines;
 mod repeated;
 mod rowspans;
+mod slices;
 
 pub use self::cells::{
     Cell, CellGrid, Celled, ResolvableCell, ResolvableGridChild, ResolvableGridItem,
@@ -19,12 +20,12 @@ use smallvec::{smallvec, SmallVec};
 use crate::diag::{bail, HintedStrResult, HintedString, SourceResult, Trace, Tracepoint};
 use crate::engine::Engine;
 use crate::foundations::{
-    cast, elem, scope, Array, Content, Fold, NativeElement, Packed, Show, Smart,
-    StyleChain, Value,
+    cast, elem, scope, Array, Content, Fold, NativeElement, Packed, SequenceElem, Show,
+    Smart, StyleChain, StyledElem, Value,
 };
 use crate::introspection::Locator;
 use crate::layout::{
-    Abs, Alignment, Axes, BlockElem, Dir, Fragment, Length, OuterHAlignment,
+    Abs, Alignment, Axes, BlockElem, BoxElem, Dir, Fragment, Length, OuterHAlignment,
     OuterVAlignment, Regions, Rel, Sides, Sizing,
 };
 use crate::model::{TableCell, TableFooter, TableHLine, TableHeader, TableVLine};
@@ -222,6 +223,20 @@ pub struct GridElem {
     #[borrowed]
     pub align: Celled<Smart<Alignment>>,
 
+    /// A character on which to align the cells' content.
+    ///
+    /// This can either be a single character, an array of characters
+    /// (corresponding to each column) or a function that returns a character.
+    /// The cells of a column are then lined up on the first occurrence of the
+    /// character in their text. Cells without the character are aligned as if
+    /// it followed their content. When set to `{auto}`, the cells are aligned
+    /// on a period, which is the decimal separator in many languages.
+    ///
+    /// You can find an example for this argument at the
+    /// [`table.char-align`]($table.char-align) parameter.
+    #[borrowed]
+    pub char_align: Celled<Option<Smart<char>>>,
+
     /// How to [stroke]($stroke) the cells.
     ///
     /// Grids have no strokes by default, which can be changed by setting this
@@ -310,6 +325,18 @@ pub struct GridElem {
     #[fold]
     pub inset: Celled<Sides<Option<Rel<Length>>>>,
 
+    /// Whether the grid may break horizontally.
+    ///
+    /// You can find an example for this argument at the
+    /// [`table.split-columns`]($table.split-columns) parameter.
+    #[default(false)]
+    pub split_columns: bool,
+
+    /// How many leading columns to repeat on every slice of a grid that is
+    /// [split horizontally]($grid.split-columns).
+    #[default(0)]
+    pub repeat_columns: usize,
+
     /// The contents of the grid cells, plus any extra grid lines specified
     /// with the [`grid.hline`]($grid.hline) and [`grid.vline`]($grid.vline)
     /// elements.
@@ -356,6 +383,7 @@ fn layout_grid(
 ) -> SourceResult<Fragment> {
     let inset = elem.inset(styles);
     let align = elem.align(styles);
+    let char_align = elem.char_align(styles);
     let columns = elem.columns(styles);
     let rows = elem.rows(styles);
     let column_gutter = elem.column_gutter(styles);
@@ -371,6 +399,7 @@ fn layout_grid(
     let children = elem.children().iter().map(|child| match child {
         GridChild::Header(header) => ResolvableGridChild::Header {
             repeat: header.repeat(styles),
+            level: header.level(styles),
             span: header.span(),
             items: header.children().iter().map(resolve_item),
         },
@@ -388,6 +417,7 @@ fn layout_grid(
         children,
         fill,
         align,
+        char_align,
         &inset,
         &stroke,
         engine,
@@ -396,7 +426,10 @@ fn layout_grid(
     )
     .trace(engine.world, tracepoint, elem.span())?;
 
-    let layouter = GridLayouter::new(&grid, regions, styles, elem.span());
+    let mut layouter = GridLayouter::new(&grid, regions, styles, elem.span());
+    if elem.split_columns(styles) {
+        layouter = layouter.with_column_split(elem.repeat_columns(styles));
+    }
 
     // Measure the columns and layout the grid row-by-row.
     layouter.layout(engine)
@@ -573,6 +606,24 @@ pub struct GridHeader {
     #[default(true)]
     pub repeat: bool,
 
+    /// The level of this header.
+    ///
+    /// A header of level 1 must start at the first row. Headers of a higher
+    /// level can start at any row and are repeated below it until the next
+    /// header of the same or a lower level. You can find an example at the
+    /// [`table.header`]($table.header) element.
+    #[default(NonZeroUsize::ONE)]
+    pub level: NonZeroUsize,
+
+    /// Whether the header is being repeated on a page after the one where it
+    /// first appeared.
+    ///
+    /// This is set by the grid and can be read in a
+    /// [context expression]($context) within the header's cells to vary their
+    /// content on continuation pages.
+    #[default(false)]
+    pub continued: bool,
+
     /// The cells and lines within the header.
     #[variadic]
     pub children: Vec<GridItem>,
@@ -590,6 +641,15 @@ pub struct GridFooter {
     #[default(true)]
     pub repeat: bool,
 
+    /// Whether the grid continues on the next page after this instance of the
+    /// footer.
+    ///
+    /// This is set by the grid and can be read in a
+    /// [context expression]($context) within the footer's cells, much like
+    /// the [`continued`]($grid.header.continued) field of the header.
+    #[default(false)]
+    pub continued: bool,
+
     /// The cells and lines within the footer.
     #[variadic]
     pub children: Vec<GridItem>,
@@ -832,6 +892,9 @@ pub struct GridCell {
     /// The cell's [alignment]($grid.align) override.
     pub align: Smart<Alignment>,
 
+    /// The cell's [character alignment]($grid.char-align) override.
+    pub char_align: Smart<Option<char>>,
+
     /// The cell's [inset]($grid.inset) override.
     pub inset: Smart<Sides<Option<Rel<Length>>>>,
 
@@ -865,6 +928,7 @@ impl ResolvableCell for Packed<GridCell> {
         y: usize,
         fill: &Option<Paint>,
         align: Smart<Alignment>,
+        char_align: Option<char>,
         inset: Sides<Option<Rel<Length>>>,
         stroke: Sides<Option<Option<Arc<Stroke<Abs>>>>>,
         breakable: bool,
@@ -876,6 +940,12 @@ impl ResolvableCell for Packed<GridCell> {
         let rowspan = cell.rowspan(styles);
         let breakable = cell.breakable(styles).unwrap_or(breakable);
         let fill = cell.fill(styles).unwrap_or_else(|| fill.clone());
+        // Only cells spanning a single column can be lined up with the others
+        // in their column.
+        let char_align = cell
+            .char_align(styles)
+            .unwrap_or(char_align)
+            .filter(|_| colspan.get() == 1);
 
         let cell_stroke = cell.stroke(styles);
         let stroke_overridden =
@@ -903,6 +973,7 @@ impl ResolvableCell for Packed<GridCell> {
             // the outer alignment when it is effectively displayed).
             Smart::Auto => cell.align(styles),
         });
+        cell.push_char_align(Smart::Custom(char_align));
         cell.push_inset(Smart::Custom(
             cell.inset(styles).map_or(inset, |inner| inner.fold(inset)),
         ));
@@ -920,6 +991,21 @@ impl ResolvableCell for Packed<GridCell> {
             }),
         );
         cell.push_breakable(Smart::Custom(breakable));
+        // The parts are measured as copies of the cell without inset and
+        // alignment, so that they are subject to the same styles and show rules
+        // as the cell itself.
+        let char_parts = char_align.map(|c| {
+            let (before, after) = split_at_char(self.body(), c);
+            let part = |body: Content| {
+                let mut part = self.clone();
+                part.push_body(body);
+                part.push_align(Smart::Auto);
+                part.push_char_align(Smart::Custom(None));
+                part.push_inset(Smart::Custom(Sides::splat(None)));
+                part.pack()
+            };
+            (c, part(before), part(after))
+        });
         Cell {
             body: self.pack(),
             locator,
@@ -929,6 +1015,7 @@ impl ResolvableCell for Packed<GridCell> {
             stroke,
             stroke_overridden,
             breakable,
+            char_parts,
         }
     }
 
@@ -955,7 +1042,12 @@ impl ResolvableCell for Packed<GridCell> {
 
 impl Show for Packed<GridCell> {
     fn show(&self, _engine: &mut Engine, styles: StyleChain) -> SourceResult<Content> {
-        show_grid_cell(self.body().clone(), self.inset(styles), self.align(styles))
+        show_grid_cell(
+            self.body().clone(),
+            self.inset(styles),
+            self.align(styles),
+            self.char_align(styles),
+        )
     }
 }
 
@@ -971,7 +1063,13 @@ pub(crate) fn show_grid_cell(
     mut body: Content,
     inset: Smart<Sides<Option<Rel<Length>>>>,
     align: Smart<Alignment>,
+    char_align: Smart<Option<char>>,
 ) -> SourceResult<Content> {
+    if let Smart::Custom(Some(c)) = char_align {
+        let (before, after) = split_at_char(&body, c);
+        body = CharAlignedElem::new(before, after).pack().spanned(body.span());
+    }
+
     let inset = inset.unwrap_or_default().map(Option::unwrap_or_default);
 
     if inset != Sides::default() {
@@ -987,3 +1085,78 @@ pub(crate) fn show_grid_cell(
 
     Ok(body)
 }
+
+/// Content which is aligned on a character within a grid column.
+///
+/// The grid pads the parts before and after the character to the widest such
+/// parts in the column.
+#[elem(Show)]
+pub struct CharAlignedElem {
+    /// The part before the character.
+    #[required]
+    pub before: Content,
+
+    /// The part starting with the character.
+    #[required]
+    pub after: Content,
+
+    /// The widths to pad the parts to. This is set by the grid once all cells
+    /// of the column were measured.
+    #[internal]
+    #[ghost]
+    pub widths: Smart<Axes<Length>>,
+}
+
+impl Show for Packed<CharAlignedElem> {
+    fn show(&self, _: &mut Engine, styles: StyleChain) -> SourceResult<Content> {
+        let Smart::Custom(widths) = CharAlignedElem::widths_in(styles) else {
+            return Ok(self.before().clone() + self.after().clone());
+        };
+
+        let part = |body: Content, width: Length| {
+            BoxElem::new()
+                .with_width(Sizing::Rel(width.into()))
+                .with_body(Some(body))
+                .pack()
+        };
+
+        Ok(part(self.before().clone().aligned(Alignment::END), widths.x)
+            + part(self.after().clone().aligned(Alignment::START), widths.y))
+    }
+}
+
+/// Splits content at the first occurrence of a character in its text. The
+/// character itself starts the second part. If the character does not occur,
+/// the whole content makes up the first part.
+pub(crate) fn split_at_char(content: &Content, c: char) -> (Content, Content) {
+    try_split_at_char(content, c).unwrap_or_else(|| (content.clone(), Content::empty()))
+}
+
+/// Splits content at a character, if it occurs in the content's text.
+fn try_split_at_char(content: &Content, c: char) -> Option<(Content, Content)> {
+    if let Some(elem) = content.to_packed::<TextElem>() {
+        let text = elem.text();
+        let i = text.find(c)?;
+        let part = |text: &str| TextElem::packed(text).spanned(content.span());
+        Some((part(&text[..i]), part(&text[i..])))
+    } else if let Some(sequence) = content.to_packed::<SequenceElem>() {
+        let children = &sequence.children;
+        children.iter().enumerate().find_map(|(i, child)| {
+            let (before, after) = try_split_at_char(child, c)?;
+            Some((
+                Content::sequence(children[..i].iter().cloned().chain([before])),
+                Content::sequence(
+                    [after].into_iter().chain(children[i + 1..].iter().cloned()),
+                ),
+            ))
+        })
+    } else if let Some(styled) = content.to_packed::<StyledElem>() {
+        let (before, after) = try_split_at_char(&styled.child, c)?;
+        Some((
+            before.styled_with_map(styled.styles.clone()),
+            after.styled_with_map(styled.styles.clone()),
+        ))
+    } else {
+        None
+    }
+}
@@ -1,12 +1,21 @@
+use std::num::NonZeroUsize;
+
 use super::rowspans::UnbreakableRowGroup;
 use crate::diag::SourceResult;
 use crate::engine::Engine;
-use crate::layout::{Abs, Axes, Frame, GridLayouter, Regions};
+use crate::foundations::Styles;
+use crate::layout::{Abs, Axes, Frame, GridFooter, GridHeader, GridLayouter, Regions};
+use crate::model::{TableFooter, TableHeader};
 
-/// A repeatable grid header. Starts at the first row.
+/// A repeatable grid header. The main header starts at the first row, while
+/// sub-headers may start at any row.
 pub(super) struct Header {
+    /// The first row included in this header.
+    pub(super) start: usize,
     /// The index after the last row included in this header.
     pub(super) end: usize,
+    /// The nesting level of this header. The main header has level 1.
+    pub(super) level: NonZeroUsize,
 }
 
 /// A repeatable grid footer. Stops at the last row.
@@ -24,6 +33,32 @@ pub(super) enum Repeatable<T> {
     NotRepeated(T),
 }
 
+/// A repeated grid object laid out again in a region after the one it first
+/// appeared in (for headers) or before the end of the grid (for footers).
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub(super) enum Continuation {
+    Header,
+    Footer,
+}
+
+impl Continuation {
+    /// The styles marking cells as belonging to a continued header or footer.
+    pub(super) fn styles(self) -> Styles {
+        let mut styles = Styles::new();
+        match self {
+            Self::Header => {
+                styles.set(GridHeader::set_continued(true));
+                styles.set(TableHeader::set_continued(true));
+            }
+            Self::Footer => {
+                styles.set(GridFooter::set_continued(true));
+                styles.set(TableFooter::set_continued(true));
+            }
+        }
+        styles
+    }
+}
+
 impl<T> Repeatable<T> {
     /// Gets the value inside this repeatable, regardless of whether
     /// it repeats.
@@ -44,23 +79,27 @@ impl<T> Repeatable<T> {
 }
 
 impl<'a> GridLayouter<'a> {
-    /// Layouts the header's rows.
+    /// Layouts the rows of the main header, if it repeats, followed by the
+    /// rows of the currently repeating sub-headers.
     /// Skips regions as necessary.
-    pub(super) fn layout_header(
+    pub(super) fn layout_headers(
         &mut self,
-        header: &Header,
         engine: &mut Engine,
         disambiguator: usize,
     ) -> SourceResult<()> {
-        let header_rows =
-            self.simulate_header(header, &self.regions, engine, disambiguator)?;
+        if disambiguator > 0 {
+            self.continuation = Some(Continuation::Header);
+        }
+
+        let headers_height =
+            self.simulate_headers(&self.regions, engine, disambiguator)?;
         let mut skipped_region = false;
         while self.unbreakable_rows_left == 0
-            && !self.regions.size.y.fits(header_rows.height + self.footer_height)
+            && !self.regions.size.y.fits(headers_height + self.footer_height)
             && !self.regions.in_last()
         {
             // Advance regions without any output until we can place the
-            // header and the footer.
+            // headers and the footer.
             self.finish_region_internal(Frame::soft(Axes::splat(Abs::zero())), vec![]);
             skipped_region = true;
         }
@@ -73,22 +112,104 @@ impl<'a> GridLayouter<'a> {
             if skipped_region {
                 // Simulate the footer again; the region's 'full' might have
                 // changed.
-                self.footer_height = self
-                    .simulate_footer(footer, &self.regions, engine, disambiguator)?
-                    .height;
+                self.footer_height =
+                    self.simulate_footer_height(footer, engine, disambiguator)?;
             }
         }
 
-        // Header is unbreakable.
+        // Headers are unbreakable.
         // Thus, no risk of 'finish_region' being recursively called from
         // within 'layout_row'.
-        self.unbreakable_rows_left += header.end;
-        for y in 0..header.end {
-            self.layout_row(y, engine, disambiguator)?;
+        if let Some(Repeatable::Repeated(header)) = &self.grid.header {
+            self.unbreakable_rows_left += header.end;
+            for y in 0..header.end {
+                self.layout_row(y, engine, disambiguator)?;
+            }
         }
+
+        for subheader in self.repeating_subheaders.clone() {
+            // Only rows of the main header are measured into the header
+            // height while being laid out, so add sub-headers here.
+            self.header_height += self
+                .simulate_header(subheader, &self.regions, engine, disambiguator)?
+                .height;
+            self.unbreakable_rows_left += subheader.end - subheader.start;
+            for y in subheader.start..subheader.end {
+                self.layout_row(y, engine, disambiguator)?;
+            }
+        }
+
+        self.header_rows = self.lrows.len();
+        self.continuation = None;
         Ok(())
     }
 
+    /// Lays out a sub-header at its original position and, if it repeats,
+    /// registers it to be repeated in the following regions until the next
+    /// header of the same or a lower level.
+    pub(super) fn layout_subheader(
+        &mut self,
+        subheader: &'a Repeatable<Header>,
+        engine: &mut Engine,
+    ) -> SourceResult<()> {
+        let header = subheader.unwrap();
+        self.repeating_subheaders.retain(|other| other.level < header.level);
+
+        // Avoid orphaning the sub-header at the end of the region: if it
+        // doesn't fit together with the row below it, move both to the next
+        // region, as long as they would fit there.
+        let mut height = self.simulate_header(header, &self.regions, engine, 0)?.height;
+        let next = header.end;
+        if next
+            < self
+                .grid
+                .footer
+                .as_ref()
+                .map_or(self.grid.rows.len(), |f| f.unwrap().start)
+        {
+            height += self
+                .simulate_unbreakable_row_group(next, Some(1), &self.regions, engine, 0)?
+                .height;
+        }
+
+        if self.lrows.len() > self.header_rows
+            && !self.regions.size.y.fits(height)
+            && (self.regions.full - self.header_height - self.footer_height).fits(height)
+            && !self.regions.in_last()
+        {
+            self.finish_region(engine, false)?;
+        }
+
+        // The sub-header's rows are kept together by
+        // 'check_for_unbreakable_rows'.
+        for y in header.start..header.end {
+            self.layout_row(y, engine, 0)?;
+        }
+
+        if let Repeatable::Repeated(header) = subheader {
+            self.repeating_subheaders.push(header);
+        }
+
+        Ok(())
+    }
+
+    /// Simulates the rows of all headers which would be repeated in a new
+    /// region, returning their total height.
+    pub(super) fn simulate_headers(
+        &self,
+        regions: &Regions<'_>,
+        engine: &mut Engine,
+        disambiguator: usize,
+    ) -> SourceResult<Abs> {
+        let main = self.grid.header.as_ref().and_then(Repeatable::as_repeated);
+        let mut height = Abs::zero();
+        for header in main.into_iter().chain(self.repeating_subheaders.iter().copied()) {
+            height +=
+                self.simulate_header(header, regions, engine, disambiguator)?.height;
+        }
+        Ok(height)
+    }
+
     /// Simulate the header's group of rows.
     pub(super) fn simulate_header(
         &self,
@@ -103,8 +224,8 @@ impl<'a> GridLayouter<'a> {
         // assume that the amount of unbreakable rows following the first row
         // in the header will be precisely the rows in the header.
         self.simulate_unbreakable_row_group(
-            0,
-            Some(header.end),
+            header.start,
+            Some(header.end - header.start),
             regions,
             engine,
             disambiguator,
@@ -118,9 +239,7 @@ impl<'a> GridLayouter<'a> {
         engine: &mut Engine,
         disambiguator: usize,
     ) -> SourceResult<()> {
-        let footer_height = self
-            .simulate_footer(footer, &self.regions, engine, disambiguator)?
-            .height;
+        let footer_height = self.simulate_footer_height(footer, engine, disambiguator)?;
         let mut skipped_region = false;
         while self.unbreakable_rows_left == 0
             && !self.regions.size.y.fits(footer_height)
@@ -135,8 +254,7 @@ impl<'a> GridLayouter<'a> {
         self.footer_height = if skipped_region {
             // Simulate the footer again; the region's 'full' might have
             // changed.
-            self.simulate_footer(footer, &self.regions, engine, disambiguator)?
-                .height
+            self.simulate_footer_height(footer, engine, disambiguator)?
         } else {
             footer_height
         };
@@ -144,6 +262,24 @@ impl<'a> GridLayouter<'a> {
         Ok(())
     }
 
+    /// Simulates the footer both as a continued and as the final footer,
+    /// returning the larger height, as it is not yet known which of both will
+    /// end the region.
+    fn simulate_footer_height(
+        &mut self,
+        footer: &Footer,
+        engine: &mut Engine,
+        disambiguator: usize,
+    ) -> SourceResult<Abs> {
+        let previous = self.continuation.take();
+        let last = self.simulate_footer(footer, &self.regions, engine, disambiguator);
+        self.continuation = Some(Continuation::Footer);
+        let continued =
+            self.simulate_footer(footer, &self.regions, engine, disambiguator);
+        self.continuation = previous;
+        Ok(last?.height.max(continued?.height))
+    }
+
     /// Lays out all rows in the footer.
     /// They are unbreakable.
     pub(super) fn layout_footer(
@@ -151,7 +287,12 @@ impl<'a> GridLayouter<'a> {
         footer: &Footer,
         engine: &mut Engine,
         disambiguator: usize,
+        continued: bool,
     ) -> SourceResult<()> {
+        if continued {
+            self.continuation = Some(Continuation::Footer);
+        }
+
         // Ensure footer rows have their own height available.
         // Won't change much as we're creating an unbreakable row group
         // anyway, so this is mostly for correctness.
@@ -163,6 +304,7 @@ impl<'a> GridLayouter<'a> {
             self.layout_row(y, engine, disambiguator)?;
         }
 
+        self.continuation = None;
         Ok(())
     }
 
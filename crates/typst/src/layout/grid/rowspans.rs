@@ -1,5 +1,5 @@
 use super::layout::{in_last_with_offset, points, Row, RowPiece};
-use super::repeated::Repeatable;
+use super::repeated::{Continuation, Repeatable};
 use crate::diag::SourceResult;
 use crate::engine::Engine;
 use crate::foundations::Resolve;
@@ -16,6 +16,8 @@ pub(super) struct Rowspan {
     pub(super) y: usize,
     /// The disambiguator for laying out the cells.
     pub(super) disambiguator: usize,
+    /// Whether the rowspan is part of a continued header or footer.
+    pub(super) continuation: Option<Continuation>,
     /// Amount of rows spanned by the cell at (x, y).
     pub(super) rowspan: usize,
     /// Whether all rows of the rowspan are part of an unbreakable row group.
@@ -105,6 +107,7 @@ impl<'a> GridLayouter<'a> {
             x,
             y,
             disambiguator,
+            continuation,
             rowspan,
             is_effectively_unbreakable,
             dx,
@@ -141,7 +144,8 @@ impl<'a> GridLayouter<'a> {
         }
 
         // Push the layouted frames directly into the finished frames.
-        let fragment = cell.layout(engine, disambiguator, self.styles, pod)?;
+        let fragment =
+            self.layout_cell(cell, engine, disambiguator, continuation, pod)?;
         let (current_region, current_rrows) = current_region_data.unzip();
         for ((i, finished), frame) in self
             .finished
@@ -156,24 +160,20 @@ impl<'a> GridLayouter<'a> {
                 // vertical offset in the first region.
                 dy
             } else {
-                // The rowspan continuation starts after the header (thus,
-                // at a position after the sum of the laid out header
-                // rows).
-                if let Some(Repeatable::Repeated(header)) = &self.grid.header {
-                    let header_rows = self
-                        .rrows
-                        .get(i)
-                        .map(Vec::as_slice)
-                        .or(current_rrows)
-                        .unwrap_or(&[])
-                        .iter()
-                        .take_while(|row| row.y < header.end);
-
-                    header_rows.map(|row| row.height).sum()
-                } else {
-                    // Without a header, start at the very top of the region.
-                    Abs::zero()
-                }
+                // The rowspan continuation starts after the repeated headers
+                // (thus, at a position after the sum of the laid out header
+                // rows, which are the only rows above the rowspan in this
+                // region). Without headers, it starts at the very top of the
+                // region.
+                self.rrows
+                    .get(first_region + i)
+                    .map(Vec::as_slice)
+                    .or(current_rrows)
+                    .unwrap_or(&[])
+                    .iter()
+                    .take_while(|row| self.grid.is_repeated_header_row(row.y))
+                    .map(|row| row.height)
+                    .sum()
             };
 
             finished.push_frame(Point::new(dx, dy), frame);
@@ -199,6 +199,7 @@ impl<'a> GridLayouter<'a> {
                     x,
                     y,
                     disambiguator,
+                    continuation: self.continuation,
                     rowspan,
                     // The field below will be updated in
                     // 'check_for_unbreakable_rows'.
@@ -235,6 +236,10 @@ impl<'a> GridLayouter<'a> {
                     amount_unbreakable_rows = Some(header.end);
                 }
             }
+            if let Some(subheader) = self.grid.subheader_at(current_row) {
+                // Keep sub-headers unbreakable.
+                amount_unbreakable_rows = Some(subheader.end - subheader.start);
+            }
             if let Some(Repeatable::NotRepeated(footer)) = &self.grid.footer {
                 if current_row >= footer.start {
                     // Non-repeated footer, so keep it unbreakable.
@@ -395,7 +400,8 @@ impl<'a> GridLayouter<'a> {
             // region.
             if breakable
                 && (matches!(self.grid.header, Some(Repeatable::Repeated(_)))
-                    || matches!(self.grid.footer, Some(Repeatable::Repeated(_))))
+                    || matches!(self.grid.footer, Some(Repeatable::Repeated(_)))
+                    || !self.repeating_subheaders.is_empty())
             {
                 // Subtract header and footer height from all upcoming regions
                 // when measuring the cell, including the last repeated region.
@@ -1126,13 +1132,7 @@ impl<'a> RowspanSimulator<'a> {
         // just use the original backlog from `self.regions`.
         let disambiguator = self.finished;
         let header_height =
-            if let Some(Repeatable::Repeated(header)) = &layouter.grid.header {
-                layouter
-                    .simulate_header(header, &self.regions, engine, disambiguator)?
-                    .height
-            } else {
-                Abs::zero()
-            };
+            layouter.simulate_headers(&self.regions, engine, disambiguator)?;
 
         let footer_height =
             if let Some(Repeatable::Repeated(footer)) = &layouter.grid.footer {
@@ -1154,17 +1154,13 @@ impl<'a> RowspanSimulator<'a> {
             skipped_region = true;
         }
 
-        if let Some(Repeatable::Repeated(header)) = &layouter.grid.header {
-            self.header_height = if skipped_region {
-                // Simulate headers again, at the new region, as
-                // the full region height may change.
-                layouter
-                    .simulate_header(header, &self.regions, engine, disambiguator)?
-                    .height
-            } else {
-                header_height
-            };
-        }
+        self.header_height = if skipped_region {
+            // Simulate headers again, at the new region, as
+            // the full region height may change.
+            layouter.simulate_headers(&self.regions, engine, disambiguator)?
+        } else {
+            header_height
+        };
 
         if let Some(Repeatable::Repeated(footer)) = &layouter.grid.footer {
             self.footer_height = if skipped_region {
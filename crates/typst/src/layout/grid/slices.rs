@@ -0,0 +1,196 @@
+use std::ops::Range;
+
+use super::cells::Entry;
+use crate::layout::{Abs, Fragment, Frame, FrameItem, GridLayouter, Point, Size};
+use crate::utils::Numeric;
+use crate::visualize::Path;
+
+/// The vertical slices of a grid that breaks horizontally.
+pub(super) struct ColumnSlices {
+    /// The horizontal extent of the repeated leading columns in the grid's
+    /// frames.
+    repeated: Range<Abs>,
+    /// The horizontal extent of each slice in the grid's frames.
+    slices: Vec<Range<Abs>>,
+    /// How far lines may reach beyond the edges of a slice.
+    slack: Abs,
+    /// Whether this is an RTL grid.
+    is_rtl: bool,
+}
+
+impl GridLayouter<'_> {
+    /// Determines how the grid's columns are split into slices. Returns `None`
+    /// if the grid may not break horizontally or fits the available width
+    /// anyway.
+    pub(super) fn column_slices(&self) -> Option<ColumnSlices> {
+        let repeat = self.column_split?;
+        let available = self.regions.size.x;
+        if !available.is_finite() || available.fits(self.width) {
+            return None;
+        }
+
+        let cols = self.rcols.len();
+        let repeated_end =
+            if self.grid.has_gutter { 2 * repeat } else { repeat }.min(cols);
+        let width = |range: Range<usize>| self.rcols[range].iter().sum::<Abs>();
+        let available = available - width(0..repeated_end);
+
+        // Gutter at the end of a slice is dropped, as there is nothing after
+        // it within the slice.
+        let trim = |mut range: Range<usize>| {
+            while range.end > range.start && self.grid.is_gutter_track(range.end - 1) {
+                range.end -= 1;
+            }
+            range
+        };
+
+        // Greedily fill each slice with as many columns as fit. A slice
+        // always contains at least one group of unseparable columns, even if
+        // it overflows.
+        let mut ranges = vec![];
+        let mut start = repeated_end;
+        let mut end = start;
+        while end < cols {
+            let next =
+                (end + 1..cols).find(|&x| self.can_break_before(x)).unwrap_or(cols);
+            if end > start && !available.fits(width(trim(start..next))) {
+                ranges.push(trim(start..end));
+                start = end;
+            }
+            end = next;
+        }
+
+        if start < cols {
+            ranges.push(trim(start..cols));
+        }
+
+        if ranges.len() <= 1 {
+            return None;
+        }
+
+        // Determine where the tracks end up in the grid's frames.
+        let extent = |range: Range<usize>| {
+            let start = width(0..range.start);
+            let end = start + width(range);
+            if self.is_rtl {
+                self.width - end..self.width - start
+            } else {
+                start..end
+            }
+        };
+
+        // Lines are centered on the edges of the tracks, so the slices must
+        // leave room for half of the thickest line.
+        let cell_strokes = self
+            .grid
+            .entries
+            .iter()
+            .filter_map(Entry::as_cell)
+            .flat_map(|cell| cell.stroke.iter().flatten());
+        let line_strokes = (self.grid.vlines.iter().chain(&self.grid.hlines))
+            .flatten()
+            .filter_map(|line| line.stroke.as_ref());
+        let slack = cell_strokes
+            .chain(line_strokes)
+            .map(|stroke| stroke.thickness.unwrap_or(Abs::pt(1.0)))
+            .fold(Abs::zero(), Abs::max)
+            / 2.0;
+
+        Some(ColumnSlices {
+            repeated: extent(0..repeated_end),
+            slices: ranges.into_iter().map(extent).collect(),
+            slack,
+            is_rtl: self.is_rtl,
+        })
+    }
+
+    /// Whether the grid may break horizontally before the given column, that
+    /// is, whether it is not gutter and no cell spans both it and the
+    /// preceding column.
+    fn can_break_before(&self, x: usize) -> bool {
+        !self.grid.is_gutter_track(x)
+            && (0..self.grid.rows.len()).all(|y| {
+                self.grid
+                    .parent_cell_position(x, y)
+                    .map_or(true, |parent| parent.x == x)
+            })
+    }
+}
+
+impl ColumnSlices {
+    /// Splits each of the grid's frames into its slices. The slices of a
+    /// frame end up in consecutive regions, followed by those of the next
+    /// frame.
+    pub(super) fn apply(&self, fragment: Fragment) -> Fragment {
+        let mut frames = vec![];
+        for frame in fragment {
+            for (i, slice) in self.slices.iter().enumerate() {
+                let part = self.cut(&frame, slice.clone(), true);
+                if self.repeated.is_empty() {
+                    frames.push(part);
+                    continue;
+                }
+
+                // Only the first slice keeps the tags of the repeated
+                // columns, so that their elements are not introspected
+                // multiple times.
+                let repeated = self.cut(&frame, self.repeated.clone(), i == 0);
+                let (repeated_x, part_x) = if self.is_rtl {
+                    (part.width(), Abs::zero())
+                } else {
+                    (Abs::zero(), repeated.width())
+                };
+
+                let size = Size::new(repeated.width() + part.width(), frame.height());
+                let mut output = Frame::soft(size);
+                output.push_frame(Point::with_x(repeated_x), repeated);
+                output.push_frame(Point::with_x(part_x), part);
+                frames.push(output);
+            }
+        }
+
+        Fragment::frames(frames)
+    }
+
+    /// Cuts a horizontal extent out of a frame. Items outside of the extent
+    /// are dropped, so that links and text of other slices do not end up in
+    /// this one. Tags within the extent are only kept if `tags` is true.
+    fn cut(&self, frame: &Frame, extent: Range<Abs>, tags: bool) -> Frame {
+        let (start, end) = (extent.start - self.slack, extent.end + self.slack);
+        let mut part = frame.clone();
+        part.retain(&mut |pos, item| {
+            let (left, right) = match item {
+                FrameItem::Tag(_) => return tags && extent.contains(&pos.x),
+                FrameItem::Group(group) => (Abs::zero(), group.frame.width()),
+                FrameItem::Text(text) => (Abs::zero(), text.width()),
+                FrameItem::Shape(shape, _) => {
+                    let width = shape.geometry.bbox_size().x;
+                    let outset = shape
+                        .stroke
+                        .as_ref()
+                        .map_or(Abs::zero(), |stroke| stroke.thickness / 2.0);
+                    (width.min(Abs::zero()) - outset, width.max(Abs::zero()) + outset)
+                }
+                FrameItem::Image(_, size, _) | FrameItem::Link(_, size) => {
+                    (Abs::zero(), size.x)
+                }
+            };
+            pos.x + right >= start && pos.x + left <= end
+        });
+        part.translate(Point::with_x(-extent.start));
+
+        let size = Size::new(extent.end - extent.start, frame.height());
+        let (min, max) = (-self.slack, size.x + self.slack);
+        let (top, bottom) = (-self.slack, size.y + self.slack);
+        let mut clip = Path::new();
+        clip.move_to(Point::new(min, top));
+        clip.line_to(Point::new(max, top));
+        clip.line_to(Point::new(max, bottom));
+        clip.line_to(Point::new(min, bottom));
+        clip.close_path();
+
+        part.set_size(size);
+        part.clip(clip);
+        part
+    }
+}
@@ -1,22 +1,28 @@
 use super::*;
 use crate::introspection::SplitLocator;
+use crate::layout::Point;
 use crate::utils::Numeric;
 
 /// Turns the selected lines into frames.
+///
+/// The first lines are restricted to the given `bounds` if there are any.
+#[allow(clippy::too_many_arguments)]
 #[typst_macros::time]
 pub fn finalize(
     engine: &mut Engine,
     p: &Preparation,
     lines: &[Line],
+    bounds: &[LineBounds],
     styles: StyleChain,
     region: Size,
     expand: bool,
     locator: &mut SplitLocator<'_>,
 ) -> SourceResult<Fragment> {
     // Determine the paragraph's width: Full width of the region if we should
-    // expand or there's fractional spacing, fit-to-width otherwise.
+    // expand, there's fractional spacing, or the lines have individual bounds.
+    // Fit-to-width otherwise.
     let width = if !region.x.is_finite()
-        || (!expand && lines.iter().all(|line| line.fr().is_zero()))
+        || (!expand && bounds.is_empty() && lines.iter().all(|line| line.fr().is_zero()))
     {
         region
             .x
@@ -29,7 +35,20 @@ pub fn finalize(
     let shrink = ParElem::shrink_in(styles);
     lines
         .iter()
-        .map(|line| commit(engine, p, line, width, region.y, shrink, locator, styles))
+        .enumerate()
+        .map(|(i, line)| {
+            let Some(&(offset, bound)) = bounds.get(i) else {
+                return commit(engine, p, line, width, region.y, shrink, locator, styles);
+            };
+
+            // Lay out the line in its bounds and then move it into place
+            // within the full width of the paragraph.
+            let mut frame =
+                commit(engine, p, line, bound, region.y, shrink, locator, styles)?;
+            frame.translate(Point::with_x(offset));
+            frame.size_mut().x = width;
+            Ok(frame)
+        })
         .collect::<SourceResult<_>>()
         .map(Fragment::frames)
 }
@@ -97,6 +97,35 @@ impl<'a> Line<'a> {
         })
     }
 
+    /// Measures the extent of the line above and below its baseline without
+    /// building its frame.
+    ///
+    /// This matches the extent determined by [`commit`], except that
+    /// fractionally sized boxes are not taken into account as they can only
+    /// be laid out once the line's width is known.
+    pub fn measure(&self, engine: &Engine) -> (Abs, Abs) {
+        let mut top = Abs::zero();
+        let mut bottom = Abs::zero();
+
+        for item in self.items.iter() {
+            match item {
+                Item::Text(shaped) => {
+                    let (t, b) = shaped.measure(engine);
+                    top.set_max(t);
+                    bottom.set_max(b);
+                }
+                Item::Frame(frame, styles) => {
+                    let baseline = frame.baseline() + TextElem::baseline_in(*styles);
+                    top.set_max(baseline);
+                    bottom.set_max(frame.height() - baseline);
+                }
+                _ => {}
+            }
+        }
+
+        (top, bottom)
+    }
+
     /// The sum of fractions in the line.
     pub fn fr(&self) -> Fr {
         self.items
@@ -112,11 +112,40 @@ impl Breakpoint {
     }
 }
 
+/// The widths available to the lines of a paragraph.
+///
+/// Similar to TeX's `\parshape`, the first few lines can have individual
+/// widths while all remaining lines share a common one.
+#[derive(Debug, Copy, Clone)]
+pub struct Widths<'a> {
+    /// The widths of the first lines.
+    pub lines: &'a [Abs],
+    /// The width of all lines after those in `lines`.
+    pub rest: Abs,
+}
+
+impl<'a> Widths<'a> {
+    /// The same width for all lines.
+    pub fn uniform(width: Abs) -> Self {
+        Self { lines: &[], rest: width }
+    }
+
+    /// The width available to the line with the given index.
+    pub fn get(&self, line: usize) -> Abs {
+        self.lines.get(line).copied().unwrap_or(self.rest)
+    }
+
+    /// Whether all lines have the same width.
+    fn is_uniform(&self) -> bool {
+        self.lines.iter().all(|&width| width == self.rest)
+    }
+}
+
 /// Breaks the paragraph into lines.
 pub fn linebreak<'a>(
     engine: &Engine,
     p: &'a Preparation<'a>,
-    width: Abs,
+    widths: Widths,
 ) -> Vec<Line<'a>> {
     let linebreaks = p.linebreaks.unwrap_or_else(|| {
         if p.justify {
@@ -127,8 +156,8 @@ pub fn linebreak<'a>(
     });
 
     match linebreaks {
-        Linebreaks::Simple => linebreak_simple(engine, p, width),
-        Linebreaks::Optimized => linebreak_optimized(engine, p, width),
+        Linebreaks::Simple => linebreak_simple(engine, p, widths),
+        Linebreaks::Optimized => linebreak_optimized(engine, p, widths),
     }
 }
 
@@ -139,7 +168,7 @@ pub fn linebreak<'a>(
 fn linebreak_simple<'a>(
     engine: &Engine,
     p: &'a Preparation<'a>,
-    width: Abs,
+    widths: Widths,
 ) -> Vec<Line<'a>> {
     let mut lines = Vec::with_capacity(16);
     let mut start = 0;
@@ -148,6 +177,7 @@ fn linebreak_simple<'a>(
     breakpoints(p, |end, breakpoint| {
         // Compute the line and its size.
         let mut attempt = line(engine, p, start..end, breakpoint, lines.last());
+        let mut width = widths.get(lines.len());
 
         // If the line doesn't fit anymore, we push the last fitting attempt
         // into the stack and rebuild the line from the attempt's end. The
@@ -157,6 +187,7 @@ fn linebreak_simple<'a>(
                 lines.push(last_attempt);
                 start = last_end;
                 attempt = line(engine, p, start..end, breakpoint, lines.last());
+                width = widths.get(lines.len());
             }
         }
 
@@ -200,17 +231,27 @@ fn linebreak_simple<'a>(
 fn linebreak_optimized<'a>(
     engine: &Engine,
     p: &'a Preparation<'a>,
-    width: Abs,
+    widths: Widths,
 ) -> Vec<Line<'a>> {
     let metrics = CostMetrics::compute(p);
 
     // Determines the exact costs of a likely good layout through Knuth-Plass
     // with approximate metrics. We can use this cost as an upper bound to prune
     // the search space in our proper optimization pass below.
-    let upper_bound = linebreak_optimized_approximate(engine, p, width, &metrics);
+    //
+    // When the lines have differing widths, the optimal layout up to a
+    // breakpoint also depends on the number of lines before it. Since we only
+    // keep one layout per breakpoint, the approximate layout wouldn't
+    // necessarily be reachable by the proper pass, so we can't use its cost
+    // as a bound.
+    let upper_bound = if widths.is_uniform() {
+        linebreak_optimized_approximate(engine, p, widths.rest, &metrics)
+    } else {
+        Cost::INFINITY
+    };
 
     // Using the upper bound, perform exact optimized linebreaking.
-    linebreak_optimized_bounded(engine, p, width, &metrics, upper_bound)
+    linebreak_optimized_bounded(engine, p, widths, &metrics, upper_bound)
 }
 
 /// Performs line breaking in optimized Knuth-Plass style, but with an upper
@@ -219,7 +260,7 @@ fn linebreak_optimized<'a>(
 fn linebreak_optimized_bounded<'a>(
     engine: &Engine,
     p: &'a Preparation<'a>,
-    width: Abs,
+    widths: Widths,
     metrics: &CostMetrics,
     upper_bound: Cost,
 ) -> Vec<Line<'a>> {
@@ -229,10 +270,17 @@ fn linebreak_optimized_bounded<'a>(
         total: Cost,
         line: Line<'a>,
         end: usize,
+        count: usize,
     }
 
     // Dynamic programming table.
-    let mut table = vec![Entry { pred: 0, total: 0.0, line: Line::empty(), end: 0 }];
+    let mut table = vec![Entry {
+        pred: 0,
+        total: 0.0,
+        line: Line::empty(),
+        end: 0,
+        count: 0,
+    }];
 
     let mut active = 0;
     let mut prev_end = 0;
@@ -263,7 +311,7 @@ fn linebreak_optimized_bounded<'a>(
             let (line_ratio, line_cost) = ratio_and_cost(
                 p,
                 metrics,
-                width,
+                widths.get(pred.count),
                 &pred.line,
                 &attempt,
                 breakpoint,
@@ -308,7 +356,13 @@ fn linebreak_optimized_bounded<'a>(
 
             // If this attempt is better than what we had before, take it!
             if best.as_ref().map_or(true, |best| best.total >= total) {
-                best = Some(Entry { pred: pred_index, total, line: attempt, end });
+                best = Some(Entry {
+                    pred: pred_index,
+                    total,
+                    line: attempt,
+                    end,
+                    count: pred.count + 1,
+                });
             }
         }
 
@@ -332,7 +386,7 @@ fn linebreak_optimized_bounded<'a>(
         panic!("bounded paragraph layout is incomplete");
 
         #[cfg(not(debug_assertions))]
-        return linebreak_optimized_bounded(engine, p, width, metrics, Cost::INFINITY);
+        return linebreak_optimized_bounded(engine, p, widths, metrics, Cost::INFINITY);
     }
 
     while idx != 0 {
@@ -4,18 +4,20 @@ mod line;
 mod linebreak;
 mod prepare;
 mod shaping;
+mod wrap;
 
 use comemo::{Track, Tracked, TrackedMut};
 
 use self::collect::{collect, Item, Segment, SpanMapper};
 use self::finalize::finalize;
 use self::line::{commit, line, Line};
-use self::linebreak::{is_default_ignorable, linebreak, Breakpoint};
+use self::linebreak::{is_default_ignorable, linebreak, Breakpoint, Widths};
 use self::prepare::{prepare, Preparation};
 use self::shaping::{
     cjk_punct_style, is_of_cj_script, shape_range, ShapedGlyph, ShapedText,
     BEGIN_PUNCT_PAT, END_PUNCT_PAT,
 };
+use self::wrap::{linebreak_around, LineBounds};
 use crate::diag::SourceResult;
 use crate::engine::{Engine, Route, Sink, Traced};
 use crate::foundations::{StyleChain, StyleVec};
@@ -24,10 +26,16 @@ use crate::layout::{Fragment, Size};
 use crate::model::ParElem;
 use crate::World;
 
+pub use self::wrap::Exclusion;
+
 /// Range of a substring of text.
 type Range = std::ops::Range<usize>;
 
 /// Layouts content inline.
+///
+/// The lines avoid the given `exclusions`, which allows paragraphs to wrap
+/// around placed content.
+#[allow(clippy::too_many_arguments)]
 pub(crate) fn layout_inline(
     engine: &mut Engine,
     children: &StyleVec,
@@ -36,6 +44,7 @@ pub(crate) fn layout_inline(
     consecutive: bool,
     region: Size,
     expand: bool,
+    exclusions: &[Exclusion],
 ) -> SourceResult<Fragment> {
     layout_inline_impl(
         children,
@@ -49,6 +58,7 @@ pub(crate) fn layout_inline(
         consecutive,
         region,
         expand,
+        exclusions,
     )
 }
 
@@ -67,6 +77,7 @@ fn layout_inline_impl(
     consecutive: bool,
     region: Size,
     expand: bool,
+    exclusions: &[Exclusion],
 ) -> SourceResult<Fragment> {
     let link = LocatorLink::new(locator);
     let locator = Locator::link(&link);
@@ -88,8 +99,13 @@ fn layout_inline_impl(
     let p = prepare(&mut engine, children, &text, segments, spans, styles)?;
 
     // Break the paragraph into lines.
-    let lines = linebreak(&engine, &p, region.x - p.hang);
+    let (lines, bounds) = if exclusions.is_empty() {
+        (linebreak(&engine, &p, Widths::uniform(region.x - p.hang)), vec![])
+    } else {
+        let leading = ParElem::leading_in(styles);
+        linebreak_around(&engine, &p, region.x, leading, exclusions)
+    };
 
     // Turn the selected lines into frames.
-    finalize(&mut engine, &p, &lines, styles, region, expand, &mut locator)
+    finalize(&mut engine, &p, &lines, &bounds, styles, region, expand, &mut locator)
 }
@@ -0,0 +1,113 @@
+use super::*;
+use crate::layout::Abs;
+
+/// How often we at most refine the assumed vertical positions of lines when
+/// breaking a paragraph around exclusions.
+const MAX_ITERATIONS: usize = 4;
+
+/// An area at the side of a paragraph that its lines must avoid.
+///
+/// Exclusions are used to make paragraphs wrap around placed content. The
+/// vertical extent is relative to the top edge of the paragraph while the
+/// horizontal amounts are measured from the left and right edges of the
+/// region.
+#[derive(Debug, Copy, Clone, PartialEq, Hash)]
+pub struct Exclusion {
+    /// The top edge of the excluded area.
+    pub top: Abs,
+    /// The bottom edge of the excluded area.
+    pub bottom: Abs,
+    /// How much horizontal space is taken away on the left.
+    pub left: Abs,
+    /// How much horizontal space is taken away on the right.
+    pub right: Abs,
+}
+
+/// The horizontal extent available to a line: Its offset from the left edge
+/// of the region and its width.
+pub type LineBounds = (Abs, Abs);
+
+/// Breaks the paragraph into lines that avoid the given exclusions.
+///
+/// Which exclusions a line must avoid depends on its vertical position, which
+/// in turn depends on the heights of the lines before it. We thus start with
+/// lines of full width and then repeatedly derive per-line bounds from the
+/// measured heights of the previous attempt until they don't change anymore.
+///
+/// Returns the lines along with the bounds of the first few lines. All lines
+/// beyond those are not affected by any exclusion.
+pub fn linebreak_around<'a>(
+    engine: &Engine,
+    p: &'a Preparation<'a>,
+    width: Abs,
+    leading: Abs,
+    exclusions: &[Exclusion],
+) -> (Vec<Line<'a>>, Vec<LineBounds>) {
+    let mut bounds = vec![];
+    let mut lines = linebreak(engine, p, Widths::uniform(width - p.hang));
+
+    for _ in 0..MAX_ITERATIONS {
+        let next = line_bounds(engine, p, &lines, width, leading, exclusions);
+        if next == bounds {
+            break;
+        }
+
+        bounds = next;
+        let widths: Vec<_> = bounds.iter().map(|&(_, w)| w - p.hang).collect();
+        lines = linebreak(engine, p, Widths { lines: &widths, rest: width - p.hang });
+    }
+
+    (lines, bounds)
+}
+
+/// Determines the horizontal bounds of the lines that are vertically affected
+/// by an exclusion, assuming the lines are stacked with the given leading.
+///
+/// When there are fewer lines than needed to clear all exclusions, the height
+/// of the last line is assumed for the missing ones.
+fn line_bounds(
+    engine: &Engine,
+    p: &Preparation,
+    lines: &[Line],
+    width: Abs,
+    leading: Abs,
+    exclusions: &[Exclusion],
+) -> Vec<LineBounds> {
+    let end = exclusions.iter().map(|e| e.bottom).max().unwrap_or_default();
+
+    let mut bounds = vec![];
+    let mut y = Abs::zero();
+    let mut height = p.size;
+
+    for i in 0.. {
+        if y >= end {
+            break;
+        }
+
+        if let Some(line) = lines.get(i) {
+            let (top, bottom) = line.measure(engine);
+            height = top + bottom;
+        }
+
+        let mut left = Abs::zero();
+        let mut right = Abs::zero();
+        for exclusion in exclusions {
+            if exclusion.top < y + height && exclusion.bottom > y {
+                left.set_max(exclusion.left);
+                right.set_max(exclusion.right);
+            }
+        }
+
+        bounds.push((left, (width - left - right).max(Abs::zero())));
+
+        // Protect against an infinite loop with zero-height lines.
+        let advance = height + leading;
+        if advance <= Abs::zero() {
+            break;
+        }
+
+        y += advance;
+    }
+
+    bounds
+}
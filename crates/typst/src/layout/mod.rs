@@ -69,7 +69,7 @@ pub use self::spacing::*;
 pub use self::stack::*;
 pub use self::transform::*;
 
-pub(crate) use self::inline::layout_inline;
+pub(crate) use self::inline::{layout_inline, Exclusion};
 
 use crate::foundations::{category, Category, Scope};
 
@@ -1,5 +1,6 @@
-use crate::foundations::{elem, scope, Cast, Content, Smart};
-use crate::layout::{Alignment, Em, Length, Rel};
+use crate::foundations::{cast, elem, scope, Cast, Content, Smart};
+use crate::introspection::Location;
+use crate::layout::{Alignment, Em, Length, Ratio, Rel};
 
 /// Places content relatively to its parent container.
 ///
@@ -116,10 +117,92 @@ pub struct PlaceElem {
     /// ```
     pub float: bool,
 
-    /// The spacing between the placed element and other elements in a floating
-    /// layout.
+    /// Whether in-flow paragraphs wrap around the placed content.
+    ///
+    /// - `{false}`: The content is overlaid on top of other content.
+    /// - `{true}`: The lines of following paragraphs are shortened to avoid
+    ///   the bounding box of the placed content.
+    /// - `{"shape"}`: The lines of following paragraphs follow the contour of
+    ///   the placed content. This is useful for round or slanted shapes like a
+    ///   [`circle`] or a [`polygon`].
+    ///
+    /// Wrapping is currently only available for non-floating placement at the
+    /// left or right side of the parent container. Only paragraphs that follow
+    /// the placed element in the same container wrap around it.
+    ///
+    /// ```example
+    /// #set page(height: 120pt)
+    /// #place(
+    ///   top + right,
+    ///   wrap: "shape",
+    ///   clearance: 6pt,
+    ///   circle(radius: 28pt, fill: aqua),
+    /// )
+    /// #lorem(40)
+    /// ```
+    pub wrap: PlacementWrap,
+
+    /// Where a floating element may end up besides the top or bottom of a
+    /// container.
+    ///
+    /// - `{"edge"}`: The element is placed at the top or bottom of the current
+    ///   or a later container, as determined by the `alignment`.
+    /// - `{"here"}`: The element stays in-flow exactly where it was specified
+    ///   if it fits there. Otherwise, it floats into the next container as
+    ///   determined by the `alignment`.
+    /// - `{"page"}`: The element is placed on a dedicated float page following
+    ///   the current page. Several consecutive such elements are collected
+    ///   onto the same float page if they fit. Outside of the page's flow,
+    ///   this behaves like `{"edge"}`.
+    ///
+    /// Has no effect if `float` is `{false}`.
+    ///
+    /// ```example
+    /// #set page(height: 120pt)
+    /// #let card(body) = rect(width: 100%, body)
+    ///
+    /// #lorem(10)
+    /// #place(top, float: true, policy: "here", card[Here])
+    /// #lorem(25)
+    /// #place(top, float: true, policy: "here", card[Next page])
+    /// #lorem(5)
+    /// ```
+    pub policy: FloatPolicy,
+
+    /// The maximum fraction of the container's height that floating elements
+    /// may occupy together with this one.
+    ///
+    /// If placing the element would exceed the fraction, it is deferred to the
+    /// next container. An element that exceeds the fraction on its own is put
+    /// onto a float page instead, as with the `{"page"}`
+    /// [policy]($place.policy). Outside of the page's flow, it is placed
+    /// regardless.
     ///
     /// Has no effect if `float` is `{false}`.
+    ///
+    /// ```example
+    /// #set page(height: 150pt)
+    /// #set place(max-fraction: 50%)
+    /// #let card(body) = rect(width: 100%, height: 30pt, body)
+    ///
+    /// #place(top, float: true, card[First])
+    /// #place(top, float: true, card[Second])
+    /// #lorem(30)
+    /// ```
+    #[default(Ratio::one())]
+    pub max_fraction: Ratio,
+
+    /// The location of an element before which a floating element must not be
+    /// placed. Until the element was laid out, the floating element is held
+    /// back.
+    #[internal]
+    #[synthesized]
+    pub anchor: Option<Location>,
+
+    /// The spacing between the placed element and other elements in a floating
+    /// or wrapping layout.
+    ///
+    /// Has no effect if `float` is `{false}` and `wrap` is `{false}`.
     #[default(Em::new(1.5).into())]
     #[resolve]
     pub clearance: Length,
@@ -167,6 +250,44 @@ pub enum PlacementScope {
     Parent,
 }
 
+/// How in-flow paragraphs wrap around placed content.
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum PlacementWrap {
+    /// Paragraphs don't wrap, the placed content is overlaid.
+    #[default]
+    None,
+    /// Paragraphs wrap around the bounding box of the placed content.
+    Bounds,
+    /// Paragraphs follow the contour of the placed content.
+    Shape,
+}
+
+cast! {
+    PlacementWrap,
+    self => match self {
+        Self::None => false.into_value(),
+        Self::Bounds => true.into_value(),
+        Self::Shape => "shape".into_value(),
+    },
+    v: bool => if v { Self::Bounds } else { Self::None },
+    /// Paragraphs follow the contour of the placed content.
+    "shape" => Self::Shape,
+}
+
+/// Where a floating element may be placed besides the top or bottom of its
+/// container.
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq, Hash, Cast)]
+pub enum FloatPolicy {
+    /// Place at the top or bottom of the container.
+    #[default]
+    Edge,
+    /// Place in-flow if possible and float into the next container
+    /// otherwise.
+    Here,
+    /// Place on a dedicated float page.
+    Page,
+}
+
 /// Asks the layout algorithm to place pending floating elements before
 /// continuing with the content.
 ///
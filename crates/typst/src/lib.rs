@@ -76,7 +76,7 @@ use crate::model::Document;
 use crate::syntax::package::PackageSpec;
 use crate::syntax::{FileId, Source, Span};
 use crate::text::{Font, FontBook};
-use crate::utils::LazyHash;
+use crate::utils::{LazyHash, SmallBitSet};
 use crate::visualize::Color;
 
 /// Compile sources into a fully layouted document.
@@ -315,6 +315,8 @@ pub struct Library {
     /// The standard library as a value.
     /// Used to provide the `std` variable.
     pub std: Value,
+    /// The opt-in compiler features that are enabled.
+    pub features: Features,
 }
 
 impl Library {
@@ -337,6 +339,7 @@ impl Default for Library {
 #[derive(Debug, Clone, Default)]
 pub struct LibraryBuilder {
     inputs: Option<Dict>,
+    features: Features,
 }
 
 impl LibraryBuilder {
@@ -346,16 +349,59 @@ impl LibraryBuilder {
         self
     }
 
+    /// Enable opt-in compiler features.
+    pub fn with_features(mut self, features: Features) -> Self {
+        self.features = features;
+        self
+    }
+
     /// Consumes the builder and returns a `Library`.
     pub fn build(self) -> Library {
         let math = math::module();
         let inputs = self.inputs.unwrap_or_default();
         let global = global(math.clone(), inputs);
         let std = Value::Module(global.clone());
-        Library { global, math, styles: Styles::new(), std }
+        Library {
+            global,
+            math,
+            styles: Styles::new(),
+            std,
+            features: self.features,
+        }
+    }
+}
+
+/// A set of opt-in compiler features.
+#[derive(Debug, Default, Clone, Hash)]
+pub struct Features(SmallBitSet);
+
+impl Features {
+    /// Whether the given feature is enabled.
+    pub fn is_enabled(&self, feature: Feature) -> bool {
+        self.0.contains(feature as usize)
     }
 }
 
+impl FromIterator<Feature> for Features {
+    fn from_iter<T: IntoIterator<Item = Feature>>(iter: T) -> Self {
+        let mut set = SmallBitSet::new();
+        for feature in iter {
+            set.insert(feature as usize);
+        }
+        Self(set)
+    }
+}
+
+/// A compiler feature that is only enabled on request, typically because it
+/// has a cost that is only worth paying for some export formats.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+#[non_exhaustive]
+pub enum Feature {
+    /// Derive accessibility information for exporters that embed it, like the
+    /// MathML representation of equations.
+    Tagging,
+}
+
 /// Construct the module with global definitions.
 fn global(math: Module, inputs: Dict) -> Module {
     let mut global = Scope::deduplicating();
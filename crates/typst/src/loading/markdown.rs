@@ -0,0 +1,408 @@
+use std::collections::HashMap;
+use std::num::NonZeroUsize;
+
+use comemo::Tracked;
+use ecow::EcoString;
+use pulldown_cmark::{Alignment, CodeBlockKind, Event, LinkType, Options, Parser, Tag};
+use smallvec::smallvec;
+
+use crate::diag::{bail, At, SourceResult};
+use crate::engine::Engine;
+use crate::foundations::{func, scope, Content, NativeElement, Packed, Smart};
+use crate::layout::{Alignment as Align, HAlignment, Ratio, Rel, Sizing, TrackSizings};
+use crate::loading::Readable;
+use crate::model::{
+    Destination, EmphElem, EnumElem, EnumItem, FootnoteElem, HeadingElem, LinkElem,
+    LinkTarget, ListElem, ListItem, ParbreakElem, QuoteElem, StrongElem, TableCell,
+    TableChild, TableElem, TableHeader, TableItem,
+};
+use crate::syntax::{FileId, Span, Spanned};
+use crate::text::{
+    LinebreakElem, RawContent, RawElem, SmartQuoteElem, SpaceElem, StrikeElem, TextElem,
+};
+use crate::visualize::{ImageElem, LineElem};
+use crate::World;
+
+/// Reads a Markdown file and converts it into content.
+///
+/// The file is parsed as [CommonMark](https://commonmark.org/) with the
+/// common extensions for tables, footnotes, and strikethrough. It is
+/// converted into the same elements that Typst's own markup produces, so that
+/// show and set rules style it just like the rest of the document: Headings
+/// become [headings]($heading), lists become [bullet lists]($list) and
+/// [numbered lists]($enum), code becomes [raw text]($raw), and so on. Raw HTML
+/// is ignored.
+///
+/// Paths of images are resolved relative to the Markdown file.
+///
+/// # Example
+/// ```example
+/// #show heading: set text(navy)
+///
+/// #markdown.decode(
+///   "# Results\n" +
+///   "The experiment was a *success*:\n" +
+///   "- It ran for `3` hours.\n" +
+///   "- Nothing broke.",
+/// )
+/// ```
+#[func(scope)]
+pub fn markdown(
+    /// The engine.
+    engine: &mut Engine,
+    /// Path to a Markdown file.
+    ///
+    /// For more details, see the [Paths section]($syntax/#paths).
+    path: Spanned<EcoString>,
+) -> SourceResult<Content> {
+    let Spanned { v: path, span } = path;
+    let id = span.resolve_path(&path).at(span)?;
+    let data = engine.world.file(id).at(span)?;
+    let text = std::str::from_utf8(&data)
+        .map_err(|_| "file is not valid utf-8")
+        .at(span)?;
+    convert(engine.world, text, Some(id), span)
+}
+
+#[scope]
+impl markdown {
+    /// Converts Markdown from a string or bytes into content.
+    ///
+    /// Paths of images are resolved relative to the file that calls this
+    /// function.
+    #[func(title = "Decode Markdown")]
+    pub fn decode(
+        /// The engine.
+        engine: &mut Engine,
+        /// Markdown data.
+        data: Spanned<Readable>,
+    ) -> SourceResult<Content> {
+        let Spanned { v: data, span } = data;
+        let text = data.as_str().ok_or("data is not valid utf-8").at(span)?;
+        convert(engine.world, text, span.id(), span)
+    }
+}
+
+/// Parses Markdown text and converts it into content.
+fn convert(
+    world: Tracked<dyn World + '_>,
+    text: &str,
+    base: Option<FileId>,
+    span: Span,
+) -> SourceResult<Content> {
+    let options = Options::ENABLE_TABLES
+        | Options::ENABLE_FOOTNOTES
+        | Options::ENABLE_STRIKETHROUGH;
+    let events: Vec<_> = Parser::new_ext(text, options).collect();
+    let mut converter = Converter {
+        world,
+        base,
+        span,
+        events,
+        cursor: 0,
+        footnotes: HashMap::new(),
+    };
+    converter.collect_footnotes()?;
+    converter.cursor = 0;
+    let children = converter.children()?;
+    Ok(join(children))
+}
+
+/// Converts a stream of Markdown events into content.
+struct Converter<'a, 'w> {
+    /// The world to load images from.
+    world: Tracked<'w, dyn World + 'w>,
+    /// The file relative to which paths are resolved.
+    base: Option<FileId>,
+    /// The span that all produced content is attributed to.
+    span: Span,
+    /// The parsed events.
+    events: Vec<Event<'a>>,
+    /// The index of the next event.
+    cursor: usize,
+    /// The bodies of footnote definitions by their name.
+    footnotes: HashMap<EcoString, Content>,
+}
+
+/// A piece of converted content and whether it is a block.
+type Child = (Content, bool);
+
+impl Converter<'_, '_> {
+    /// Convert all footnote definitions up-front since they may be referenced
+    /// before they are defined.
+    fn collect_footnotes(&mut self) -> SourceResult<()> {
+        while self.cursor < self.events.len() {
+            let event = &self.events[self.cursor];
+            self.cursor += 1;
+            if let Event::Start(Tag::FootnoteDefinition(name)) = event {
+                let name = name.as_ref().into();
+                let body = join(self.children()?);
+                self.footnotes.insert(name, body);
+            }
+        }
+        Ok(())
+    }
+
+    /// Convert events until the end of the current element or the end of the
+    /// document.
+    fn children(&mut self) -> SourceResult<Vec<Child>> {
+        let mut children = vec![];
+        while self.cursor < self.events.len() {
+            let event = self.events[self.cursor].clone();
+            self.cursor += 1;
+            match event {
+                Event::End(_) => break,
+                Event::Start(tag) => {
+                    if let Some(child) = self.element(tag)? {
+                        children.push(child);
+                    }
+                }
+                Event::Text(text) => children
+                    .extend(self.text(&text).into_iter().map(|content| (content, false))),
+                Event::Code(code) => children.push((
+                    self.pack(RawElem::new(RawContent::Text(code.as_ref().into()))),
+                    false,
+                )),
+                Event::SoftBreak => children.push((SpaceElem::shared().clone(), false)),
+                Event::HardBreak => {
+                    children.push((LinebreakElem::shared().clone(), false))
+                }
+                Event::Rule => children.push((
+                    self.pack(LineElem::new().with_length(Rel::from(Ratio::one()))),
+                    true,
+                )),
+                Event::FootnoteReference(name) => {
+                    let content = match self.footnotes.get(name.as_ref()) {
+                        Some(body) => self.pack(FootnoteElem::with_content(body.clone())),
+                        None => TextElem::packed(format!("[^{name}]")),
+                    };
+                    children.push((content, false));
+                }
+                Event::Html(_) | Event::TaskListMarker(_) => {}
+            }
+        }
+        Ok(children)
+    }
+
+    /// Convert the element started by the given tag, consuming its children.
+    fn element(&mut self, tag: Tag) -> SourceResult<Option<Child>> {
+        let content = match tag {
+            Tag::Paragraph => return Ok(Some((join(self.children()?), true))),
+            Tag::Heading(level, ..) => {
+                let body = join(self.children()?);
+                let depth =
+                    NonZeroUsize::new(level as usize).unwrap_or(NonZeroUsize::MIN);
+                (self.pack(HeadingElem::new(body).with_depth(depth)), true)
+            }
+            Tag::BlockQuote => {
+                let body = join(self.children()?);
+                (self.pack(QuoteElem::new(body).with_block(true)), true)
+            }
+            Tag::CodeBlock(kind) => {
+                // Read the text directly instead of converting it, which would
+                // turn quotes into smart quotes.
+                let mut text = String::new();
+                while let Some(event) = self.events.get(self.cursor) {
+                    self.cursor += 1;
+                    match event {
+                        Event::Text(part) => text.push_str(part),
+                        Event::End(_) => break,
+                        _ => {}
+                    }
+                }
+                if text.ends_with('\n') {
+                    text.pop();
+                }
+                let mut elem =
+                    RawElem::new(RawContent::Text(text.into())).with_block(true);
+                if let CodeBlockKind::Fenced(info) = kind {
+                    if let Some(lang) = info.split_whitespace().next() {
+                        elem.push_lang(Some(lang.into()));
+                    }
+                }
+                (self.pack(elem), true)
+            }
+            Tag::List(start) => {
+                let mut tight = true;
+                let mut items = vec![];
+                while let Some(Event::Start(Tag::Item)) = self.events.get(self.cursor) {
+                    self.cursor += 1;
+                    tight &= !self.item_has_paragraphs();
+                    items.push(join(self.children()?));
+                }
+                // Skip the end of the list.
+                self.cursor += 1;
+                let content = match start {
+                    None => self.pack(
+                        ListElem::new(
+                            items
+                                .into_iter()
+                                .map(|body| Packed::new(ListItem::new(body)))
+                                .collect(),
+                        )
+                        .with_tight(tight),
+                    ),
+                    Some(start) => self.pack(
+                        EnumElem::new(
+                            items
+                                .into_iter()
+                                .map(|body| Packed::new(EnumItem::new(body)))
+                                .collect(),
+                        )
+                        .with_start(start as usize)
+                        .with_tight(tight),
+                    ),
+                };
+                (content, true)
+            }
+            Tag::Table(alignments) => {
+                let columns = alignments.len();
+                let aligns: Vec<_> = alignments
+                    .into_iter()
+                    .map(|alignment| match alignment {
+                        Alignment::None => None,
+                        Alignment::Left => Some(HAlignment::Left),
+                        Alignment::Center => Some(HAlignment::Center),
+                        Alignment::Right => Some(HAlignment::Right),
+                    })
+                    .collect();
+                let mut children = vec![];
+                while let Some(Event::Start(tag)) = self.events.get(self.cursor).cloned()
+                {
+                    self.cursor += 1;
+                    let mut cells = vec![];
+                    while let Some(Event::Start(Tag::TableCell)) =
+                        self.events.get(self.cursor)
+                    {
+                        self.cursor += 1;
+                        // Only explicitly aligned columns override the table's
+                        // alignment, so that set rules apply to the others.
+                        let mut cell = TableCell::new(join(self.children()?));
+                        if let Some(align) = aligns.get(cells.len()).copied().flatten() {
+                            cell.push_align(Smart::Custom(Align::H(align)));
+                        }
+                        cells.push(TableItem::Cell(Packed::new(cell).spanned(self.span)));
+                    }
+                    // Skip the end of the row.
+                    self.cursor += 1;
+                    if tag == Tag::TableHead {
+                        children.push(TableChild::Header(
+                            Packed::new(TableHeader::new(cells)).spanned(self.span),
+                        ));
+                    } else {
+                        children.extend(cells.into_iter().map(TableChild::Item));
+                    }
+                }
+                // Skip the end of the table.
+                self.cursor += 1;
+                let elem = TableElem::new(children)
+                    .with_columns(TrackSizings(smallvec![Sizing::Auto; columns]));
+                (self.pack(elem), true)
+            }
+            Tag::Emphasis => {
+                let body = join(self.children()?);
+                (self.pack(EmphElem::new(body)), false)
+            }
+            Tag::Strong => {
+                let body = join(self.children()?);
+                (self.pack(StrongElem::new(body)), false)
+            }
+            Tag::Strikethrough => {
+                let body = join(self.children()?);
+                (self.pack(StrikeElem::new(body)), false)
+            }
+            Tag::Link(kind, dest, _) => {
+                let body = join(self.children()?);
+                let mut url: EcoString = dest.as_ref().into();
+                if kind == LinkType::Email {
+                    url = EcoString::from("mailto:") + url;
+                }
+                let elem = if body.is_empty() {
+                    LinkElem::from_url(url)
+                } else {
+                    LinkElem::new(LinkTarget::Dest(Destination::Url(url)), body)
+                };
+                (self.pack(elem), false)
+            }
+            Tag::Image(_, dest, _) => {
+                let alt = join(self.children()?).plain_text();
+                let path: EcoString = dest.as_ref().into();
+                if path.contains("://") {
+                    bail!(self.span, "cannot load remote image `{path}` from Markdown");
+                }
+                let Some(base) = self.base else {
+                    bail!(self.span, "cannot access file system from here");
+                };
+                let data = self.world.file(base.join(&path)).at(self.span)?;
+                let mut elem = ImageElem::new(path, Readable::Bytes(data));
+                if !alt.is_empty() {
+                    elem.push_alt(Some(alt));
+                }
+                (self.pack(elem), false)
+            }
+            Tag::FootnoteDefinition(_) => {
+                // Already converted up-front.
+                self.children()?;
+                return Ok(None);
+            }
+            Tag::Item | Tag::TableHead | Tag::TableRow | Tag::TableCell => {
+                (join(self.children()?), false)
+            }
+        };
+        Ok(Some(content))
+    }
+
+    /// Whether the list item starting at the cursor wraps its text in
+    /// paragraphs, which is how the parser marks the items of loose lists.
+    fn item_has_paragraphs(&self) -> bool {
+        let mut depth = 0;
+        for event in &self.events[self.cursor..] {
+            match event {
+                Event::Start(Tag::Paragraph) if depth == 0 => return true,
+                Event::Start(_) => depth += 1,
+                Event::End(_) if depth == 0 => return false,
+                Event::End(_) => depth -= 1,
+                _ => {}
+            }
+        }
+        false
+    }
+
+    /// Convert text, turning quotes into smart quotes like in markup.
+    fn text(&self, text: &str) -> Vec<Content> {
+        let mut pieces = vec![];
+        let mut rest = text;
+        while let Some(i) = rest.find(['"', '\'']) {
+            if i > 0 {
+                pieces.push(TextElem::packed(&rest[..i]));
+            }
+            let double = rest[i..].starts_with('"');
+            pieces.push(self.pack(SmartQuoteElem::new().with_double(double)));
+            rest = &rest[i + 1..];
+        }
+        if !rest.is_empty() {
+            pieces.push(TextElem::packed(rest));
+        }
+        pieces
+    }
+
+    /// Pack an element and attribute it to the call site.
+    fn pack(&self, elem: impl NativeElement) -> Content {
+        elem.pack().spanned(self.span)
+    }
+}
+
+/// Join converted children, separating consecutive blocks with paragraph
+/// breaks.
+fn join(children: Vec<Child>) -> Content {
+    let mut seq = Vec::with_capacity(children.len());
+    let mut prev_block = false;
+    for (content, block) in children {
+        if block && prev_block {
+            seq.push(ParbreakElem::shared().clone());
+        }
+        prev_block = block;
+        seq.push(content);
+    }
+    Content::sequence(seq)
+}
@@ -6,6 +6,8 @@ mod cbor_;
 mod csv_;
 #[path = "json.rs"]
 mod json_;
+#[path = "markdown.rs"]
+mod markdown_;
 #[path = "read.rs"]
 mod read_;
 #[path = "toml.rs"]
@@ -18,6 +20,7 @@ mod yaml_;
 pub use self::cbor_::*;
 pub use self::csv_::*;
 pub use self::json_::*;
+pub use self::markdown_::*;
 pub use self::read_::*;
 pub use self::toml_::*;
 pub use self::xml_::*;
@@ -42,6 +45,7 @@ pub(super) fn define(global: &mut Scope) {
     global.define_func::<yaml>();
     global.define_func::<cbor>();
     global.define_func::<xml>();
+    global.define_func::<markdown>();
 }
 
 /// A value that can be read from a file.
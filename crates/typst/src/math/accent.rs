@@ -4,8 +4,8 @@ use crate::foundations::{
 };
 use crate::layout::{Em, Frame, Length, Point, Rel, Size};
 use crate::math::{
-    style_cramped, FrameFragment, GlyphFragment, LayoutMath, MathContext, MathFragment,
-    Scaled,
+    mathml, style_cramped, FrameFragment, GlyphFragment, LayoutMath, MathContext,
+    MathFragment, Scaled,
 };
 use crate::text::TextElem;
 
@@ -132,6 +132,11 @@ impl LayoutMath for Packed<AccentElem> {
         let glyph = GlyphFragment::new(ctx, styles, *c, self.span());
         let short_fall = ACCENT_SHORT_FALL.at(glyph.font_size);
         let variant = glyph.stretch_horizontal(ctx, width, short_fall);
+        let mathml = mathml::node(
+            "mover",
+            [ctx.mathml_of(&base), ctx.mathml.then(|| mathml::char(*c, variant.class))],
+        )
+        .map(|node| node.with_attr("accent", "true"));
         let accent = variant.frame;
         let accent_attach = variant.accent_attach;
 
@@ -163,7 +168,8 @@ impl LayoutMath for Packed<AccentElem> {
                 .with_base_ascent(base_ascent)
                 .with_italics_correction(base_italics_correction)
                 .with_accent_attach(base_attach)
-                .with_text_like(base_text_like),
+                .with_text_like(base_text_like)
+                .with_mathml(mathml),
         );
 
         Ok(())
@@ -179,6 +185,11 @@ impl Accent {
     pub fn new(c: char) -> Self {
         Self(Self::combine(c).unwrap_or(c))
     }
+
+    /// The accent's character.
+    pub fn char(self) -> char {
+        self.0
+    }
 }
 
 // Keep it synced with the documenting table above.
@@ -4,8 +4,9 @@ use crate::diag::SourceResult;
 use crate::foundations::{elem, Content, Packed, Smart, StyleChain};
 use crate::layout::{Abs, Axis, Corner, Frame, Length, Point, Rel, Size};
 use crate::math::{
-    stretch_fragment, style_for_subscript, style_for_superscript, EquationElem,
-    FrameFragment, LayoutMath, MathContext, MathFragment, MathSize, Scaled, StretchElem,
+    mathml, stretch_fragment, style_for_subscript, style_for_superscript, EquationElem,
+    FrameFragment, LayoutMath, MathContext, MathFragment, MathMl, MathSize, Scaled,
+    StretchElem,
 };
 use crate::text::TextElem;
 use crate::utils::OptionExt;
@@ -170,7 +171,12 @@ impl LayoutMath for Packed<PrimesElem> {
                         prime.clone(),
                     )
                 }
-                ctx.push(FrameFragment::new(ctx, styles, frame).with_text_like(true));
+                let mathml = ctx.mathml.then(|| MathMl::token("mo", "′".repeat(count)));
+                ctx.push(
+                    FrameFragment::new(ctx, styles, frame)
+                        .with_text_like(true)
+                        .with_mathml(mathml),
+                );
             }
         }
         Ok(())
@@ -334,6 +340,11 @@ fn layout_attachments(
     [tl, t, tr, bl, b, br]: [Option<MathFragment>; 6],
 ) -> SourceResult<()> {
     let base_class = base.class();
+    let mathml = ctx.mathml.then(|| {
+        let attachments = [&tl, &t, &tr, &bl, &b, &br]
+            .map(|attachment| attachment.as_ref().map(mathml::fragment));
+        mathml::attach(mathml::fragment(&base), attachments)
+    });
 
     // Calculate the distance from the base's baseline to the superscripts' and
     // subscripts' baseline.
@@ -435,7 +446,11 @@ fn layout_attachments(
     layout!(b, b_x, b_y); // lower-limit
 
     // Done! Note that we retain the class of the base.
-    ctx.push(FrameFragment::new(ctx, styles, frame).with_class(base_class));
+    ctx.push(
+        FrameFragment::new(ctx, styles, frame)
+            .with_class(base_class)
+            .with_mathml(mathml),
+    );
 
     Ok(())
 }
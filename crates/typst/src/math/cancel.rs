@@ -7,7 +7,7 @@ use crate::foundations::{
 use crate::layout::{
     Abs, Angle, Frame, FrameItem, Length, Point, Ratio, Rel, Size, Transform,
 };
-use crate::math::{FrameFragment, LayoutMath, MathContext};
+use crate::math::{mathml, FrameFragment, LayoutMath, MathContext};
 use crate::syntax::Span;
 use crate::text::TextElem;
 use crate::visualize::{FixedStroke, Geometry, Stroke};
@@ -116,6 +116,7 @@ impl LayoutMath for Packed<CancelElem> {
         let body_italics = body.italics_correction();
         let body_attach = body.accent_attach();
         let body_text_like = body.is_text_like();
+        let body_mathml = ctx.mathml_of(&body);
 
         let mut body = body.into_frame();
         let body_size = body.size();
@@ -131,6 +132,16 @@ impl LayoutMath for Packed<CancelElem> {
         let cross = self.cross(styles);
         let angle = self.angle(styles);
 
+        let notation = if cross {
+            "updiagonalstrike downdiagonalstrike"
+        } else if invert {
+            "downdiagonalstrike"
+        } else {
+            "updiagonalstrike"
+        };
+        let mathml = mathml::node("menclose", [body_mathml])
+            .map(|node| node.with_attr("notation", notation));
+
         let invert_first_line = !cross && invert;
         let first_line = draw_cancel_line(
             ctx,
@@ -161,7 +172,8 @@ impl LayoutMath for Packed<CancelElem> {
                 .with_class(body_class)
                 .with_italics_correction(body_italics)
                 .with_accent_attach(body_attach)
-                .with_text_like(body_text_like),
+                .with_text_like(body_text_like)
+                .with_mathml(mathml),
         );
 
         Ok(())
@@ -0,0 +1,242 @@
+use ecow::EcoString;
+use unicode_math_class::MathClass;
+use unscanny::Scanner;
+
+use crate::diag::{bail, At, SourceResult, StrResult};
+use crate::foundations::{func, Content, NativeElement, Smart};
+use crate::math::{AttachElem, ClassElem, EquationElem, OpElem, StretchElem};
+use crate::syntax::Spanned;
+use crate::text::TextElem;
+
+/// Typesets a chemical formula or reaction.
+///
+/// The formula is given as a compact string that is converted into regular
+/// math content. Element symbols are set upright, counts after a symbol or
+/// group become subscripts, and charges become superscripts. The following
+/// notation is understood:
+///
+/// - `H2O`, `Ca(OH)2`: Element symbols followed by counts. Counts may also
+///   follow parenthesized or bracketed groups.
+/// - `2H2`: A leading number is a stoichiometric coefficient.
+/// - `Na+`, `SO4^2-`, `Fe^{3+}`: Charges, either trailing a species or
+///   introduced with `^`.
+/// - `(s)`, `(l)`, `(g)`, `(aq)`: State annotations.
+/// - `CuSO4*5H2O`: Adducts, joined with `*` or `.`.
+/// - ` + `: A plus between two species, separated by spaces.
+/// - `->`, `<-`, `<->`, `<=>`: Reaction arrows. Conditions can be placed
+///   above and below the arrow in square brackets, as in `->[above][below]`.
+///   They are themselves parsed as formulas.
+///
+/// ```example
+/// $ chem("2H2 + O2 -> 2H2O") $
+/// $ chem("CuSO4*5H2O") $
+/// $ chem("NH4+ + OH- <=>[H2O] NH3 + H2O") $
+/// $ chem("CaCO3(s) ->[heat] CaO(s) + CO2(g)") $
+/// ```
+#[func]
+pub fn chem(
+    /// The formula to typeset.
+    formula: Spanned<EcoString>,
+) -> SourceResult<Content> {
+    let body = parse(&formula.v).at(formula.span)?;
+    Ok(body
+        .styled(EquationElem::set_italic(Smart::Custom(false)))
+        .spanned(formula.span))
+}
+
+/// Parse a complete formula, which may consist of multiple species joined by
+/// plus signs and reaction arrows.
+fn parse(text: &str) -> StrResult<Content> {
+    let mut s = Scanner::new(text);
+    let mut seq = vec![];
+
+    loop {
+        s.eat_whitespace();
+        if s.done() {
+            break;
+        }
+
+        if let Some(arrow) = arrow(&mut s) {
+            let above = bracketed(&mut s)?.map(condition).transpose()?;
+            let below = bracketed(&mut s)?.map(condition).transpose()?;
+            let stretched = StretchElem::new(TextElem::packed(arrow)).pack();
+            seq.push(operator(
+                if above.is_some() || below.is_some() {
+                    AttachElem::new(stretched).with_t(above).with_b(below).pack()
+                } else {
+                    stretched
+                },
+                MathClass::Relation,
+            ));
+        } else if s.at('+') && at_boundary(&s, 1) {
+            s.eat();
+            seq.push(operator(TextElem::packed('+'), MathClass::Binary));
+        } else {
+            let end = s.cursor()
+                + s.after().find(char::is_whitespace).unwrap_or(s.after().len());
+            seq.push(species(s.get(s.cursor()..end))?);
+            s.jump(end);
+        }
+    }
+
+    Ok(Content::sequence(seq))
+}
+
+/// Parse the condition of a reaction arrow. It is laid out within the arrow's
+/// operator, so it must not inherit the operator's class.
+fn condition(text: &str) -> StrResult<Content> {
+    Ok(parse(text)?.styled(EquationElem::set_class(None)))
+}
+
+/// Wrap a reaction arrow or plus sign into an operator that is spaced like the
+/// given class.
+fn operator(body: Content, class: MathClass) -> Content {
+    ClassElem::new(class, OpElem::new(body).pack()).pack()
+}
+
+/// Parse a reaction arrow, if there is one.
+fn arrow(s: &mut Scanner) -> Option<char> {
+    for (pattern, arrow) in [("<=>", '⇌'), ("<->", '↔'), ("->", '→'), ("<-", '←')]
+    {
+        if s.after().starts_with(pattern)
+            && (at_boundary(s, pattern.len())
+                || s.after()[pattern.len()..].starts_with('['))
+        {
+            s.jump(s.cursor() + pattern.len());
+            return Some(arrow);
+        }
+    }
+    None
+}
+
+/// Whether the token of the given length at the scanner's cursor is followed
+/// by whitespace or the end of the text.
+fn at_boundary(s: &Scanner, len: usize) -> bool {
+    s.after()[len..].chars().next().map_or(true, char::is_whitespace)
+}
+
+/// Parse a bracketed piece of text, if there is one, and return its inside.
+fn bracketed<'a>(s: &mut Scanner<'a>) -> StrResult<Option<&'a str>> {
+    let Some(&open) = ['[', '(', '{'].iter().find(|&&c| s.at(c)) else {
+        return Ok(None);
+    };
+    let close = match open {
+        '[' => ']',
+        '(' => ')',
+        _ => '}',
+    };
+
+    let start = s.cursor();
+    let mut depth = 0;
+    while let Some(c) = s.eat() {
+        if c == open {
+            depth += 1;
+        } else if c == close {
+            depth -= 1;
+            if depth == 0 {
+                return Ok(Some(s.get(start + open.len_utf8()..s.cursor() - 1)));
+            }
+        }
+    }
+
+    bail!("unclosed delimiter `{open}` in chemical formula")
+}
+
+/// Parse a single species, like `2H2O`, `SO4^2-` or `NaCl(aq)`.
+fn species(text: &str) -> StrResult<Content> {
+    let mut s = Scanner::new(text);
+    let mut seq = vec![];
+    coefficient(&mut s, &mut seq);
+
+    while let Some(c) = s.peek() {
+        let base = match c {
+            'A'..='Z' => {
+                let start = s.cursor();
+                s.eat();
+                s.eat_while(char::is_lowercase);
+                symbol(s.from(start))
+            }
+            'a'..='z' => symbol(s.eat_while(char::is_lowercase)),
+            '(' | '[' => {
+                let start = s.cursor();
+                let inner = bracketed(&mut s)?.unwrap_or_default();
+                if !inner.is_empty() && inner.chars().all(|c| c.is_ascii_lowercase()) {
+                    // A state annotation like `(aq)`.
+                    seq.push(TextElem::packed(s.from(start)));
+                    continue;
+                }
+                Content::sequence([
+                    TextElem::packed(c),
+                    species(inner)?,
+                    TextElem::packed(s.before().chars().next_back().unwrap()),
+                ])
+            }
+            ')' | ']' | '}' => {
+                bail!("unexpected closing delimiter `{c}` in chemical formula")
+            }
+            '*' | '.' | '·' => {
+                s.eat();
+                seq.push(TextElem::packed('·'));
+                coefficient(&mut s, &mut seq);
+                continue;
+            }
+            '-' => {
+                s.eat();
+                TextElem::packed('−')
+            }
+            _ => {
+                s.eat();
+                TextElem::packed(c)
+            }
+        };
+
+        let count = s.eat_while(|c: char| c.is_ascii_digit());
+        let count = (!count.is_empty()).then(|| TextElem::packed(count));
+        let charge = charge(&mut s)?;
+        seq.push(if count.is_some() || charge.is_some() {
+            AttachElem::new(base).with_br(count).with_tr(charge).pack()
+        } else {
+            base
+        });
+    }
+
+    Ok(Content::sequence(seq))
+}
+
+/// Parse a stoichiometric coefficient, if there is one.
+fn coefficient(s: &mut Scanner, seq: &mut Vec<Content>) {
+    let coefficient = s.eat_while(|c: char| c.is_ascii_digit());
+    if !coefficient.is_empty() {
+        seq.push(TextElem::packed(coefficient));
+    }
+}
+
+/// Parse a charge, if there is one. Charges are either introduced with `^` or
+/// consist of the signs at the very end of a species.
+fn charge(s: &mut Scanner) -> StrResult<Option<Content>> {
+    let text = if s.eat_if('^') {
+        match bracketed(s)? {
+            Some(inner) => inner,
+            None => {
+                let start = s.cursor();
+                s.eat_while(|c: char| c.is_ascii_digit());
+                s.eat_while(['+', '-']);
+                s.from(start)
+            }
+        }
+    } else if !s.after().is_empty() && s.after().chars().all(|c| matches!(c, '+' | '-')) {
+        s.eat_while(['+', '-'])
+    } else {
+        return Ok(None);
+    };
+
+    Ok(Some(Content::sequence(text.chars().map(|c| match c {
+        '-' => TextElem::packed('−'),
+        c => TextElem::packed(c),
+    }))))
+}
+
+/// An upright element symbol or other run of letters.
+fn symbol(text: &str) -> Content {
+    Content::sequence(text.chars().map(TextElem::packed))
+}
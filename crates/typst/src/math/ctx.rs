@@ -17,8 +17,8 @@ use crate::layout::{
     layout_frame, Abs, Axes, BoxElem, Em, Frame, HElem, PlaceElem, Region, Size, Spacing,
 };
 use crate::math::{
-    scaled_font_size, styled_char, EquationElem, FrameFragment, GlyphFragment,
-    LayoutMath, MathFragment, MathRun, MathSize, THICK,
+    mathml, scaled_font_size, styled_char, EquationElem, FrameFragment, GlyphFragment,
+    LayoutMath, MathFragment, MathMl, MathRun, MathSize, THICK,
 };
 use crate::realize::{realize, Arenas, RealizationKind};
 use crate::syntax::{is_newline, Span};
@@ -63,6 +63,8 @@ pub struct MathContext<'a, 'v, 'e> {
     pub space_width: Em,
     // Mutable.
     pub fragments: Vec<MathFragment>,
+    /// Whether to record the MathML representation of the fragments.
+    pub mathml: bool,
 }
 
 impl<'a, 'v, 'e> MathContext<'a, 'v, 'e> {
@@ -118,9 +120,23 @@ impl<'a, 'v, 'e> MathContext<'a, 'v, 'e> {
             glyphwise_tables,
             space_width,
             fragments: vec![],
+            mathml: false,
         }
     }
 
+    /// The MathML representation of a fragment, if it is recorded.
+    pub fn mathml_of(&self, fragment: &MathFragment) -> Option<MathMl> {
+        self.mathml.then(|| mathml::fragment(fragment))
+    }
+
+    /// The MathML representation of a run of fragments, if it is recorded.
+    pub fn mathml_of_run<'f>(
+        &self,
+        fragments: impl IntoIterator<Item = &'f MathFragment>,
+    ) -> Option<MathMl> {
+        self.mathml.then(|| mathml::run(fragments))
+    }
+
     /// Push a fragment.
     pub fn push(&mut self, fragment: impl Into<MathFragment>) {
         self.fragments.push(fragment.into());
@@ -193,7 +209,12 @@ impl MathContext<'_, '_, '_> {
             // Hack because the font is fixed in math.
             if styles != outer && TextElem::font_in(styles) != TextElem::font_in(outer) {
                 let frame = self.layout_external(elem, styles)?;
-                self.push(FrameFragment::new(self, styles, frame).with_spaced(true));
+                let mathml = self.mathml.then(|| mathml::text(&elem.plain_text()));
+                self.push(
+                    FrameFragment::new(self, styles, frame)
+                        .with_spaced(true)
+                        .with_mathml(mathml),
+                );
                 continue;
             }
 
@@ -230,7 +251,12 @@ impl MathContext<'_, '_, '_> {
             self.push(fragment);
         } else if let Some(boxed) = elem.to_packed::<BoxElem>() {
             let frame = self.layout_box(boxed, styles)?;
-            self.push(FrameFragment::new(self, styles, frame).with_spaced(true));
+            let mathml = self.mathml.then(|| mathml::text(&elem.plain_text()));
+            self.push(
+                FrameFragment::new(self, styles, frame)
+                    .with_spaced(true)
+                    .with_mathml(mathml),
+            );
         } else if let Some(elem) = elem.with::<dyn LayoutMath>() {
             elem.layout_math(self, styles)?;
         } else {
@@ -239,10 +265,13 @@ impl MathContext<'_, '_, '_> {
                 let axis = scaled!(self, styles, axis_height);
                 frame.set_baseline(frame.height() / 2.0 + axis);
             }
+            let text = elem.plain_text();
+            let mathml = (self.mathml && !text.is_empty()).then(|| mathml::text(&text));
             self.push(
                 FrameFragment::new(self, styles, frame)
                     .with_spaced(true)
-                    .with_ignorant(elem.is::<PlaceElem>()),
+                    .with_ignorant(elem.is::<PlaceElem>())
+                    .with_mathml(mathml),
             );
         }
 
@@ -331,7 +360,10 @@ impl MathContext<'_, '_, '_> {
                 fragments.push(GlyphFragment::new(self, styles, c, span).into());
             }
             let frame = MathRun::new(fragments).into_frame(self, styles);
-            FrameFragment::new(self, styles, frame).with_text_like(true).into()
+            FrameFragment::new(self, styles, frame)
+                .with_text_like(true)
+                .with_mathml(self.mathml.then(|| mathml::text(text)))
+                .into()
         } else {
             let local = [
                 TextElem::set_top_edge(TopEdge::Metric(TopEdgeMetric::Bounds)),
@@ -355,10 +387,12 @@ impl MathContext<'_, '_, '_> {
                             .push(self.layout_complex_text(piece, span, styles)?.into());
                     }
                 }
-                let mut frame = MathRun::new(fragments).into_frame(self, styles);
+                let run = MathRun::new(fragments);
+                let mathml = self.mathml_of_run(run.iter());
+                let mut frame = run.into_frame(self, styles);
                 let axis = scaled!(self, styles, axis_height);
                 frame.set_baseline(frame.height() / 2.0 + axis);
-                FrameFragment::new(self, styles, frame).into()
+                FrameFragment::new(self, styles, frame).with_mathml(mathml).into()
             } else {
                 self.layout_complex_text(&text, span, styles)?.into()
             }
@@ -387,13 +421,15 @@ impl MathContext<'_, '_, '_> {
             false,
             Size::splat(Abs::inf()),
             false,
+            &[],
         )?
         .into_frame();
 
         Ok(FrameFragment::new(self, styles, frame)
             .with_class(MathClass::Alphabetic)
             .with_text_like(true)
-            .with_spaced(spaced))
+            .with_spaced(spaced)
+            .with_mathml(self.mathml.then(|| mathml::text(text))))
     }
 }
 
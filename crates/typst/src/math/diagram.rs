@@ -0,0 +1,392 @@
+use crate::diag::{bail, HintedStrResult, SourceResult};
+use crate::foundations::{
+    array, cast, dict, elem, Array, Content, Dict, Packed, Resolve, StyleChain, Value,
+};
+use crate::layout::{
+    Abs, Angle, Axes, Em, Frame, FrameItem, HAlignment, Length, Point, Rel, Size,
+};
+use crate::math::{
+    layout_delimiters, layout_mat_body, parse_rows, scaled_font_size,
+    style_for_superscript, DelimiterPair, LayoutMath, MathContext,
+    DEFAULT_STROKE_THICKNESS,
+};
+use crate::syntax::Span;
+use crate::text::TextElem;
+use crate::visualize::{
+    FillRule, FixedStroke, Geometry, LineCap, LineJoin, Path, Shape, Stroke,
+};
+
+const DEFAULT_ROW_GAP: Em = Em::new(1.5);
+const DEFAULT_COL_GAP: Em = Em::new(2.0);
+const ARROW_PADDING: Em = Em::new(0.25);
+const ARROW_HEAD_LENGTH: Em = Em::new(0.3);
+const LABEL_GAP: Em = Em::new(0.15);
+
+/// A commutative diagram.
+///
+/// The objects of a diagram are laid out in a grid just like the cells of a
+/// [matrix]($math.mat): The elements of a row are separated by commas and the
+/// rows themselves by semicolons. The objects are then connected with
+/// [arrows]($math.diagram.arrows), which refer to cells by their coordinates.
+///
+/// # Example
+/// ```example
+/// $ diagram(
+///   A, B;
+///   C, D;
+///   arrows: #(
+///     (from: (0, 0), to: (0, 1), label: $f$),
+///     (from: (0, 0), to: (1, 0), label: $g$, side: right),
+///     (from: (0, 1), to: (1, 1), label: $h$),
+///     (from: (1, 0), to: (1, 1), label: $k$, side: right),
+///   ),
+/// ) $
+/// ```
+#[elem(LayoutMath)]
+pub struct DiagramElem {
+    /// The delimiter to use.
+    ///
+    /// ```example
+    /// #set math.diagram(delim: "[")
+    /// $ diagram(A, B; arrows: #((from: (0, 0), to: (0, 1)),)) $
+    /// ```
+    #[default(DelimiterPair::NONE)]
+    pub delim: DelimiterPair,
+
+    /// The horizontal alignment that each cell should have.
+    #[resolve]
+    #[default(HAlignment::Center)]
+    pub align: HAlignment,
+
+    /// The arrows between the cells of the diagram.
+    ///
+    /// Each arrow is given as a dictionary with the following keys:
+    /// - `from`: The cell at which the arrow starts, as a `(row, column)`
+    ///   pair of zero-based indices.
+    /// - `to`: The cell at which the arrow ends, in the same format.
+    /// - `label`: Optional content that is placed midway along the arrow.
+    /// - `side`: On which side of the arrow the label is placed, relative to
+    ///   the arrow's direction. Either `{left}` (the default) or `{right}`.
+    /// - `bend`: An angle by which the arrow curves away from the straight
+    ///   line between its cells. Positive angles bend it to the left.
+    /// - `stroke`: How to [stroke]($stroke) the arrow. Dash patterns only
+    ///   apply to the arrow's shaft, not to its head.
+    ///
+    /// Arrows start and end at the bounding boxes of their cells.
+    ///
+    /// ```example
+    /// $ diagram(
+    ///   X, Y;
+    ///   arrows: #(
+    ///     (from: (0, 0), to: (0, 1), bend: 30deg, label: $f$),
+    ///     (from: (0, 0), to: (0, 1), bend: -30deg, label: $g$, side: right),
+    ///     (from: (0, 1), to: (0, 0), stroke: (dash: "dashed")),
+    ///   ),
+    /// ) $
+    /// ```
+    pub arrows: Vec<Arrow>,
+
+    /// The gap between rows and columns.
+    ///
+    /// ```example
+    /// #set math.diagram(gap: 1em)
+    /// $ diagram(A, B; C, D; arrows: #((from: (0, 0), to: (1, 1)),)) $
+    /// ```
+    #[external]
+    pub gap: Rel<Length>,
+
+    /// The gap between rows. Takes precedence over `gap`.
+    #[resolve]
+    #[parse(
+        let gap = args.named("gap")?;
+        args.named("row-gap")?.or(gap)
+    )]
+    #[default(DEFAULT_ROW_GAP.into())]
+    pub row_gap: Rel<Length>,
+
+    /// The gap between columns. Takes precedence over `gap`.
+    #[resolve]
+    #[parse(args.named("column-gap")?.or(gap))]
+    #[default(DEFAULT_COL_GAP.into())]
+    pub column_gap: Rel<Length>,
+
+    /// An array of arrays with the rows of the diagram.
+    #[variadic]
+    #[parse(parse_rows(args)?)]
+    pub rows: Vec<Vec<Content>>,
+}
+
+impl LayoutMath for Packed<DiagramElem> {
+    #[typst_macros::time(name = "math.diagram", span = self.span())]
+    fn layout_math(&self, ctx: &mut MathContext, styles: StyleChain) -> SourceResult<()> {
+        let rows = self.rows();
+        let nrows = rows.len();
+        let ncols = rows.first().map_or(0, |row| row.len());
+        let arrows = self.arrows(styles);
+
+        for arrow in &arrows {
+            for (row, col) in [arrow.from, arrow.to] {
+                if row >= nrows || col >= ncols {
+                    bail!(
+                        self.span(),
+                        "arrow refers to cell ({row}, {col}) of a diagram with {nrows} rows and {ncols} columns"
+                    );
+                }
+            }
+
+            if arrow.from == arrow.to {
+                bail!(self.span(), "arrow must connect two different cells");
+            }
+        }
+
+        let mut cells = vec![];
+        let (mut frame, mathml) = layout_mat_body(
+            ctx,
+            styles,
+            rows,
+            self.align(styles),
+            None,
+            Axes::new(self.column_gap(styles), self.row_gap(styles)),
+            self.span(),
+            Some(&mut cells),
+        )?;
+
+        let mut min = Point::zero();
+        let mut max = frame.size().to_point();
+        for arrow in arrows {
+            let from = cells[arrow.from.0][arrow.from.1];
+            let to = cells[arrow.to.0][arrow.to.1];
+            let (lo, hi) =
+                layout_arrow(ctx, styles, &mut frame, &arrow, from, to, self.span())?;
+            min = min.min(lo);
+            max = max.max(hi);
+        }
+
+        // Arrows that bend or carry labels may reach beyond the cells, so the
+        // frame grows to include them.
+        frame.translate(-min);
+        frame.set_size((max - min).to_size());
+
+        let delim = self.delim(styles);
+        layout_delimiters(
+            ctx,
+            styles,
+            frame,
+            mathml,
+            delim.open(),
+            delim.close(),
+            self.span(),
+        )
+    }
+}
+
+/// An arrow between two cells of a diagram.
+#[derive(Debug, Clone, PartialEq, Hash)]
+pub struct Arrow {
+    /// The cell at which the arrow starts.
+    pub from: (usize, usize),
+    /// The cell at which the arrow ends.
+    pub to: (usize, usize),
+    /// The label placed midway along the arrow.
+    pub label: Option<Content>,
+    /// On which side of the arrow the label is placed.
+    pub side: ArrowSide,
+    /// How much the arrow curves away from a straight line.
+    pub bend: Angle,
+    /// How to stroke the arrow.
+    pub stroke: Stroke,
+}
+
+cast! {
+    Arrow,
+    self => {
+        let cell = |(row, col): (usize, usize)| array![row, col];
+        let mut dict = dict! {
+            "from" => cell(self.from),
+            "to" => cell(self.to),
+            "side" => self.side,
+            "bend" => self.bend,
+            "stroke" => self.stroke,
+        };
+        if let Some(label) = self.label {
+            dict.insert("label".into(), label.into_value());
+        }
+        dict.into_value()
+    },
+    mut dict: Dict => {
+        let from = cell(dict.take("from")?)?;
+        let to = cell(dict.take("to")?)?;
+        let label = dict.take("label").ok().map(Value::display);
+        let side = dict.take("side").ok().map(Value::cast).transpose()?.unwrap_or_default();
+        let bend = dict.take("bend").ok().map(Value::cast).transpose()?.unwrap_or_default();
+        let stroke = dict.take("stroke").ok().map(Value::cast).transpose()?.unwrap_or_default();
+        dict.finish(&["from", "to", "label", "side", "bend", "stroke"])?;
+        Self { from, to, label, side, bend, stroke }
+    },
+}
+
+/// Cast a value into the `(row, column)` coordinates of a cell.
+fn cell(value: Value) -> HintedStrResult<(usize, usize)> {
+    let array = value.cast::<Array>()?;
+    match array.as_slice() {
+        [row, col] => Ok((row.clone().cast()?, col.clone().cast()?)),
+        _ => bail!(
+            "expected a pair of row and column indices, found {} values",
+            array.len()
+        ),
+    }
+}
+
+/// On which side of an arrow its label is placed.
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum ArrowSide {
+    /// To the left of the arrow's direction.
+    #[default]
+    Left,
+    /// To the right of the arrow's direction.
+    Right,
+}
+
+cast! {
+    ArrowSide,
+    self => match self {
+        Self::Left => HAlignment::Left.into_value(),
+        Self::Right => HAlignment::Right.into_value(),
+    },
+    v: HAlignment => match v {
+        HAlignment::Left => Self::Left,
+        HAlignment::Right => Self::Right,
+        _ => bail!("expected `left` or `right`"),
+    },
+}
+
+/// Draw an arrow between two cells into the diagram's frame.
+///
+/// Returns the top-left and bottom-right corners of the area covered by the
+/// arrow and its label.
+fn layout_arrow(
+    ctx: &mut MathContext,
+    styles: StyleChain,
+    frame: &mut Frame,
+    arrow: &Arrow,
+    (from_pos, from_size): (Point, Size),
+    (to_pos, to_size): (Point, Size),
+    span: Span,
+) -> SourceResult<(Point, Point)> {
+    let font_size = scaled_font_size(ctx, styles);
+    let padding = ARROW_PADDING.at(font_size);
+    let default_stroke = FixedStroke {
+        thickness: DEFAULT_STROKE_THICKNESS.at(font_size),
+        paint: TextElem::fill_in(styles).as_decoration(),
+        cap: LineCap::Round,
+        join: LineJoin::Round,
+        ..Default::default()
+    };
+    let stroke = arrow.stroke.clone().resolve(styles).unwrap_or(default_stroke);
+
+    // The arrow leaves its start cell at the given bend angle to the direct
+    // line between the cells' centers and enters the end cell symmetrically.
+    let from_center = from_pos + from_size.to_point() * 0.5;
+    let to_center = to_pos + to_size.to_point() * 0.5;
+    let dir = direction(from_center, to_center);
+    let start_dir = rotate(dir, arrow.bend);
+    let end_dir = rotate((-dir.0, -dir.1), -arrow.bend);
+    let start = boundary(from_center, from_size, start_dir, padding);
+    let end = boundary(to_center, to_size, end_dir, padding);
+
+    let reach = (end - start).hypot() / 3.0;
+    let c1 = advance(start, start_dir, reach);
+    let c2 = advance(end, end_dir, reach);
+
+    let mut shaft = Path::new();
+    shaft.move_to(start);
+    shaft.cubic_to(c1, c2, end);
+    frame.push(Point::zero(), shape(shaft, stroke.clone(), span));
+
+    // The head follows the curve's tangent at its end and is always solid.
+    let head_dir = direction(c2, end);
+    let head_length = ARROW_HEAD_LENGTH.at(font_size);
+    let back = (-head_dir.0, -head_dir.1);
+    let spread = Angle::deg(35.0);
+    let mut head = Path::new();
+    head.move_to(advance(end, rotate(back, spread), head_length));
+    head.line_to(end);
+    head.line_to(advance(end, rotate(back, -spread), head_length));
+    frame.push(Point::zero(), shape(head, FixedStroke { dash: None, ..stroke }, span));
+
+    let mut min = start.min(end).min(c1).min(c2);
+    let mut max = start.max(end).max(c1).max(c2);
+
+    if let Some(label) = &arrow.label {
+        let label =
+            ctx.layout_into_frame(label, styles.chain(&style_for_superscript(styles)))?;
+
+        // Place the label next to the curve's midpoint, far enough along the
+        // normal that it does not touch the arrow.
+        let mid = (start + c1 * 3.0 + c2 * 3.0 + end) / 8.0;
+        let tangent = direction(start + c1, c2 + end);
+        let normal = match arrow.side {
+            ArrowSide::Left => rotate(tangent, Angle::deg(90.0)),
+            ArrowSide::Right => rotate(tangent, Angle::deg(-90.0)),
+        };
+        let half = label.size().to_point() * 0.5;
+        let distance =
+            LABEL_GAP.at(font_size) + half.x * normal.0.abs() + half.y * normal.1.abs();
+        let pos = advance(mid, normal, distance) - half;
+
+        min = min.min(pos);
+        max = max.max(pos + label.size().to_point());
+        frame.push_frame(pos, label);
+    }
+
+    Ok((min, max))
+}
+
+/// Create a stroked, unfilled shape from a path.
+fn shape(path: Path, stroke: FixedStroke, span: Span) -> FrameItem {
+    FrameItem::Shape(
+        Shape {
+            geometry: Geometry::Path(path),
+            fill: None,
+            fill_rule: FillRule::default(),
+            stroke: Some(stroke),
+        },
+        span,
+    )
+}
+
+/// The unit vector pointing from one point to another.
+fn direction(from: Point, to: Point) -> (f64, f64) {
+    let delta = to - from;
+    let length = delta.hypot();
+    if length == Abs::zero() {
+        return (1.0, 0.0);
+    }
+    (delta.x / length, delta.y / length)
+}
+
+/// Rotate a unit vector by an angle. Positive angles rotate counterclockwise
+/// on the page, i.e. towards the left of the vector's direction.
+fn rotate((x, y): (f64, f64), angle: Angle) -> (f64, f64) {
+    let (sin, cos) = (angle.sin(), angle.cos());
+    (x * cos + y * sin, y * cos - x * sin)
+}
+
+/// Move a point along a unit vector.
+fn advance(point: Point, (x, y): (f64, f64), distance: Abs) -> Point {
+    point + Point::new(distance * x, distance * y)
+}
+
+/// The point at which a ray from a cell's center in the given direction
+/// leaves the cell's padded bounding box.
+fn boundary(center: Point, size: Size, dir: (f64, f64), padding: Abs) -> Point {
+    let half = size / 2.0 + Size::splat(padding);
+    let mut distance = Abs::inf();
+    if dir.0 != 0.0 {
+        distance.set_min(half.x / dir.0.abs());
+    }
+    if dir.1 != 0.0 {
+        distance.set_min(half.y / dir.1.abs());
+    }
+    advance(center, dir, distance)
+}
@@ -1,29 +1,34 @@
 use std::num::NonZeroUsize;
 
+use ecow::{eco_format, EcoString};
 use unicode_math_class::MathClass;
 
 use crate::diag::{bail, SourceResult};
 use crate::engine::Engine;
 use crate::foundations::{
-    elem, Content, NativeElement, Packed, Resolve, Show, ShowSet, Smart, StyleChain,
-    Styles, Synthesize,
+    elem, Args, Cast, Construct, Content, Label, NativeElement, Packed, Resolve,
+    SequenceElem, Show, ShowSet, Smart, StyleChain, Styles, Synthesize,
+};
+use crate::introspection::{
+    Count, Counter, CounterUpdate, Locatable, Location, Locator, TagKind,
 };
-use crate::introspection::{Count, Counter, CounterUpdate, Locatable, Locator};
 use crate::layout::{
     layout_frame, Abs, AlignElem, Alignment, Axes, BlockElem, Em, FixedAlignment,
-    Fragment, Frame, InlineElem, InlineItem, OuterHAlignment, Point, Region, Regions,
-    Size, SpecificAlignment, VAlignment,
+    Fragment, Frame, FrameItem, InlineElem, InlineItem, Length, OuterHAlignment, Point,
+    Region, Regions, Size, SpecificAlignment, VAlignment,
 };
 use crate::math::{
-    scaled_font_size, MathContext, MathRunFrameBuilder, MathSize, MathVariant,
+    mathml, scaled_font_size, LayoutMath, MathContext, MathFragment, MathMl, MathRun,
+    MathRunFrameBuilder, MathSize, MathVariant,
 };
 use crate::model::{Numbering, Outlinable, ParElem, Refable, Supplement};
 use crate::syntax::Span;
 use crate::text::{
-    families, variant, Font, FontFamily, FontList, FontWeight, LocalName, TextElem,
+    families, variant, Font, FontFamily, FontList, FontWeight, LinebreakElem, LocalName,
+    SpaceElem, TextElem,
 };
 use crate::utils::{NonZeroExt, Numeric};
-use crate::World;
+use crate::{Feature, World};
 
 /// A mathematical equation.
 ///
@@ -54,6 +59,38 @@ pub struct EquationElem {
     #[default(false)]
     pub block: bool,
 
+    /// Whether to automatically break block-level equations that are too wide
+    /// for the available space.
+    ///
+    /// Lines are broken before top-level relations and binary operators, such
+    /// that the operator starts the continuation line. Breaking at a relation
+    /// is preferred as long as the line stays reasonably full. Rows that fit
+    /// are unaffected, while broken rows ignore their alignment points.
+    ///
+    /// ```example
+    /// #set page(width: 150pt)
+    /// #set math.equation(linebreaking: true)
+    ///
+    /// $ (a + b)^4 = a^4 + 4a^3 b + 6a^2 b^2
+    ///     + 4a b^3 + b^4 $
+    /// ```
+    #[default(false)]
+    pub linebreaking: bool,
+
+    /// The indent of the continuation lines of automatically broken
+    /// equations.
+    ///
+    /// ```example
+    /// #set page(width: 150pt)
+    /// #set math.equation(linebreaking: true, hanging-indent: 1em)
+    ///
+    /// $ x = a + b + c + d + e
+    ///     + f + g + h + i $
+    /// ```
+    #[resolve]
+    #[default(Em::new(2.0).into())]
+    pub hanging_indent: Length,
+
     /// How to [number]($numbering) block-level equations.
     ///
     /// ```example
@@ -85,6 +122,33 @@ pub struct EquationElem {
     #[default(SpecificAlignment::Both(OuterHAlignment::End, VAlignment::Horizon))]
     pub number_align: SpecificAlignment<OuterHAlignment, VAlignment>,
 
+    /// Whether to number the lines of a multi-line block equation separately.
+    ///
+    /// - `{none}`: The equation as a whole receives a single number.
+    /// - `{"each"}`: Each line receives its own number from the equation
+    ///   counter.
+    /// - `{"sub"}`: The equation receives a single number and each line a
+    ///   sub-number. Use a numbering pattern with two levels (like `{"(1a)"}`)
+    ///   to display them.
+    ///
+    /// The number of a single line can be suppressed, replaced, or labelled
+    /// with a [`tag`]($math.tag). Line numbers are aligned with the baseline
+    /// of their line, so the vertical component of the
+    /// [`number-align`]($math.equation.number-align) is ignored.
+    ///
+    /// ```example
+    /// #set math.equation(
+    ///   numbering: "(1a)",
+    ///   line-numbering: "sub",
+    /// )
+    ///
+    /// $ a &= b + c \
+    ///     &= c + b #math.tag(label: <comm>) $
+    ///
+    /// By @comm, we are done.
+    /// ```
+    pub line_numbering: Option<LineNumbering>,
+
     /// A supplement for the equation.
     ///
     /// For references to equations, this is added before the referenced number.
@@ -103,10 +167,40 @@ pub struct EquationElem {
     /// ```
     pub supplement: Smart<Option<Supplement>>,
 
+    /// An alternative description of the equation.
+    ///
+    /// Exporters make this text available to assistive technology. In PDF
+    /// export, for instance, it becomes the equation's alternative text. If
+    /// no description is given, a plain-text rendering of the equation's
+    /// [MathML]($math.equation.mathml) representation is used instead.
+    ///
+    /// ```example
+    /// #set math.equation(alt: "Pythagorean theorem")
+    /// $ a^2 + b^2 = c^2 $
+    /// ```
+    pub alt: Option<EcoString>,
+
     /// The contents of the equation.
     #[required]
     pub body: Content,
 
+    /// The equation's representation as MathML presentation markup.
+    ///
+    /// This is derived from the laid-out body and can be retrieved with a
+    /// [query]($query). Because deriving it has a cost, it is only available
+    /// when the document is exported in a format that embeds it, like PDF or
+    /// SVG, or queried with `typst query`. Otherwise, it is `{none}`.
+    ///
+    /// ```example
+    /// $ x^2 $ <square>
+    ///
+    /// #context raw(
+    ///   query(<square>).first().mathml
+    /// )
+    /// ```
+    #[synthesized]
+    pub mathml: Option<EcoString>,
+
     /// The size of the glyphs.
     #[internal]
     #[default(MathSize::Text)]
@@ -155,7 +249,17 @@ impl Synthesize for Packed<EquationElem> {
             }
         };
 
+        let mut mathml = None;
+        if engine.world.library().features.is_enabled(Feature::Tagging) {
+            let math = layout_mathml(self, engine, styles)?;
+            if self.as_ref().alt(styles).is_none() {
+                self.push_alt(Some(math.plain_text()));
+            }
+            mathml = Some(eco_format!("{math}"));
+        }
+
         self.push_supplement(Smart::Custom(Some(Supplement::Content(supplement))));
+        self.push_mathml(mathml);
         Ok(())
     }
 }
@@ -181,8 +285,13 @@ impl ShowSet for Packed<EquationElem> {
             out.set(AlignElem::set_alignment(Alignment::CENTER));
             out.set(BlockElem::set_breakable(false));
             out.set(EquationElem::set_size(MathSize::Display));
+
+            // Make the numbering available to the tags of the lines.
+            out.set(EquationElem::set_numbering((**self).numbering(styles).clone()));
+            out.set(EquationElem::set_line_numbering(self.line_numbering(styles)));
         } else {
             out.set(EquationElem::set_size(MathSize::Text));
+            out.set(EquationElem::set_line_numbering(None));
         }
         out.set(TextElem::set_weight(FontWeight::from_number(450)));
         out.set(TextElem::set_font(FontList(vec![FontFamily::new(
@@ -194,8 +303,21 @@ impl ShowSet for Packed<EquationElem> {
 
 impl Count for Packed<EquationElem> {
     fn update(&self) -> Option<CounterUpdate> {
-        (self.block(StyleChain::default()) && self.numbering().is_some())
-            .then(|| CounterUpdate::Step(NonZeroUsize::ONE))
+        let styles = StyleChain::default();
+        if !self.block(styles) || (**self).numbering(styles).is_none() {
+            return None;
+        }
+
+        // An equation whose lines are numbered separately shares its number
+        // with its first numbered line, so it only steps the counter if it
+        // has such a line.
+        if self.line_numbering(styles) == Some(LineNumbering::Each)
+            && !has_numbered_line(&self.body)
+        {
+            return None;
+        }
+
+        Some(CounterUpdate::Step(NonZeroUsize::ONE))
     }
 }
 
@@ -255,6 +377,164 @@ impl Outlinable for Packed<EquationElem> {
     }
 }
 
+/// How the lines of a multi-line block equation are numbered.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Cast)]
+pub enum LineNumbering {
+    /// Each line receives its own number.
+    Each,
+    /// The equation receives a single number and each line a sub-number.
+    Sub,
+}
+
+/// Controls the number of a line in a multi-line block equation.
+///
+/// Tags only have an effect in equations whose lines are numbered separately
+/// (see the equation's [`line-numbering`]($math.equation.line-numbering)
+/// property). In such equations, lines without a tag are numbered
+/// automatically. A tag can suppress the number of its line, replace it with
+/// custom content, or label the line so that it can be referenced.
+///
+/// # Example
+/// ```example
+/// #set math.equation(
+///   numbering: "(1)",
+///   line-numbering: "each",
+/// )
+///
+/// $ a &= b + c #math.tag(label: <first>) \
+///     &= c + b #math.tag(none) \
+///     &= d #math.tag[(\*)] $
+///
+/// See @first.
+/// ```
+#[elem(
+    name = "tag",
+    title = "Equation Tag",
+    Construct,
+    Locatable,
+    Synthesize,
+    Refable,
+    LayoutMath
+)]
+pub struct EquationTagElem {
+    /// How to number the line.
+    ///
+    /// - `{auto}`: The line is numbered by the equation counter.
+    /// - `{none}`: The line is not numbered.
+    /// - Content: The content is displayed instead of a number and the
+    ///   equation counter is not stepped.
+    ///
+    /// To attach a label to the line, pass it as the named `label` argument.
+    #[positional]
+    pub body: Smart<Option<Content>>,
+
+    /// The numbering of the line, derived from the equation's numbering.
+    #[internal]
+    #[synthesized]
+    pub numbering: Option<Numbering>,
+
+    /// The counter level stepped by the line, if any.
+    #[internal]
+    #[synthesized]
+    pub level: Option<NonZeroUsize>,
+
+    /// The supplement for references to the line.
+    #[internal]
+    #[synthesized]
+    pub supplement: Content,
+}
+
+impl Construct for EquationTagElem {
+    fn construct(_: &mut Engine, args: &mut Args) -> SourceResult<Content> {
+        let label = args.named::<Label>("label")?;
+        let mut elem = Self::new();
+        if let Some(body) = args.eat()? {
+            elem.push_body(body);
+        }
+
+        let mut content = elem.pack().spanned(args.span);
+        if let Some(label) = label {
+            content.set_label(label);
+        }
+        Ok(content)
+    }
+}
+
+impl Synthesize for Packed<EquationTagElem> {
+    fn synthesize(
+        &mut self,
+        engine: &mut Engine,
+        styles: StyleChain,
+    ) -> SourceResult<()> {
+        let numbering = EquationElem::numbering_in(styles).clone();
+        let level = match EquationElem::line_numbering_in(styles) {
+            _ if numbering.is_none() || !self.body(styles).is_auto() => None,
+            None => None,
+            Some(LineNumbering::Each) => Some(NonZeroUsize::ONE),
+            Some(LineNumbering::Sub) => NonZeroUsize::new(2),
+        };
+
+        let supplement = match EquationElem::supplement_in(styles) {
+            Smart::Auto => {
+                TextElem::packed(Packed::<EquationElem>::local_name_in(styles))
+            }
+            Smart::Custom(None) => Content::empty(),
+            Smart::Custom(Some(supplement)) => {
+                supplement.resolve(engine, styles, [self.clone().pack()])?
+            }
+        };
+
+        self.push_numbering(numbering);
+        self.push_level(level);
+        self.push_supplement(supplement);
+        Ok(())
+    }
+}
+
+impl Refable for Packed<EquationTagElem> {
+    fn supplement(&self) -> Content {
+        (**self).supplement().cloned().unwrap_or_default()
+    }
+
+    fn counter(&self) -> Counter {
+        Counter::of(EquationElem::elem())
+    }
+
+    fn numbering(&self) -> Option<&Numbering> {
+        // Only lines that step the counter have a number to refer to.
+        self.level().copied().flatten()?;
+        (**self).numbering().and_then(Option::as_ref)
+    }
+}
+
+impl LayoutMath for Packed<EquationTagElem> {
+    fn layout_math(&self, _: &mut MathContext, _: StyleChain) -> SourceResult<()> {
+        // The number is laid out with the equation.
+        Ok(())
+    }
+}
+
+/// Layout the body of an equation to derive its MathML representation from the
+/// resulting fragments.
+///
+/// The frames are discarded, so the layout only needs to be faithful enough to
+/// classify the fragments.
+fn layout_mathml(
+    elem: &Packed<EquationElem>,
+    engine: &mut Engine,
+    styles: StyleChain,
+) -> SourceResult<MathMl> {
+    let font = find_math_font(engine, styles, elem.span())?;
+
+    let locator = elem.location().map_or_else(Locator::root, Locator::synthesize);
+    let mut locator = locator.split();
+    let mut ctx =
+        MathContext::new(engine, &mut locator, styles, Size::splat(Abs::inf()), &font);
+    ctx.mathml = true;
+    let run = ctx.layout_into_run(&elem.body, styles)?;
+    Ok(mathml::math(mathml::run(run.iter()), elem.block(styles)))
+}
+
 /// Layout an inline equation (in a paragraph).
 #[typst_macros::time(span = elem.span())]
 fn layout_equation_inline(
@@ -317,10 +597,58 @@ fn layout_equation_block(
     let font = find_math_font(engine, styles, span)?;
 
     let mut locator = locator.split();
+
+    let tagged = match elem.line_numbering(styles) {
+        Some(mode) if (**elem).numbering(styles).is_some() => tag_lines(&elem.body, mode),
+        _ => None,
+    };
+    let untagged = tagged.is_none();
+
     let mut ctx = MathContext::new(engine, &mut locator, styles, regions.base(), &font);
-    let full_equation_builder = ctx
-        .layout_into_run(&elem.body, styles)?
-        .multiline_frame_builder(&ctx, styles);
+    let (run, line_numbers) = match tagged {
+        Some(body) => {
+            let run = ctx.layout_into_run(&body, styles)?;
+            let line_numbers = layout_line_numbers(&mut ctx, &run, styles)?;
+            (run, line_numbers)
+        }
+        None => (ctx.layout_into_run(&elem.body, styles)?, vec![]),
+    };
+
+    // Lay out the number before breaking lines, so that its width can be taken
+    // into account.
+    let number = match (**elem).numbering(styles) {
+        Some(numbering) if untagged => {
+            let pod = Region::new(regions.base(), Axes::splat(false));
+            let counter = Counter::of(EquationElem::elem())
+                .display_at_loc(ctx.engine, elem.location().unwrap(), styles, numbering)?
+                .spanned(span);
+            let locator = ctx.locator.next(&());
+            Some(layout_frame(ctx.engine, &counter, locator, styles, pod)?)
+        }
+        _ => None,
+    };
+
+    static NUMBER_GUTTER: Em = Em::new(0.5);
+    let full_number_width = number
+        .iter()
+        .chain(line_numbers.iter().map(|(_, number)| number))
+        .map(|number| number.width() + NUMBER_GUTTER.resolve(styles))
+        .max()
+        .unwrap_or_default();
+    let equation_align = AlignElem::alignment_in(styles).resolve(styles).x;
+
+    let full_equation_builder = if elem.linebreaking(styles) && regions.size.x.is_finite()
+    {
+        // A centered equation must leave space for the number on both sides.
+        let reserved = match equation_align {
+            FixedAlignment::Center => 2.0 * full_number_width,
+            _ => full_number_width,
+        };
+        let width = regions.size.x - reserved;
+        run.breaking_frame_builder(&ctx, styles, width, elem.hanging_indent(styles))
+    } else {
+        run.multiline_frame_builder(&ctx, styles)
+    };
     let width = full_equation_builder.size.x;
     let can_break =
         BlockElem::breakable_in(styles) && full_equation_builder.frames.len() > 1;
@@ -383,27 +711,35 @@ fn layout_equation_block(
         vec![full_equation_builder]
     };
 
-    let Some(numbering) = (**elem).numbering(styles) else {
+    let number_align = match elem.number_align(styles) {
+        SpecificAlignment::H(h) => SpecificAlignment::Both(h, VAlignment::Horizon),
+        SpecificAlignment::V(v) => SpecificAlignment::Both(OuterHAlignment::End, v),
+        SpecificAlignment::Both(h, v) => SpecificAlignment::Both(h, v),
+    };
+
+    if !line_numbers.is_empty() {
         let frames = equation_builders
             .into_iter()
-            .map(MathRunFrameBuilder::build)
+            .map(|builder| {
+                add_line_numbers(
+                    builder,
+                    &line_numbers,
+                    number_align.resolve(styles),
+                    equation_align,
+                    regions.size.x,
+                    full_number_width,
+                )
+            })
             .collect();
         return Ok(Fragment::frames(frames));
-    };
-
-    let pod = Region::new(regions.base(), Axes::splat(false));
-    let counter = Counter::of(EquationElem::elem())
-        .display_at_loc(engine, elem.location().unwrap(), styles, numbering)?
-        .spanned(span);
-    let number = layout_frame(engine, &counter, locator.next(&()), styles, pod)?;
-
-    static NUMBER_GUTTER: Em = Em::new(0.5);
-    let full_number_width = number.width() + NUMBER_GUTTER.resolve(styles);
+    }
 
-    let number_align = match elem.number_align(styles) {
-        SpecificAlignment::H(h) => SpecificAlignment::Both(h, VAlignment::Horizon),
-        SpecificAlignment::V(v) => SpecificAlignment::Both(OuterHAlignment::End, v),
-        SpecificAlignment::Both(h, v) => SpecificAlignment::Both(h, v),
+    let Some(number) = number else {
+        let frames = equation_builders
+            .into_iter()
+            .map(MathRunFrameBuilder::build)
+            .collect();
+        return Ok(Fragment::frames(frames));
     };
 
     // Add equation numbers to each equation region.
@@ -419,7 +755,7 @@ fn layout_equation_block(
                 builder,
                 number.clone(),
                 number_align.resolve(styles),
-                AlignElem::alignment_in(styles).resolve(styles).x,
+                equation_align,
                 regions.size.x,
                 full_number_width,
             )
@@ -558,3 +894,174 @@ fn resize_equation(
     equation.translate(Point::with_y(excess_above));
     resizing_offset + Point::with_y(excess_above)
 }
+
+/// Adds an automatic tag to each line of the equation's body that doesn't
+/// have one yet.
+///
+/// Returns `None` if the equation consists of a single line that should be
+/// numbered as a whole.
+fn tag_lines(body: &Content, mode: LineNumbering) -> Option<Content> {
+    let children = match body.to_packed::<SequenceElem>() {
+        Some(sequence) => sequence.children.clone(),
+        None => vec![body.clone()],
+    };
+
+    // A single line doesn't need a sub-number.
+    if mode == LineNumbering::Sub
+        && !children.iter().any(|child| child.is::<LinebreakElem>())
+    {
+        return None;
+    }
+
+    // Numbered lines step the equation counter right before their tag. With
+    // separately numbered lines, the first one uses the equation's own step.
+    let level = match mode {
+        LineNumbering::Each => NonZeroUsize::ONE,
+        LineNumbering::Sub => NonZeroUsize::new(2).unwrap(),
+    };
+    let mut first = mode == LineNumbering::Each;
+    let mut step = |out: &mut Vec<Content>| {
+        if !std::mem::take(&mut first) {
+            out.push(
+                Counter::of(EquationElem::elem())
+                    .update(body.span(), CounterUpdate::Step(level)),
+            );
+        }
+    };
+
+    let tag = || EquationTagElem::new().pack().spanned(body.span());
+    let mut out = Vec::with_capacity(children.len() + 1);
+    let mut tagged = false;
+    let mut empty = true;
+    for child in children {
+        if child.is::<LinebreakElem>() {
+            if !tagged {
+                step(&mut out);
+                out.push(tag());
+            }
+            tagged = false;
+            empty = true;
+        } else if let Some(elem) = child.to_packed::<EquationTagElem>() {
+            if elem.body(StyleChain::default()).is_auto() {
+                step(&mut out);
+            }
+            tagged = true;
+        } else if !child.is::<SpaceElem>() {
+            empty = false;
+        }
+        out.push(child);
+    }
+
+    // A trailing linebreak doesn't start another line.
+    if !tagged && !empty {
+        step(&mut out);
+        out.push(tag());
+    }
+
+    Some(Content::sequence(out))
+}
+
+/// Whether any line of the equation body is numbered automatically when its
+/// lines are numbered separately.
+fn has_numbered_line(body: &Content) -> bool {
+    let Some(tagged) = tag_lines(body, LineNumbering::Each) else { return false };
+    let Some(sequence) = tagged.to_packed::<SequenceElem>() else { return false };
+    sequence.children.iter().any(|child| {
+        child
+            .to_packed::<EquationTagElem>()
+            .is_some_and(|elem| elem.body(StyleChain::default()).is_auto())
+    })
+}
+
+/// Lays out the numbers of the tagged lines in the run.
+fn layout_line_numbers(
+    ctx: &mut MathContext,
+    run: &MathRun,
+    styles: StyleChain,
+) -> SourceResult<Vec<(Location, Frame)>> {
+    let mut numbers = vec![];
+    for fragment in run.iter() {
+        let MathFragment::Tag(tag) = fragment else { continue };
+        if tag.kind() != TagKind::Start {
+            continue;
+        }
+        let Some(elem) = tag.elem().to_packed::<EquationTagElem>() else { continue };
+
+        let content = match elem.body(StyleChain::default()) {
+            Smart::Auto => match elem.numbering() {
+                Some(numbering) => Counter::of(EquationElem::elem())
+                    .display_at_loc(ctx.engine, tag.location(), styles, numbering)?
+                    .spanned(elem.span()),
+                None => continue,
+            },
+            Smart::Custom(Some(body)) => body,
+            Smart::Custom(None) => continue,
+        };
+
+        let pod = Region::new(ctx.region.size, Axes::splat(false));
+        let number =
+            layout_frame(ctx.engine, &content, ctx.locator.next(&()), styles, pod)?;
+        numbers.push((tag.location(), number));
+    }
+    Ok(numbers)
+}
+
+/// Adds the numbers of the tagged lines to the equation.
+fn add_line_numbers(
+    equation_builder: MathRunFrameBuilder,
+    numbers: &[(Location, Frame)],
+    number_align: Axes<FixedAlignment>,
+    equation_align: FixedAlignment,
+    region_size_x: Abs,
+    full_number_width: Abs,
+) -> Frame {
+    let mut lines = vec![];
+    for (frame, pos) in &equation_builder.frames {
+        let Some(location) = find_line_tag(frame) else { continue };
+        if let Some((_, number)) = numbers.iter().find(|(loc, _)| *loc == location) {
+            lines.push((pos.y + frame.baseline(), number.clone()));
+        }
+    }
+
+    let mut equation = equation_builder.build();
+    let width = if region_size_x.is_finite() {
+        region_size_x
+    } else {
+        equation.width() + 2.0 * full_number_width
+    };
+
+    equation.resize(
+        Size::new(width, equation.height()),
+        Axes::<FixedAlignment>::new(equation_align, FixedAlignment::Start),
+    );
+    equation.translate(Point::with_x(match (equation_align, number_align.x) {
+        (FixedAlignment::Start, FixedAlignment::Start) => full_number_width,
+        (FixedAlignment::End, FixedAlignment::End) => -full_number_width,
+        _ => Abs::zero(),
+    }));
+
+    for (baseline, number) in lines {
+        let x = match number_align.x {
+            FixedAlignment::Start => Abs::zero(),
+            FixedAlignment::End => equation.width() - number.width(),
+            _ => unreachable!(),
+        };
+        let y = baseline - number.baseline();
+        equation.push_frame(Point::new(x, y), number);
+    }
+
+    equation
+}
+
+/// Finds the location of the tag within a line of an equation.
+fn find_line_tag(frame: &Frame) -> Option<Location> {
+    frame.items().find_map(|(_, item)| match item {
+        FrameItem::Tag(tag)
+            if tag.kind() == TagKind::Start && tag.elem().is::<EquationTagElem>() =>
+        {
+            Some(tag.location())
+        }
+        FrameItem::Group(group) => find_line_tag(&group.frame),
+        _ => None,
+    })
+}
@@ -2,7 +2,7 @@ use crate::diag::{bail, SourceResult};
 use crate::foundations::{elem, Content, Packed, StyleChain, Value};
 use crate::layout::{Em, Frame, FrameItem, Point, Size};
 use crate::math::{
-    scaled_font_size, style_for_denominator, style_for_numerator, FrameFragment,
+    mathml, scaled_font_size, style_for_denominator, style_for_numerator, FrameFragment,
     GlyphFragment, LayoutMath, MathContext, Scaled, DELIM_SHORT_FALL,
 };
 use crate::syntax::{Span, Spanned};
@@ -118,16 +118,22 @@ fn layout(
     );
 
     let num_style = style_for_numerator(styles);
-    let num = ctx.layout_into_frame(num, styles.chain(&num_style))?;
+    let num = ctx.layout_into_fragment(num, styles.chain(&num_style))?;
+    let num_mathml = ctx.mathml_of(&num);
+    let num = num.into_frame();
 
     let denom_style = style_for_denominator(styles);
-    let denom = ctx.layout_into_frame(
+    let denom = ctx.layout_into_fragment(
         &Content::sequence(
             // Add a comma between each element.
             denom.iter().flat_map(|a| [TextElem::packed(','), a.clone()]).skip(1),
         ),
         styles.chain(&denom_style),
     )?;
+    let denom_mathml = ctx.mathml_of(&denom);
+    let denom = denom.into_frame();
+
+    let mathml = mathml::node("mfrac", [num_mathml, denom_mathml]);
 
     let around = FRAC_AROUND.at(font_size);
     let num_gap = (shift_up - axis - num.descent()).max(num_min + thickness / 2.0);
@@ -153,7 +159,10 @@ fn layout(
             .stretch_vertical(ctx, height, short_fall);
         left.center_on_axis(ctx);
         ctx.push(left);
-        ctx.push(FrameFragment::new(ctx, styles, frame));
+        ctx.push(
+            FrameFragment::new(ctx, styles, frame)
+                .with_mathml(mathml.map(|node| node.with_attr("linethickness", "0"))),
+        );
         let mut right = GlyphFragment::new(ctx, styles, ')', span)
             .stretch_vertical(ctx, height, short_fall);
         right.center_on_axis(ctx);
@@ -171,7 +180,7 @@ fn layout(
                 span,
             ),
         );
-        ctx.push(FrameFragment::new(ctx, styles, frame));
+        ctx.push(FrameFragment::new(ctx, styles, frame).with_mathml(mathml));
     }
 
     Ok(())
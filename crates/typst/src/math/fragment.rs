@@ -11,7 +11,7 @@ use crate::layout::{
     Abs, Corner, Em, Frame, FrameItem, HideElem, Point, Size, VAlignment,
 };
 use crate::math::{
-    scaled_font_size, EquationElem, Limits, MathContext, MathSize, Scaled,
+    scaled_font_size, EquationElem, Limits, MathContext, MathMl, MathSize, Scaled,
 };
 use crate::model::{Destination, LinkElem};
 use crate::syntax::Span;
@@ -464,6 +464,7 @@ pub struct FrameFragment {
     pub accent_attach: Abs,
     pub text_like: bool,
     pub ignorant: bool,
+    pub mathml: Option<MathMl>,
 }
 
 impl FrameFragment {
@@ -482,6 +483,7 @@ impl FrameFragment {
             accent_attach,
             text_like: false,
             ignorant: false,
+            mathml: None,
         }
     }
 
@@ -516,6 +518,10 @@ impl FrameFragment {
     pub fn with_ignorant(self, ignorant: bool) -> Self {
         Self { ignorant, ..self }
     }
+
+    pub fn with_mathml(self, mathml: Option<MathMl>) -> Self {
+        Self { mathml, ..self }
+    }
 }
 
 /// Look up the italics correction for a glyph.
@@ -0,0 +1,344 @@
+//! Conversion of laid-out equations to MathML presentation markup.
+//!
+//! The MathML representation is derived from the [`MathFragment`]s that are
+//! produced by math layout. Glyphs are classified with the same math classes
+//! that are used for spacing, while frames that are composed by the layout of
+//! a math element carry the MathML of that element. Because this has a cost,
+//! fragments only carry MathML if the [`MathContext`] was asked to record it.
+//!
+//! [`MathContext`]: super::MathContext
+
+use std::fmt::{self, Display, Formatter, Write};
+
+use ecow::{eco_format, EcoString};
+use unicode_math_class::MathClass;
+
+use crate::math::MathFragment;
+
+/// The MathML namespace.
+const NAMESPACE: &str = "http://www.w3.org/1998/Math/MathML";
+
+/// An element of MathML presentation markup.
+#[derive(Debug, Clone, PartialEq, Hash)]
+pub struct MathMl {
+    /// The element's name, e.g. `mrow`.
+    pub name: &'static str,
+    /// The element's attributes.
+    pub attrs: Vec<(&'static str, EcoString)>,
+    /// The element's contents.
+    pub body: MathMlBody,
+}
+
+/// The contents of a MathML element.
+#[derive(Debug, Clone, PartialEq, Hash)]
+pub enum MathMlBody {
+    /// The text of a token element like `<mi>`.
+    Text(EcoString),
+    /// The children of a layout element like `<mrow>`.
+    Children(Vec<MathMl>),
+}
+
+impl MathMl {
+    /// Create a token element with text.
+    pub fn token(name: &'static str, text: impl Into<EcoString>) -> Self {
+        Self {
+            name,
+            attrs: vec![],
+            body: MathMlBody::Text(text.into()),
+        }
+    }
+
+    /// Create a layout element with children.
+    pub fn node(name: &'static str, children: Vec<Self>) -> Self {
+        Self {
+            name,
+            attrs: vec![],
+            body: MathMlBody::Children(children),
+        }
+    }
+
+    /// Add an attribute to the element.
+    pub fn with_attr(mut self, name: &'static str, value: impl Into<EcoString>) -> Self {
+        self.attrs.push((name, value.into()));
+        self
+    }
+
+    /// Renders the element as linear plain text in the style of Typst's math
+    /// syntax, e.g. `(a + b)/2`.
+    ///
+    /// This is suitable as an alternative description of an equation.
+    pub fn plain_text(&self) -> EcoString {
+        let mut text = EcoString::new();
+        self.write_plain_text(&mut text);
+        text
+    }
+
+    fn write_plain_text(&self, out: &mut EcoString) {
+        let children = match &self.body {
+            MathMlBody::Text(text) => {
+                out.push_str(text);
+                return;
+            }
+            MathMlBody::Children(children) => children,
+        };
+
+        let script = |out: &mut EcoString, op: &str, child: &MathMl| {
+            out.push_str(op);
+            child.write_grouped(out);
+        };
+
+        match (self.name, children.as_slice()) {
+            ("mfrac", [num, denom]) => {
+                num.write_grouped(out);
+                out.push('/');
+                denom.write_grouped(out);
+            }
+            ("msqrt", [radicand]) => {
+                out.push_str("sqrt(");
+                radicand.write_plain_text(out);
+                out.push(')');
+            }
+            ("mroot", [radicand, index]) => {
+                out.push_str("root(");
+                index.write_plain_text(out);
+                out.push_str(", ");
+                radicand.write_plain_text(out);
+                out.push(')');
+            }
+            ("msub" | "munder", [base, sub]) => {
+                base.write_plain_text(out);
+                script(out, "_", sub);
+            }
+            ("msup" | "mover", [base, sup]) => {
+                base.write_plain_text(out);
+                script(out, "^", sup);
+            }
+            ("msubsup" | "munderover", [base, sub, sup]) => {
+                base.write_plain_text(out);
+                script(out, "_", sub);
+                script(out, "^", sup);
+            }
+            ("mtable", rows) => {
+                for (i, row) in rows.iter().enumerate() {
+                    if i > 0 {
+                        out.push_str("; ");
+                    }
+                    row.write_plain_text(out);
+                }
+            }
+            (_, children) => {
+                for (i, child) in children.iter().enumerate() {
+                    if i > 0 {
+                        out.push(' ');
+                    }
+                    child.write_plain_text(out);
+                }
+            }
+        }
+    }
+
+    /// Writes the plain text, wrapped in parentheses unless it is a single
+    /// token.
+    fn write_grouped(&self, out: &mut EcoString) {
+        if matches!(self.body, MathMlBody::Text(_)) {
+            self.write_plain_text(out);
+        } else {
+            out.push('(');
+            self.write_plain_text(out);
+            out.push(')');
+        }
+    }
+}
+
+impl Display for MathMl {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "<{}", self.name)?;
+        for (name, value) in &self.attrs {
+            write!(f, " {name}=\"")?;
+            escape(f, value)?;
+            f.write_char('"')?;
+        }
+        f.write_char('>')?;
+        match &self.body {
+            MathMlBody::Text(text) => escape(f, text)?,
+            MathMlBody::Children(children) => {
+                for child in children {
+                    child.fmt(f)?;
+                }
+            }
+        }
+        write!(f, "</{}>", self.name)
+    }
+}
+
+/// Write text with XML special characters escaped.
+fn escape(f: &mut Formatter, text: &str) -> fmt::Result {
+    for c in text.chars() {
+        match c {
+            '<' => f.write_str("&lt;")?,
+            '>' => f.write_str("&gt;")?,
+            '&' => f.write_str("&amp;")?,
+            '"' => f.write_str("&quot;")?,
+            c => f.write_char(c)?,
+        }
+    }
+    Ok(())
+}
+
+/// Wraps the MathML of an equation's body into a `<math>` element.
+pub fn math(body: MathMl, block: bool) -> MathMl {
+    let display = if block { "block" } else { "inline" };
+    MathMl::node("math", vec![body])
+        .with_attr("xmlns", NAMESPACE)
+        .with_attr("display", display)
+}
+
+/// Creates a layout element from the MathML of its children.
+///
+/// Returns `None` if any child's MathML was not recorded.
+pub fn node(
+    name: &'static str,
+    children: impl IntoIterator<Item = Option<MathMl>>,
+) -> Option<MathMl> {
+    let children = children.into_iter().collect::<Option<Vec<_>>>()?;
+    Some(MathMl::node(name, children))
+}
+
+/// Converts a single fragment.
+pub fn fragment(fragment: &MathFragment) -> MathMl {
+    run(std::slice::from_ref(fragment))
+}
+
+/// Converts a run of fragments into a single element, turning multiple lines
+/// into a table whose cells are delimited by the alignment points.
+pub fn run<'a>(fragments: impl IntoIterator<Item = &'a MathFragment>) -> MathMl {
+    let mut lines = lines(fragments);
+    if lines.len() == 1 {
+        row(lines.pop().unwrap())
+    } else {
+        table(lines)
+    }
+}
+
+/// Splits a run of fragments into lines at linebreaks and converts the lines
+/// into cells delimited by the alignment points.
+pub fn lines<'a>(
+    fragments: impl IntoIterator<Item = &'a MathFragment>,
+) -> Vec<Vec<MathMl>> {
+    let mut lines = vec![];
+    let mut cells = vec![];
+    let mut cell = vec![];
+    for fragment in fragments {
+        match fragment {
+            MathFragment::Glyph(glyph) => cell.push(char(glyph.c, glyph.class)),
+            MathFragment::Variant(variant) => cell.push(char(variant.c, variant.class)),
+            MathFragment::Frame(fragment) => cell.extend(fragment.mathml.clone()),
+            MathFragment::Align => cells.push(row(std::mem::take(&mut cell))),
+            MathFragment::Linebreak => {
+                cells.push(row(std::mem::take(&mut cell)));
+                lines.push(std::mem::take(&mut cells));
+            }
+            MathFragment::Spacing(..) | MathFragment::Space(_) | MathFragment::Tag(_) => {
+            }
+        }
+    }
+
+    // A trailing linebreak does not start another line.
+    if lines.is_empty() || !cell.is_empty() || !cells.is_empty() {
+        cells.push(row(cell));
+        lines.push(cells);
+    }
+
+    lines
+}
+
+/// Converts rows of cells into a table.
+pub fn table(rows: Vec<Vec<MathMl>>) -> MathMl {
+    MathMl::node(
+        "mtable",
+        rows.into_iter()
+            .map(|cells| {
+                MathMl::node(
+                    "mtr",
+                    cells
+                        .into_iter()
+                        .map(|cell| MathMl::node("mtd", vec![cell]))
+                        .collect(),
+                )
+            })
+            .collect(),
+    )
+}
+
+/// Converts text into a token element, classifying it like layout does:
+/// Numbers become `<mn>`, single characters `<mi>` or `<mo>` depending on
+/// their math class, and everything else `<mtext>`.
+pub fn text(text: &str) -> MathMl {
+    let mut chars = text.chars();
+    match (chars.next(), chars.next()) {
+        (Some(c), _) if c.is_ascii_digit() && is_number(text) => {
+            MathMl::token("mn", text)
+        }
+        (Some(c), None) => {
+            char(c, unicode_math_class::class(c).unwrap_or(MathClass::Normal))
+        }
+        _ => MathMl::token("mtext", text),
+    }
+}
+
+/// Converts a character of the given math class into a token element.
+pub fn char(c: char, class: MathClass) -> MathMl {
+    let name = match class {
+        _ if c.is_ascii_digit() => "mn",
+        MathClass::Normal | MathClass::Alphabetic | MathClass::Space => "mi",
+        _ => "mo",
+    };
+    MathMl::token(name, eco_format!("{c}"))
+}
+
+/// Whether the text is a decimal number.
+fn is_number(text: &str) -> bool {
+    text.chars().all(|c| c.is_ascii_digit() || c == '.')
+}
+
+/// Wraps multiple elements into an `<mrow>`.
+fn row(mut nodes: Vec<MathMl>) -> MathMl {
+    if nodes.len() == 1 {
+        nodes.pop().unwrap()
+    } else {
+        MathMl::node("mrow", nodes)
+    }
+}
+
+/// Attaches scripts and limits to a base. The attachments are given in the
+/// order top-left, top, top-right, bottom-left, bottom, bottom-right.
+pub fn attach(base: MathMl, [tl, t, tr, bl, b, br]: [Option<MathMl>; 6]) -> MathMl {
+    let base = match (b, t) {
+        (Some(b), Some(t)) => MathMl::node("munderover", vec![base, b, t]),
+        (Some(b), None) => MathMl::node("munder", vec![base, b]),
+        (None, Some(t)) => MathMl::node("mover", vec![base, t]),
+        (None, None) => base,
+    };
+
+    if tl.is_some() || bl.is_some() {
+        let none = || MathMl::node("none", vec![]);
+        return MathMl::node(
+            "mmultiscripts",
+            vec![
+                base,
+                br.unwrap_or_else(none),
+                tr.unwrap_or_else(none),
+                MathMl::node("mprescripts", vec![]),
+                bl.unwrap_or_else(none),
+                tl.unwrap_or_else(none),
+            ],
+        );
+    }
+
+    match (br, tr) {
+        (Some(br), Some(tr)) => MathMl::node("msubsup", vec![base, br, tr]),
+        (Some(br), None) => MathMl::node("msub", vec![base, br]),
+        (None, Some(tr)) => MathMl::node("msup", vec![base, tr]),
+        (None, None) => base,
+    }
+}
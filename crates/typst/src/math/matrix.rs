@@ -3,17 +3,17 @@ use unicode_math_class::MathClass;
 
 use crate::diag::{bail, At, HintedStrResult, SourceResult, StrResult};
 use crate::foundations::{
-    array, cast, dict, elem, Array, Content, Dict, Fold, NoneValue, Packed, Resolve,
-    Smart, StyleChain, Value,
+    array, cast, dict, elem, Args, Array, Content, Dict, Fold, NoneValue, Packed,
+    Resolve, Smart, StyleChain, Value,
 };
 use crate::layout::{
     Abs, Axes, Em, FixedAlignment, Frame, FrameItem, HAlignment, Length, Point, Ratio,
     Rel, Size,
 };
 use crate::math::{
-    alignments, delimiter_alignment, scaled_font_size, stack, style_for_denominator,
-    AlignmentResult, FrameFragment, GlyphFragment, LayoutMath, LeftRightAlternator,
-    MathContext, Scaled, DELIM_SHORT_FALL,
+    alignments, delimiter_alignment, mathml, scaled_font_size, stack,
+    style_for_denominator, AlignmentResult, FrameFragment, GlyphFragment, LayoutMath,
+    LeftRightAlternator, MathContext, MathMl, Scaled, DELIM_SHORT_FALL,
 };
 use crate::symbols::Symbol;
 use crate::syntax::{Span, Spanned};
@@ -24,7 +24,7 @@ use crate::visualize::{FillRule, FixedStroke, Geometry, LineCap, Shape, Stroke};
 const DEFAULT_ROW_GAP: Em = Em::new(0.2);
 const DEFAULT_COL_GAP: Em = Em::new(0.5);
 const VERTICAL_PADDING: Ratio = Ratio::new(0.1);
-const DEFAULT_STROKE_THICKNESS: Em = Em::new(0.05);
+pub(super) const DEFAULT_STROKE_THICKNESS: Em = Em::new(0.05);
 
 /// A column vector.
 ///
@@ -76,7 +76,7 @@ impl LayoutMath for Packed<VecElem> {
     #[typst_macros::time(name = "math.vec", span = self.span())]
     fn layout_math(&self, ctx: &mut MathContext, styles: StyleChain) -> SourceResult<()> {
         let delim = self.delim(styles);
-        let frame = layout_vec_body(
+        let (frame, mathml) = layout_vec_body(
             ctx,
             styles,
             self.children(),
@@ -85,7 +85,15 @@ impl LayoutMath for Packed<VecElem> {
             LeftRightAlternator::Right,
         )?;
 
-        layout_delimiters(ctx, styles, frame, delim.open(), delim.close(), self.span())
+        layout_delimiters(
+            ctx,
+            styles,
+            frame,
+            mathml,
+            delim.open(),
+            delim.close(),
+            self.span(),
+        )
     }
 }
 
@@ -207,30 +215,7 @@ pub struct MatElem {
     /// $ v := matrix $
     /// ```
     #[variadic]
-    #[parse(
-        let mut rows = vec![];
-        let mut width = 0;
-
-        let values = args.all::<Spanned<Value>>()?;
-        if values.iter().any(|spanned| matches!(spanned.v, Value::Array(_))) {
-            for Spanned { v, span } in values {
-                let array = v.cast::<Array>().at(span)?;
-                let row: Vec<_> = array.into_iter().map(Value::display).collect();
-                width = width.max(row.len());
-                rows.push(row);
-            }
-        } else {
-            rows = vec![values.into_iter().map(|spanned| spanned.v.display()).collect()];
-        }
-
-        for row in &mut rows {
-            if row.len() < width {
-                row.resize(width, Content::empty());
-            }
-        }
-
-        rows
-    )]
+    #[parse(parse_rows(args)?)]
     pub rows: Vec<Vec<Content>>,
 }
 
@@ -267,7 +252,7 @@ impl LayoutMath for Packed<MatElem> {
         }
 
         let delim = self.delim(styles);
-        let frame = layout_mat_body(
+        let (frame, mathml) = layout_mat_body(
             ctx,
             styles,
             rows,
@@ -275,12 +260,47 @@ impl LayoutMath for Packed<MatElem> {
             augment,
             Axes::new(self.column_gap(styles), self.row_gap(styles)),
             self.span(),
+            None,
         )?;
 
-        layout_delimiters(ctx, styles, frame, delim.open(), delim.close(), self.span())
+        layout_delimiters(
+            ctx,
+            styles,
+            frame,
+            mathml,
+            delim.open(),
+            delim.close(),
+            self.span(),
+        )
     }
 }
 
+/// Parse the rows of a matrix-like element from its arguments.
+pub(super) fn parse_rows(args: &mut Args) -> SourceResult<Vec<Vec<Content>>> {
+    let mut rows = vec![];
+    let mut width = 0;
+
+    let values = args.all::<Spanned<Value>>()?;
+    if values.iter().any(|spanned| matches!(spanned.v, Value::Array(_))) {
+        for Spanned { v, span } in values {
+            let array = v.cast::<Array>().at(span)?;
+            let row: Vec<_> = array.into_iter().map(Value::display).collect();
+            width = width.max(row.len());
+            rows.push(row);
+        }
+    } else {
+        rows = vec![values.into_iter().map(|spanned| spanned.v.display()).collect()];
+    }
+
+    for row in &mut rows {
+        if row.len() < width {
+            row.resize(width, Content::empty());
+        }
+    }
+
+    Ok(rows)
+}
+
 /// A case distinction.
 ///
 /// Content across different branches can be aligned with the `&` symbol.
@@ -333,7 +353,7 @@ impl LayoutMath for Packed<CasesElem> {
     #[typst_macros::time(name = "math.cases", span = self.span())]
     fn layout_math(&self, ctx: &mut MathContext, styles: StyleChain) -> SourceResult<()> {
         let delim = self.delim(styles);
-        let frame = layout_vec_body(
+        let (frame, mathml) = layout_vec_body(
             ctx,
             styles,
             self.children(),
@@ -348,7 +368,7 @@ impl LayoutMath for Packed<CasesElem> {
             (delim.open(), None)
         };
 
-        layout_delimiters(ctx, styles, frame, open, close, self.span())
+        layout_delimiters(ctx, styles, frame, mathml, open, close, self.span())
     }
 }
 
@@ -428,6 +448,7 @@ cast! {
 }
 
 impl DelimiterPair {
+    pub(super) const NONE: Self = Self { open: Delimiter(None), close: Delimiter(None) };
     const PAREN: Self = Self {
         open: Delimiter(Some('(')),
         close: Delimiter(Some(')')),
@@ -438,17 +459,18 @@ impl DelimiterPair {
     };
 
     /// The delimiter's opening character.
-    fn open(self) -> Option<char> {
+    pub(super) fn open(self) -> Option<char> {
         self.open.get()
     }
 
     /// The delimiter's closing character.
-    fn close(self) -> Option<char> {
+    pub(super) fn close(self) -> Option<char> {
         self.close.get()
     }
 }
 
-/// Layout the inner contents of a vector.
+/// Layout the inner contents of a vector, along with its MathML
+/// representation if it is recorded.
 fn layout_vec_body(
     ctx: &mut MathContext,
     styles: StyleChain,
@@ -456,7 +478,7 @@ fn layout_vec_body(
     align: FixedAlignment,
     row_gap: Rel<Abs>,
     alternator: LeftRightAlternator,
-) -> SourceResult<Frame> {
+) -> SourceResult<(Frame, Option<MathMl>)> {
     let gap = row_gap.relative_to(ctx.region.size.y);
 
     let denom_style = style_for_denominator(styles);
@@ -464,16 +486,34 @@ fn layout_vec_body(
     for child in column {
         flat.push(ctx.layout_into_run(child, styles.chain(&denom_style))?);
     }
+
+    // Each element becomes a table row, whose cells are delimited by the
+    // alignment points.
+    let mathml = ctx.mathml.then(|| {
+        mathml::table(
+            flat.iter()
+                .map(|run| mathml::lines(run.iter()).into_iter().flatten().collect())
+                .collect(),
+        )
+    });
+
     // We pad ascent and descent with the ascent and descent of the paren
     // to ensure that normal vectors are aligned with others unless they are
     // way too big.
     let paren =
         GlyphFragment::new(ctx, styles.chain(&denom_style), '(', Span::detached());
-    Ok(stack(flat, align, gap, 0, alternator, Some((paren.ascent, paren.descent))))
+    let frame =
+        stack(flat, align, gap, 0, alternator, Some((paren.ascent, paren.descent)));
+    Ok((frame, mathml))
 }
 
-/// Layout the inner contents of a matrix.
-fn layout_mat_body(
+/// Layout the inner contents of a matrix, along with its MathML representation
+/// if it is recorded.
+///
+/// If `cells` is given, it is filled with the position and size of each laid
+/// out cell, indexed by row and then column.
+#[allow(clippy::too_many_arguments)]
+pub(super) fn layout_mat_body(
     ctx: &mut MathContext,
     styles: StyleChain,
     rows: &[Vec<Content>],
@@ -481,11 +521,13 @@ fn layout_mat_body(
     augment: Option<Augment<Abs>>,
     gap: Axes<Rel<Abs>>,
     span: Span,
-) -> SourceResult<Frame> {
+    mut cells: Option<&mut Vec<Vec<(Point, Size)>>>,
+) -> SourceResult<(Frame, Option<MathMl>)> {
     let ncols = rows.first().map_or(0, |row| row.len());
     let nrows = rows.len();
     if ncols == 0 || nrows == 0 {
-        return Ok(Frame::soft(Size::zero()));
+        let mathml = ctx.mathml.then(|| mathml::table(vec![]));
+        return Ok((Frame::soft(Size::zero()), mathml));
     }
 
     let gap = gap.zip_map(ctx.region.size, Rel::relative_to);
@@ -533,9 +575,17 @@ fn layout_mat_body(
     let paren =
         GlyphFragment::new(ctx, styles.chain(&denom_style), '(', Span::detached());
 
+    let mut mathml = ctx.mathml.then(Vec::new);
     for (row, (ascent, descent)) in rows.iter().zip(&mut heights) {
+        if let Some(mathml) = &mut mathml {
+            mathml.push(vec![]);
+        }
+
         for (cell, col) in row.iter().zip(&mut cols) {
             let cell = ctx.layout_into_run(cell, styles.chain(&denom_style))?;
+            if let Some(row) = mathml.as_mut().and_then(|rows| rows.last_mut()) {
+                row.push(mathml::run(cell.iter()));
+            }
 
             ascent.set_max(cell.ascent().max(paren.ascent));
             descent.set_max(cell.descent().max(paren.descent));
@@ -552,6 +602,10 @@ fn layout_mat_body(
     // Width starts at zero because it can't be calculated until later
     let mut frame = Frame::soft(Size::new(Abs::zero(), total_height));
 
+    if let Some(cells) = cells.as_deref_mut() {
+        *cells = vec![vec![(Point::zero(), Size::zero()); ncols]; nrows];
+    }
+
     let mut x = Abs::zero();
 
     for (index, col) in cols.into_iter().enumerate() {
@@ -559,7 +613,8 @@ fn layout_mat_body(
 
         let mut y = Abs::zero();
 
-        for (cell, &(ascent, descent)) in col.into_iter().zip(&heights) {
+        for (row, (cell, &(ascent, descent))) in col.into_iter().zip(&heights).enumerate()
+        {
             let cell = cell.into_line_frame(&points, LeftRightAlternator::Right);
             let pos = Point::new(
                 if points.is_empty() {
@@ -570,6 +625,10 @@ fn layout_mat_body(
                 y + ascent - cell.ascent(),
             );
 
+            if let Some(cells) = cells.as_deref_mut() {
+                cells[row][index] = (pos, cell.size());
+            }
+
             frame.push_frame(pos, cell);
 
             y += ascent + descent + gap.y;
@@ -611,7 +670,7 @@ fn layout_mat_body(
 
     frame.size_mut().x = total_width;
 
-    Ok(frame)
+    Ok((frame, mathml.map(mathml::table)))
 }
 
 fn line_item(length: Abs, vertical: bool, stroke: FixedStroke, span: Span) -> FrameItem {
@@ -633,10 +692,11 @@ fn line_item(length: Abs, vertical: bool, stroke: FixedStroke, span: Span) -> Fr
 }
 
 /// Layout the outer wrapper around the body of a vector or matrix.
-fn layout_delimiters(
+pub(super) fn layout_delimiters(
     ctx: &mut MathContext,
     styles: StyleChain,
     mut frame: Frame,
+    mathml: Option<MathMl>,
     left: Option<char>,
     right: Option<char>,
     span: Span,
@@ -655,7 +715,7 @@ fn layout_delimiters(
         ctx.push(left);
     }
 
-    ctx.push(FrameFragment::new(ctx, styles, frame));
+    ctx.push(FrameFragment::new(ctx, styles, frame).with_mathml(mathml));
 
     if let Some(right) = right {
         let mut right = GlyphFragment::new(ctx, styles, right, span)
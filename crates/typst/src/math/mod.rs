@@ -8,12 +8,16 @@ pub mod accent;
 mod align;
 mod attach;
 mod cancel;
+#[path = "chem.rs"]
+mod chem_;
 #[path = "class.rs"]
 mod class_;
+mod diagram;
 mod equation;
 mod frac;
 mod fragment;
 mod lr;
+mod mathml;
 mod matrix;
 mod op;
 mod root;
@@ -27,10 +31,13 @@ pub use self::accent::{Accent, AccentElem};
 pub use self::align::*;
 pub use self::attach::*;
 pub use self::cancel::*;
+pub use self::chem_::*;
 pub use self::class_::*;
+pub use self::diagram::*;
 pub use self::equation::*;
 pub use self::frac::*;
 pub use self::lr::*;
+pub use self::mathml::{MathMl, MathMlBody};
 pub use self::matrix::*;
 pub use self::op::*;
 pub use self::root::*;
@@ -157,6 +164,7 @@ pub fn module() -> Module {
     let mut math = Scope::deduplicating();
     math.category(MATH);
     math.define_elem::<EquationElem>();
+    math.define_elem::<EquationTagElem>();
     math.define_elem::<TextElem>();
     math.define_elem::<LrElem>();
     math.define_elem::<MidElem>();
@@ -180,6 +188,7 @@ pub fn module() -> Module {
     math.define_elem::<BinomElem>();
     math.define_elem::<VecElem>();
     math.define_elem::<MatElem>();
+    math.define_elem::<DiagramElem>();
     math.define_elem::<CasesElem>();
     math.define_elem::<RootElem>();
     math.define_elem::<ClassElem>();
@@ -187,6 +196,7 @@ pub fn module() -> Module {
     math.define_elem::<PrimesElem>();
     math.define_func::<abs>();
     math.define_func::<norm>();
+    math.define_func::<chem>();
     math.define_func::<round>();
     math.define_func::<sqrt>();
     math.define_func::<upright>();
@@ -4,7 +4,9 @@ use unicode_math_class::MathClass;
 use crate::diag::SourceResult;
 use crate::foundations::{elem, Content, NativeElement, Packed, Scope, StyleChain};
 use crate::layout::HElem;
-use crate::math::{upright, FrameFragment, LayoutMath, Limits, MathContext, THIN};
+use crate::math::{
+    upright, FrameFragment, LayoutMath, Limits, MathContext, MathMl, MathMlBody, THIN,
+};
 use crate::text::TextElem;
 
 /// A text operator in an equation.
@@ -40,6 +42,12 @@ impl LayoutMath for Packed<OpElem> {
         let italics = fragment.italics_correction();
         let accent_attach = fragment.accent_attach();
         let text_like = fragment.is_text_like();
+        // Text operators like `sin` are identifiers, but structured operators
+        // keep the MathML of their body.
+        let mathml = ctx.mathml_of(&fragment).map(|mathml| match mathml.body {
+            MathMlBody::Text(text) if mathml.name == "mtext" => MathMl::token("mi", text),
+            _ => mathml,
+        });
 
         ctx.push(
             FrameFragment::new(ctx, styles, fragment.into_frame())
@@ -51,7 +59,8 @@ impl LayoutMath for Packed<OpElem> {
                     Limits::Display
                 } else {
                     Limits::Never
-                }),
+                })
+                .with_mathml(mathml),
         );
         Ok(())
     }
@@ -2,8 +2,8 @@ use crate::diag::SourceResult;
 use crate::foundations::{elem, func, Content, NativeElement, Packed, StyleChain};
 use crate::layout::{Abs, Frame, FrameItem, Point, Size};
 use crate::math::{
-    style_cramped, EquationElem, FrameFragment, GlyphFragment, LayoutMath, MathContext,
-    MathSize, Scaled,
+    mathml, style_cramped, EquationElem, FrameFragment, GlyphFragment, LayoutMath,
+    MathContext, MathFragment, MathSize, Scaled,
 };
 use crate::syntax::Span;
 use crate::text::TextElem;
@@ -70,11 +70,12 @@ fn layout(
     let raise_factor = percent!(ctx, radical_degree_bottom_raise_percent);
 
     // Layout radicand.
-    let radicand = {
+    let (radicand, radicand_mathml) = {
         let cramped = style_cramped();
         let styles = styles.chain(&cramped);
         let run = ctx.layout_into_run(radicand, styles)?;
         let multiline = run.is_multiline();
+        let mathml = ctx.mathml_of_run(run.iter());
         let mut radicand = run.into_fragment(ctx, styles).into_frame();
         if multiline {
             // Align the frame center line with the math axis.
@@ -82,7 +83,7 @@ fn layout(
                 radicand.height() / 2.0 + scaled!(ctx, styles, axis_height),
             );
         }
-        radicand
+        (radicand, mathml)
     };
 
     // Layout root symbol.
@@ -94,8 +95,13 @@ fn layout(
     // Layout the index.
     let sscript = EquationElem::set_size(MathSize::ScriptScript).wrap();
     let index = index
-        .map(|elem| ctx.layout_into_frame(elem, styles.chain(&sscript)))
+        .map(|elem| ctx.layout_into_fragment(elem, styles.chain(&sscript)))
         .transpose()?;
+    let mathml = match &index {
+        Some(index) => mathml::node("mroot", [radicand_mathml, ctx.mathml_of(index)]),
+        None => mathml::node("msqrt", [radicand_mathml]),
+    };
+    let index = index.map(MathFragment::into_frame);
 
     // TeXbook, page 443, item 11
     // Keep original gap, and then distribute any remaining free space
@@ -156,7 +162,7 @@ fn layout(
     );
 
     frame.push_frame(radicand_pos, radicand);
-    ctx.push(FrameFragment::new(ctx, styles, frame));
+    ctx.push(FrameFragment::new(ctx, styles, frame).with_mathml(mathml));
 
     Ok(())
 }
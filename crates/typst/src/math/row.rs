@@ -183,8 +183,10 @@ impl MathRun {
             .filter(|e| e.math_size().is_some())
             .all(|e| e.is_text_like());
 
+        let mathml = ctx.mathml_of_run(self.iter());
         FrameFragment::new(ctx, styles, self.into_frame(ctx, styles))
             .with_text_like(text_like)
+            .with_mathml(mathml)
             .into()
     }
 
@@ -195,10 +197,40 @@ impl MathRun {
         self,
         ctx: &MathContext,
         styles: StyleChain,
+    ) -> MathRunFrameBuilder {
+        self.breaking_frame_builder(ctx, styles, Abs::inf(), Abs::zero())
+    }
+
+    /// Like [`multiline_frame_builder`](Self::multiline_frame_builder), but
+    /// additionally breaks rows that are wider than `width` into multiple
+    /// lines. Continuation lines are indented by `indent`.
+    ///
+    /// Broken rows don't take part in the alignment of the other rows and
+    /// ignore their own alignment points.
+    pub fn breaking_frame_builder(
+        self,
+        ctx: &MathContext,
+        styles: StyleChain,
+        width: Abs,
+        indent: Abs,
     ) -> MathRunFrameBuilder {
         let rows: Vec<_> = self.rows();
         let row_count = rows.len();
-        let alignments = alignments(&rows);
+        let broken: Vec<bool> = rows
+            .iter()
+            .map(|row| row.width() > width && row.breakpoints().next().is_some())
+            .collect();
+
+        let alignments = if broken.contains(&true) {
+            let unbroken: Vec<_> = rows
+                .iter()
+                .zip(&broken)
+                .map(|(row, &broken)| if broken { Self::default() } else { row.clone() })
+                .collect();
+            alignments(&unbroken)
+        } else {
+            alignments(&rows)
+        };
 
         let leading = if EquationElem::size_in(styles) >= MathSize::Text {
             ParElem::leading_in(styles)
@@ -209,29 +241,159 @@ impl MathRun {
 
         let align = AlignElem::alignment_in(styles).resolve(styles).x;
         let mut frames: Vec<(Frame, Point)> = vec![];
+        let mut unbroken = vec![];
         let mut size = Size::zero();
-        for (i, row) in rows.into_iter().enumerate() {
+        for (i, (row, broken)) in rows.into_iter().zip(broken).enumerate() {
             if i == row_count - 1 && row.0.is_empty() {
                 continue;
             }
 
-            let sub = row.into_line_frame(&alignments.points, LeftRightAlternator::Right);
-            if i > 0 {
-                size.y += leading;
+            let lines = if broken {
+                row.break_lines(width, indent)
+                    .into_iter()
+                    .map(|(line, offset)| (line, Some(offset)))
+                    .collect()
+            } else {
+                vec![(row, None)]
+            };
+
+            for (line, offset) in lines {
+                let points = if offset.is_some() { &[][..] } else { &alignments.points };
+                let sub = line.into_line_frame(points, LeftRightAlternator::Right);
+                if !frames.is_empty() {
+                    size.y += leading;
+                }
+
+                let mut pos = Point::with_y(size.y);
+                if let Some(offset) = offset {
+                    pos.x = offset;
+                    size.x.set_max(offset + sub.width());
+                } else {
+                    if alignments.points.is_empty() {
+                        pos.x = align.position(alignments.width - sub.width());
+                    }
+                    size.x.set_max(sub.width());
+                    unbroken.push(frames.len());
+                }
+                size.y += sub.height();
+                frames.push((sub, pos));
             }
+        }
 
-            let mut pos = Point::with_y(size.y);
-            if alignments.points.is_empty() {
-                pos.x = align.position(alignments.width - sub.width());
+        // If broken rows widened the frame, align the other rows within the
+        // full width.
+        if unbroken.len() < frames.len() && size.x > alignments.width {
+            let shift = align.position(size.x - alignments.width);
+            for i in unbroken {
+                frames[i].1.x += shift;
             }
-            size.x.set_max(sub.width());
-            size.y += sub.height();
-            frames.push((sub, pos));
         }
 
         MathRunFrameBuilder { size, frames }
     }
 
+    /// Breaks this row into lines that fit into `width`, with all but the
+    /// first line indented by `indent`. Returns each line together with its
+    /// horizontal offset.
+    ///
+    /// Lines are broken before top-level relations and binary operators,
+    /// such that the operator starts the next line. Relations are preferred
+    /// over binary operators as long as the line stays at least half full.
+    fn break_lines(self, width: Abs, indent: Abs) -> Vec<(Self, Abs)> {
+        let mut lines = vec![];
+        let mut fragments = self.0;
+        let mut offset = Abs::zero();
+
+        loop {
+            let available = width - offset;
+            let total: Abs = fragments.iter().map(MathFragment::width).sum();
+            if total <= available {
+                break;
+            }
+
+            // The last fitting breakpoint, the last fitting breakpoint at a
+            // relation, and the first breakpoint overall (used if nothing
+            // fits).
+            let mut fitting = None;
+            let mut relation = None;
+            let mut first = None;
+            for (i, end, class) in Self::breakpoints_in(&fragments) {
+                first.get_or_insert(i);
+                if end > available {
+                    break;
+                }
+                fitting = Some(i);
+                if class == MathClass::Relation && end >= available / 2.0 {
+                    relation = Some(i);
+                }
+            }
+
+            let Some(i) = relation.or(fitting).or(first) else { break };
+            let rest = fragments.split_off(i);
+            while fragments.last().is_some_and(is_space) {
+                fragments.pop();
+            }
+
+            lines.push((Self(fragments), offset));
+            fragments = rest;
+            offset = indent;
+        }
+
+        lines.push((Self(fragments), offset));
+        lines
+    }
+
+    /// The possible breakpoints in this row.
+    fn breakpoints(&self) -> impl Iterator<Item = (usize, Abs, MathClass)> + '_ {
+        Self::breakpoints_in(&self.0)
+    }
+
+    /// The possible breakpoints in the given fragments: The index of the
+    /// operator before which a line can be broken, the width of the line
+    /// ending there, and the operator's class.
+    fn breakpoints_in(
+        fragments: &[MathFragment],
+    ) -> impl Iterator<Item = (usize, Abs, MathClass)> + '_ {
+        let mut x = Abs::zero();
+        let mut end = None;
+        let mut depth = 0usize;
+        let mut prev = None;
+        fragments.iter().enumerate().filter_map(move |(i, fragment)| {
+            if is_space(fragment)
+                || fragment.is_ignorant()
+                || matches!(fragment, MathFragment::Align)
+            {
+                x += fragment.width();
+                return None;
+            }
+
+            let class = fragment.class();
+            let breakable = depth == 0
+                && match class {
+                    MathClass::Binary => true,
+                    MathClass::Relation => prev != Some(MathClass::Relation),
+                    _ => false,
+                };
+
+            match class {
+                MathClass::Opening => depth += 1,
+                MathClass::Closing => depth = depth.saturating_sub(1),
+                _ => {}
+            }
+
+            let point = end.filter(|_| breakable).map(|end| (i, end, class));
+            x += fragment.width();
+            end = Some(x);
+            prev = Some(class);
+            point
+        })
+    }
+
+    /// The total width of the fragments in this row.
+    fn width(&self) -> Abs {
+        self.iter().map(MathFragment::width).sum()
+    }
+
     /// Lay out [`MathFragment`]s into a one-row [`Frame`], using the
     /// caller-provided alignment points.
     pub fn into_line_frame(
@@ -420,6 +582,10 @@ impl MathRunFrameBuilder {
     }
 }
 
+fn is_space(fragment: &MathFragment) -> bool {
+    matches!(fragment, MathFragment::Space(_) | MathFragment::Spacing(_, _))
+}
+
 fn affects_row_height(fragment: &MathFragment) -> bool {
     !matches!(fragment, MathFragment::Align | MathFragment::Linebreak)
 }
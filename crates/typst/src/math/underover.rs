@@ -2,9 +2,9 @@ use crate::diag::SourceResult;
 use crate::foundations::{elem, Content, Packed, StyleChain};
 use crate::layout::{Abs, Em, FixedAlignment, Frame, FrameItem, Point, Size};
 use crate::math::{
-    alignments, scaled_font_size, style_cramped, style_for_subscript,
+    alignments, mathml, scaled_font_size, style_cramped, style_for_subscript,
     style_for_superscript, AlignmentResult, FrameFragment, GlyphFragment, LayoutMath,
-    LeftRightAlternator, MathContext, MathRun, Scaled,
+    LeftRightAlternator, MathContext, MathMl, MathRun, Scaled,
 };
 use crate::syntax::Span;
 use crate::text::TextElem;
@@ -106,6 +106,16 @@ fn layout_underoverline(
     let content_class = content.class();
     let content_is_text_like = content.is_text_like();
     let content_italics_correction = content.italics_correction();
+    let mathml = match position {
+        Position::Under => mathml::node(
+            "munder",
+            [ctx.mathml_of(&content), ctx.mathml.then(|| MathMl::token("mo", "_"))],
+        ),
+        Position::Over => mathml::node(
+            "mover",
+            [ctx.mathml_of(&content), ctx.mathml.then(|| MathMl::token("mo", "‾"))],
+        ),
+    };
     let mut frame = Frame::soft(size);
     frame.set_baseline(baseline);
     frame.push_frame(content_pos, content.into_frame());
@@ -125,7 +135,8 @@ fn layout_underoverline(
         FrameFragment::new(ctx, styles, frame)
             .with_class(content_class)
             .with_text_like(content_is_text_like)
-            .with_italics_correction(content_italics_correction),
+            .with_italics_correction(content_italics_correction)
+            .with_mathml(mathml),
     );
 
     Ok(())
@@ -407,6 +418,15 @@ fn layout_underoverspreader(
     let glyph = GlyphFragment::new(ctx, styles, c, span);
     let stretched = glyph.stretch_horizontal(ctx, body.width(), Abs::zero());
 
+    let name = match position {
+        Position::Under => "munder",
+        Position::Over => "mover",
+    };
+    let mut mathml = mathml::node(
+        name,
+        [ctx.mathml_of(&body), ctx.mathml.then(|| mathml::char(c, stretched.class))],
+    );
+
     let mut rows = vec![];
     let baseline = match position {
         Position::Under => {
@@ -415,7 +435,9 @@ fn layout_underoverspreader(
             if let Some(annotation) = annotation {
                 let under_style = style_for_subscript(styles);
                 let annotation_styles = styles.chain(&under_style);
-                rows.push(ctx.layout_into_run(annotation, annotation_styles)?);
+                let run = ctx.layout_into_run(annotation, annotation_styles)?;
+                mathml = mathml::node(name, [mathml, ctx.mathml_of_run(run.iter())]);
+                rows.push(run);
             }
             0
         }
@@ -423,7 +445,9 @@ fn layout_underoverspreader(
             if let Some(annotation) = annotation {
                 let over_style = style_for_superscript(styles);
                 let annotation_styles = styles.chain(&over_style);
-                rows.push(ctx.layout_into_run(annotation, annotation_styles)?);
+                let run = ctx.layout_into_run(annotation, annotation_styles)?;
+                mathml = mathml::node(name, [mathml, ctx.mathml_of_run(run.iter())]);
+                rows.push(run);
             }
             rows.push(stretched.into());
             rows.push(MathRun::new(vec![body]));
@@ -439,7 +463,11 @@ fn layout_underoverspreader(
         LeftRightAlternator::Right,
         None,
     );
-    ctx.push(FrameFragment::new(ctx, styles, frame).with_class(body_class));
+    ctx.push(
+        FrameFragment::new(ctx, styles, frame)
+            .with_class(body_class)
+            .with_mathml(mathml),
+    );
 
     Ok(())
 }
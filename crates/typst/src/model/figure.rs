@@ -14,10 +14,13 @@ use crate::introspection::{
     Count, Counter, CounterKey, CounterUpdate, Locatable, Location,
 };
 use crate::layout::{
-    AlignElem, Alignment, BlockBody, BlockElem, Em, HAlignment, Length, OuterVAlignment,
-    PlaceElem, PlacementScope, VAlignment, VElem,
+    AlignElem, Alignment, BlockBody, BlockElem, Em, FloatPolicy, HAlignment, Length,
+    OuterHAlignment, OuterVAlignment, PlaceElem, PlacementScope, PlacementWrap,
+    VAlignment, VElem,
+};
+use crate::model::{
+    Numbering, NumberingPattern, Outlinable, RefElem, Refable, Supplement,
 };
-use crate::model::{Numbering, NumberingPattern, Outlinable, Refable, Supplement};
 use crate::text::{Lang, Region, TextElem};
 use crate::utils::NonZeroExt;
 use crate::visualize::ImageElem;
@@ -115,10 +118,16 @@ pub struct FigureElem {
     ///   is closer.
     /// - `{top}`: The figure floats to the top of the page.
     /// - `{bottom}`: The figure floats to the bottom of the page.
+    /// - `{"here"}`: The figure stays in-flow where it was specified if it
+    ///   fits there. Otherwise, it floats to the top of the next page.
+    /// - `{"page"}`: The figure is placed on a dedicated float page after the
+    ///   current page, together with other such figures that fit.
     ///
     /// The gap between the main flow content and the floating figure is
     /// controlled by the [`clearance`]($place.clearance) argument on the
-    /// `place` function.
+    /// `place` function. Similarly, the maximum fraction of a page that
+    /// floating figures may occupy is controlled by its
+    /// [`max-fraction`]($place.max-fraction) argument.
     ///
     /// ```example
     /// #set page(height: 200pt)
@@ -131,7 +140,7 @@ pub struct FigureElem {
     /// )
     /// #lorem(60)
     /// ```
-    pub placement: Option<Smart<VAlignment>>,
+    pub placement: Option<FigurePlacement>,
 
     /// Relative to which containing scope something is placed.
     ///
@@ -141,6 +150,58 @@ pub struct FigureElem {
     /// Has no effect if `placement` is `{none}`.
     pub scope: PlacementScope,
 
+    /// Whether a floating figure must not be placed before its first
+    /// [reference]($ref).
+    ///
+    /// When enabled and the figure is first referenced after it was
+    /// specified, the figure is held back until the reference was laid out.
+    /// It then floats into the page of the reference or a later one. The
+    /// figure must have a [label] for this to have an effect.
+    ///
+    /// Has no effect if `placement` is `{none}`.
+    ///
+    /// ```example
+    /// #set page(height: 150pt)
+    ///
+    /// #figure(
+    ///   placement: auto,
+    ///   after-reference: true,
+    ///   rect[A figure],
+    ///   caption: [Held back],
+    /// ) <held>
+    ///
+    /// #lorem(30)
+    ///
+    /// See @held.
+    /// ```
+    pub after_reference: bool,
+
+    /// The side at which the figure is attached such that the following
+    /// text wraps around it.
+    ///
+    /// - `{none}`: The figure takes up the full width of the flow.
+    /// - `{left}`: The figure sits at the left and text flows to its right.
+    /// - `{right}`: The figure sits at the right and text flows to its left.
+    /// - `{start}` and `{end}`: Like `{left}` and `{right}`, but depending on
+    ///   the text direction.
+    ///
+    /// The gap between the wrapping text and the figure is controlled by the
+    /// [`clearance`]($place.clearance) argument on the `place` function. A
+    /// wrapping figure cannot also float, so this has no effect if
+    /// `placement` is not `{none}`.
+    ///
+    /// ```example
+    /// #set page(width: 200pt)
+    ///
+    /// #figure(
+    ///   wrap: right,
+    ///   rect(width: 60pt, height: 40pt),
+    ///   caption: [A box],
+    /// )
+    /// #lorem(40)
+    /// ```
+    pub wrap: Option<OuterHAlignment>,
+
     /// The figure's caption.
     pub caption: Option<Packed<FigureCaption>>,
 
@@ -245,7 +306,7 @@ impl Synthesize for Packed<FigureElem> {
         let kind = elem.kind(styles).unwrap_or_else(|| {
             elem.body()
                 .query_first(Selector::can::<dyn Figurable>())
-                .map(|elem| FigureKind::Elem(elem.func()))
+                .map(|elem| FigureKind::Elem(elem.elem()))
                 .unwrap_or_else(|| FigureKind::Elem(ImageElem::elem()))
         });
 
@@ -312,7 +373,7 @@ impl Synthesize for Packed<FigureElem> {
 
 impl Show for Packed<FigureElem> {
     #[typst_macros::time(name = "figure", span = self.span())]
-    fn show(&self, _: &mut Engine, styles: StyleChain) -> SourceResult<Content> {
+    fn show(&self, engine: &mut Engine, styles: StyleChain) -> SourceResult<Content> {
         let mut realized = self.body().clone();
 
         // Build the caption, if any.
@@ -331,11 +392,32 @@ impl Show for Packed<FigureElem> {
             .spanned(self.span());
 
         // Wrap in a float.
-        if let Some(align) = self.placement(styles) {
-            realized = PlaceElem::new(realized)
+        if let Some(placement) = self.placement(styles) {
+            let (align, policy) = match placement {
+                FigurePlacement::Float(align) => (align, FloatPolicy::Edge),
+                FigurePlacement::Here => {
+                    (Smart::Custom(VAlignment::Top), FloatPolicy::Here)
+                }
+                FigurePlacement::Page => (Smart::Auto, FloatPolicy::Page),
+            };
+
+            let mut place = PlaceElem::new(realized)
                 .with_alignment(align.map(|align| HAlignment::Center + align))
                 .with_scope(self.scope(styles))
                 .with_float(true)
+                .with_policy(policy);
+
+            if self.after_reference(styles) {
+                place.push_anchor(self.later_reference(engine));
+            }
+
+            realized = place.pack().spanned(self.span());
+        } else if let Some(side) = self.wrap(styles) {
+            // Leave the vertical alignment unspecified such that the figure
+            // stays where it was specified.
+            realized = PlaceElem::new(realized)
+                .with_alignment(Smart::Custom(HAlignment::from(side).into()))
+                .with_wrap(PlacementWrap::Bounds)
                 .pack()
                 .spanned(self.span());
         }
@@ -344,6 +426,20 @@ impl Show for Packed<FigureElem> {
     }
 }
 
+impl Packed<FigureElem> {
+    /// The location of the first reference to the figure if there is no
+    /// reference before the figure.
+    fn later_reference(&self, engine: &Engine) -> Option<Location> {
+        let label = self.label()?;
+        let location = self.location()?;
+        let selector = select_where!(RefElem, Target => label);
+        if engine.introspector.query_count_before(&selector, location) > 0 {
+            return None;
+        }
+        engine.introspector.query_first(&selector)?.location()
+    }
+}
+
 impl ShowSet for Packed<FigureElem> {
     fn show_set(&self, _: StyleChain) -> Styles {
         // Still allows breakable figures with
@@ -431,6 +527,31 @@ impl Outlinable for Packed<FigureElem> {
     }
 }
 
+/// Where a figure is placed.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum FigurePlacement {
+    /// Float to the top or bottom of the page, or pick automatically.
+    Float(Smart<VAlignment>),
+    /// Stay in-flow if possible and float to the next page otherwise.
+    Here,
+    /// Go onto a dedicated float page.
+    Page,
+}
+
+cast! {
+    FigurePlacement,
+    self => match self {
+        Self::Float(align) => align.into_value(),
+        Self::Here => "here".into_value(),
+        Self::Page => "page".into_value(),
+    },
+    align: Smart<VAlignment> => Self::Float(align),
+    /// Stay in-flow if possible and float to the next page otherwise.
+    "here" => Self::Here,
+    /// Go onto a dedicated float page.
+    "page" => Self::Page,
+}
+
 /// The caption of a figure. This element can be used in set and show rules to
 /// customize the appearance of captions for all figures or figures of a
 /// specific kind.
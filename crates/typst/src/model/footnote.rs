@@ -4,13 +4,17 @@ use std::str::FromStr;
 use crate::diag::{bail, At, SourceResult, StrResult};
 use crate::engine::Engine;
 use crate::foundations::{
-    cast, elem, scope, Content, Label, NativeElement, Packed, Show, ShowSet, Smart,
-    StyleChain, Styles,
+    cast, elem, scope, select_where, Content, Label, LocatableSelector, NativeElement,
+    Packed, Selector, Show, ShowSet, Smart, Str, StyleChain, Styles,
+};
+use crate::introspection::{
+    Count, Counter, CounterKey, CounterUpdate, Locatable, Location,
 };
-use crate::introspection::{Count, Counter, CounterUpdate, Locatable, Location};
 use crate::layout::{Abs, Em, HElem, Length, Ratio};
-use crate::model::{Destination, Numbering, NumberingPattern, ParElem};
-use crate::text::{SuperElem, TextElem, TextSize};
+use crate::model::{
+    Destination, HeadingElem, Numbering, NumberingPattern, ParElem, ParbreakElem,
+};
+use crate::text::{LocalName, SuperElem, TextElem, TextSize};
 use crate::utils::NonZeroExt;
 use crate::visualize::{LineElem, Stroke};
 
@@ -46,6 +50,12 @@ use crate::visualize::{LineElem, Stroke};
 /// And the online app. #footnote(<fn>)
 /// ```
 ///
+/// # Series and endnotes
+/// Footnotes can be grouped into independent [series]($footnote.series), each
+/// with its own counter and listing at the bottom of the page. Notes can also
+/// be turned into [endnotes]($footnote.endnote), which are not listed on the
+/// page, but collected by an [`endnotes`] listing instead.
+///
 /// _Note:_ Set and show rules in the scope where `footnote` is called may not
 /// apply to the footnote's content. See [here][issue] for more information.
 ///
@@ -70,6 +80,64 @@ pub struct FootnoteElem {
     #[default(Numbering::Pattern(NumberingPattern::from_str("1").unwrap()))]
     pub numbering: Numbering,
 
+    /// The series the footnote belongs to.
+    ///
+    /// Footnotes of different series are numbered independently and listed in
+    /// separate groups at the bottom of the page, for instance to typeset the
+    /// multiple apparatuses of a critical edition. Footnotes without a series
+    /// belong to the main series, which is counted by `{counter(footnote)}`.
+    /// The counter of another series is available as
+    /// `{counter(footnote.where(series: "name"))}`.
+    ///
+    /// ```example
+    /// #let note = footnote.with(
+    ///   series: "variants",
+    ///   numbering: "a",
+    /// )
+    ///
+    /// Arma virumque#footnote[Vergil]
+    /// cano#note[canto]
+    /// Troiae#note[Troia]
+    /// ```
+    pub series: Option<Str>,
+
+    /// The separator between the document body and the listing of the
+    /// footnote's series at the bottom of the page.
+    ///
+    /// When `{auto}`, the [separator]($footnote.entry.separator) of the
+    /// footnote entry is used. Only the separator of the first note of a
+    /// series on each page has an effect.
+    ///
+    /// ```example
+    /// #let note = footnote.with(
+    ///   series: "variants",
+    ///   numbering: "a",
+    ///   separator: line(length: 100%, stroke: 0.5pt),
+    /// )
+    ///
+    /// Arma virumque#footnote[Vergil]
+    /// cano#note[canto]
+    /// ```
+    pub separator: Smart<Content>,
+
+    /// Whether the footnote is an endnote.
+    ///
+    /// Endnotes are not listed at the bottom of the page. Instead, they are
+    /// collected by the next [`endnotes`] listing of the same series.
+    ///
+    /// ```example
+    /// #let endnote = footnote.with(
+    ///   series: "end",
+    ///   endnote: true,
+    /// )
+    ///
+    /// Some remark.#endnote[Explained at the end.]
+    /// Another one.#endnote[Also explained.]
+    ///
+    /// #endnotes(series: "end")
+    /// ```
+    pub endnote: bool,
+
     /// The content to put into the footnote. Can also be the label of another
     /// footnote this one should point to.
     #[required]
@@ -116,9 +184,22 @@ impl FootnoteElem {
     }
 }
 
+impl FootnoteElem {
+    /// The counter that numbers the footnotes of the given series.
+    pub fn counter_of(series: Option<Str>) -> Counter {
+        match series {
+            None => Counter::of(FootnoteElem::elem()),
+            series => Counter::new(CounterKey::Selector(select_where!(
+                FootnoteElem,
+                Series => series,
+            ))),
+        }
+    }
+}
+
 impl Packed<FootnoteElem> {
-    /// Returns the location of the definition of this footnote.
-    pub fn declaration_location(&self, engine: &Engine) -> StrResult<Location> {
+    /// Returns the footnote that defines this footnote's content.
+    pub fn declaration(&self, engine: &Engine) -> StrResult<Packed<FootnoteElem>> {
         match self.body() {
             FootnoteBody::Reference(label) => {
                 let element = engine.introspector.query_label(*label)?;
@@ -128,20 +209,31 @@ impl Packed<FootnoteElem> {
                 if self.location() == footnote.location() {
                     bail!("footnote cannot reference itself");
                 }
-                footnote.declaration_location(engine)
+                footnote.declaration(engine)
             }
-            _ => Ok(self.location().unwrap()),
+            _ => Ok(self.clone()),
         }
     }
+
+    /// Returns the location of the definition of this footnote.
+    pub fn declaration_location(&self, engine: &Engine) -> StrResult<Location> {
+        Ok(self.declaration(engine)?.location().unwrap())
+    }
+
+    /// The counter that numbers this footnote.
+    pub fn counter(&self) -> Counter {
+        FootnoteElem::counter_of(self.series(StyleChain::default()))
+    }
 }
 
 impl Show for Packed<FootnoteElem> {
     #[typst_macros::time(name = "footnote", span = self.span())]
     fn show(&self, engine: &mut Engine, styles: StyleChain) -> SourceResult<Content> {
         let span = self.span();
-        let loc = self.declaration_location(engine).at(span)?;
+        let declaration = self.declaration(engine).at(span)?;
+        let loc = declaration.location().unwrap();
         let numbering = self.numbering(styles);
-        let counter = Counter::of(FootnoteElem::elem());
+        let counter = declaration.counter();
         let num = counter.display_at_loc(engine, loc, styles, numbering)?;
         let sup = SuperElem::new(num).pack().spanned(span);
         let loc = loc.variant(1);
@@ -154,6 +246,11 @@ impl Count for Packed<FootnoteElem> {
     fn update(&self) -> Option<CounterUpdate> {
         (!self.is_ref()).then(|| CounterUpdate::Step(NonZeroUsize::ONE))
     }
+
+    fn in_plain_counter(&self) -> bool {
+        // Only the main series is counted by `counter(footnote)`.
+        self.series(StyleChain::default()).is_none()
+    }
 }
 
 /// The body of a footnote can be either some content or a label referencing
@@ -285,7 +382,7 @@ impl Show for Packed<FootnoteEntry> {
         let number_gap = Em::new(0.05);
         let default = StyleChain::default();
         let numbering = note.numbering(default);
-        let counter = Counter::of(FootnoteElem::elem());
+        let counter = note.counter();
         let Some(loc) = note.location() else {
             bail!(
                 span, "footnote entry must have a location";
@@ -324,3 +421,116 @@ cast! {
     FootnoteElem,
     v: Content => v.unpack::<Self>().unwrap_or_else(Self::with_content)
 }
+
+/// A listing of endnotes.
+///
+/// Collects the [endnotes]($footnote.endnote) of a footnote series and shows
+/// their entries where the listing is placed. A listing contains the notes
+/// between the previous listing of the same series (or the start of the
+/// document) and itself. To collect the notes of each chapter, you can thus
+/// put a listing at the end of every chapter.
+///
+/// The entries are realized as [footnote entries]($footnote.entry) and can be
+/// customized in the same way.
+///
+/// # Example
+/// ```example
+/// #set footnote(endnote: true)
+///
+/// = Chapter
+/// Some remark.#footnote[A first note.]
+/// Another remark.#footnote[A second note.]
+///
+/// #endnotes()
+/// ```
+#[elem(Locatable, Show, ShowSet, LocalName)]
+pub struct EndnotesElem {
+    /// The title of the listing.
+    ///
+    /// - When set to `{auto}`, an appropriate title for the
+    ///   [text language]($text.lang) will be used.
+    /// - When set to `{none}`, the listing will not have a title.
+    /// - A custom title can be set by passing content.
+    ///
+    /// The listing's heading will not be numbered by default, but you can
+    /// force it to be with a show-set rule:
+    /// `{show endnotes: set heading(numbering: "1.")}`
+    pub title: Smart<Option<Content>>,
+
+    /// The footnote series whose endnotes to list.
+    ///
+    /// When `{none}`, the endnotes of the main series are listed.
+    pub series: Option<Str>,
+}
+
+impl Packed<EndnotesElem> {
+    /// The endnotes collected by this listing.
+    pub fn notes(&self, engine: &Engine) -> Vec<Packed<FootnoteElem>> {
+        let loc = self.location().unwrap();
+        let series = self.series(StyleChain::default());
+        let end = LocatableSelector(Selector::Location(loc));
+
+        // Find the previous listing of the same series. Notes before it belong
+        // to that listing.
+        let previous = engine
+            .introspector
+            .query(
+                &select_where!(EndnotesElem, Series => series.clone())
+                    .before(end.clone(), false),
+            )
+            .last()
+            .and_then(Content::location);
+
+        let mut selector = select_where!(FootnoteElem, Series => series, Endnote => true)
+            .before(end, false);
+        if let Some(previous) = previous {
+            let start = LocatableSelector(Selector::Location(previous));
+            selector = selector.after(start, false);
+        }
+
+        engine
+            .introspector
+            .query(&selector)
+            .into_iter()
+            .filter_map(|elem| elem.to_packed::<FootnoteElem>().cloned())
+            .filter(|note| !note.is_ref())
+            .collect()
+    }
+}
+
+impl Show for Packed<EndnotesElem> {
+    #[typst_macros::time(name = "endnotes", span = self.span())]
+    fn show(&self, engine: &mut Engine, styles: StyleChain) -> SourceResult<Content> {
+        let mut seq = vec![ParbreakElem::shared().clone()];
+        if let Some(title) = self.title(styles).unwrap_or_else(|| {
+            Some(TextElem::packed(Self::local_name_in(styles)).spanned(self.span()))
+        }) {
+            seq.push(
+                HeadingElem::new(title)
+                    .with_depth(NonZeroUsize::ONE)
+                    .pack()
+                    .spanned(self.span()),
+            );
+        }
+
+        for note in self.notes(engine) {
+            seq.push(FootnoteEntry::new(note).pack().spanned(self.span()));
+            seq.push(ParbreakElem::shared().clone());
+        }
+
+        Ok(Content::sequence(seq))
+    }
+}
+
+impl ShowSet for Packed<EndnotesElem> {
+    fn show_set(&self, styles: StyleChain) -> Styles {
+        let mut out = Styles::new();
+        out.set(HeadingElem::set_numbering(None));
+        out.set(ParElem::set_spacing(FootnoteEntry::gap_in(styles).into()));
+        out
+    }
+}
+
+impl LocalName for Packed<EndnotesElem> {
+    const KEY: &'static str = "endnotes";
+}
@@ -6,8 +6,8 @@ use comemo::Track;
 use crate::diag::{bail, At, SourceResult};
 use crate::engine::Engine;
 use crate::foundations::{
-    cast, elem, scope, select_where, Content, Context, Func, LocatableSelector,
-    NativeElement, Packed, Show, ShowSet, Smart, StyleChain, Styles,
+    cast, elem, scope, select_where, Cast, Content, Context, Func, LocatableSelector,
+    NativeElement, Packed, Selector, Show, ShowSet, Smart, StyleChain, Styles,
 };
 use crate::introspection::{Counter, CounterKey, Locatable};
 use crate::layout::{BoxElem, Em, Fr, HElem, HideElem, Length, Rel, RepeatElem, Spacing};
@@ -56,12 +56,30 @@ use crate::utils::NonZeroExt;
 /// )
 /// ```
 ///
+/// # Section outlines
+/// By setting the `scope` parameter to `{"section"}`, the outline only lists
+/// the elements in the section it is placed in. This way, a chapter can start
+/// with a small table of contents of its own.
+///
+/// ```example
+/// #set heading(numbering: "1.1")
+///
+/// = Animals
+/// #outline(title: none, scope: "section")
+///
+/// == Mammals
+/// == Birds
+///
+/// = Plants
+/// == Trees
+/// ```
+///
 /// # Styling the outline
 /// The outline element has several options for customization, such as its
 /// `title` and `indent` parameters. If desired, however, it is possible to have
 /// more control over the outline's look and style through the
 /// [`outline.entry`]($outline.entry) element.
-#[elem(scope, keywords = ["Table of Contents"], Show, ShowSet, LocalName)]
+#[elem(scope, keywords = ["Table of Contents"], Locatable, Show, ShowSet, LocalName)]
 pub struct OutlineElem {
     /// The title of the outline.
     ///
@@ -117,6 +135,27 @@ pub struct OutlineElem {
     /// ```
     pub depth: Option<NonZeroUsize>,
 
+    /// Which part of the document to list elements from.
+    ///
+    /// With `{"section"}`, only the elements between the heading preceding
+    /// the outline and the next heading of the same or a higher level are
+    /// listed. Headings that are not [outlined]($heading.outlined) are not
+    /// considered as section boundaries. If there is no heading before the
+    /// outline, all elements are listed.
+    ///
+    /// ```example
+    /// = Introduction
+    /// #outline(title: [In this chapter], scope: "section")
+    ///
+    /// == Motivation
+    /// == Contributions
+    ///
+    /// = Background
+    /// == Prior work
+    /// ```
+    #[default(OutlineScope::Document)]
+    pub scope: OutlineScope,
+
     /// How to indent the outline's entries.
     ///
     /// - `{none}`: No indent
@@ -207,7 +246,11 @@ impl Show for Packed<OutlineElem> {
         let depth = self.depth(styles).unwrap_or(NonZeroUsize::new(usize::MAX).unwrap());
 
         let mut ancestors: Vec<&Content> = vec![];
-        let elems = engine.introspector.query(&self.target(styles).0);
+        let mut target = self.target(styles).0.clone();
+        if self.scope(styles) == OutlineScope::Section {
+            target = self.restrict_to_section(engine, target);
+        }
+        let elems = engine.introspector.query(&target);
 
         for elem in &elems {
             let Some(entry) = OutlineEntry::from_outlinable(
@@ -258,6 +301,46 @@ impl Show for Packed<OutlineElem> {
     }
 }
 
+impl Packed<OutlineElem> {
+    /// Restricts a selector to the elements between the heading preceding the
+    /// outline and the next heading of the same or a higher level.
+    fn restrict_to_section(&self, engine: &Engine, target: Selector) -> Selector {
+        let headings = select_where!(HeadingElem, Outlined => true);
+        let location = self.location().unwrap();
+        let Some(current) = engine
+            .introspector
+            .query(&headings.clone().before(location.into(), false))
+            .last()
+            .cloned()
+        else {
+            return target;
+        };
+
+        let start = current.location().unwrap();
+        let level = current
+            .to_packed::<HeadingElem>()
+            .unwrap()
+            .resolve_level(StyleChain::default());
+        let next = engine
+            .introspector
+            .query(&headings.after(start.into(), false))
+            .into_iter()
+            .find(|heading| {
+                heading
+                    .to_packed::<HeadingElem>()
+                    .unwrap()
+                    .resolve_level(StyleChain::default())
+                    <= level
+            });
+
+        let target = target.after(start.into(), false);
+        match next {
+            Some(next) => target.before(next.location().unwrap().into(), false),
+            None => target,
+        }
+    }
+}
+
 impl ShowSet for Packed<OutlineElem> {
     fn show_set(&self, _: StyleChain) -> Styles {
         let mut out = Styles::new();
@@ -272,6 +355,15 @@ impl LocalName for Packed<OutlineElem> {
     const KEY: &'static str = "outline";
 }
 
+/// Which part of the document an outline lists elements from.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Cast)]
+pub enum OutlineScope {
+    /// All elements in the document.
+    Document,
+    /// Only the elements in the section the outline is placed in.
+    Section,
+}
+
 /// Marks an element as being able to be outlined. This is used to implement the
 /// `#outline()` element.
 pub trait Outlinable: Refable {
@@ -456,7 +548,7 @@ impl OutlineEntry {
         styles: StyleChain,
     ) -> SourceResult<Option<Self>> {
         let Some(outlinable) = elem.with::<dyn Outlinable>() else {
-            bail!(span, "cannot outline {}", elem.func().name());
+            bail!(span, "cannot outline {}", elem.elem().name());
         };
 
         let Some(body) = outlinable.outline(engine, styles)? else {
@@ -491,11 +583,11 @@ impl Show for Packed<OutlineEntry> {
         let Some(location) = elem.location() else {
             if elem.can::<dyn Locatable>() && elem.can::<dyn Outlinable>() {
                 bail!(
-                    self.span(), "{} must have a location", elem.func().name();
+                    self.span(), "{} must have a location", elem.elem().name();
                     hint: "try using a query or a show rule to customize the outline.entry instead",
                 )
             } else {
-                bail!(self.span(), "cannot outline {}", elem.func().name())
+                bail!(self.span(), "cannot outline {}", elem.elem().name())
             }
         };
 
@@ -1,5 +1,5 @@
 use comemo::Track;
-use ecow::eco_format;
+use ecow::{eco_format, EcoString};
 
 use crate::diag::{bail, At, Hint, SourceResult};
 use crate::engine::Engine;
@@ -8,7 +8,7 @@ use crate::foundations::{
     Smart, StyleChain, Synthesize,
 };
 use crate::introspection::{Counter, Locatable};
-use crate::math::EquationElem;
+use crate::math::{EquationElem, EquationTagElem};
 use crate::model::{
     BibliographyElem, CiteElem, Destination, Figurable, FootnoteElem, Numbering,
 };
@@ -188,10 +188,10 @@ impl Show for Packed<RefElem> {
                 if elem.can::<dyn Figurable>() {
                     eco_format!(
                         "cannot reference {} directly, try putting it into a figure",
-                        elem.func().name()
+                        elem.elem().name()
                     )
                 } else {
-                    eco_format!("cannot reference {}", elem.func().name())
+                    eco_format!("cannot reference {}", elem.elem().name())
                 }
             })
             .at(span)?;
@@ -199,17 +199,9 @@ impl Show for Packed<RefElem> {
         let numbering = refable
             .numbering()
             .ok_or_else(|| {
-                eco_format!("cannot reference {} without numbering", elem.func().name())
+                eco_format!("cannot reference {} without numbering", elem.elem().name())
             })
-            .hint(eco_format!(
-                "you can enable {} numbering with `#set {}(numbering: \"1.\")`",
-                elem.func().name(),
-                if elem.func() == EquationElem::elem() {
-                    "math.equation"
-                } else {
-                    elem.func().name()
-                }
-            ))
+            .hint(numbering_hint(&elem))
             .at(span)?;
 
         let loc = elem.location().unwrap();
@@ -237,6 +229,25 @@ impl Show for Packed<RefElem> {
     }
 }
 
+/// A hint for how to enable the numbering of a referenced element.
+fn numbering_hint(elem: &Content) -> EcoString {
+    if elem.elem() == EquationTagElem::elem() {
+        return "lines of equations are numbered with \
+                `#set math.equation(numbering: \"(1)\", line-numbering: \"each\")`"
+            .into();
+    }
+
+    eco_format!(
+        "you can enable {} numbering with `#set {}(numbering: \"1.\")`",
+        elem.elem().name(),
+        if elem.elem() == EquationElem::elem() {
+            "math.equation"
+        } else {
+            elem.elem().name()
+        }
+    )
+}
+
 /// Turn a reference into a citation.
 fn to_citation(
     reference: &Packed<RefElem>,
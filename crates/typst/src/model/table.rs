@@ -1,24 +1,31 @@
+use std::cmp::Ordering;
 use std::num::NonZeroUsize;
 use std::sync::Arc;
 
+use comemo::Tracked;
 use ecow::eco_format;
+use indexmap::IndexMap;
 
-use crate::diag::{bail, HintedStrResult, HintedString, SourceResult, Trace, Tracepoint};
+use crate::diag::{
+    bail, At, HintedStrResult, HintedString, SourceResult, StrResult, Trace, Tracepoint,
+};
 use crate::engine::Engine;
+use crate::eval::ops;
 use crate::foundations::{
-    cast, elem, scope, Content, Fold, NativeElement, Packed, Show, Smart, StyleChain,
+    cast, elem, func, repr, scope, Content, Context, Dict, Fold, Func, NativeElement,
+    Packed, Show, Smart, Str, StyleChain, Type, Value,
 };
 use crate::introspection::Locator;
 use crate::layout::{
-    show_grid_cell, Abs, Alignment, Axes, BlockElem, Cell, CellGrid, Celled, Dir,
-    Fragment, GridCell, GridFooter, GridHLine, GridHeader, GridLayouter, GridVLine,
-    Length, LinePosition, OuterHAlignment, OuterVAlignment, Regions, Rel, ResolvableCell,
-    ResolvableGridChild, ResolvableGridItem, Sides, TrackSizings,
+    show_grid_cell, split_at_char, Abs, Alignment, Axes, BlockElem, Cell, CellGrid,
+    Celled, Dir, Fragment, GridCell, GridFooter, GridHLine, GridHeader, GridLayouter,
+    GridVLine, Length, LinePosition, OuterHAlignment, OuterVAlignment, Regions, Rel,
+    ResolvableCell, ResolvableGridChild, ResolvableGridItem, Sides, Sizing, TrackSizings,
 };
-use crate::model::Figurable;
-use crate::syntax::Span;
+use crate::model::{Figurable, StrongElem};
+use crate::syntax::{Span, Spanned};
 use crate::text::{LocalName, TextElem};
-use crate::utils::NonZeroExt;
+use crate::utils::{hash128, NonZeroExt};
 use crate::visualize::{Paint, Stroke};
 
 /// A table of items.
@@ -196,6 +203,33 @@ pub struct TableElem {
     #[borrowed]
     pub align: Celled<Smart<Alignment>>,
 
+    /// A character on which to align the cells' content.
+    ///
+    /// This can either be a single character, an array of characters
+    /// (corresponding to each column) or a function that returns a character.
+    /// The cells of a column are then lined up on the first occurrence of the
+    /// character in their text, which makes it possible to align numbers on
+    /// their decimal separator. Cells without the character are aligned as if
+    /// it followed their content. The cells' [alignment]($table.align) then
+    /// positions the lined up content within the column. When set to
+    /// `{auto}`, the cells are aligned on a period, which is the decimal
+    /// separator in many languages.
+    ///
+    /// ```example
+    /// #table(
+    ///   columns: 2,
+    ///   align: (left, right),
+    ///   char-align: (x, y) => if x == 1 and y > 0 { auto },
+    ///   [*Item*], [*Amount*],
+    ///   [Rent], [1250.00],
+    ///   [Refund], [−84.5],
+    ///   [Fees], [(12.75)],
+    ///   [Interest], [3#footnote[Estimated.]],
+    /// )
+    /// ```
+    #[borrowed]
+    pub char_align: Celled<Option<Smart<char>>>,
+
     /// How to [stroke] the cells.
     ///
     /// Strokes can be disabled by setting this to `{none}`.
@@ -236,6 +270,36 @@ pub struct TableElem {
     #[default(Celled::Value(Sides::splat(Some(Abs::pt(5.0).into()))))]
     pub inset: Celled<Sides<Option<Rel<Length>>>>,
 
+    /// Whether the table may break horizontally.
+    ///
+    /// By default, columns that do not fit into the available width overflow
+    /// the page. When this is enabled, the columns are instead split into
+    /// vertical slices which are placed on consecutive pages. The table only
+    /// breaks between columns that are not spanned by a common cell. Any
+    /// repeated [header]($table.header) and [footer]($table.footer) rows are
+    /// shown on every slice.
+    ///
+    /// ```example
+    /// #set page(width: 160pt, height: 80pt)
+    /// #table(
+    ///   columns: 6,
+    ///   split-columns: true,
+    ///   repeat-columns: 1,
+    ///   [*Year*], ..range(2019, 2024).map(str),
+    ///   [*Sales*], ..range(5).map(n => str(n * 17 + 40)),
+    /// )
+    /// ```
+    #[default(false)]
+    pub split_columns: bool,
+
+    /// How many leading columns to repeat on every slice of a table that is
+    /// [split horizontally]($table.split-columns).
+    ///
+    /// This is useful for columns with row labels, which would otherwise only
+    /// be visible in the first slice.
+    #[default(0)]
+    pub repeat_columns: usize,
+
     /// The contents of the table cells, plus any extra table lines specified
     /// with the [`table.hline`]($table.hline) and
     /// [`table.vline`]($table.vline) elements.
@@ -259,6 +323,225 @@ impl TableElem {
 
     #[elem]
     type TableFooter;
+
+    /// Builds a table from tabular data, such as the rows returned by
+    /// [`csv`] or [`json`].
+    ///
+    /// Each row is either a dictionary or an array. The `columns` select which
+    /// fields to show and how to display them. A header row with the column
+    /// headers is added automatically and repeats across pages.
+    ///
+    /// Rows can be sorted by a field and grouped by another one. Each group
+    /// starts with a [sub-header]($table.header.level) that shows the field's
+    /// value and is repeated when the group continues on a new page. Columns
+    /// marked with `subtotal` are summed up at the end of each group.
+    ///
+    /// The resulting table can be styled like any other table, for example
+    /// with [set rules]($styling/#set-rules).
+    ///
+    /// ```example
+    /// #let fruit = (
+    ///   (name: "Apple", kind: "Pome", price: "0.80"),
+    ///   (name: "Pear", kind: "Pome", price: "1.10"),
+    ///   (name: "Cherry", kind: "Drupe", price: "4.00"),
+    ///   (name: "Plum", kind: "Drupe", price: "2.20"),
+    /// )
+    ///
+    /// #table.from-data(
+    ///   fruit,
+    ///   columns: (
+    ///     (key: "name", header: [*Fruit*], width: 1fr),
+    ///     (
+    ///       key: "price",
+    ///       header: [*Price*],
+    ///       type: float,
+    ///       digits: 2,
+    ///       align: right,
+    ///       subtotal: true,
+    ///     ),
+    ///   ),
+    ///   sort-by: "name",
+    ///   group-by: "kind",
+    /// )
+    /// ```
+    #[func(title = "Table from Data")]
+    pub fn from_data(
+        /// The engine.
+        engine: &mut Engine,
+        /// The callsite context.
+        context: Tracked<Context>,
+        /// The rows of the table. Each row is a dictionary whose fields are
+        /// selected by string keys or an array whose fields are selected by
+        /// their index.
+        rows: Spanned<Vec<Value>>,
+        /// The columns of the table.
+        ///
+        /// Each column is either a key selecting a field in each row or a
+        /// dictionary with the following entries:
+        ///
+        /// - `key`: The key of the field shown in the column. Required.
+        /// - `header`: The header of the column. Defaults to the key for
+        ///   string keys and to no header for integer keys.
+        /// - `type`: A type the field's values are converted to with the
+        ///   type's constructor, for instance `{float}` to compute with the
+        ///   strings read by [`csv`]. Empty strings are converted to `{none}`.
+        /// - `format`: A function which receives a value and returns how it
+        ///   should be displayed.
+        /// - `digits`: If the values are numbers and there is no `format`
+        ///   function, how many digits to show after the decimal separator.
+        /// - `align`: How to align the column's cells.
+        /// - `width`: The width of the column, like in the table's
+        ///   [`columns`]($table.columns) argument.
+        /// - `subtotal`: Whether to show the sum of the column's values at
+        ///   the end of each group.
+        ///
+        /// When set to `{auto}`, shows all fields of the first row.
+        #[named]
+        #[default]
+        columns: Smart<Vec<TableColumn>>,
+        /// The key of the field to sort the rows by. The sorting is stable.
+        #[named]
+        #[default]
+        sort_by: Option<DataKey>,
+        /// Whether to sort in descending order.
+        #[named]
+        #[default(false)]
+        descending: bool,
+        /// The key of the field to group the rows by. Groups appear in the
+        /// order in which their first row appears after sorting.
+        #[named]
+        #[default]
+        group_by: Option<DataKey>,
+    ) -> SourceResult<Content> {
+        let Spanned { v: rows, span } = rows;
+        let columns = match columns {
+            Smart::Custom(columns) => columns,
+            Smart::Auto => default_columns(rows.first()).at(span)?,
+        };
+        if columns.is_empty() {
+            bail!(span, "at least one column is required");
+        }
+
+        // Extract and convert the fields of each row once, so that sorting
+        // and grouping can work with the converted values.
+        let mut records = rows
+            .iter()
+            .map(|row| {
+                let fields = columns
+                    .iter()
+                    .map(|column| {
+                        let value = field(row, &column.key).at(span)?;
+                        column.convert(engine, context, span, value)
+                    })
+                    .collect::<SourceResult<Vec<_>>>()?;
+                let group = group_by
+                    .as_ref()
+                    .map(|key| field_or_column(row, key, &columns, &fields))
+                    .transpose()
+                    .at(span)?
+                    .unwrap_or_default();
+                let sort = sort_by
+                    .as_ref()
+                    .map(|key| field_or_column(row, key, &columns, &fields))
+                    .transpose()
+                    .at(span)?
+                    .unwrap_or_default();
+                Ok((group, sort, fields))
+            })
+            .collect::<SourceResult<Vec<_>>>()?;
+
+        if sort_by.is_some() {
+            let mut result = Ok(());
+            records.sort_by(|(_, a, _), (_, b, _)| match (a, b) {
+                // Missing values always come last.
+                (Value::None, Value::None) => Ordering::Equal,
+                (Value::None, _) => Ordering::Greater,
+                (_, Value::None) => Ordering::Less,
+                _ => {
+                    let ordering = ops::compare(a, b).unwrap_or_else(|err| {
+                        if result.is_ok() {
+                            result = Err(err).at(span);
+                        }
+                        Ordering::Equal
+                    });
+                    if descending {
+                        ordering.reverse()
+                    } else {
+                        ordering
+                    }
+                }
+            });
+            result?;
+        }
+
+        // Values are not `Eq`, so we group by their hash instead.
+        let mut groups: IndexMap<u128, (Value, Vec<Vec<Value>>)> = IndexMap::new();
+        for (group, _, fields) in records {
+            groups
+                .entry(hash128(&group))
+                .or_insert_with(|| (group, vec![]))
+                .1
+                .push(fields);
+        }
+
+        let mut children = vec![];
+        if columns.iter().any(|column| column.header.is_some()) {
+            let cells = columns.iter().map(|column| {
+                let body = column.header.clone().unwrap_or_default();
+                TableItem::Cell(column.cell(body, span))
+            });
+            children.push(TableChild::Header(
+                Packed::new(TableHeader::new(cells.collect())).spanned(span),
+            ));
+        }
+
+        let colspan = NonZeroUsize::new(columns.len()).unwrap();
+        let subtotals = columns.iter().any(|column| column.subtotal);
+        for (group, rows) in groups.into_values() {
+            if group_by.is_some() {
+                let cell = TableCell::new(group.display()).with_colspan(colspan);
+                let header = TableHeader::new(vec![TableItem::Cell(
+                    Packed::new(cell).spanned(span),
+                )])
+                .with_level(NonZeroUsize::new(2).unwrap());
+                children.push(TableChild::Header(Packed::new(header).spanned(span)));
+            }
+
+            for (column, value) in
+                rows.iter().flat_map(|fields| columns.iter().zip(fields))
+            {
+                let body = column.display(engine, context, value.clone())?;
+                children.push(TableChild::Item(TableItem::Cell(column.cell(body, span))));
+            }
+
+            if group_by.is_some() && subtotals {
+                for (x, column) in columns.iter().enumerate() {
+                    let mut body = Content::empty();
+                    if column.subtotal {
+                        let mut sum = Value::None;
+                        for value in rows.iter().map(|fields| &fields[x]) {
+                            if !matches!(value, Value::None) {
+                                sum = ops::add(sum, value.clone()).at(span)?;
+                            }
+                        }
+                        body =
+                            StrongElem::new(column.display(engine, context, sum)?).pack();
+                    }
+                    children
+                        .push(TableChild::Item(TableItem::Cell(column.cell(body, span))));
+                }
+            }
+        }
+
+        // Only explicit widths are set, such that the columns are otherwise
+        // sized like those of a table with a number of columns.
+        let widths = columns.iter().map(|column| column.width.unwrap_or(Sizing::Auto));
+
+        Ok(TableElem::new(children)
+            .with_columns(TrackSizings(widths.collect()))
+            .pack()
+            .spanned(span))
+    }
 }
 
 impl Show for Packed<TableElem> {
@@ -280,6 +563,7 @@ fn layout_table(
 ) -> SourceResult<Fragment> {
     let inset = elem.inset(styles);
     let align = elem.align(styles);
+    let char_align = elem.char_align(styles);
     let columns = elem.columns(styles);
     let rows = elem.rows(styles);
     let column_gutter = elem.column_gutter(styles);
@@ -295,6 +579,7 @@ fn layout_table(
     let children = elem.children().iter().map(|child| match child {
         TableChild::Header(header) => ResolvableGridChild::Header {
             repeat: header.repeat(styles),
+            level: header.level(styles),
             span: header.span(),
             items: header.children().iter().map(resolve_item),
         },
@@ -312,6 +597,7 @@ fn layout_table(
         children,
         fill,
         align,
+        char_align,
         &inset,
         &stroke,
         engine,
@@ -320,7 +606,10 @@ fn layout_table(
     )
     .trace(engine.world, tracepoint, elem.span())?;
 
-    let layouter = GridLayouter::new(&grid, regions, styles, elem.span());
+    let mut layouter = GridLayouter::new(&grid, regions, styles, elem.span());
+    if elem.split_columns(styles) {
+        layouter = layouter.with_column_split(elem.repeat_columns(styles));
+    }
     layouter.layout(engine)
 }
 
@@ -530,6 +819,54 @@ pub struct TableHeader {
     #[default(true)]
     pub repeat: bool,
 
+    /// The level of this header.
+    ///
+    /// A header of level 1 must start at the first row. Headers of a higher
+    /// level, called sub-headers, can start at any row. When a group of rows
+    /// continues on a new page, its sub-header is repeated there below the
+    /// main header. A sub-header stops repeating once a header of the same or
+    /// a lower level is reached.
+    ///
+    /// ```example
+    /// #set page(height: 12em)
+    /// #table(
+    ///   columns: 2,
+    ///   table.header[*Item*][*Price*],
+    ///   table.header(level: 2, table.cell(colspan: 2)[_Fruit_]),
+    ///   [Apple], [1.20],
+    ///   [Banana], [0.80],
+    ///   [Cherry], [4.00],
+    ///   table.header(level: 2, table.cell(colspan: 2)[_Vegetables_]),
+    ///   [Carrot], [0.60],
+    ///   [Potato], [1.10],
+    /// )
+    /// ```
+    #[default(NonZeroUsize::ONE)]
+    pub level: NonZeroUsize,
+
+    /// Whether the header is being repeated on a page after the one where it
+    /// first appeared.
+    ///
+    /// This is set by the table and can be read in a
+    /// [context expression]($context) within the header's cells, for example
+    /// to mark the table as continued.
+    ///
+    /// ```example
+    /// #set page(height: 8em)
+    /// #table(
+    ///   columns: 2,
+    ///   table.header(
+    ///     table.cell(colspan: 2, context {
+    ///       [*Table 3*]
+    ///       if table.header.continued [ (continued)]
+    ///     }),
+    ///   ),
+    ///   ..range(8).map(str),
+    /// )
+    /// ```
+    #[default(false)]
+    pub continued: bool,
+
     /// The cells and lines within the header.
     #[variadic]
     pub children: Vec<TableItem>,
@@ -549,6 +886,15 @@ pub struct TableFooter {
     #[default(true)]
     pub repeat: bool,
 
+    /// Whether the table continues on the next page after this instance of
+    /// the footer.
+    ///
+    /// This is set by the table and can be read in a
+    /// [context expression]($context) within the footer's cells, much like
+    /// the [`continued`]($table.header.continued) field of the header.
+    #[default(false)]
+    pub continued: bool,
+
     /// The cells and lines within the footer.
     #[variadic]
     pub children: Vec<TableItem>,
@@ -778,6 +1124,9 @@ pub struct TableCell {
     /// The cell's [alignment]($table.align) override.
     pub align: Smart<Alignment>,
 
+    /// The cell's [character alignment]($table.char-align) override.
+    pub char_align: Smart<Option<char>>,
+
     /// The cell's [inset]($table.inset) override.
     pub inset: Smart<Sides<Option<Rel<Length>>>>,
 
@@ -811,6 +1160,7 @@ impl ResolvableCell for Packed<TableCell> {
         y: usize,
         fill: &Option<Paint>,
         align: Smart<Alignment>,
+        char_align: Option<char>,
         inset: Sides<Option<Rel<Length>>>,
         stroke: Sides<Option<Option<Arc<Stroke<Abs>>>>>,
         breakable: bool,
@@ -822,6 +1172,12 @@ impl ResolvableCell for Packed<TableCell> {
         let rowspan = cell.rowspan(styles);
         let breakable = cell.breakable(styles).unwrap_or(breakable);
         let fill = cell.fill(styles).unwrap_or_else(|| fill.clone());
+        // Only cells spanning a single column can be lined up with the others
+        // in their column.
+        let char_align = cell
+            .char_align(styles)
+            .unwrap_or(char_align)
+            .filter(|_| colspan.get() == 1);
 
         let cell_stroke = cell.stroke(styles);
         let stroke_overridden =
@@ -849,6 +1205,7 @@ impl ResolvableCell for Packed<TableCell> {
             // the outer alignment when it is effectively displayed).
             Smart::Auto => cell.align(styles),
         });
+        cell.push_char_align(Smart::Custom(char_align));
         cell.push_inset(Smart::Custom(
             cell.inset(styles).map_or(inset, |inner| inner.fold(inset)),
         ));
@@ -866,6 +1223,21 @@ impl ResolvableCell for Packed<TableCell> {
             }),
         );
         cell.push_breakable(Smart::Custom(breakable));
+        // The parts are measured as copies of the cell without inset and
+        // alignment, so that they are subject to the same styles and show rules
+        // as the cell itself.
+        let char_parts = char_align.map(|c| {
+            let (before, after) = split_at_char(self.body(), c);
+            let part = |body: Content| {
+                let mut part = self.clone();
+                part.push_body(body);
+                part.push_align(Smart::Auto);
+                part.push_char_align(Smart::Custom(None));
+                part.push_inset(Smart::Custom(Sides::splat(None)));
+                part.pack()
+            };
+            (c, part(before), part(after))
+        });
         Cell {
             body: self.pack(),
             locator,
@@ -875,6 +1247,7 @@ impl ResolvableCell for Packed<TableCell> {
             stroke,
             stroke_overridden,
             breakable,
+            char_parts,
         }
     }
 
@@ -901,7 +1274,12 @@ impl ResolvableCell for Packed<TableCell> {
 
 impl Show for Packed<TableCell> {
     fn show(&self, _engine: &mut Engine, styles: StyleChain) -> SourceResult<Content> {
-        show_grid_cell(self.body().clone(), self.inset(styles), self.align(styles))
+        show_grid_cell(
+            self.body().clone(),
+            self.inset(styles),
+            self.align(styles),
+            self.char_align(styles),
+        )
     }
 }
 
@@ -911,3 +1289,222 @@ impl From<Content> for TableCell {
         value.unpack::<Self>().unwrap_or_else(Self::new)
     }
 }
+
+/// A key selecting a field in a row of data: a string for dictionary rows and
+/// an integer for array rows.
+#[derive(Debug, Clone, PartialEq, Hash)]
+pub enum DataKey {
+    Index(i64),
+    Name(Str),
+}
+
+cast! {
+    DataKey,
+    self => match self {
+        Self::Index(index) => index.into_value(),
+        Self::Name(name) => name.into_value(),
+    },
+    v: i64 => Self::Index(v),
+    v: Str => Self::Name(v),
+}
+
+/// A column of a table built with [`table.from-data`]($table.from-data).
+#[derive(Debug, Clone, PartialEq, Hash)]
+pub struct TableColumn {
+    /// The field shown in the column.
+    key: DataKey,
+    /// The header of the column.
+    header: Option<Content>,
+    /// The type to convert the field's values to.
+    ty: Option<Type>,
+    /// How to display the field's values.
+    format: Option<Func>,
+    /// How many digits to show after the decimal separator of numbers.
+    digits: Option<u8>,
+    /// How to align the column's cells.
+    align: Smart<Alignment>,
+    /// The width of the column, if given explicitly.
+    width: Smart<Sizing>,
+    /// Whether to sum up the column's values for each group.
+    subtotal: bool,
+}
+
+impl TableColumn {
+    /// Creates a column showing the field with the given key.
+    fn new(key: DataKey) -> Self {
+        let header = match &key {
+            DataKey::Name(name) => Some(TextElem::packed(name.clone())),
+            DataKey::Index(_) => None,
+        };
+        Self {
+            key,
+            header,
+            ty: None,
+            format: None,
+            digits: None,
+            align: Smart::Auto,
+            width: Smart::Auto,
+            subtotal: false,
+        }
+    }
+
+    /// Converts a value read from a row to the column's type.
+    fn convert(
+        &self,
+        engine: &mut Engine,
+        context: Tracked<Context>,
+        span: Span,
+        value: Value,
+    ) -> SourceResult<Value> {
+        let Some(ty) = self.ty else { return Ok(value) };
+        match value {
+            Value::None => Ok(Value::None),
+            Value::Str(s) if s.as_str().trim().is_empty() => Ok(Value::None),
+            value if value.ty() == ty => Ok(value),
+            value => {
+                ty.constructor()
+                    .at(span)?
+                    .spanned(span)
+                    .call(engine, context, [value])
+            }
+        }
+    }
+
+    /// Creates one of the column's cells. Only explicitly aligned columns
+    /// override the alignment of their cells, so that the table's alignment
+    /// applies otherwise.
+    fn cell(&self, body: Content, span: Span) -> Packed<TableCell> {
+        let cell = match self.align {
+            Smart::Custom(align) => TableCell::new(body).with_align(Smart::Custom(align)),
+            Smart::Auto => TableCell::new(body),
+        };
+        Packed::new(cell).spanned(span)
+    }
+
+    /// Turns a value into the content of a cell.
+    fn display(
+        &self,
+        engine: &mut Engine,
+        context: Tracked<Context>,
+        value: Value,
+    ) -> SourceResult<Content> {
+        if let Some(format) = &self.format {
+            return Ok(format.call(engine, context, [value])?.display());
+        }
+
+        Ok(match (value, self.digits) {
+            (Value::None, _) => Content::empty(),
+            (Value::Int(v), Some(digits)) => format_number(v as f64, digits),
+            (Value::Float(v), Some(digits)) => format_number(v, digits),
+            (Value::Decimal(v), Some(digits)) => {
+                Value::Decimal(v.round(digits.into())).display()
+            }
+            (value, _) => value.display(),
+        })
+    }
+}
+
+cast! {
+    TableColumn,
+    self => {
+        let mut dict = Dict::new();
+        dict.insert("key".into(), self.key.into_value());
+        dict.insert("header".into(), self.header.into_value());
+        dict.insert("type".into(), self.ty.into_value());
+        dict.insert("format".into(), self.format.into_value());
+        dict.insert("digits".into(), self.digits.into_value());
+        dict.insert("align".into(), self.align.into_value());
+        dict.insert("width".into(), self.width.into_value());
+        dict.insert("subtotal".into(), self.subtotal.into_value());
+        dict.into_value()
+    },
+    key: DataKey => Self::new(key),
+    mut dict: Dict => {
+        let mut column = Self::new(dict.take("key")?.cast()?);
+        if let Ok(header) = dict.take("header") {
+            column.header = header.cast()?;
+        }
+        if let Ok(ty) = dict.take("type") {
+            column.ty = ty.cast()?;
+        }
+        if let Ok(format) = dict.take("format") {
+            column.format = format.cast()?;
+        }
+        if let Ok(digits) = dict.take("digits") {
+            column.digits = digits.cast()?;
+        }
+        if let Ok(align) = dict.take("align") {
+            column.align = align.cast()?;
+        }
+        if let Ok(width) = dict.take("width") {
+            column.width = width.cast()?;
+        }
+        if let Ok(subtotal) = dict.take("subtotal") {
+            column.subtotal = subtotal.cast()?;
+        }
+        dict.finish(&[
+            "key", "header", "type", "format", "digits", "align", "width", "subtotal",
+        ])?;
+        column
+    },
+}
+
+/// Determines the columns of a data table whose columns were not specified.
+fn default_columns(first: Option<&Value>) -> StrResult<Vec<TableColumn>> {
+    Ok(match first {
+        None => vec![],
+        Some(Value::Dict(dict)) => dict
+            .iter()
+            .map(|(key, _)| TableColumn::new(DataKey::Name(key.clone())))
+            .collect(),
+        Some(Value::Array(array)) => (0..array.len() as i64)
+            .map(|index| TableColumn::new(DataKey::Index(index)))
+            .collect(),
+        Some(v) => bail!("expected array or dictionary, found {}", v.ty()),
+    })
+}
+
+/// Reads the field with the given key from a row, yielding `none` for missing
+/// fields.
+fn field(row: &Value, key: &DataKey) -> StrResult<Value> {
+    Ok(match (row, key) {
+        (Value::Dict(dict), DataKey::Name(name)) => {
+            dict.get(name).cloned().unwrap_or_default()
+        }
+        (Value::Array(array), DataKey::Index(index)) => {
+            array.at(*index, Some(Value::None))?
+        }
+        (Value::Dict(_), DataKey::Index(_)) => {
+            bail!("fields of dictionary rows must be selected by string keys")
+        }
+        (Value::Array(_), DataKey::Name(_)) => {
+            bail!("fields of array rows must be selected by integer keys")
+        }
+        (v, _) => bail!("expected array or dictionary, found {}", v.ty()),
+    })
+}
+
+/// Reads a field from a row, preferring the converted value if the field is
+/// also shown in a column.
+fn field_or_column(
+    row: &Value,
+    key: &DataKey,
+    columns: &[TableColumn],
+    fields: &[Value],
+) -> StrResult<Value> {
+    match columns.iter().position(|column| &column.key == key) {
+        Some(x) => Ok(fields[x].clone()),
+        None => field(row, key),
+    }
+}
+
+/// Displays a number with a fixed amount of digits after the decimal
+/// separator.
+fn format_number(value: f64, digits: u8) -> Content {
+    let text = eco_format!("{:.*}", usize::from(digits), value.abs());
+    if value < 0.0 && text.chars().any(|c| c.is_ascii_digit() && c != '0') {
+        TextElem::packed(eco_format!("{}{text}", repr::MINUS_SIGN))
+    } else {
+        TextElem::packed(text)
+    }
+}
@@ -363,7 +363,7 @@ fn func_model(
     }
 
     let mut returns = vec![];
-    casts(resolver, &mut returns, &mut vec![], func.returns().unwrap());
+    casts(resolver, &mut returns, &mut vec![], &func.returns().unwrap());
     returns.sort_by_key(|ty| type_index(ty));
     if returns == ["none"] {
         returns.clear();
@@ -403,7 +403,7 @@ fn param_model(resolver: &dyn Resolver, info: &ParamInfo) -> ParamModel {
     types.sort_by_key(|ty| type_index(ty));
 
     ParamModel {
-        name: info.name,
+        name: info.name.clone(),
         details: Html::markdown(resolver, details, None),
         example: example.map(|md| Html::markdown(resolver, md, None)),
         types,
@@ -484,8 +484,8 @@ fn func_outline(model: &FuncModel, id_base: &str) -> Vec<OutlineItem> {
                     .params
                     .iter()
                     .map(|param| OutlineItem {
-                        id: eco_format!("parameters-{}", urlify(param.name)),
-                        name: param.name.into(),
+                        id: eco_format!("parameters-{}", urlify(&param.name)),
+                        name: param.name.clone(),
                         children: vec![],
                     })
                     .collect(),
@@ -495,8 +495,8 @@ fn func_outline(model: &FuncModel, id_base: &str) -> Vec<OutlineItem> {
         outline.extend(scope_outline(&model.scope));
     } else {
         outline.extend(model.params.iter().map(|param| OutlineItem {
-            id: eco_format!("{id_base}-{}", urlify(param.name)),
-            name: param.name.into(),
+            id: eco_format!("{id_base}-{}", urlify(&param.name)),
+            name: param.name.clone(),
             children: vec![],
         }));
     }
@@ -102,7 +102,7 @@ pub struct FuncModel {
 /// Details about a function parameter.
 #[derive(Debug, Serialize)]
 pub struct ParamModel {
-    pub name: &'static str,
+    pub name: EcoString,
     pub details: Html,
     pub example: Option<Html>,
     pub types: Vec<&'static str>,
@@ -0,0 +1,24 @@
+;; A plugin of protocol version 2, whose arguments and results are CBOR.
+(module
+  (import "typst_env" "wasm_minimal_protocol_send_result_to_host"
+    (func $send (param i32 i32)))
+  (import "typst_env" "wasm_minimal_protocol_write_args_to_buffer"
+    (func $args (param i32)))
+  (memory (export "memory") 1)
+
+  ;; A lone CBOR break code, which is not a valid data item.
+  (data (i32.const 0) "\ff")
+
+  (func (export "typst_protocol_version") (result i32)
+    (i32.const 2))
+
+  ;; Returns its argument unchanged.
+  (func (export "echo") (param $len i32) (result i32)
+    (call $args (i32.const 16))
+    (call $send (i32.const 16) (local.get $len))
+    (i32.const 0))
+
+  ;; Returns bytes that aren't valid CBOR.
+  (func (export "invalid") (result i32)
+    (call $send (i32.const 0) (i32.const 1))
+    (i32.const 0)))
@@ -0,0 +1,46 @@
+;; A plugin of protocol version 1 that forwards requests to the host. Each
+;; function receives a UTF-8 string as its only argument.
+(module
+  (import "typst_env" "wasm_minimal_protocol_send_result_to_host"
+    (func $send (param i32 i32)))
+  (import "typst_env" "wasm_minimal_protocol_write_args_to_buffer"
+    (func $args (param i32)))
+  (import "typst_env" "typst_read_file"
+    (func $read_file (param i32 i32) (result i32)))
+  (import "typst_env" "typst_query_fonts"
+    (func $query_fonts (param i32 i32) (result i32)))
+  (import "typst_env" "typst_response_len"
+    (func $response_len (result i32)))
+  (import "typst_env" "typst_write_response"
+    (func $write_response (param i32)))
+  (import "typst_env" "typst_warn"
+    (func $warn (param i32 i32)))
+  (memory (export "memory") 1)
+
+  ;; Sends the response to the last request back to the host as the result.
+  (func $respond
+    (call $write_response (i32.const 1024))
+    (call $send (i32.const 1024) (call $response_len)))
+
+  ;; Reads the file at the given path. Fails with the host's error message if
+  ;; the file can't be read.
+  (func (export "read") (param $len i32) (result i32)
+    (local $status i32)
+    (call $args (i32.const 0))
+    (local.set $status (call $read_file (i32.const 0) (local.get $len)))
+    (call $respond)
+    (local.get $status))
+
+  ;; Returns the CBOR encoded fonts of the given family.
+  (func (export "fonts") (param $len i32) (result i32)
+    (call $args (i32.const 0))
+    (drop (call $query_fonts (i32.const 0) (local.get $len)))
+    (call $respond)
+    (i32.const 0))
+
+  ;; Emits the given message as a warning and returns nothing.
+  (func (export "warn") (param $len i32) (result i32)
+    (call $args (i32.const 0))
+    (call $warn (i32.const 0) (local.get $len))
+    (call $send (i32.const 0) (i32.const 0))
+    (i32.const 0)))
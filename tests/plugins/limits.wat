@@ -0,0 +1,15 @@
+;; A plugin of protocol version 1 whose functions exceed their budgets.
+(module
+  (memory (export "memory") 1)
+
+  ;; Loops forever.
+  (func (export "spin") (result i32)
+    (loop $again
+      (br $again))
+    (i32.const 0))
+
+  ;; Tries to grow the memory to 1 GiB and traps when that fails.
+  (func (export "grow") (result i32)
+    (if (i32.eq (memory.grow (i32.const 16384)) (i32.const -1))
+      (then (unreachable)))
+    (i32.const 0)))
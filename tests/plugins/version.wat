@@ -0,0 +1,6 @@
+;; A plugin that requires a protocol version that doesn't exist.
+(module
+  (memory (export "memory") 1)
+
+  (func (export "typst_protocol_version") (result i32)
+    (i32.const 3)))
@@ -91,3 +91,134 @@
 --- datetime-display-insufficient-information ---
 // Error: 2-36 failed to format datetime (insufficient information)
 #datetime.today().display("[hour]")
+
+--- datetime-constructor-iso ---
+#test(datetime("2024-10-17"), datetime(year: 2024, month: 10, day: 17))
+#test(datetime("09:30"), datetime(hour: 9, minute: 30, second: 0))
+#test(datetime("09:30:15.75"), datetime(hour: 9, minute: 30, second: 15))
+#test(
+  datetime("2024-10-17T09:30:15"),
+  datetime(year: 2024, month: 10, day: 17, hour: 9, minute: 30, second: 15),
+)
+#test(
+  datetime("2024-10-17 09:30:15"),
+  datetime(year: 2024, month: 10, day: 17, hour: 9, minute: 30, second: 15),
+)
+#test(
+  datetime("2024-10-17T09:30:15+02:00"),
+  datetime(
+    year: 2024, month: 10, day: 17,
+    hour: 9, minute: 30, second: 15,
+    offset: 2,
+  ),
+)
+#test(datetime("2024-10-17T09:30:15Z").offset(), duration())
+#test(datetime("2024-10-17T09:30-0530").offset(), -duration(hours: 5, minutes: 30))
+#test(datetime("2024-10-17T09:30+01").offset(), duration(hours: 1))
+#test(datetime("2024-10-17T09:30:15").offset(), none)
+
+--- datetime-constructor-iso-invalid-date ---
+// Error: 2-24 invalid date: 2024-13-01
+#datetime("2024-13-01")
+
+--- datetime-constructor-iso-invalid-time ---
+// Error: 2-18 invalid time: 9:30
+#datetime("9:30")
+
+--- datetime-constructor-iso-invalid-offset ---
+// Error: 2-35 invalid offset: +2:00
+#datetime("2024-10-17T09:30+2:00")
+
+--- datetime-constructor-iso-time-offset ---
+// Error: 2-20 a time without a date cannot have an offset
+#datetime("09:30Z")
+
+--- datetime-constructor-iso-with-components ---
+// Error: 2-36 cannot specify components along with an ISO 8601 string
+#datetime("2024-10-17", year: 2025)
+
+--- datetime-constructor-offset-without-time ---
+// Error: 2-60 only a datetime with both a date and a time can have an offset
+#datetime(year: 2024, month: 10, day: 17, offset: "+01:00")
+
+--- datetime-offset ---
+#let d = datetime("2026-10-17T09:30:00+02:00")
+#test(d.display(), "2026-10-17 09:30:00 +02:00")
+#test(d.offset(), duration(hours: 2))
+#test(d.to-offset(0).display(), "2026-10-17 07:30:00 +00:00")
+#test(d.to-offset(-10).display(), "2026-10-16 21:30:00 -10:00")
+#test(d.to-offset("+05:30").hour(), 13)
+#test(d.to-offset(duration(hours: 9)).day(), 17)
+#test(repr(d).contains("offset: duration(hours: 2)"), true)
+
+// Comparison and arithmetic work on the point in time.
+#test(d < datetime("2026-10-17T08:00:00Z"), true)
+#test(datetime("2026-10-17T08:00:00Z") - d, duration(minutes: 30))
+#test((d + duration(hours: 15)).display(), "2026-10-18 00:30:00 +02:00")
+
+// The same point in time at different offsets is not equal, but ordered by
+// the offset, so that ordering stays consistent with equality.
+#test(d == d.to-offset(0), false)
+#test(d == d.to-offset(0).to-offset(2), true)
+#test(d.to-offset(0) < d, true)
+#test(d <= d.to-offset(0), false)
+#test((d, d.to-offset(0)).dedup().len(), 2)
+
+--- datetime-to-offset-without-offset ---
+// Error: 2-55 cannot convert a date without an offset to another offset
+#datetime(year: 2024, month: 10, day: 17).to-offset(0)
+
+--- datetime-offset-invalid ---
+// Error: 80-82 offset is invalid
+#datetime(year: 2024, month: 1, day: 1, hour: 0, minute: 0, second: 0, offset: 30)
+
+--- datetime-display-length ---
+#let d = datetime(year: 2026, month: 10, day: 7)
+#let t = datetime(year: 2026, month: 10, day: 7, hour: 14, minute: 5, second: 9)
+#context {
+  test(d.display(length: "short"), "10/7/26")
+  test(d.display(length: "medium"), "Oct 7, 2026")
+  test(d.display(length: "long"), "October 7, 2026")
+  test(d.display(length: "full"), "Wednesday, October 7, 2026")
+  test(t.display(length: "short"), "10/7/26, 2:05 PM")
+  test(datetime(hour: 9, minute: 0, second: 0).display(length: "long"), "9:00:00 AM")
+}
+
+#set text(region: "gb")
+#context test(d.display(length: "long"), "7 October 2026")
+#context test(t.display(length: "short"), "07/10/2026, 14:05")
+
+#set text(lang: "de", region: none)
+#context {
+  test(d.display(length: "short"), "07.10.26")
+  test(d.display(length: "medium"), "07.10.2026")
+  test(d.display(length: "long"), "7. Oktober 2026")
+  test(d.display(length: "full"), "Mittwoch, 7. Oktober 2026")
+  test(t.display(length: "medium"), "07.10.2026 14:05:09")
+  test(
+    datetime("2026-10-07T14:05:09+02:00").display(length: "long"),
+    "7. Oktober 2026 14:05:09 UTC+02:00",
+  )
+}
+
+#set text(lang: "es")
+#context test(d.display(length: "full"), "miércoles, 7 de octubre de 2026")
+
+#set text(lang: "ja")
+#context test(d.display(length: "full"), "2026年10月7日水曜日")
+
+--- datetime-display-localized-names ---
+#let d = datetime(year: 2026, month: 3, day: 1)
+#test(d.display("[month repr:long]"), "March")
+#test(d.display("[weekday] [month repr:short]", lang: "fr"), "dimanche mars")
+#test(d.display("[weekday repr:short], [month repr:long]", lang: "de"), "So., März")
+#test(d.display("[month repr:numerical]", lang: "de"), "03")
+
+#set text(lang: "it")
+// Without context, English is used.
+#test(d.display("[month repr:long]"), "March")
+#context test(d.display("[month repr:long]"), "marzo")
+
+--- datetime-display-pattern-and-length ---
+// Error: 2-74 cannot specify both a pattern and a length
+#datetime(year: 2026, month: 3, day: 1).display("[year]", length: "long")
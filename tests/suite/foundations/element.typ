@@ -0,0 +1,108 @@
+--- element-basic ---
+#let note = element(
+  "note",
+  fields: (
+    body: (type: content, required: true),
+    kind: (type: str, default: "Note"),
+  ),
+  display: it => block(stroke: 0.5pt, inset: 6pt)[*#it.kind:* #it.body],
+)
+
+#note[Plain note.]
+#note(kind: "Warning")[With a kind.]
+
+#set note(kind: "Tip")
+#note[Set by a set rule.]
+
+#show note.where(kind: "Warning"): set text(red)
+#note(kind: "Warning")[Styled by a show rule.]
+
+--- element-show-rule ---
+#let badge = element("badge", fields: (label-text: (type: str, required: true)))
+#show badge: it => box(fill: aqua, inset: 2pt, it.label-text)
+Status: #badge("passing")
+
+--- element-fields ---
+#let point = element("point", fields: (
+  x: (type: float, required: true),
+  y: (type: float, default: 0.0),
+  tag: str,
+))
+#let p = point(1)
+#test(p.func(), point)
+#test(p.x, 1.0)
+#test(p.has("y"), false)
+#test(p.fields(), (x: 1.0))
+#test(point(2, y: 3, tag: "a").fields(), (x: 2.0, y: 3.0, tag: "a"))
+#test(repr(point(2, y: 3)), "point(x: 2.0, y: 3.0)")
+#test(p == point(1), true)
+#test(p == point(2), false)
+
+--- element-context-field ---
+#let ex = element("ex", fields: (size: (type: length, default: 1pt)))
+#set ex(size: 5pt)
+#context test(ex.size, 5pt)
+
+--- element-identity ---
+#let make() = element("thing")
+#test(make() == make(), true)
+#test(make() == element("thing"), false)
+#test(element("thing") in (make(),), false)
+
+--- element-redefine ---
+// Defining an element at the same place repeatedly yields the same element,
+// but each element function keeps its own definition.
+#let swatch(color) = element(
+  "swatch",
+  display: it => box(fill: color, width: 1em, height: 1em),
+)
+#let red-swatch = swatch(red)
+#let blue-swatch = swatch(blue)
+#test(red-swatch == blue-swatch, false)
+#test(red-swatch == swatch(red), true)
+#test(red-swatch() == blue-swatch(), false)
+#test(red-swatch().func(), red-swatch)
+#show selector(red-swatch): box.with(stroke: green, inset: 1pt)
+#red-swatch() #blue-swatch()
+
+--- element-query ---
+#let fig = element("fig", fields: (caption: (type: str, required: true)))
+#fig("First")
+#fig("Second")
+#context test(query(fig).map(it => it.caption), ("First", "Second"))
+#context test(query(fig.where(caption: "Second")).len(), 1)
+
+--- element-set-required ---
+#let item = element("item", fields: (body: (type: content, required: true)))
+// Error: 11-13 unexpected argument
+#set item([])
+
+--- element-missing-argument ---
+#let item = element("item", fields: (body: (type: content, required: true)))
+// Error: 2-8 missing argument: body
+#item()
+
+--- element-wrong-type ---
+#let item = element("item", fields: (count: (type: int, default: 1)))
+// Error: 14-19 expected integer, found string
+#item(count: "one")
+
+--- element-bad-default ---
+// Error: 2-63 invalid specification for field `count`: default value does not match type: expected integer, found string
+#element("item", fields: (count: (type: int, default: "one")))
+
+--- element-required-default ---
+// Error: 2-71 invalid specification for field `x`: a required field cannot have a default value
+#element("item", fields: (x: (type: int, required: true, default: 1)))
+
+--- element-reserved-field ---
+// Error: 2-39 field name `label` is reserved
+#element("item", fields: (label: str))
+
+--- element-bad-name ---
+// Error: 2-22 element name must be a valid identifier
+#element("not valid")
+
+--- element-unknown-key ---
+// Error: 2-54 invalid specification for field `x`: unexpected key "kind", valid keys are "type", "default", "required", and "positional"
+#element("item", fields: (x: (type: int, kind: "a")))
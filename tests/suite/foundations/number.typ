@@ -0,0 +1,121 @@
+--- format-number-grouping ---
+#context {
+  test(format-number(0), "0")
+  test(format-number(123), "123")
+  test(format-number(1234567), "1,234,567")
+  test(format-number(-1234567), "\u{2212}1,234,567")
+  test(format-number(1234567, grouping: false), "1234567")
+  test(format-number(1234567.25), "1,234,567.25")
+  test(format-number(decimal("1234.500")), "1,234.500")
+}
+
+--- format-number-precision ---
+#context {
+  test(format-number(3.14159, precision: 2), "3.14")
+  test(format-number(2, precision: 3), "2.000")
+  test(format-number(0.0001, precision: 2), "0.00")
+  test(format-number(-0.001, precision: 2), "0.00")
+  test(format-number(9.999, precision: 2), "10.00")
+  test(format-number(decimal("0.125"), precision: 2), "0.13")
+  test(format-number(1234.5, precision: 0), "1,235")
+}
+
+--- format-number-rounding ---
+#let cases = ("2.5", "3.5", "-2.5", "2.4", "-2.6")
+#let expected = (
+  "half-up": ("3", "4", "−3", "2", "−3"),
+  "half-down": ("2", "3", "−2", "2", "−3"),
+  "half-even": ("2", "4", "−2", "2", "−3"),
+  "up": ("3", "4", "−3", "3", "−3"),
+  "down": ("2", "3", "−2", "2", "−2"),
+  "ceiling": ("3", "4", "−2", "3", "−2"),
+  "floor": ("2", "3", "−3", "2", "−3"),
+)
+#context for (mode, results) in expected {
+  for (n, result) in cases.zip(results) {
+    test(
+      format-number(decimal(n), precision: 0, rounding: mode),
+      result,
+    )
+  }
+}
+
+--- format-number-notation ---
+#context {
+  test(format-number(299792458, notation: "scientific", precision: 3), "2.998\u{a0}×\u{a0}10⁸")
+  test(format-number(0.000123, notation: "scientific"), "1.23\u{a0}×\u{a0}10⁻⁴")
+  test(format-number(0.000123, notation: "engineering"), "123\u{a0}×\u{a0}10⁻⁶")
+  test(format-number(12345, notation: "engineering"), "12.345\u{a0}×\u{a0}10³")
+  test(format-number(9.99, notation: "scientific", precision: 1), "1.0\u{a0}×\u{a0}10¹")
+  test(format-number(999.9, notation: "engineering", precision: 0), "1\u{a0}×\u{a0}10³")
+  test(format-number(0, notation: "scientific"), "0\u{a0}×\u{a0}10⁰")
+}
+
+--- format-number-percent ---
+#context test(format-number(0.256, style: "percent"), "25.6%")
+#context test(format-number(1, style: "percent", precision: 1), "100.0%")
+#set text(lang: "de")
+#context test(format-number(0.5, style: "percent"), "50\u{a0}%")
+#set text(lang: "tr")
+#context test(format-number(0.5, style: "percent"), "%50")
+
+--- format-number-currency ---
+#set text(region: "us")
+#context test(format-number(1234.5, style: "currency"), "$1,234.50")
+#context test(format-number(-3, style: "currency"), "−$3.00")
+#context test(format-number(1234.5, style: "currency", currency: "JPY"), "¥1,235")
+#context test(format-number(10, style: "currency", currency: "CHF"), "CHF\u{a0}10.00")
+#set text(lang: "de", region: "de")
+#context test(format-number(decimal("1234.5"), style: "currency"), "1.234,50\u{a0}€")
+#set text(lang: "de", region: "ch")
+#context test(format-number(1234.5, style: "currency"), "CHF\u{a0}1’234.50")
+#set text(lang: "fr", region: "fr")
+#context test(format-number(1234.5, style: "currency"), "1\u{202f}234,50\u{a0}€")
+
+--- format-number-locale ---
+#let n = 1234567.891
+#context test(format-number(n), "1,234,567.891")
+#set text(lang: "de")
+#context test(format-number(n), "1.234.567,891")
+#set text(lang: "fr")
+#context test(format-number(n), "1\u{202f}234\u{202f}567,891")
+#set text(lang: "sv")
+#context test(format-number(n), "1\u{a0}234\u{a0}567,891")
+#context test(
+  format-number(n, decimal-separator: "·", group-separator: "'"),
+  "1'234'567·891",
+)
+#set text(lang: "en", region: "in")
+#context test(format-number(n, precision: 0), "12,34,568")
+
+--- format-number-unit ---
+#context test(format-number(42.195, unit: "km"), "42.195\u{202f}km")
+#context test(format-number(3e8, notation: "scientific", unit: "m/s"), "3\u{a0}×\u{a0}10⁸\u{202f}m/s")
+
+--- format-number-rendered ---
+#context format-number(1234567.891, precision: 2) \
+#context format-number(0.256, style: "percent") \
+#context format-number(299792458, notation: "scientific", precision: 3, unit: "m/s")
+
+#set text(lang: "de", region: "de")
+#context format-number(1234567.891, precision: 2) \
+#context format-number(decimal("19.9"), style: "currency")
+
+--- format-number-currency-unknown ---
+// Error: 10-45 cannot determine the currency
+// Hint: 10-45 specify it with the `currency` argument or set the text region
+#context format-number(5, style: "currency")
+
+--- format-number-float-nan ---
+// Error: 10-34 cannot format float.nan
+#context format-number(float.nan)
+
+--- format-number-without-context ---
+// Error: 2-18 can only be used when context is known
+// Hint: 2-18 try wrapping this in a `context` expression
+// Hint: 2-18 the `context` expression should wrap everything that depends on this function
+#format-number(1)
+
+--- format-number-bad-rounding ---
+// Error: 53-62 expected "half-up", "half-down", "half-even", "up", "down", "ceiling", or "floor"
+#context format-number(1.5, precision: 0, rounding: "nearest")
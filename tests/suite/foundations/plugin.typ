@@ -45,3 +45,66 @@
 
 // Error: 2-27 plugin tried to write out of bounds: pointer 0x40000000 is out of bounds for write of length 3
 #p.write_oob(bytes("xyz"))
+
+--- plugin-version-2-cbor ---
+#let p = plugin("/tests/plugins/cbor.wasm")
+#let value = (a: 1, b: ("x", 2.5), c: none, d: bytes((1, 2)))
+#test(p.echo(value), value)
+#test(p.echo("hi"), "hi")
+
+--- plugin-version-2-invalid-cbor ---
+#let p = plugin("/tests/plugins/cbor.wasm")
+
+// Error: 2-13 plugin returned a value that is not valid CBOR (Semantic(None, "invalid type: break, expected non-break"))
+#p.invalid()
+
+--- plugin-unsupported-version ---
+// Error: 9-38 plugin requires unsupported protocol version 3
+#plugin("/tests/plugins/version.wasm")
+
+--- plugin-read-file ---
+#let p = plugin("/tests/plugins/host.wasm")
+#test(
+  p.read(bytes("/tests/plugins/host.wat")),
+  read("/tests/plugins/host.wat", encoding: none),
+)
+
+--- plugin-read-file-relative ---
+// Relative paths are resolved relative to the calling file.
+#let p = plugin("/tests/plugins/host.wasm")
+#test(p.read(bytes("plugin.typ")), read("plugin.typ", encoding: none))
+
+--- plugin-read-file-error ---
+#let p = plugin("/tests/plugins/host.wasm")
+
+// Error: 2-30 plugin errored with: file not found (searched at tests/suite/foundations/missing.txt)
+#p.read(bytes("missing.txt"))
+
+--- plugin-query-fonts ---
+#let p = plugin("/tests/plugins/host.wasm")
+#let fonts = cbor.decode(p.fonts(bytes("Libertinus Serif")))
+#test(fonts.len() > 0, true)
+#test(fonts.all(font => font.family == "Libertinus Serif"), true)
+#test(fonts.first().keys(), ("family", "style", "weight", "stretch"))
+#test(fonts.any(font => font.style == "normal" and font.weight == 400), true)
+#test(cbor.decode(p.fonts(bytes("Nonexistent"))), ())
+
+--- plugin-warn ---
+#let p = plugin("/tests/plugins/host.wasm")
+
+// Warning: 15-53 hello from the plugin
+// Hint: 15-53 this warning was emitted by the plugin function `warn`
+#let result = p.warn(bytes("hello from the plugin"))
+#test(result, bytes(()))
+
+--- plugin-instruction-budget ---
+#let p = plugin("/tests/plugins/limits.wasm")
+
+// Error: 2-10 plugin exceeded its instruction budget
+#p.spin()
+
+--- plugin-memory-budget ---
+#let p = plugin("/tests/plugins/limits.wasm")
+
+// Error: 2-10 plugin exceeded its memory budget
+#p.grow()
@@ -0,0 +1,66 @@
+// Test marks for running headers.
+
+--- mark-first-last ---
+#set page(
+  height: 80pt,
+  margin: (top: 24pt, rest: 8pt),
+  header: context {
+    let first = mark.first("entry")
+    let last = mark.last("entry")
+    if first != none [*#first -- #last*]
+  },
+)
+
+#set par(spacing: 0.65em)
+#let entry(term) = {
+  mark("entry", term)
+  term
+  parbreak()
+}
+
+#for term in ("Apple", "Banana", "Cherry", "Date", "Elderberry", "Fig") {
+  entry(term)
+}
+
+--- mark-top ---
+#set page(height: 60pt)
+#mark("chapter", "One")
+#pagebreak()
+#context {
+  test(mark.top("chapter"), "One")
+  test(mark.first("chapter"), "One")
+  test(mark.last("chapter"), "One")
+}
+#pagebreak()
+#context {
+  test(mark.top("chapter"), "One")
+  test(mark.first("chapter"), "Two")
+  test(mark.last("chapter"), "Three")
+  test(mark.first("other"), none)
+}
+#mark("chapter", "Two")
+#mark("chapter", "Three")
+
+--- mark-heading-at-top ---
+// A heading at the very top of a page must be found.
+#set page(
+  height: 60pt,
+  margin: (top: 20pt, rest: 8pt),
+  header: context emph(mark.first("chapter")),
+)
+#show heading: it => {
+  mark("chapter", it.body)
+  it
+}
+
+= Intro
+#lines(2)
+#pagebreak()
+= Body
+#lines(2)
+
+--- mark-no-context ---
+// Error: 2-21 can only be used when context is known
+// Hint: 2-21 try wrapping this in a `context` expression
+// Hint: 2-21 the `context` expression should wrap everything that depends on this function
+#mark.first("entry")
@@ -143,7 +143,7 @@ Paragraph
 --- block-consistent-width ---
 // Test that block enforces consistent width across regions. Also use some
 // introspection to check that measurement is working correctly.
-#block(stroke: 1pt, inset: 5pt)[
+#block(stroke: 1pt, inset: 5pt, split-stroke: auto)[
   #align(right)[Hi]
   #colbreak()
   Hello @netwok
@@ -152,6 +152,43 @@ Paragraph
 #show bibliography: none
 #bibliography("/assets/bib/works.bib")
 
+--- block-split-stroke ---
+// Test that the edges at which a block is split stay open.
+#set page(height: 60pt)
+#block(stroke: 1pt, radius: 4pt, inset: 4pt, lines(6))
+
+--- block-split-stroke-custom ---
+#set page(height: 60pt)
+#block(
+  stroke: 1pt,
+  radius: 4pt,
+  inset: 4pt,
+  split-stroke: (paint: gray, dash: "dotted"),
+  split-radius: auto,
+  split-inset: 1pt,
+  lines(6),
+)
+
+--- block-split-markers ---
+#set page(height: 80pt)
+#block(
+  fill: luma(235),
+  inset: 4pt,
+  split-before: align(right, text(8pt)[...]),
+  split-after: text(8pt)[(cont.)],
+  lines(8),
+)
+
+--- block-split-markers-unsplit ---
+// Markers only show up if the block is split.
+#block(
+  stroke: 1pt,
+  inset: 4pt,
+  split-before: [Not shown],
+  split-after: [Not shown],
+  lines(2),
+)
+
 --- block-sticky ---
 #set page(height: 100pt)
 #lines(3)
@@ -215,7 +252,7 @@ Emoji: #box(height: 0.5em, clip: true, stroke: 1pt + black)[🐪, 🌋, 🏞]
 
 First!
 
-#block(height: 4em, clip: true, stroke: 1pt + black)[
+#block(height: 4em, clip: true, stroke: 1pt + black, split-stroke: auto)[
   But, soft! what light through yonder window breaks? It is the east, and Juliet
   is the sun.
 ]
@@ -254,6 +254,42 @@ Ref @fn
 A #footnote[a]
 B #footnote[b]
 
+--- footnote-series ---
+#set page(height: 120pt)
+#let variant = footnote.with(
+  series: "variants",
+  numbering: "a",
+  separator: line(length: 100%, stroke: 0.5pt),
+)
+
+Arma#footnote[First] virumque#variant[armaque]
+cano#footnote[Second] Troiae#variant[Troia]
+
+--- footnote-series-counter ---
+#let note = footnote.with(series: "a")
+A#footnote[1] B#note[2] C#footnote[3] D#note[4] E#note[5]
+#context test(counter(footnote).get(), (2,))
+#context test(counter(footnote.where(series: "a")).get(), (3,))
+
+--- footnote-series-ref ---
+#let note = footnote.with(series: "a", numbering: "i")
+A#footnote[B] C#note[D]<d> E @d
+
+--- footnote-endnotes ---
+#set page(height: 140pt)
+#let endnote = footnote.with(series: "end", endnote: true)
+
+= One
+A#endnote[First note] B#footnote[Page note]
+#endnotes(series: "end", title: none)
+
+= Two
+C#endnote[Second note] D#endnote[Third note]
+#endnotes(series: "end")
+
+--- footnote-endnotes-empty ---
+#endnotes(title: none)
+
 --- issue-1433-footnote-in-list ---
 // Test that footnotes in lists do not produce extraneous page breaks. The list
 // layout itself does not currently react to the footnotes layout, weakening the
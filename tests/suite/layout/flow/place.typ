@@ -322,6 +322,98 @@ C
 // Error: 2-34 vertical floating placement must be `auto`, `top`, or `bottom`
 #place(right, float: true)[Hello]
 
+--- place-wrap-left ---
+#set page("a8")
+#place(left, wrap: true, rect(width: 30pt, height: 30pt, fill: aqua))
+#lorem(20)
+
+--- place-wrap-right-clearance ---
+#set page("a8")
+Before
+#place(right, wrap: true, clearance: 10pt, rect(width: 30pt, height: 20pt))
+#lorem(16)
+
+--- place-wrap-shape ---
+#set page("a8")
+#place(left, wrap: "shape", circle(radius: 20pt, fill: aqua))
+#lorem(24)
+
+--- place-wrap-page-break ---
+#set page("a8", height: 60pt)
+#place(right, wrap: true, rect(width: 20pt, height: 30pt))
+#lorem(30)
+
+--- place-wrap-passed ---
+// Paragraphs after the placed content has been passed use the full width.
+#set page(width: 120pt, height: auto)
+#place(left, wrap: true, rect(width: 30pt, height: 15pt, fill: aqua))
+#lorem(12)
+
+#lorem(12)
+
+--- place-wrap-float ---
+// Error: 2-44 wrapping is currently only available for non-floating placement
+#place(top, float: true, wrap: true)[Hello]
+
+--- place-wrap-center ---
+// Error: 2-34 wrapping placement must be aligned to the left or right
+// Hint: 2-34 try using `left` or `right` as the horizontal alignment
+#place(center, wrap: true)[Hello]
+
+--- place-float-here ---
+#set page(height: 100pt)
+#set place(top, float: true, policy: "here", clearance: 6pt)
+
+#lines(2)
+#place(rect(width: 100%)[Here])
+#lines(3)
+#place(rect(width: 100%, height: 30pt)[Next])
+#lines(2)
+
+--- place-float-page ---
+#set page(height: 100pt)
+#set place(auto, float: true, policy: "page", clearance: 6pt)
+
+#lines(2)
+#place(rect(height: 35pt)[A])
+#place(rect(height: 35pt)[B])
+#place(rect(height: 35pt)[C])
+#lines(2)
+
+--- place-float-page-footnote ---
+#set page(height: 100pt)
+#lines(2)
+#place(auto, float: true, policy: "page", rect(height: 30pt)[A#footnote[Note]])
+#lines(2)
+
+--- place-float-max-fraction ---
+#set page(height: 120pt)
+#set place(top, float: true, clearance: 6pt, max-fraction: 50%)
+
+#place(rect(height: 20pt)[A])
+#place(rect(height: 20pt)[B])
+#place(rect(height: 20pt)[C])
+#lines(4)
+
+--- place-float-max-fraction-exceeded ---
+#set page(height: 100pt)
+#lines(2)
+#place(top, float: true, max-fraction: 30%, rect(height: 50pt)[Big])
+#lines(2)
+
+--- place-float-max-fraction-auto-height ---
+// On an auto-height page, the maximum fraction doesn't push floats onto a
+// separate float page.
+#set page(height: auto)
+#lines(2)
+#place(top, float: true, max-fraction: 30%, rect(height: 50pt)[Big])
+#lines(2)
+
+--- place-float-policy-not-float ---
+// Error: 2-35 float policies are only available for floating placement
+// Hint: 2-35 you can enable floating placement with `place(float: true, ..)`
+#place(top, policy: "here")[Hello]
+
 --- place-flush ---
 #set page(height: 120pt)
 #let floater(align, height) = place(
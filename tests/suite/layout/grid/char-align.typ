@@ -0,0 +1,99 @@
+// Test aligning cells on a character.
+
+--- grid-char-align ---
+#set page(width: auto)
+#table(
+  columns: 2,
+  char-align: (none, "."),
+  [Rent], [1250.00],
+  [Refund], [−84.5],
+  [Fees], [(12.75)],
+  [Misc], [3],
+)
+
+--- grid-char-align-auto ---
+// Test that `auto` aligns on a period.
+#set page(width: auto)
+#table(
+  columns: 2,
+  char-align: (none, auto),
+  [Rent], [1250.00],
+  [Refund], [−84.5],
+  [Fees], [(12.75)],
+  [Misc], [3],
+)
+
+--- grid-char-align-footnote ---
+#set page(width: 120pt)
+#table(
+  columns: 2,
+  align: (left, right),
+  char-align: (x, y) => if x == 1 and y > 0 { "." },
+  [*Item*], [*Amount*],
+  [Rent], [1250.0],
+  [Interest], [3.25#footnote[Estimated.]],
+  [Fees], [*12*.5],
+)
+
+--- grid-char-align-comma ---
+#set page(width: auto)
+#grid(
+  columns: 1,
+  gutter: 3pt,
+  align: center,
+  char-align: ",",
+  [12,5 €], [1.234,75 €], [−0,125 €], [7 €],
+)
+
+--- grid-char-align-cell-override ---
+#set page(width: auto)
+#table(
+  columns: 1,
+  char-align: ".",
+  table.cell(char-align: none)[Total amount],
+  [10.5], [100.25],
+  table.cell(char-align: "/")[1/2],
+)
+
+--- grid-char-align-cell-styles ---
+// The parts are measured with the styles of their cell.
+#set page(width: auto)
+#show table.cell.where(y: 1): set text(size: 16pt)
+#table(
+  columns: 1,
+  char-align: ".",
+  [1.5], [20.25], [300.125],
+)
+
+--- grid-char-align-mixed-chars ---
+// Cells aligned on different characters are lined up separately.
+#set page(width: auto)
+#table(
+  columns: 1,
+  char-align: (_, y) => if calc.even(y) { "." } else { "," },
+  [1.5], [20,25], [300.125], [4,5 €],
+)
+
+--- grid-char-align-colspan ---
+#set page(width: auto)
+#table(
+  columns: 2,
+  char-align: ".",
+  [1.5], [20.25],
+  table.cell(colspan: 2)[300.125],
+  [40.0], [5.5],
+)
+
+--- grid-char-align-show-field ---
+#set page(width: auto)
+#show table.cell: it => raw(repr(it.char-align))
+#table(
+  columns: 2,
+  char-align: (".", none),
+  table.cell(colspan: 2)[a],
+  [b], [c],
+)
+
+--- grid-char-align-invalid ---
+// Error: 32-36 expected exactly one character
+#table(columns: 1, char-align: "ab")[1.5]
@@ -0,0 +1,85 @@
+// Test grids that break horizontally.
+
+--- grid-split-columns ---
+#set page(width: 120pt, height: 80pt)
+#table(
+  columns: 6,
+  split-columns: true,
+  ..range(18).map(n => [Cell #n]),
+)
+
+--- grid-split-columns-repeat ---
+#set page(width: 120pt, height: 80pt)
+#table(
+  columns: 6,
+  split-columns: true,
+  repeat-columns: 1,
+  fill: (x, _) => if x == 0 { luma(220) },
+  [*Year*], ..range(2019, 2024).map(str),
+  [*Sales*], ..range(5).map(n => str(n * 17 + 40)),
+  [*Costs*], ..range(5).map(n => str(n * 11 + 30)),
+)
+
+--- grid-split-columns-header ---
+#set page(width: 120pt, height: 100pt)
+#table(
+  columns: 5,
+  split-columns: true,
+  repeat-columns: 1,
+  table.header([], [Alpha], [Beta], [Gamma], [Delta]),
+  ..range(8).map(y => ([*#y*], ..range(4).map(x => [#(x * y)]))).flatten(),
+)
+
+--- grid-split-columns-colspan ---
+// The grid never breaks between columns spanned by the same cell.
+#set page(width: 120pt, height: 80pt)
+#table(
+  columns: 4,
+  split-columns: true,
+  [Alpha], table.cell(colspan: 2)[Beta and Gamma], [Delta],
+  [A], [B], [C], [D],
+)
+
+--- grid-split-columns-gutter ---
+#set page(width: 100pt, height: 60pt)
+#grid(
+  columns: 4,
+  gutter: 5pt,
+  split-columns: true,
+  repeat-columns: 1,
+  ..range(8).map(n => rect(width: 30pt, height: 15pt)[#n]),
+)
+
+--- grid-split-columns-fits ---
+// Grids that fit the available width are not split.
+#set page(width: 120pt, height: 60pt)
+#table(columns: 3, split-columns: true, [A], [B], [C])
+
+--- grid-split-columns-rtl ---
+#set page(width: 120pt, height: 80pt)
+#set text(dir: rtl)
+#table(
+  columns: 5,
+  split-columns: true,
+  repeat-columns: 1,
+  ..range(10).map(n => [Cell #n]),
+)
+
+--- grid-split-columns-introspection ---
+#set page(width: 120pt, height: 80pt)
+#table(
+  columns: 4,
+  split-columns: true,
+  repeat-columns: 1,
+  [#metadata("repeated") <repeated>], [Lorem ipsum], [Dolor sit], [Amet],
+)
+#context test(query(<repeated>).len(), 1)
+
+--- grid-split-columns-links ---
+// Links and text are only kept in the slice they belong to.
+#set page(width: 120pt, height: 80pt)
+#table(
+  columns: 4,
+  split-columns: true,
+  ..range(8).map(n => link("https://typst.app/" + str(n))[Link #n]),
+)
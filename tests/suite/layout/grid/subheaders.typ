@@ -0,0 +1,99 @@
+--- grid-subheaders-repeat ---
+#set page(width: auto, height: 12em)
+#table(
+  columns: 2,
+  table.header([*Item*], [*Price*]),
+  table.header(level: 2, table.cell(colspan: 2)[_Fruit_]),
+  ..range(4).map(i => ([Fruit #i], [#i.00])).flatten(),
+  table.header(level: 2, table.cell(colspan: 2)[_Vegetables_]),
+  ..range(6).map(i => ([Vegetable #i], [#i.50])).flatten(),
+)
+
+--- grid-subheaders-nested ---
+#set page(width: auto, height: 12em)
+#table(
+  columns: 2,
+  table.header([*Item*], [*Price*]),
+  table.header(level: 2, table.cell(colspan: 2, fill: aqua)[Food]),
+  table.header(level: 3, table.cell(colspan: 2)[_Fruit_]),
+  ..range(3).map(i => ([Fruit #i], [#i.00])).flatten(),
+  table.header(level: 3, table.cell(colspan: 2)[_Vegetables_]),
+  ..range(3).map(i => ([Vegetable #i], [#i.50])).flatten(),
+  table.header(level: 2, table.cell(colspan: 2, fill: aqua)[Drinks]),
+  ..range(3).map(i => ([Drink #i], [#i.25])).flatten(),
+)
+
+--- grid-subheaders-no-repeat ---
+#set page(width: auto, height: 8em)
+#table(
+  columns: 2,
+  table.header(level: 2, repeat: false, table.cell(colspan: 2)[_Fruit_]),
+  ..range(5).map(i => ([Fruit #i], [#i.00])).flatten(),
+)
+
+--- grid-subheaders-orphan ---
+// The sub-header is moved to the next page along with the row below it.
+#set page(width: auto, height: 6em)
+#table(
+  columns: 2,
+  [A], [B],
+  [C], [D],
+  table.header(level: 2, table.cell(colspan: 2)[_Group_]),
+  [E], [F],
+)
+
+--- grid-subheaders-gutter ---
+#set page(width: auto, height: 10em)
+#grid(
+  columns: 2,
+  gutter: 3pt,
+  grid.header(grid.cell(colspan: 2, fill: yellow)[*Title*]),
+  grid.header(level: 2, grid.cell(colspan: 2, fill: aqua)[Group]),
+  ..range(8).map(i => (rect[#i], rect[#(i * 2)])).flatten(),
+)
+
+--- grid-header-continued ---
+#set page(width: auto, height: 10em)
+#table(
+  columns: (4em, 4em),
+  table.header(
+    table.cell(colspan: 2, context {
+      [*Table 3*]
+      if table.header.continued [ (continued)]
+    }),
+  ),
+  ..range(8).map(str),
+  table.footer(
+    table.cell(colspan: 2, context {
+      if table.footer.continued [Continued on next page] else [End]
+    }),
+  ),
+)
+
+--- grid-subheader-continued ---
+#set page(width: 8em, height: 6em)
+#grid(
+  columns: 2,
+  column-gutter: 1em,
+  grid.header(level: 2, grid.cell(colspan: 2, context {
+    if grid.header.continued [_Group (cont.)_] else [_Group_]
+  })),
+  ..range(12).map(str),
+)
+
+--- grid-subheaders-common-rows ---
+#table(
+  columns: 2,
+  table.header(level: 2, [a]),
+  // Error: 3-52 headers must not have common rows
+  table.header(level: 2, table.cell(x: 1, y: 0)[b]),
+)
+
+--- grid-subheaders-conflict-with-cell-before ---
+#table(
+  columns: 2,
+  table.cell(rowspan: 2)[a], [b],
+  // Error: 3-46 header would conflict with a cell placed before it at column 0 row 0
+  // Hint: 3-46 try reducing that cell's rowspan or moving the header
+  table.header(level: 2, table.cell(y: 1)[c]),
+)
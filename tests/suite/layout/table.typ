@@ -332,3 +332,74 @@ Hello
   rows: 16pt,
   ..range(6).map(str).flatten(),
 )
+
+--- table-from-data-dictionaries ---
+#table.from-data((
+  (name: "Apple", color: "red"),
+  (name: "Banana", color: "yellow"),
+  (name: "Plum"),
+))
+
+--- table-from-data-arrays ---
+#table.from-data(
+  (("a", "1"), ("b", "2"), ("c", "3")),
+  columns: ((key: 1, header: [*N*]), (key: 0, header: [*Letter*])),
+)
+
+--- table-from-data-typed-and-sorted ---
+#table.from-data(
+  (("Cherry", "4"), ("Apple", "0.8"), ("Pear", "1.1"), ("Kiwi", "")),
+  columns: (
+    0,
+    (key: 1, type: float, digits: 2, align: right, width: 3em),
+  ),
+  sort-by: 1,
+  descending: true,
+)
+
+--- table-from-data-grouped ---
+#set page(height: 10em)
+#table.from-data(
+  (
+    (name: "Apple", kind: "Pome", price: 0.8),
+    (name: "Cherry", kind: "Drupe", price: 4),
+    (name: "Pear", kind: "Pome", price: 1.1),
+    (name: "Plum", kind: "Drupe", price: 2.2),
+    (name: "Quince", kind: "Pome", price: -0.5),
+  ),
+  columns: (
+    (key: "name", header: [*Fruit*]),
+    (key: "price", header: [*Price*], digits: 1, subtotal: true),
+  ),
+  sort-by: "name",
+  group-by: "kind",
+)
+
+--- table-from-data-format ---
+#table.from-data(
+  ((item: "Tea", qty: 2), (item: "Coffee", qty: 0)),
+  columns: (
+    "item",
+    (key: "qty", format: n => if n == 0 [--] else [#n ×]),
+  ),
+)
+
+--- table-from-data-set-rules ---
+// Columns without explicit alignment follow the table's alignment.
+#set table(align: center)
+#table.from-data(
+  ((item: "Tea", price: "2.50"), (item: "Coffee", price: "12.00")),
+  columns: ("item", (key: "price", align: right)),
+)
+
+--- table-from-data-wrong-key ---
+// Error: 18-34 fields of dictionary rows must be selected by string keys
+#table.from-data(((a: 1), (a: 2)), columns: (0,))
+
+--- table-from-data-unknown-column-entry ---
+// Error: 38-65 unexpected key "heading", valid keys are "key", "header", "type", "format", "digits", "align", "width", and "subtotal"
+#table.from-data(((a: 1),), columns: ((key: "a", heading: [A]),))
+
+--- table-from-data-bad-conversion ---
+// Error: 18-27 invalid float: x
+#table.from-data((("x",),), columns: ((key: 0, type: float),))
@@ -0,0 +1,94 @@
+--- markdown-basic ---
+// Test converting Markdown to native content.
+#markdown.decode(
+  "# Introduction\n" +
+  "Some *emphasized*, **strong**, and ~~struck~~ text with `code`.\n" +
+  "It's \"quoted\" and has a [link](https://typst.app).\n\n" +
+  "- First\n" +
+  "- Second\n\n" +
+  "3. Third\n" +
+  "4. Fourth\n\n" +
+  "> A quote.\n\n" +
+  "```rust\nfn main() {}\n```\n\n" +
+  "---\n",
+)
+
+--- markdown-elements ---
+// Test that the produced elements are the native ones.
+#let doc = markdown.decode("## Title\n\n- a\n- b\n\n1. c\n\n```py\nx\n```")
+#let kids = doc.children.filter(c => c.func() != parbreak)
+#test(kids.map(c => c.func()), (heading, list, enum, raw))
+#test(kids.at(0).depth, 2)
+#test(kids.at(0).body, [Title])
+#test(kids.at(1).children.len(), 2)
+#test(kids.at(3).lang, "py")
+#test(kids.at(3).text, "x")
+#test(kids.at(3).block, true)
+
+--- markdown-code-block-quotes ---
+// Test that quotes in code blocks are kept verbatim.
+#let doc = markdown.decode("```\nlet s = \"it's\";\n```")
+#test(doc.text, "let s = \"it's\";")
+
+--- markdown-list-tightness ---
+// Test that tightness follows the Markdown rather than the converted content.
+#test(markdown.decode("- a\n- b").tight, true)
+#test(markdown.decode("- a\n\n- b").tight, false)
+#test(markdown.decode("- a\n  > quote\n- b").tight, true)
+#test(markdown.decode("- a\n\n  more\n- b").tight, false)
+#test(markdown.decode("1. a\n2. b").tight, true)
+#test(markdown.decode("1. a\n\n2. b").tight, false)
+
+--- markdown-show-rules ---
+// Test that show rules apply to converted content.
+#show heading: set text(red)
+#show raw: set text(blue)
+#show list: set list(marker: [--])
+#markdown.decode("# Red\n\n- `blue` item\n- another")
+
+--- markdown-table ---
+// Test tables with alignment.
+#markdown.decode(
+  "| Name | Count |\n" +
+  "|:-----|------:|\n" +
+  "| Apples | 3 |\n" +
+  "| Pears | 12 |\n",
+)
+
+--- markdown-table-set-align ---
+// Test that only explicitly aligned columns override set rules.
+#set table(align: center)
+#let doc = markdown.decode(
+  "| Name | Count |\n" +
+  "|------|------:|\n" +
+  "| Apples | 3 |\n" +
+  "| Pears | 12 |\n",
+)
+#test(doc.has("align"), false)
+#test(doc.children.at(1).has("align"), false)
+#test(doc.children.at(2).align, right)
+#doc
+
+--- markdown-footnote ---
+// Test footnotes.
+#markdown.decode("Hello[^1] world[^missing].\n\n[^1]: The note.")
+
+--- markdown-link-email ---
+#let doc = markdown.decode("<hi@typst.app>")
+#test(doc.dest, "mailto:hi@typst.app")
+
+--- markdown-file-image ---
+// Test that images in a Markdown file are resolved relative to that file.
+#markdown("/tests/markdown/note.md")
+
+--- markdown-image-missing ---
+// Error: 18-37 file not found (searched at tests/suite/loading/missing.png)
+#markdown.decode("![x](missing.png)")
+
+--- markdown-missing ---
+// Error: 11-23 file not found (searched at tests/suite/loading/missing.md)
+#markdown("missing.md")
+
+--- markdown-invalid-utf8 ---
+// Error: 18-31 data is not valid utf-8
+#markdown.decode(bytes((255,)))
@@ -0,0 +1,26 @@
+// Test chemical formulas.
+
+--- math-chem-formula ---
+$ chem("H2O") quad chem("Ca(OH)2") $
+$ chem("[Fe(CN)6]^4-") $
+$ chem("CuSO4*5H2O") $
+
+--- math-chem-charge ---
+$ chem("Na+") quad chem("Cl-") quad chem("SO4^2-") quad chem("Fe^{3+}") $
+
+--- math-chem-reaction ---
+#set page(width: 180pt)
+$ chem("2H2 + O2 -> 2H2O") $
+$ chem("NH4+ + OH- <=>[H2O] NH3 + H2O") $
+$ chem("CaCO3(s) ->[heat][-CO2] CaO(s) + CO2(g)") $
+
+--- math-chem-inline ---
+Water is $chem("H2O")$ and table salt is $chem("NaCl")$.
+
+--- math-chem-unclosed ---
+// Error: 8-16 unclosed delimiter `(` in chemical formula
+$ chem("Ca(OH2") $
+
+--- math-chem-unexpected-closing ---
+// Error: 8-16 unexpected closing delimiter `)` in chemical formula
+$ chem("CaOH)2") $
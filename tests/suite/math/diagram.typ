@@ -0,0 +1,45 @@
+// Test commutative diagrams.
+
+--- math-diagram ---
+$ diagram(
+  A, B;
+  C, D;
+  arrows: #(
+    (from: (0, 0), to: (0, 1), label: $f$),
+    (from: (0, 0), to: (1, 0), label: $g$, side: right),
+    (from: (0, 1), to: (1, 1), label: $h$),
+    (from: (1, 0), to: (1, 1), label: $k$, side: right),
+    (from: (0, 0), to: (1, 1), stroke: (dash: "dashed")),
+  ),
+) $
+
+--- math-diagram-bend ---
+$ diagram(
+  X, Y;
+  arrows: #(
+    (from: (0, 0), to: (0, 1), bend: 40deg, label: $f$),
+    (from: (0, 0), to: (0, 1), bend: -40deg, label: $g$, side: right),
+  ),
+) $
+
+--- math-diagram-delim-and-gap ---
+#set math.diagram(delim: "[", gap: 1.5em)
+$ diagram(
+  0, M, N;
+  arrows: #(
+    (from: (0, 0), to: (0, 1)),
+    (from: (0, 1), to: (0, 2), label: $phi$, stroke: blue),
+  ),
+) $
+
+--- math-diagram-out-of-range ---
+// Error: 3-56 arrow refers to cell (1, 0) of a diagram with 1 rows and 2 columns
+$ diagram(A, B; arrows: #((from: (0, 0), to: (1, 0)),)) $
+
+--- math-diagram-same-cell ---
+// Error: 3-56 arrow must connect two different cells
+$ diagram(A, B; arrows: #((from: (0, 0), to: (0, 0)),)) $
+
+--- math-diagram-bad-side ---
+// Error: 26-69 expected `left` or `right`
+$ diagram(A, B; arrows: #((from: (0, 0), to: (0, 1), side: center),)) $
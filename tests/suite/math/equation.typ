@@ -260,6 +260,86 @@ $ q &= sum_k ln A \
 // Test numbering on empty equations.
 #math.equation(numbering: "1", block: true, [])
 
+--- math-equation-line-numbering-each ---
+// Test numbering each line of an equation separately.
+#set math.equation(numbering: "(1)", line-numbering: "each")
+$ a + b $
+$ a &= b + c #math.tag(label: <first>) \
+    &= c + b #math.tag(none) \
+    &= d #math.tag[(\*)] \
+    &= e $
+$ f $
+See @first.
+
+--- math-equation-line-numbering-sub ---
+// Test sub-numbering the lines of an equation.
+#set math.equation(numbering: "(1a)", line-numbering: "sub")
+$ a + b $
+$ a &= b #math.tag(label: <sub-b>) \
+    &= c \
+    &= d \ $ <whole>
+$ e $
+See @whole and @sub-b.
+
+--- math-equation-line-numbering-start ---
+// Test numbering lines at the start.
+#set math.equation(numbering: "(1)", number-align: left, line-numbering: "each")
+$ a = b \ c = d $
+
+--- math-equation-tag-no-effect ---
+// Test that tags have no effect without line numbering.
+#set math.equation(numbering: "(1)")
+$ a = b #math.tag(none) \ c = d $
+Inline $x #math.tag[(\*)]$.
+
+--- math-equation-tag-reference-unnumbered ---
+#set math.equation(numbering: "(1)", line-numbering: "each")
+$ a = b #math.tag(none, label: <none>) $
+
+// Error: 1-6 cannot reference tag without numbering
+// Hint: 1-6 lines of equations are numbered with `#set math.equation(numbering: "(1)", line-numbering: "each")`
+@none
+
+--- math-equation-line-numbering-reference-equation ---
+// An equation with separately numbered lines shares the number of its first
+// numbered line.
+#set math.equation(numbering: "(1)", line-numbering: "each")
+$ a = b #math.tag(none) \ c = d #math.tag(label: <line>) \ e = f $ <eq>
+See @eq and @line.
+
+--- math-equation-mathml ---
+$ a^2 + b_1 = 1/2 $ <eq>
+#context test(
+  query(<eq>).first().mathml,
+  "<math xmlns=\"http://www.w3.org/1998/Math/MathML\" display=\"block\"><mrow><msup><mi>𝑎</mi><mn>2</mn></msup><mo>+</mo><msub><mi>𝑏</mi><mn>1</mn></msub><mo>=</mo><mfrac><mn>1</mn><mn>2</mn></mfrac></mrow></math>",
+)
+
+// Without a description, the alternative text is derived from the MathML.
+#context test(query(<eq>).first().alt, "𝑎^2 + 𝑏_1 = 1/2")
+
+--- math-equation-mathml-structure ---
+#let mathml(body) = {
+  let math = body.split("display=\"inline\">").at(1)
+  math.slice(0, math.len() - "</math>".len())
+}
+
+$sum_(i=0)^n sqrt(x) "if" (a < b)$ <sum>
+#context test(
+  mathml(query(<sum>).first().mathml),
+  "<mrow><msubsup><mo>∑</mo><mrow><mi>𝑖</mi><mo>=</mo><mn>0</mn></mrow><mi>𝑛</mi></msubsup><msqrt><mi>𝑥</mi></msqrt><mtext>if</mtext><mo>(</mo><mi>𝑎</mi><mo>&lt;</mo><mi>𝑏</mi><mo>)</mo></mrow>",
+)
+
+$x &= 1 \ &= vec(1, 2)$ <lines>
+#context test(
+  mathml(query(<lines>).first().mathml),
+  "<mtable><mtr><mtd><mi>𝑥</mi></mtd><mtd><mrow><mo>=</mo><mn>1</mn></mrow></mtd></mtr><mtr><mtd><mrow></mrow></mtd><mtd><mrow><mo>=</mo><mo>(</mo><mtable><mtr><mtd><mn>1</mn></mtd></mtr><mtr><mtd><mn>2</mn></mtd></mtr></mtable><mo>)</mo></mrow></mtd></mtr></mtable>",
+)
+
+--- math-equation-alt ---
+#set math.equation(alt: "Pythagorean theorem")
+$ a^2 + b^2 = c^2 $ <eq>
+#context test(query(<eq>).first().alt, "Pythagorean theorem")
+
 --- issue-4187-alignment-point-affects-row-height ---
 // In this bug, a row of "-" only should have a very small height; but
 // after adding an alignment point "&", the row gains a larger height.
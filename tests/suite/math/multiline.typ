@@ -107,6 +107,43 @@ Multiple trailing line breaks.
 $ $\
 Nothing: $ $, just empty.
 
+--- math-linebreaking-block ---
+// Test automatic line breaking of block equations.
+#set page(width: 150pt)
+#set math.equation(linebreaking: true)
+$ (a + b)^4 = a^4 + 4a^3 b + 6a^2 b^2 + 4a b^3 + b^4 $
+
+--- math-linebreaking-block-prefer-relation ---
+// Test that breaking at relations is preferred.
+#set page(width: 120pt)
+#set math.equation(linebreaking: true)
+$ x + y + z + w = a + b + c + d + e $
+
+--- math-linebreaking-block-top-level ---
+// Test that operators within delimiters are not broken.
+#set page(width: 120pt)
+#set math.equation(linebreaking: true, hanging-indent: 1em)
+$ f = 1 + \( a + b + c + d + e \) + g + h $
+
+--- math-linebreaking-block-mixed-rows ---
+// Test that rows which fit keep their alignment.
+#set page(width: 150pt)
+#set math.equation(linebreaking: true)
+$ x &= a + b \
+  y &= a + b + c + d + e + f + g + h + i \
+  z &= c $
+
+--- math-linebreaking-block-numbered ---
+// Test that the number is taken into account.
+#set page(width: 150pt)
+#set math.equation(linebreaking: true, numbering: "(1)")
+$ a + b + c + d + e + f + g + h $
+
+--- math-linebreaking-block-disabled ---
+// Test that block equations don't break by default.
+#set page(width: 150pt)
+$ a + b + c + d + e + f + g + h + i + j $
+
 --- math-pagebreaking ---
 // Test breaking of equations at page boundaries.
 #set page(height: 5em)
@@ -77,6 +77,53 @@ We can clearly see that @fig-cylinder and
 
 #lines(15)
 
+--- figure-wrap ---
+#set page(width: 150pt, height: auto)
+#figure(
+  wrap: right,
+  caption: [Box],
+  rect(width: 50pt, height: 30pt),
+)
+#lorem(30)
+
+--- figure-wrap-center ---
+// Error: 15-21 expected `start`, `left`, `right`, or `end`, found center
+#figure(wrap: center, rect())
+
+--- figure-placement-here ---
+#set page(height: 120pt)
+#set place(clearance: 6pt)
+
+#lines(2)
+#figure(placement: "here", caption: [I], rect(height: 20pt))
+#lines(2)
+#figure(placement: "here", caption: [II], rect(height: 40pt))
+#lines(2)
+
+--- figure-placement-page ---
+#set page(height: 120pt)
+#set place(clearance: 6pt)
+
+#lines(2)
+#figure(placement: "page", caption: [I], rect(height: 30pt))
+#figure(placement: "page", caption: [II], rect(height: 30pt))
+#lines(2)
+
+--- figure-after-reference ---
+#set page(height: 100pt)
+#set place(clearance: 6pt)
+
+#figure(
+  placement: top,
+  after-reference: true,
+  caption: [Late],
+  rect(height: 20pt),
+) <late>
+
+#lines(6)
+See @late.
+#lines(2)
+
 --- figure-theorem ---
 // Testing show rules with figures with a simple theorem display
 #show figure.where(kind: "theorem"): it => {
@@ -171,3 +171,28 @@ A
 
 = הוקוס Pocus
 = זוהי כותרת שתורגמה על ידי מחשב
+
+--- outline-scope-section ---
+#set page(width: 150pt)
+#set heading(numbering: "1.1")
+
+= Animals
+#outline(title: none, scope: "section")
+
+== Mammals
+=== Cats
+== Birds
+
+= Plants
+#outline(title: [In this chapter], scope: "section", target: figure)
+
+#figure(rect[Oak], caption: [A tree])
+
+= Fungi
+#figure(rect[Morel], caption: [A mushroom])
+
+--- outline-scope-section-no-heading ---
+#outline(scope: "section")
+
+= First
+= Second
@@ -221,3 +221,181 @@
 #let x = 1
 #let c = [#(x) => (1, 2)]
 #test(c.children.last(), [(1, 2)]))
+
+--- closure-typed-signature ---
+#let scale(x as int, factor: float = 2.0) -> float = x * factor
+#test(scale(3), 6.0)
+#test(scale(3, factor: 0.5), 1.5)
+#test(scale(3, factor: 1), 3.0)
+
+#let wrap = (body as content) -> content => [(#body)]
+#test(wrap("hi"), wrap[hi])
+#test(type(wrap("hi")), content)
+
+#let either = (x as (int, str)) -> (int, str) => x
+#test(either(1), 1)
+#test(either("a"), "a")
+
+#let pad = (body, by: (length, ratio) = 1pt) => box(inset: by, body)
+#test(pad[a].inset, 1pt)
+#test(pad(by: 10%)[a].inset, 10%)
+
+--- closure-typed-without-return-type ---
+// Positional parameters can be annotated without a return type, too.
+#let double(x as int) = 2 * x
+#test(double(2), 4)
+
+#let sum((a, b) as array) = a + b
+#test(sum((1, 2)), 3)
+
+--- closure-typed-return-tuple ---
+#let f(x) -> (int, str) = x
+#test(f(1), 1)
+#test(f("a"), "a")
+
+#let g = (x) -> (int, str) => x
+#test(g(2), 2)
+
+--- closure-typed-return-explicit ---
+#let clamp(x as int) -> int = {
+  if x < 0 { return 0 }
+  x
+}
+#test(clamp(-5), 0)
+#test(clamp(5), 5)
+
+--- closure-typed-none ---
+#let maybe(x as (int, none)) -> none = none
+#test(maybe(none), none)
+#test(maybe(1), none)
+
+--- closure-named-annotation ---
+// Named parameters can be annotated in untyped signatures, too.
+#let f(x, sep: str = ", ") = x.join(sep)
+#test(f(("a", "b")), "a, b")
+#test(f(("a", "b"), sep: "-"), "a-b")
+
+--- closure-untyped-named-type-default ---
+// `x: int` is a named parameter with a default, with or without a return type.
+#let f(kind: int) = kind
+#test(f(), int)
+#test(f(kind: str), str)
+
+#let g(kind: int) -> type = kind
+#test(g(), int)
+#test(g(kind: str), str)
+
+--- closure-typed-capture ---
+#let number = int
+#let f(x as number) -> number = x
+#test(f(1), 1)
+
+--- closure-typed-wrong-positional ---
+#let f(x as int, y as str) -> str = y * x
+// Error: 4-7 expected integer, found string
+#f("3", "a")
+
+--- closure-typed-wrong-pattern ---
+#let f((a, b) as array) = a + b
+// Error: 4-16 expected array, found dictionary
+#f((a: 1, b: 2))
+
+--- closure-typed-wrong-named ---
+#let f(x, times: int = 1) = x * times
+// Error: 16-19 expected integer, found float
+#f("a", times: 2.5)
+
+--- closure-typed-wrong-return ---
+// Error: 31-36 expected content, found integer
+#let f(x as int) -> content = x + 1
+#f(1)
+
+--- closure-typed-wrong-return-tuple ---
+// Error: 27-28 expected integer or string, found float
+#let f(x) -> (int, str) = x
+#f(1.5)
+
+--- closure-typed-wrong-explicit-return ---
+// Error: 29-37 expected string, found integer
+#let f(x as int) -> str = { return x }
+#f(1)
+
+--- closure-typed-missing-arg ---
+#let f(x as int) -> int = x
+// Error: 2-5 missing argument: x
+#f()
+
+--- closure-typed-bad-default ---
+// Error: 23-26 expected length, found string
+#let f(size: length = "1") = size
+
+--- closure-typed-bad-annotation ---
+// Error: 13-14 expected type, none, auto, or array, found integer
+#let f(x as 1) -> int = x
+
+--- closure-locals-mutation ---
+// Locals stay in sync when mutated through fields, indices and patterns.
+#let f(n) = {
+  let items = ()
+  let counts = (sum: 0)
+  for i in range(n) {
+    items.push(i)
+    counts.sum += i
+    let (x, y) = (i, i * 2)
+    items.at(-1) = x + y
+  }
+  (items, counts)
+}
+#test(f(4), ((0, 3, 6, 9), (sum: 6)))
+
+--- closure-locals-captured ---
+// Closures created in a loop capture the current iteration's value.
+#let make(n) = {
+  let fs = ()
+  for i in range(n) {
+    fs.push(() => i * 10)
+  }
+  fs.map(f => f())
+}
+#test(make(3), (0, 10, 20))
+
+--- closure-locals-shadowing ---
+#let f(x) = {
+  let y = x
+  {
+    let x = x + 1
+    y += x
+  }
+  (x, y)
+}
+#test(f(1), (1, 3))
+
+--- closure-flow-partial-output ---
+// Output joined before a break or return is kept.
+#let f() = {
+  for i in range(5) {
+    str(i)
+    if i == 2 { break }
+    "-"
+  }
+}
+#let g(xs, x) = {
+  for (i, y) in xs.enumerate() {
+    if y == x { return i }
+  }
+  "missing"
+}
+#test(f(), "0-1-2")
+#test(g((3, 5, 7), 7), 2)
+#test(g((3, 5, 7), 4), "missing")
+
+--- closure-error-in-loop ---
+#let f(xs) = {
+  let total = 0
+  for x in xs {
+    // Error: 5-15 cannot add integer and string
+    total += x
+  }
+  total
+}
+#f((1, 2, "3"))